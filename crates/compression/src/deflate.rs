@@ -107,6 +107,36 @@ pub fn decompress(source: &[u8]) -> Result<(Vec<u8>, usize), Error> {
     Ok((output_stream, reader.num_consumed_bytes()))
 }
 
+/// Compress the data into a `DEFLATE` stream
+///
+/// FIXME: This only emits uncompressed blocks, so the output is slightly *larger* than the input.
+///        Actually compressing the data with LZ77 and huffman codes is left for the future.
+#[must_use]
+pub fn compress(source: &[u8]) -> Vec<u8> {
+    // The length of an uncompressed block is stored as a u16
+    const MAX_BLOCK_SIZE: usize = u16::MAX as usize;
+
+    // There must be at least one (possibly empty) block
+    let num_blocks = source.len().div_ceil(MAX_BLOCK_SIZE).max(1);
+    let mut output_stream = Vec::with_capacity(source.len() + num_blocks * 5);
+
+    for block_index in 0..num_blocks {
+        let block = &source[block_index * MAX_BLOCK_SIZE..]
+            [..(source.len() - block_index * MAX_BLOCK_SIZE).min(MAX_BLOCK_SIZE)];
+
+        // BFINAL and BTYPE (00 for uncompressed) are followed by padding up to the next byte boundary
+        let is_final = block_index == num_blocks - 1;
+        output_stream.push(is_final as u8);
+
+        let len = block.len() as u16;
+        output_stream.extend_from_slice(&len.to_le_bytes());
+        output_stream.extend_from_slice(&(!len).to_le_bytes());
+        output_stream.extend_from_slice(block);
+    }
+
+    output_stream
+}
+
 fn decompress_block(
     literal_tree: &HuffmanTree<usize>,
     distance_tree: &HuffmanTree<usize>,
@@ -313,7 +343,7 @@ impl TryFrom<u8> for CompressionScheme {
 
 #[cfg(test)]
 mod tests {
-    use super::{decompress, Error};
+    use super::{compress, decompress, Error};
 
     #[test]
     fn test_basic() -> Result<(), Error> {
@@ -324,4 +354,20 @@ mod tests {
         assert_eq!(num_consumed_bytes, bytes.len());
         Ok(())
     }
+
+    #[test]
+    fn compress_roundtrip() -> Result<(), Error> {
+        for data in [
+            vec![],
+            b"abc".to_vec(),
+            (0..200_000).map(|i| i as u8).collect(),
+        ] {
+            let compressed = compress(&data);
+            let (decompressed, num_consumed_bytes) = decompress(&compressed)?;
+
+            assert_eq!(decompressed, data);
+            assert_eq!(num_consumed_bytes, compressed.len());
+        }
+        Ok(())
+    }
 }
@@ -47,6 +47,24 @@ const FLAG_DICT_BIT: u8 = 1 << 5;
 /// Note that the minimum length of a DEFLATE archive is not included since zlib may use algorithms other than DEFLATE.
 const MINIMUM_ZLIB_LEN: usize = 6;
 
+/// Compress the data into a zlib stream, using `DEFLATE` as the compression method
+///
+/// See [deflate::compress] for the limitations of the compressor.
+#[must_use]
+pub fn compress(bytes: &[u8]) -> Vec<u8> {
+    // CM = 8 (DEFLATE) with a window size of 32K (CINFO = 7)
+    let compression_method_and_flags = 0x78;
+
+    // FLEVEL = 0 (fastest compression), the remaining bits are chosen such that
+    // the header checksum is a multiple of 31
+    let flags = 0x01;
+
+    let mut compressed = vec![compression_method_and_flags, flags];
+    compressed.extend(deflate::compress(bytes));
+    compressed.extend_from_slice(&hash::adler32(bytes).to_be_bytes());
+    compressed
+}
+
 pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
     if bytes.len() < MINIMUM_ZLIB_LEN {
         return Err(Error::UnexpectedEOF);
@@ -121,4 +139,13 @@ mod tests {
         assert_eq!(&decompressed, b"abc");
         Ok(())
     }
+
+    #[test]
+    fn test_zlib_compression() -> Result<(), Error> {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let compressed = compress(data);
+
+        assert_eq!(decompress(&compressed)?, data);
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
 mod aes;
 mod chacha20;
+pub mod rsa;
+pub mod x25519;
 
 pub use aes::Aes128Cipher;
 pub use chacha20::ChaCha20;
@@ -0,0 +1,250 @@
+//! RSA signature verification ([RFC 8017](https://datatracker.ietf.org/doc/html/rfc8017))
+
+use error_derive::Error;
+use hash::{constant_time_eq, HashAlgorithm, Md5, Sha224, Sha256};
+use sl_std::big_num::BigNum;
+
+/// ASN.1 DER encoded `DigestInfo` prefixes, the digest itself follows directly after
+///
+/// Refer to <https://datatracker.ietf.org/doc/html/rfc8017#section-9.2>, Note 1.
+const MD5_DIGEST_INFO: [u8; 18] = [
+    0x30, 0x20, 0x30, 0x0c, 0x06, 0x08, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x05, 0x05, 0x00,
+    0x04, 0x10,
+];
+const SHA224_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04, 0x05,
+    0x00, 0x04, 0x1c,
+];
+const SHA256_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// The padding string must be at least eight bytes long
+const MIN_PADDING_LENGTH: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum VerificationError {
+    #[msg = "signature length does not match the modulus length"]
+    InvalidSignatureLength,
+
+    #[msg = "signature representative out of range"]
+    SignatureOutOfRange,
+
+    #[msg = "modulus too short for the digest"]
+    ModulusTooShort,
+
+    #[msg = "invalid signature"]
+    InvalidSignature,
+}
+
+/// The hash function that was used to create a signature
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Sha224,
+    Sha256,
+}
+
+/// <https://datatracker.ietf.org/doc/html/rfc8017#section-3.1>
+#[derive(Clone, Debug)]
+pub struct PublicKey {
+    /// The RSA modulus `n`
+    modulus: BigNum,
+
+    /// The RSA public exponent `e`
+    exponent: BigNum,
+}
+
+impl PublicKey {
+    #[must_use]
+    pub fn new(modulus: BigNum, exponent: BigNum) -> Self {
+        Self { modulus, exponent }
+    }
+
+    #[must_use]
+    pub fn from_be_bytes(modulus: &[u8], exponent: &[u8]) -> Self {
+        Self::new(
+            BigNum::from_be_bytes(modulus),
+            BigNum::from_be_bytes(exponent),
+        )
+    }
+
+    /// The length of the modulus in bytes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.modulus.bit_length().div_ceil(8)
+    }
+}
+
+impl DigestAlgorithm {
+    /// Compute the `DigestInfo` value which is embedded in a signature
+    ///
+    /// <https://datatracker.ietf.org/doc/html/rfc8017#section-9.2>
+    #[must_use]
+    fn digest_info(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Md5 => [MD5_DIGEST_INFO.as_slice(), &Md5::hash(message)].concat(),
+            Self::Sha224 => [SHA224_DIGEST_INFO.as_slice(), &Sha224::hash(message)].concat(),
+            Self::Sha256 => [SHA256_DIGEST_INFO.as_slice(), &Sha256::hash(message)].concat(),
+        }
+    }
+}
+
+/// Verify a `RSASSA-PKCS1-v1_5` signature over `message`
+///
+/// <https://datatracker.ietf.org/doc/html/rfc8017#section-8.2.2>
+pub fn verify_pkcs1_v15(
+    public_key: &PublicKey,
+    message: &[u8],
+    signature: &[u8],
+    hash_algorithm: DigestAlgorithm,
+) -> Result<(), VerificationError> {
+    // 1. Length checking: If the length of the signature S is not k octets, output "invalid signature" and stop.
+    let k = public_key.len();
+    if signature.len() != k {
+        return Err(VerificationError::InvalidSignatureLength);
+    }
+
+    // 2. RSA verification
+    //    a. Convert the signature S to an integer signature representative s
+    let s = BigNum::from_be_bytes(signature);
+
+    //    b. Apply the RSAVP1 verification primitive to the RSA public key (n, e) and the signature
+    //       representative s to produce an integer message representative m
+    //       https://datatracker.ietf.org/doc/html/rfc8017#section-5.2.2
+    if s >= public_key.modulus {
+        return Err(VerificationError::SignatureOutOfRange);
+    }
+    let m = s.pow_mod(&public_key.exponent, &public_key.modulus);
+
+    //    c. Convert the message representative m to an encoded message EM of length k octets
+    let m = m.to_be_bytes();
+    let mut encoded_message = vec![0; k - m.len()];
+    encoded_message.extend_from_slice(&m);
+
+    // 3. EMSA-PKCS1-v1_5 encoding: Apply the EMSA-PKCS1-v1_5 encoding operation to the message M to
+    //    produce a second encoded message EM' of length k octets
+    let expected = emsa_pkcs1_v15_encode(message, k, hash_algorithm)?;
+
+    // 4. Compare the encoded message EM and the second encoded message EM'.
+    //    If they are the same, output "valid signature"; otherwise, output "invalid signature".
+    if !constant_time_eq(&encoded_message, &expected) {
+        return Err(VerificationError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+/// <https://datatracker.ietf.org/doc/html/rfc8017#section-9.2>
+fn emsa_pkcs1_v15_encode(
+    message: &[u8],
+    encoded_message_length: usize,
+    hash_algorithm: DigestAlgorithm,
+) -> Result<Vec<u8>, VerificationError> {
+    let digest_info = hash_algorithm.digest_info(message);
+
+    if encoded_message_length < digest_info.len() + MIN_PADDING_LENGTH + 3 {
+        return Err(VerificationError::ModulusTooShort);
+    }
+
+    // EM = 0x00 || 0x01 || PS || 0x00 || T, where PS consists of 0xff bytes
+    let padding_length = encoded_message_length - digest_info.len() - 3;
+
+    let mut encoded_message = Vec::with_capacity(encoded_message_length);
+    encoded_message.extend_from_slice(&[0x00, 0x01]);
+    encoded_message.resize(padding_length + 2, 0xff);
+    encoded_message.push(0x00);
+    encoded_message.extend_from_slice(&digest_info);
+
+    Ok(encoded_message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULUS: &str = "9b3c2a622c7082b480f88fad025b00114caecad52cd10c8c0b9fc08b94eee2a8\
+        10a648ea1eab90f5e5ca3234f1e14b361b51e02d7afff861376beff9dc6cd841\
+        66621ee8909e9025e74375b6661905989fc074f86c278c0eddd2a178a70d9d75\
+        394786c1c14ea8760f5fff1b759d64b9ed28eeb51ab74887503670fd72111679";
+
+    const SHA256_SIGNATURE: &str =
+        "14944ab0c3ae6bc02efb4c052dcf12bde4dee35c2850ff607504dae06685ae74\
+        109b5e33de801eaa5bbb9b9b7f5f18c970fa05269d48384b633912f415a1797c\
+        b03269fecb6e2ef28665985c1fb8e19ddd4921490cac91910e6258d9da78eb4f\
+        8be6a8b86a19d22c9a73cd0d58f10b2356fa369a440f6953663beea0f4e91728";
+
+    const SHA224_SIGNATURE: &str =
+        "98b1bde1bb2d14e9dbce9aa9ad449efa33b27665cd57408004e3fdcf94069147\
+        710e9aa60d5269dddd2e9912563b99717965c2b90531d6ff71eb8c3793272794\
+        0a89f9e57944032351a3da1c7f164b4621a135a6fbf5e95ec5324b80805ff746\
+        6e40aea266974b16df51b1be2878f0505d72525e2a87310807ee01c4f47feb94";
+
+    const MESSAGE: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn public_key() -> PublicKey {
+        PublicKey::from_be_bytes(&from_hex(MODULUS), &[0x01, 0x00, 0x01])
+    }
+
+    #[test]
+    fn verify_valid_signature() {
+        let key = public_key();
+        assert_eq!(key.len(), 128);
+
+        let signature = from_hex(SHA256_SIGNATURE);
+        assert_eq!(
+            verify_pkcs1_v15(&key, MESSAGE, &signature, DigestAlgorithm::Sha256),
+            Ok(())
+        );
+
+        let signature = from_hex(SHA224_SIGNATURE);
+        assert_eq!(
+            verify_pkcs1_v15(&key, MESSAGE, &signature, DigestAlgorithm::Sha224),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn reject_invalid_signature() {
+        let key = public_key();
+        let signature = from_hex(SHA256_SIGNATURE);
+
+        assert_eq!(
+            verify_pkcs1_v15(
+                &key,
+                b"another message",
+                &signature,
+                DigestAlgorithm::Sha256
+            ),
+            Err(VerificationError::InvalidSignature)
+        );
+        assert_eq!(
+            verify_pkcs1_v15(&key, MESSAGE, &signature, DigestAlgorithm::Sha224),
+            Err(VerificationError::InvalidSignature)
+        );
+
+        let mut tampered = signature.clone();
+        tampered[64] ^= 1;
+        assert_eq!(
+            verify_pkcs1_v15(&key, MESSAGE, &tampered, DigestAlgorithm::Sha256),
+            Err(VerificationError::InvalidSignature)
+        );
+
+        assert_eq!(
+            verify_pkcs1_v15(&key, MESSAGE, &signature[1..], DigestAlgorithm::Sha256),
+            Err(VerificationError::InvalidSignatureLength)
+        );
+        assert_eq!(
+            verify_pkcs1_v15(&key, MESSAGE, &[0xff; 128], DigestAlgorithm::Sha256),
+            Err(VerificationError::SignatureOutOfRange)
+        );
+    }
+}
@@ -0,0 +1,429 @@
+//! X25519 key exchange ([RFC 7748](https://datatracker.ietf.org/doc/html/rfc7748))
+
+use std::{fs, io, io::Read, ops};
+
+use error_derive::Error;
+use hash::constant_time_eq;
+
+/// The length of X25519 scalars, coordinates and shared secrets in bytes
+pub const KEY_LENGTH: usize = 32;
+
+/// The u-coordinate of the base point
+const BASE_POINT: [u8; KEY_LENGTH] = {
+    let mut base_point = [0; KEY_LENGTH];
+    base_point[0] = 9;
+    base_point
+};
+
+/// `(486662 - 2) / 4`
+const A24: u64 = 121665;
+
+/// Each limb holds 51 bits
+const LIMB_MASK: u64 = (1 << 51) - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum KeyExchangeError {
+    /// The peer sent a point of small order
+    #[msg = "shared secret is zero"]
+    ZeroSharedSecret,
+}
+
+/// An element of the field `GF(2^255 - 19)`
+///
+/// The value is split into five 51-bit limbs, the least significant limb comes first.
+/// Limbs may temporarily exceed 51 bits, the representation is only unique after
+/// [FieldElement::to_bytes].
+#[derive(Clone, Copy, Debug)]
+struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    const ZERO: Self = Self([0; 5]);
+    const ONE: Self = Self([1, 0, 0, 0, 0]);
+
+    /// Decode a little-endian u-coordinate, ignoring the most significant bit
+    ///
+    /// <https://datatracker.ietf.org/doc/html/rfc7748#section-5>
+    fn from_bytes(bytes: &[u8; KEY_LENGTH]) -> Self {
+        let load = |offset: usize| {
+            u64::from_le_bytes(
+                bytes[offset..offset + 8]
+                    .try_into()
+                    .expect("slice is eight bytes long"),
+            )
+        };
+
+        Self([
+            load(0) & LIMB_MASK,
+            (load(6) >> 3) & LIMB_MASK,
+            (load(12) >> 6) & LIMB_MASK,
+            (load(19) >> 1) & LIMB_MASK,
+            (load(24) >> 12) & LIMB_MASK,
+        ])
+    }
+
+    /// Encode the fully reduced value as little-endian bytes
+    fn to_bytes(self) -> [u8; KEY_LENGTH] {
+        let mut limbs = self.carry().0;
+
+        // The value is now less than 2^255 + 2^52, so it is reduced by subtracting p at most once.
+        // Adding 19 carries into bit 255 exactly if the value is at least p.
+        let mut quotient = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            quotient = (limb + quotient) >> 51;
+        }
+
+        limbs[0] += 19 * quotient;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+        limbs[4] &= LIMB_MASK;
+
+        let mut bytes = [0; KEY_LENGTH];
+        let mut buffer: u128 = 0;
+        let mut buffered_bits = 0;
+        let mut position = 0;
+        for limb in limbs {
+            buffer |= u128::from(limb) << buffered_bits;
+            buffered_bits += 51;
+
+            while buffered_bits >= 8 {
+                bytes[position] = buffer as u8;
+                buffer >>= 8;
+                buffered_bits -= 8;
+                position += 1;
+            }
+        }
+        bytes[position] = buffer as u8;
+
+        bytes
+    }
+
+    /// Propagate the carries between the limbs, so that each limb is at most 52 bits wide
+    fn carry(self) -> Self {
+        let mut limbs = self.0;
+
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= LIMB_MASK;
+        }
+
+        // 2^255 = 19 (mod p)
+        limbs[0] += 19 * (limbs[4] >> 51);
+        limbs[4] &= LIMB_MASK;
+
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= LIMB_MASK;
+
+        Self(limbs)
+    }
+
+    fn square(self) -> Self {
+        self * self
+    }
+
+    fn multiply_small(self, factor: u64) -> Self {
+        let mut limbs = [0; 5];
+        let mut carry: u128 = 0;
+
+        for (result, limb) in limbs.iter_mut().zip(self.0) {
+            let product = u128::from(limb) * u128::from(factor) + carry;
+            *result = product as u64 & LIMB_MASK;
+            carry = product >> 51;
+        }
+
+        limbs[0] += carry as u64 * 19;
+        Self(limbs).carry()
+    }
+
+    /// Compute the multiplicative inverse using Fermat's little theorem, `x^(p - 2)`
+    fn invert(self) -> Self {
+        // p - 2 = 2^255 - 21, in little-endian bytes
+        let mut exponent = [0xff; KEY_LENGTH];
+        exponent[0] = 0xeb;
+        exponent[31] = 0x7f;
+
+        let mut result = Self::ONE;
+        for bit in (0..255).rev() {
+            result = result.square();
+
+            if (exponent[bit / 8] >> (bit % 8)) & 1 == 1 {
+                result = result * self;
+            }
+        }
+
+        result
+    }
+
+    /// Swap `a` and `b` if `swap` is `1`, without branching on `swap`
+    fn conditional_swap(a: &mut Self, b: &mut Self, swap: u64) {
+        let mask = 0_u64.wrapping_sub(swap);
+
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let difference = mask & (*x ^ *y);
+            *x ^= difference;
+            *y ^= difference;
+        }
+    }
+}
+
+impl ops::Add for FieldElement {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        let mut limbs = self.0;
+        for (limb, other) in limbs.iter_mut().zip(other.0) {
+            *limb += other;
+        }
+
+        Self(limbs).carry()
+    }
+}
+
+impl ops::Sub for FieldElement {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        // Add 4p first, so none of the limbs underflow
+        const FOUR_P: [u64; 5] = [
+            0x1fffffffffffb4,
+            0x1ffffffffffffc,
+            0x1ffffffffffffc,
+            0x1ffffffffffffc,
+            0x1ffffffffffffc,
+        ];
+
+        let mut limbs = self.0;
+        for ((limb, other), four_p) in limbs.iter_mut().zip(other.0).zip(FOUR_P) {
+            *limb = *limb + four_p - other;
+        }
+
+        Self(limbs).carry()
+    }
+}
+
+impl ops::Mul for FieldElement {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let a = self.0.map(u128::from);
+        let b = other.0.map(u128::from);
+
+        // Products that overflow 2^255 wrap around multiplied with 19
+        let b1 = b[1] * 19;
+        let b2 = b[2] * 19;
+        let b3 = b[3] * 19;
+        let b4 = b[4] * 19;
+
+        let mut products = [
+            a[0] * b[0] + a[1] * b4 + a[2] * b3 + a[3] * b2 + a[4] * b1,
+            a[0] * b[1] + a[1] * b[0] + a[2] * b4 + a[3] * b3 + a[4] * b2,
+            a[0] * b[2] + a[1] * b[1] + a[2] * b[0] + a[3] * b4 + a[4] * b3,
+            a[0] * b[3] + a[1] * b[2] + a[2] * b[1] + a[3] * b[0] + a[4] * b4,
+            a[0] * b[4] + a[1] * b[3] + a[2] * b[2] + a[3] * b[1] + a[4] * b[0],
+        ];
+
+        for i in 0..4 {
+            products[i + 1] += products[i] >> 51;
+            products[i] &= u128::from(LIMB_MASK);
+        }
+
+        products[0] += (products[4] >> 51) * 19;
+        products[4] &= u128::from(LIMB_MASK);
+
+        Self(products.map(|product| product as u64)).carry()
+    }
+}
+
+/// Multiply the point with u-coordinate `u` by `scalar`, using the montgomery ladder
+///
+/// The scalar is clamped before use.
+///
+/// <https://datatracker.ietf.org/doc/html/rfc7748#section-5>
+#[must_use]
+pub fn x25519(scalar: [u8; KEY_LENGTH], u: [u8; KEY_LENGTH]) -> [u8; KEY_LENGTH] {
+    let scalar = clamp(scalar);
+
+    let x_1 = FieldElement::from_bytes(&u);
+    let mut x_2 = FieldElement::ONE;
+    let mut z_2 = FieldElement::ZERO;
+    let mut x_3 = x_1;
+    let mut z_3 = FieldElement::ONE;
+    let mut swap = 0;
+
+    for t in (0..255).rev() {
+        let k_t = u64::from((scalar[t / 8] >> (t % 8)) & 1);
+        swap ^= k_t;
+        FieldElement::conditional_swap(&mut x_2, &mut x_3, swap);
+        FieldElement::conditional_swap(&mut z_2, &mut z_3, swap);
+        swap = k_t;
+
+        let a = x_2 + z_2;
+        let aa = a.square();
+        let b = x_2 - z_2;
+        let bb = b.square();
+        let e = aa - bb;
+        let c = x_3 + z_3;
+        let d = x_3 - z_3;
+        let da = d * a;
+        let cb = c * b;
+
+        x_3 = (da + cb).square();
+        z_3 = x_1 * (da - cb).square();
+        x_2 = aa * bb;
+        z_2 = e * (aa + e.multiply_small(A24));
+    }
+
+    FieldElement::conditional_swap(&mut x_2, &mut x_3, swap);
+    FieldElement::conditional_swap(&mut z_2, &mut z_3, swap);
+
+    (x_2 * z_2.invert()).to_bytes()
+}
+
+/// <https://datatracker.ietf.org/doc/html/rfc7748#section-5>
+fn clamp(mut scalar: [u8; KEY_LENGTH]) -> [u8; KEY_LENGTH] {
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    scalar
+}
+
+/// Generate a random private key and the matching public key
+///
+/// Returns a tuple of `(private_key, public_key)`.
+///
+/// The private key is read from the operating system's cryptographically secure
+/// random number generator. [sl_std::rand::RNG] must not be used here, since
+/// its output can be predicted from very little state.
+pub fn generate_keypair() -> io::Result<([u8; KEY_LENGTH], [u8; KEY_LENGTH])> {
+    let mut private_key = [0; KEY_LENGTH];
+    fs::File::open("/dev/urandom")?.read_exact(&mut private_key)?;
+
+    let public_key = x25519(private_key, BASE_POINT);
+    Ok((private_key, public_key))
+}
+
+/// Compute the secret that is shared with the owner of `peer_public_key`
+///
+/// <https://datatracker.ietf.org/doc/html/rfc7748#section-6.1>
+pub fn diffie_hellman(
+    private_key: &[u8; KEY_LENGTH],
+    peer_public_key: &[u8; KEY_LENGTH],
+) -> Result<[u8; KEY_LENGTH], KeyExchangeError> {
+    let shared_secret = x25519(*private_key, *peer_public_key);
+
+    // Points of small order always produce an all-zero secret, which must be rejected
+    if constant_time_eq(&shared_secret, &[0; KEY_LENGTH]) {
+        return Err(KeyExchangeError::ZeroSharedSecret);
+    }
+
+    Ok(shared_secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> [u8; KEY_LENGTH] {
+        let mut bytes = [0; KEY_LENGTH];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        bytes
+    }
+
+    /// <https://datatracker.ietf.org/doc/html/rfc7748#section-5.2>
+    #[test]
+    fn scalar_multiplication() {
+        assert_eq!(
+            x25519(
+                from_hex("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"),
+                from_hex("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c")
+            ),
+            from_hex("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")
+        );
+
+        // The most significant bit of the u-coordinate is ignored
+        assert_eq!(
+            x25519(
+                from_hex("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"),
+                from_hex("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493")
+            ),
+            from_hex("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957")
+        );
+    }
+
+    /// <https://datatracker.ietf.org/doc/html/rfc7748#section-5.2>
+    #[test]
+    fn iterated_scalar_multiplication() {
+        let mut k = BASE_POINT;
+        let mut u = BASE_POINT;
+
+        let mut iterate = |n| {
+            for _ in 0..n {
+                let result = x25519(k, u);
+                u = k;
+                k = result;
+            }
+            k
+        };
+
+        assert_eq!(
+            iterate(1),
+            from_hex("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+        );
+        assert_eq!(
+            iterate(999),
+            from_hex("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51")
+        );
+    }
+
+    /// <https://datatracker.ietf.org/doc/html/rfc7748#section-6.1>
+    #[test]
+    fn key_exchange() {
+        let alice_private =
+            from_hex("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let alice_public =
+            from_hex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a");
+        let bob_private =
+            from_hex("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let bob_public =
+            from_hex("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+        let shared_secret =
+            from_hex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+
+        assert_eq!(x25519(alice_private, BASE_POINT), alice_public);
+        assert_eq!(x25519(bob_private, BASE_POINT), bob_public);
+
+        assert_eq!(
+            diffie_hellman(&alice_private, &bob_public),
+            Ok(shared_secret)
+        );
+        assert_eq!(
+            diffie_hellman(&bob_private, &alice_public),
+            Ok(shared_secret)
+        );
+    }
+
+    #[test]
+    fn generated_keys_agree() {
+        let (alice_private, alice_public) = generate_keypair().unwrap();
+        let (bob_private, bob_public) = generate_keypair().unwrap();
+
+        assert_eq!(
+            diffie_hellman(&alice_private, &bob_public),
+            diffie_hellman(&bob_private, &alice_public)
+        );
+    }
+
+    #[test]
+    fn reject_small_order_points() {
+        let (private_key, _) = generate_keypair().unwrap();
+
+        // The point with u = 0 has order 4
+        assert_eq!(
+            diffie_hellman(&private_key, &[0; KEY_LENGTH]),
+            Err(KeyExchangeError::ZeroSharedSecret)
+        );
+    }
+}
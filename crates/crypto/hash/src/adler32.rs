@@ -1,3 +1,5 @@
+use std::io;
+
 const BASE: u32 = 65521;
 
 pub fn adler32(bytes: &[u8]) -> u32 {
@@ -48,6 +50,11 @@ impl Adler32Hasher {
         self.1 %= BASE;
     }
 
+    /// Feed all the bytes from `reader` into the hasher, using [read_chunks](crate::read_chunks)
+    pub fn write_reader<R: io::Read>(&mut self, reader: R) -> io::Result<()> {
+        crate::read_chunks(reader, |chunk| self.write(chunk))
+    }
+
     pub fn finish(&self) -> u32 {
         self.1 << 16 | self.0
     }
@@ -64,4 +71,27 @@ mod tests {
         hasher.write(&text[..]);
         assert_eq!(hasher.finish(), 0x6d7fd7c8);
     }
+
+    #[test]
+    fn test_adler32_chunked() {
+        // Large enough to cross the 5552 byte reduction boundary multiple times
+        let data = crate::test_data(20_000);
+
+        let mut bytewise = Adler32Hasher::default();
+        for byte in &data {
+            bytewise.write(&[*byte]);
+        }
+
+        let mut chunked = Adler32Hasher::default();
+        for chunk in data.chunks(4099) {
+            chunked.write(chunk);
+        }
+
+        let mut from_reader = Adler32Hasher::default();
+        from_reader.write_reader(&data[..]).unwrap();
+
+        assert_eq!(bytewise.finish(), adler32(&data));
+        assert_eq!(chunked.finish(), adler32(&data));
+        assert_eq!(from_reader.finish(), adler32(&data));
+    }
 }
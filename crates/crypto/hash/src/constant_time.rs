@@ -0,0 +1,41 @@
+//! Comparisons that don't leak information about their inputs through timing
+
+use std::hint;
+
+/// Compare two byte slices in an amount of time that only depends on their length
+///
+/// This should be used whenever secret values like MACs or digests are compared,
+/// since `==` returns as soon as it encounters the first differing byte.
+///
+/// Slices with different lengths are never equal. Their lengths are not considered
+/// to be secret, so this returns early in that case.
+#[must_use]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let difference = a
+        .iter()
+        .zip(b)
+        .fold(0, |accumulator, (x, y)| accumulator | (x ^ y));
+
+    // Prevent the compiler from turning the loop back into an early return
+    hint::black_box(difference) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"secret", b"secret"));
+
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"Secret"));
+        assert!(!constant_time_eq(b"secret", b"secret!"));
+        assert!(!constant_time_eq(b"", b"a"));
+    }
+}
@@ -1,52 +1,97 @@
+//! HMAC ([RFC 2104](https://www.rfc-editor.org/rfc/rfc2104)) implementation
+
 use std::cmp::Ordering;
 
-use crate::CryptographicHashAlgorithm;
+use crate::{constant_time_eq, HashAlgorithm};
+
+/// Keyed-Hash Message Authentication Code over an arbitrary [HashAlgorithm]
+///
+/// Note that while this works with any hash algorithm, HMAC only provides
+/// any security guarantees when used with a [CryptographicHashAlgorithm](crate::CryptographicHashAlgorithm).
+#[derive(Clone, Copy, Debug)]
+pub struct Hmac<H: HashAlgorithm> {
+    /// Hasher that has already consumed `K0 ^ ipad`
+    inner: H,
+
+    /// Hasher that has already consumed `K0 ^ opad`
+    outer: H,
+}
 
-pub(crate) fn hmac<T: CryptographicHashAlgorithm>(
-    key: &[u8],
-    data: &[u8],
-) -> [u8; T::BLOCK_SIZE_OUT]
+impl<H: HashAlgorithm> Hmac<H>
 // NOTE: This is a meaningless trait bound, necessary to work around quirks
 //       in the incomplete generic_const_exprs feature.
 //       If you see this, try removing it to see if its still necessary (:
 //       (tested on rustc 1.76.0-nightly (3f28fe133 2023-12-18))
 where
-    [(); T::BLOCK_SIZE_IN]: Sized,
+    [(); H::BLOCK_SIZE_IN]: Sized,
+    [(); H::BLOCK_SIZE_OUT]: Sized,
+{
+    #[must_use]
+    pub fn new(key: &[u8]) -> Self {
+        let k0: [u8; H::BLOCK_SIZE_IN] = match key.len().cmp(&H::BLOCK_SIZE_IN) {
+            Ordering::Less => {
+                // Pad key with zeros on the right
+                let mut padded_key = [0; H::BLOCK_SIZE_IN];
+                padded_key[..key.len()].copy_from_slice(key);
+                padded_key
+            },
+            Ordering::Equal => key.try_into().expect("key size is equal to block size"),
+            Ordering::Greater => {
+                // Hash the key and pad the result with zeros on the right to the required size
+                let mut padded_key = [0; H::BLOCK_SIZE_IN];
+                padded_key[..H::BLOCK_SIZE_OUT].copy_from_slice(&H::hash(key));
+                padded_key
+            },
+        };
+
+        let mut inner = H::default();
+        inner.update(&k0.map(|e| e ^ 0x36));
+
+        let mut outer = H::default();
+        outer.update(&k0.map(|e| e ^ 0x5c));
+
+        Self { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    #[must_use]
+    pub fn finish(self) -> [u8; H::BLOCK_SIZE_OUT] {
+        let Self {
+            inner,
+            outer: mut hasher,
+        } = self;
+
+        hasher.update(&inner.finish());
+        hasher.finish()
+    }
+
+    /// Check whether `tag` is the authentication code for the data that was consumed
+    ///
+    /// The comparison is done in constant time.
+    #[must_use]
+    pub fn verify(self, tag: &[u8]) -> bool {
+        constant_time_eq(&self.finish(), tag)
+    }
+}
+
+pub(crate) fn hmac<H: HashAlgorithm>(key: &[u8], data: &[u8]) -> [u8; H::BLOCK_SIZE_OUT]
+// Refer to the comment on Hmac on why this trait bound is necessary
+where
+    [(); H::BLOCK_SIZE_IN]: Sized,
+    [(); H::BLOCK_SIZE_OUT]: Sized,
 {
-    let k0: [u8; T::BLOCK_SIZE_IN] = match key.len().cmp(&T::BLOCK_SIZE_IN) {
-        Ordering::Less => {
-            // Pad key with zeros on the right
-            let mut padded_key = [0; T::BLOCK_SIZE_IN];
-            padded_key[..key.len()].copy_from_slice(key);
-            padded_key
-        },
-        Ordering::Equal => key.try_into().expect("key size is equal to block size"),
-        Ordering::Greater => {
-            // Hash the key and pad the result with zeros on the right to the required size
-            let mut padded_key = [0; T::BLOCK_SIZE_IN];
-            padded_key[..T::BLOCK_SIZE_OUT].copy_from_slice(&T::hash(key));
-            padded_key
-        },
-    };
-
-    let k0_xor_opad = k0.map(|e| e ^ 0x5c);
-    let k0_xor_ipad = k0.map(|e| e ^ 0x36);
-
-    let mut hasher = T::default();
-    hasher.update(&k0_xor_ipad);
-    hasher.update(data);
-    let rhs = hasher.finish();
-
-    let mut hasher = T::default();
-    hasher.update(&k0_xor_opad);
-    hasher.update(&rhs);
-    hasher.finish()
+    let mut hmac = Hmac::<H>::new(key);
+    hmac.update(data);
+    hmac.finish()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Md5;
+    use crate::{Md5, Sha256};
 
     #[test]
     fn test_hmac() {
@@ -58,4 +103,76 @@ mod tests {
             ]
         );
     }
+
+    /// Test vectors from [RFC 4231](https://www.rfc-editor.org/rfc/rfc4231#section-4)
+    #[test]
+    fn test_hmac_sha256() {
+        // Test Case 1
+        assert_eq!(
+            hmac::<Sha256>(&[0x0b; 20], b"Hi There"),
+            [
+                0xb0, 0x34, 0x4c, 0x61, 0xd8, 0xdb, 0x38, 0x53, 0x5c, 0xa8, 0xaf, 0xce, 0xaf, 0x0b,
+                0xf1, 0x2b, 0x88, 0x1d, 0xc2, 0x00, 0xc9, 0x83, 0x3d, 0xa7, 0x26, 0xe9, 0x37, 0x6c,
+                0x2e, 0x32, 0xcf, 0xf7
+            ]
+        );
+
+        // Test Case 2
+        assert_eq!(
+            hmac::<Sha256>(b"Jefe", b"what do ya want for nothing?"),
+            [
+                0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+                0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+                0x64, 0xec, 0x38, 0x43
+            ]
+        );
+
+        // Test Case 3
+        assert_eq!(
+            hmac::<Sha256>(&[0xaa; 20], &[0xdd; 50]),
+            [
+                0x77, 0x3e, 0xa9, 0x1e, 0x36, 0x80, 0x0e, 0x46, 0x85, 0x4d, 0xb8, 0xeb, 0xd0, 0x91,
+                0x81, 0xa7, 0x29, 0x59, 0x09, 0x8b, 0x3e, 0xf8, 0xc1, 0x22, 0xd9, 0x63, 0x55, 0x14,
+                0xce, 0xd5, 0x65, 0xfe
+            ]
+        );
+
+        // Test Case 6 (Key is larger than the block size)
+        assert_eq!(
+            hmac::<Sha256>(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            [
+                0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, 0x0d, 0x8a, 0x26, 0xaa, 0xcb, 0xf5,
+                0xb7, 0x7f, 0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, 0x05, 0x46, 0x04, 0x0f,
+                0x0e, 0xe3, 0x7f, 0x54
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hmac_incremental() {
+        let mut hasher = Hmac::<Sha256>::new(b"Jefe");
+        hasher.update(b"what do ya want ");
+        hasher.update(b"for nothing?");
+
+        assert_eq!(
+            hasher.finish(),
+            hmac::<Sha256>(b"Jefe", b"what do ya want for nothing?")
+        );
+    }
+
+    #[test]
+    fn test_hmac_verify() {
+        let tag = hmac::<Sha256>(b"Jefe", b"what do ya want for nothing?");
+
+        let mut hasher = Hmac::<Sha256>::new(b"Jefe");
+        hasher.update(b"what do ya want for nothing?");
+        assert!(hasher.verify(&tag));
+
+        let mut hasher = Hmac::<Sha256>::new(b"Jeff");
+        hasher.update(b"what do ya want for nothing?");
+        assert!(!hasher.verify(&tag));
+    }
 }
@@ -1,16 +1,50 @@
 #![allow(incomplete_features)]
 #![feature(cfg_match, generic_const_exprs)]
 
+use std::io;
+
 mod adler32;
+mod constant_time;
 mod crc32;
 mod hmac;
 mod md5;
+mod pbkdf2;
 mod sha;
+mod sha1;
 
 pub use adler32::{adler32, Adler32Hasher};
+pub use constant_time::constant_time_eq;
+pub use crc32::{crc32, crc32c, Crc32Hasher, Crc32cHasher};
+pub use hmac::Hmac;
 pub use md5::Md5;
+pub use pbkdf2::pbkdf2;
 pub use sha::{Sha224, Sha256};
-pub use {crc32::crc32, crc32::Crc32Hasher};
+pub use sha1::Sha1;
+
+/// Size of the buffer used when hashing data from a [Read](io::Read) source
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// Read `reader` to the end, passing each chunk of data to `consume`
+///
+/// The data is read in chunks of [READ_BUFFER_SIZE] bytes, so the input never needs
+/// to be kept in memory all at once.
+fn read_chunks<R: io::Read>(mut reader: R, mut consume: impl FnMut(&[u8])) -> io::Result<()> {
+    let mut buffer = [0; READ_BUFFER_SIZE];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(n) => consume(&buffer[..n]),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {},
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Generate `length` bytes of input for tests that hash more than a few blocks of data
+#[cfg(test)]
+fn test_data(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i * 31 % 251) as u8).collect()
+}
 
 /// Something that is able to calculate a checksum over arbitrary bytes.
 ///
@@ -28,6 +62,13 @@ pub trait HashAlgorithm: Default {
         hasher.update(data);
         hasher.finish()
     }
+
+    /// Hash all the data produced by `reader`, using [read_chunks]
+    fn hash_reader<R: io::Read>(reader: R) -> io::Result<[u8; Self::BLOCK_SIZE_OUT]> {
+        let mut hasher = Self::default();
+        read_chunks(reader, |chunk| hasher.update(chunk))?;
+        Ok(hasher.finish())
+    }
 }
 
 /// An algorithm suitable as a cryptographic hash function
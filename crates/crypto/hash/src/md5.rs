@@ -315,4 +315,12 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn md5_hash_reader() {
+        let data = crate::test_data(10_000);
+
+        let hashed = Md5::hash_reader(std::io::Cursor::new(&data)).unwrap();
+        assert_eq!(hashed, Md5::hash(&data));
+    }
 }
@@ -0,0 +1,126 @@
+//! PBKDF2 ([RFC 8018](https://www.rfc-editor.org/rfc/rfc8018#section-5.2)) implementation
+
+use crate::{HashAlgorithm, Hmac};
+
+/// Derive a key of length `dk_len` from a password, using HMAC over `H` as the pseudorandom function
+///
+/// A higher number of `iterations` makes brute-forcing the password more expensive.
+///
+/// # Panics
+/// This function panics if `iterations` is zero or if `dk_len` exceeds `(2^32 - 1) * H::BLOCK_SIZE_OUT`.
+#[must_use]
+pub fn pbkdf2<H: HashAlgorithm + Clone>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    dk_len: usize,
+) -> Vec<u8>
+// Refer to the comment on Hmac on why this trait bound is necessary
+where
+    [(); H::BLOCK_SIZE_IN]: Sized,
+    [(); H::BLOCK_SIZE_OUT]: Sized,
+{
+    assert!(iterations != 0, "need at least one iteration");
+    assert!(
+        dk_len.div_ceil(H::BLOCK_SIZE_OUT) <= u32::MAX as usize,
+        "derived key too long"
+    );
+
+    // The password is the key for every HMAC computation, so we only need to process it once
+    let prf = Hmac::<H>::new(password);
+
+    let mut derived_key = vec![0; dk_len];
+    for (index, block) in derived_key.chunks_mut(H::BLOCK_SIZE_OUT).enumerate() {
+        // Block indices start at 1
+        let block_index = index as u32 + 1;
+
+        let mut hmac = prf.clone();
+        hmac.update(salt);
+        hmac.update(&block_index.to_be_bytes());
+        let mut u = hmac.finish();
+        let mut t = u;
+
+        for _ in 1..iterations {
+            let mut hmac = prf.clone();
+            hmac.update(&u);
+            u = hmac.finish();
+
+            for (t_byte, u_byte) in t.iter_mut().zip(u) {
+                *t_byte ^= u_byte;
+            }
+        }
+
+        // The last block might only be used partially
+        block.copy_from_slice(&t[..block.len()]);
+    }
+
+    derived_key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Sha1, Sha256};
+
+    /// Test vectors from [RFC 6070](https://www.rfc-editor.org/rfc/rfc6070#section-2)
+    #[test]
+    fn test_pbkdf2_hmac_sha1() {
+        assert_eq!(
+            pbkdf2::<Sha1>(b"password", b"salt", 1, 20),
+            [
+                0x0c, 0x60, 0xc8, 0x0f, 0x96, 0x1f, 0x0e, 0x71, 0xf3, 0xa9, 0xb5, 0x24, 0xaf, 0x60,
+                0x12, 0x06, 0x2f, 0xe0, 0x37, 0xa6
+            ]
+        );
+
+        assert_eq!(
+            pbkdf2::<Sha1>(b"password", b"salt", 2, 20),
+            [
+                0xea, 0x6c, 0x01, 0x4d, 0xc7, 0x2d, 0x6f, 0x8c, 0xcd, 0x1e, 0xd9, 0x2a, 0xce, 0x1d,
+                0x41, 0xf0, 0xd8, 0xde, 0x89, 0x57
+            ]
+        );
+
+        assert_eq!(
+            pbkdf2::<Sha1>(b"password", b"salt", 4096, 20),
+            [
+                0x4b, 0x00, 0x79, 0x01, 0xb7, 0x65, 0x48, 0x9a, 0xbe, 0xad, 0x49, 0xd9, 0x26, 0xf7,
+                0x21, 0xd0, 0x65, 0xa4, 0x29, 0xc1
+            ]
+        );
+
+        // Spans multiple blocks, the last one is truncated
+        assert_eq!(
+            pbkdf2::<Sha1>(
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                25
+            ),
+            [
+                0x3d, 0x2e, 0xec, 0x4f, 0xe4, 0x1c, 0x84, 0x9b, 0x80, 0xc8, 0xd8, 0x36, 0x62, 0xc0,
+                0xe4, 0x4a, 0x8b, 0x29, 0x1a, 0x96, 0x4c, 0xf2, 0xf0, 0x70, 0x38
+            ]
+        );
+
+        assert_eq!(
+            pbkdf2::<Sha1>(b"pass\0word", b"sa\0lt", 4096, 16),
+            [
+                0x56, 0xfa, 0x6a, 0xa7, 0x55, 0x48, 0x09, 0x9d, 0xcc, 0x37, 0xd7, 0xf0, 0x34, 0x25,
+                0xe0, 0xc3
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pbkdf2_hmac_sha256() {
+        assert_eq!(
+            pbkdf2::<Sha256>(b"password", b"salt", 1, 32),
+            [
+                0x12, 0x0f, 0xb6, 0xcf, 0xfc, 0xf8, 0xb3, 0x2c, 0x43, 0xe7, 0x22, 0x52, 0x56, 0xc4,
+                0xf8, 0x37, 0xa8, 0x65, 0x48, 0xc9, 0x2c, 0xcc, 0x35, 0x48, 0x08, 0x05, 0x98, 0x7c,
+                0xb7, 0x0b, 0xe1, 0x7b
+            ]
+        );
+    }
+}
@@ -239,7 +239,7 @@ impl Sha256 {
         self.state[6] = self.state[6].wrapping_add(g);
         self.state[7] = self.state[7].wrapping_add(h);
 
-        self.num_bytes_consumed += 4;
+        self.num_bytes_consumed += 64;
         self.buffer_ptr = 0;
     }
 }
@@ -287,4 +287,13 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_sha256_hash_reader() {
+        // Spans multiple read buffers and does not end on a block boundary
+        let data = crate::test_data(10_000);
+
+        let hashed = Sha256::hash_reader(std::io::Cursor::new(&data)).unwrap();
+        assert_eq!(hashed, Sha256::hash(&data));
+    }
 }
@@ -0,0 +1,210 @@
+//! SHA-1 ([RFC 3174](https://www.rfc-editor.org/rfc/rfc3174)) hash implementation
+//!
+//! # Security
+//! SHA-1 is **broken**: Practical collision attacks have been demonstrated
+//! (see <https://shattered.io>). It must not be used anywhere where collision
+//! resistance matters, like signatures or certificate validation.
+//!
+//! It is only provided because some protocols still require it, like the
+//! WebSocket opening handshake. For this reason, [Sha1] does not implement
+//! [CryptographicHashAlgorithm](crate::CryptographicHashAlgorithm).
+
+use crate::HashAlgorithm;
+
+const PADDING: [u8; 64] = [
+    0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+const SHA1_INITIAL: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+/// SHA-1 Hasher
+///
+/// **Do not use this where collision resistance matters**, refer to the
+/// [module documentation](self) for more information.
+#[derive(Clone, Copy, Debug)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: [u8; 64],
+    buffer_ptr: usize,
+    num_bytes_consumed: u64,
+}
+
+impl Default for Sha1 {
+    fn default() -> Self {
+        Self {
+            state: SHA1_INITIAL,
+            buffer: [0; 64],
+            buffer_ptr: 0,
+            num_bytes_consumed: 0,
+        }
+    }
+}
+
+impl HashAlgorithm for Sha1 {
+    const BLOCK_SIZE_IN: usize = 64;
+    const BLOCK_SIZE_OUT: usize = 20;
+
+    fn update(&mut self, data: &[u8]) {
+        let bytes_to_fill = 64 - self.buffer_ptr;
+        if data.len() < bytes_to_fill {
+            // Not enough to get a full chunk of 64 bytes, just update the buffer and call it a day
+            self.buffer[self.buffer_ptr..self.buffer_ptr + data.len()].copy_from_slice(data);
+            self.buffer_ptr += data.len();
+            return;
+        }
+
+        // At this point, we have at least enough bytes to fill the buffer once
+        self.buffer[self.buffer_ptr..].copy_from_slice(&data[..bytes_to_fill]);
+        self.step();
+
+        let chunks = data[bytes_to_fill..].chunks_exact(64);
+        let remaining_bytes = chunks.remainder();
+        for chunk in chunks {
+            self.buffer.copy_from_slice(chunk);
+            self.step();
+        }
+
+        // Copy the remaining bytes into the buffer, the next `update()`
+        // call will take care of them
+        self.buffer[..remaining_bytes.len()].copy_from_slice(remaining_bytes);
+        self.buffer_ptr = remaining_bytes.len();
+    }
+
+    fn finish(mut self) -> [u8; Self::BLOCK_SIZE_OUT] {
+        // Important to get the length (in bits) now *before* we consume any padding
+        let length: u64 = (self.num_bytes_consumed + self.buffer_ptr as u64) * 8;
+
+        let needed_bytes = 64 - self.buffer_ptr;
+        self.buffer[self.buffer_ptr..].copy_from_slice(&PADDING[..needed_bytes]);
+
+        // The padding must leave room for the 8 byte length at the end of the block,
+        // otherwise we need to process this block and pad another one
+        if needed_bytes <= 8 {
+            self.step();
+            self.buffer[..56].fill(0);
+        }
+
+        self.buffer[56..64].copy_from_slice(&length.to_be_bytes());
+
+        // At this point the buffer is completely filled, perform one final step
+        self.step();
+
+        let mut hash = [0; 20];
+        for (index, word) in self.state.iter().enumerate() {
+            hash[4 * index..][..4].copy_from_slice(&word.to_be_bytes());
+        }
+        hash
+    }
+}
+
+impl Sha1 {
+    fn step(&mut self) {
+        let mut w = [0; 80];
+        for (index, word_bytes) in self.buffer.chunks_exact(4).enumerate() {
+            w[index] = u32::from_be_bytes(word_bytes.try_into().unwrap());
+        }
+
+        for t in 16..80 {
+            w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = self.state;
+
+        for (t, word) in w.iter().enumerate() {
+            let (f, k) = match t {
+                0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(*word)
+                .wrapping_add(k);
+
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        self.state[0] = self.state[0].wrapping_add(a);
+        self.state[1] = self.state[1].wrapping_add(b);
+        self.state[2] = self.state[2].wrapping_add(c);
+        self.state[3] = self.state[3].wrapping_add(d);
+        self.state[4] = self.state[4].wrapping_add(e);
+
+        self.num_bytes_consumed += 64;
+        self.buffer_ptr = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vectors from [RFC 3174](https://www.rfc-editor.org/rfc/rfc3174#section-7.3)
+    #[test]
+    fn test_sha1() {
+        assert_eq!(
+            Sha1::hash(b""),
+            [
+                0xda, 0x39, 0xa3, 0xee, 0x5e, 0x6b, 0x4b, 0x0d, 0x32, 0x55, 0xbf, 0xef, 0x95, 0x60,
+                0x18, 0x90, 0xaf, 0xd8, 0x07, 0x09
+            ]
+        );
+
+        assert_eq!(
+            Sha1::hash(b"abc"),
+            [
+                0xa9, 0x99, 0x3e, 0x36, 0x47, 0x06, 0x81, 0x6a, 0xba, 0x3e, 0x25, 0x71, 0x78, 0x50,
+                0xc2, 0x6c, 0x9c, 0xd0, 0xd8, 0x9d
+            ]
+        );
+
+        assert_eq!(
+            Sha1::hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            [
+                0x84, 0x98, 0x3e, 0x44, 0x1c, 0x3b, 0xd2, 0x6e, 0xba, 0xae, 0x4a, 0xa1, 0xf9, 0x51,
+                0x29, 0xe5, 0xe5, 0x46, 0x70, 0xf1
+            ]
+        );
+
+        assert_eq!(
+            Sha1::hash(&vec![b'a'; 1_000_000]),
+            [
+                0x34, 0xaa, 0x97, 0x3c, 0xd4, 0xc4, 0xda, 0xa4, 0xf6, 0x1e, 0xeb, 0x2b, 0xdb, 0xad,
+                0x27, 0x31, 0x65, 0x34, 0x01, 0x6f
+            ]
+        );
+
+        assert_eq!(
+            Sha1::hash(
+                &b"0123456701234567012345670123456701234567012345670123456701234567".repeat(10)
+            ),
+            [
+                0xde, 0xa3, 0x56, 0xa2, 0xcd, 0xdd, 0x90, 0xc7, 0xa7, 0xec, 0xed, 0xc5, 0xeb, 0xb5,
+                0x63, 0x93, 0x4f, 0x46, 0x04, 0x52
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sha1_incremental() {
+        let mut hasher = Sha1::default();
+        hasher.update(b"abcdbcdecdefdefgefghfghighij");
+        hasher.update(b"hijkijkljklmklmnlmnomnopnopq");
+
+        assert_eq!(
+            hasher.finish(),
+            Sha1::hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")
+        );
+    }
+}
@@ -5,7 +5,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::{DNSError, Domain};
+use crate::{message::NegativeAnswer, DNSError, Domain};
 
 const MAX_CACHE_SIZE: usize = 1000;
 
@@ -35,47 +35,75 @@ impl Cache {
             .insert(domain, ip, ttl);
     }
 
+    /// Remove all entries whose TTL has expired.
+    ///
+    /// Expired entries are never returned from [Cache::get], so this is only
+    /// necessary to free up memory.
+    pub fn purge_expired(&self) {
+        self.cache
+            .lock()
+            .expect("DNS Cache lock was poisoned")
+            .compact();
+    }
+
     /// Try to get an entry from the cache.
     ///
-    /// If the entry is present but expired, it is deleted and `None` is returned.
+    /// If the entry is not present or expired, the domain is resolved
+    /// and the result is inserted into the cache. Domains that do not exist
+    /// are cached too, so repeated lookups fail without querying the network.
     pub fn get(&self, domain: &Domain) -> Result<IpAddr, DNSError> {
+        self.get_or_resolve_with(domain, Domain::resolve)
+    }
+
+    fn get_or_resolve_with<F>(&self, domain: &Domain, resolve: F) -> Result<IpAddr, DNSError>
+    where
+        F: FnOnce(&Domain) -> Result<(IpAddr, u32), DNSError>,
+    {
         let now = Instant::now();
         let mut locked_cache = self.cache.lock().expect("DNS Cache lock was poisoned");
         let cached_entry = locked_cache.0.get_mut(domain);
 
-        match cached_entry {
-            Some(entry) if now < entry.expires_at => {
-                // The entry is valid, great, nothing else to do
-                entry.last_accessed = now;
-                Ok(entry.ip)
-            },
-            _ => {
-                // Resolving a domain is recursive, so to prevent deadlocks we drop the lock here
-                drop(locked_cache);
-
-                // We need to resolve the domain and put it in the cache
-                let (ip, ttl) = domain.resolve()?;
-
-                // A ttl of 0 means we shouldn't cache the entry at all
-                if ttl == 0 {
-                    return Ok(ip);
-                }
+        if let Some(entry) = cached_entry.filter(|entry| now < entry.expires_at) {
+            // The entry is valid, great, nothing else to do
+            entry.last_accessed = now;
+            return entry.ip.ok_or_else(|| {
+                let remaining = entry.expires_at.duration_since(now).as_secs() as u32;
+                DNSError::NonExistentDomain(NegativeAnswer::new(remaining))
+            });
+        }
 
-                // It's safe to reacquire the lock now since we've done all lookups
-                let mut locked_cache = self.cache.lock().expect("DNS Cache lock was poisoned");
-                if locked_cache.len() >= MAX_CACHE_SIZE {
-                    locked_cache.compact();
+        // Resolving a domain is recursive, so to prevent deadlocks we drop the lock here
+        drop(locked_cache);
 
-                    if locked_cache.len() > MAX_CACHE_SIZE {
-                        let to_remove = locked_cache.len() - MAX_CACHE_SIZE;
-                        locked_cache.evict_n_least_recently_used(to_remove);
-                    }
+        // We need to resolve the domain and put it in the cache
+        let (ip, ttl) = match resolve(domain) {
+            Ok((ip, ttl)) => (Some(ip), ttl),
+            Err(DNSError::NonExistentDomain(negative_answer)) => {
+                (None, negative_answer.time_to_live())
+            },
+            Err(error) => return Err(error),
+        };
+
+        // A ttl of 0 means we shouldn't cache the entry at all
+        if ttl != 0 {
+            // It's safe to reacquire the lock now since we've done all lookups
+            let mut locked_cache = self.cache.lock().expect("DNS Cache lock was poisoned");
+            if locked_cache.len() >= MAX_CACHE_SIZE {
+                locked_cache.compact();
+
+                if locked_cache.len() > MAX_CACHE_SIZE {
+                    let to_remove = locked_cache.len() - MAX_CACHE_SIZE;
+                    locked_cache.evict_n_least_recently_used(to_remove);
                 }
+            }
 
-                locked_cache.insert(domain.clone(), ip, ttl);
-                Ok(ip)
-            },
+            match ip {
+                Some(ip) => locked_cache.insert(domain.clone(), ip, ttl),
+                None => locked_cache.insert_nonexistent(domain.clone(), ttl),
+            }
         }
+
+        ip.ok_or(DNSError::NonExistentDomain(NegativeAnswer::new(ttl)))
     }
 }
 
@@ -109,6 +137,15 @@ impl LockedCache {
             return;
         }
 
+        self.insert_entry(domain, Some(ip), ttl);
+    }
+
+    /// Remember that `domain` does not exist for the next `ttl` seconds
+    fn insert_nonexistent(&mut self, domain: Domain, ttl: u32) {
+        self.insert_entry(domain, None, ttl);
+    }
+
+    fn insert_entry(&mut self, domain: Domain, ip: Option<IpAddr>, ttl: u32) {
         self.0.insert(
             domain,
             CacheEntry {
@@ -130,5 +167,56 @@ pub struct CacheEntry {
     expires_at: Instant,
     last_accessed: Instant,
     // In the future we might want to differentiate between IPv4 and IPv6 here
-    ip: IpAddr,
+    /// `None` if the domain does not exist
+    ip: Option<IpAddr>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{cell::Cell, net::Ipv4Addr};
+
+    #[test]
+    fn purge_expired_entries() {
+        let cache = Cache::default();
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+
+        cache.insert(Domain::new("example.com"), ip, 3600);
+
+        // Entries with a TTL of zero are never inserted through the public api,
+        // so we have to bypass it here
+        cache
+            .cache
+            .lock()
+            .unwrap()
+            .insert(Domain::new("expired.com"), ip, 0);
+
+        assert_eq!(cache.cache.lock().unwrap().len(), 2);
+
+        cache.purge_expired();
+
+        let locked_cache = cache.cache.lock().unwrap();
+        assert_eq!(locked_cache.len(), 1);
+        assert!(locked_cache.0.contains_key(&Domain::new("example.com")));
+    }
+
+    #[test]
+    fn cache_nonexistent_domains() {
+        let cache = Cache::default();
+        let domain = Domain::new("nonexistent.example.com");
+        let queries = Cell::new(0);
+
+        let resolve = |_: &Domain| {
+            queries.set(queries.get() + 1);
+            Err(DNSError::NonExistentDomain(NegativeAnswer::new(300)))
+        };
+
+        for _ in 0..2 {
+            let result = cache.get_or_resolve_with(&domain, resolve);
+            assert!(matches!(result, Err(DNSError::NonExistentDomain(_))));
+        }
+
+        assert_eq!(queries.get(), 1);
+    }
 }
@@ -1,23 +1,44 @@
 use crate::{
-    message::Message,
+    message::{Message, Resource},
     reader::Reader,
-    resource_type::{ResourceRecord, ResourceRecordClass},
-    DNSError, DNS_CACHE, MAX_DATAGRAM_SIZE, MAX_RESOLUTION_STEPS, ROOT_SERVER, UDP_SOCKET,
+    resolver,
+    resource_type::{ResourceRecord, ResourceRecordType},
+    srv::{self, ServiceTarget},
+    DNSError, DNS_CACHE, MAX_RESOLUTION_STEPS,
 };
-use sl_std::{punycode::idna_encode, read::ReadExt};
+use sl_std::{punycode::idna_encode, rand::RNG, read::ReadExt};
 
-use std::{
-    fmt,
-    io::Read,
-    net::{IpAddr, UdpSocket},
-};
+use std::{fmt, io::Read, net::IpAddr};
 
 const DOMAIN_MAX_SEGMENTS: u8 = 10;
-const DNS_PORT: u16 = 53;
 
 #[derive(Clone, Hash, PartialEq, Eq)]
 pub struct Domain(Vec<String>);
 
+/// The kind of addresses to look up when resolving a domain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressFamily {
+    /// Only query `A` records
+    V4,
+
+    /// Only query `AAAA` records
+    V6,
+
+    /// Query both `A` and `AAAA` records
+    Both,
+}
+
+impl AddressFamily {
+    #[must_use]
+    fn record_types(&self) -> &'static [ResourceRecordType] {
+        match self {
+            Self::V4 => &[ResourceRecordType::A],
+            Self::V6 => &[ResourceRecordType::AAAA],
+            Self::Both => &[ResourceRecordType::A, ResourceRecordType::AAAA],
+        }
+    }
+}
+
 impl fmt::Debug for Domain {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0.join("."))
@@ -148,75 +169,327 @@ impl Domain {
     /// This function **does not** make use of a cache.
     /// You should prefer [lookup] instead.
     pub(crate) fn resolve(&self) -> Result<(IpAddr, u32), DNSError> {
-        let mut nameserver = ROOT_SERVER;
+        self.resolve_records(ResourceRecordType::A)?
+            .iter()
+            .find_map(|resource| {
+                resource
+                    .record
+                    .ip_address()
+                    .map(|ip| (ip, resource.time_to_live))
+            })
+            .ok_or(DNSError::CouldNotResolve)
+    }
+
+    /// Resolve all addresses of the given family by contacting the DNS server.
+    ///
+    /// When querying [AddressFamily::Both], the lookup only fails if neither
+    /// `A` nor `AAAA` records could be found.
+    ///
+    /// This function **does not** make use of a cache.
+    pub fn resolve_addresses(&self, family: AddressFamily) -> Result<Vec<IpAddr>, DNSError> {
+        let mut addresses = vec![];
+        let mut last_error = None;
+
+        for &record_type in family.record_types() {
+            match self.resolve_records(record_type) {
+                Ok(resources) => addresses.extend(
+                    resources
+                        .iter()
+                        .filter_map(|resource| resource.record.ip_address()),
+                ),
+                Err(error) => {
+                    log::debug!(
+                        "Failed to resolve {record_type:?} records for {self:?}: {error:?}"
+                    );
+                    last_error = Some(error);
+                },
+            }
+        }
+
+        if addresses.is_empty() {
+            return Err(last_error.unwrap_or(DNSError::CouldNotResolve));
+        }
+
+        Ok(addresses)
+    }
+
+    /// Resolve the mail exchanges responsible for this domain by contacting the DNS server.
+    ///
+    /// Returns a list of `(preference, mail exchange)` tuples, sorted by
+    /// preference. Mail exchanges with a lower preference value should be
+    /// tried first.
+    ///
+    /// This function **does not** make use of a cache.
+    pub fn resolve_mx(&self) -> Result<Vec<(u16, Domain)>, DNSError> {
+        let mut mail_exchanges: Vec<(u16, Domain)> = self
+            .resolve_records(ResourceRecordType::MX)?
+            .into_iter()
+            .filter_map(|resource| match resource.record {
+                ResourceRecord::MX {
+                    preference,
+                    exchange,
+                } => Some((preference, exchange)),
+                _ => None,
+            })
+            .collect();
+
+        mail_exchanges.sort_by_key(|(preference, _)| *preference);
+        Ok(mail_exchanges)
+    }
+
+    /// Resolve the hosts providing a service by contacting the DNS server.
+    ///
+    /// `self` should be the service name, like `_xmpp-server._tcp.example.com`.
+    /// The returned targets are ordered according to their priorities and weights,
+    /// and should be contacted in that order.
+    ///
+    /// If the domain explicitly states that the service is not available (using a
+    /// single record with target `.`), then the list is empty.
+    ///
+    /// This function **does not** make use of a cache.
+    pub fn resolve_srv(&self) -> Result<Vec<ServiceTarget>, DNSError> {
+        let targets: Vec<ServiceTarget> = self
+            .resolve_records(ResourceRecordType::SRV)?
+            .into_iter()
+            .filter_map(|resource| match resource.record {
+                ResourceRecord::SRV {
+                    priority,
+                    weight,
+                    port,
+                    target,
+                } => Some(ServiceTarget {
+                    priority,
+                    weight,
+                    port,
+                    target,
+                }),
+                _ => None,
+            })
+            .collect();
+
+        if let [target] = targets.as_slice() {
+            if target.target.is_root() {
+                return Ok(vec![]);
+            }
+        }
+
+        Ok(srv::order_targets(targets, &mut RNG::from_entropy()))
+    }
+
+    /// Resolve the text records of this domain by contacting the DNS server.
+    ///
+    /// The character-strings of each record are concatenated, so every element of the
+    /// returned list corresponds to one record. Text records may contain arbitrary bytes,
+    /// invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// This function **does not** make use of a cache.
+    pub fn resolve_txt(&self) -> Result<Vec<String>, DNSError> {
+        let texts = self
+            .resolve_records(ResourceRecordType::TXT)?
+            .into_iter()
+            .filter_map(|resource| match resource.record {
+                ResourceRecord::TXT { strings } => {
+                    Some(String::from_utf8_lossy(&strings.concat()).into_owned())
+                },
+                _ => None,
+            })
+            .collect();
+
+        Ok(texts)
+    }
+
+    /// Returns true if this is the root domain (`.`)
+    #[must_use]
+    pub(crate) fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Resolve all records of the given type by contacting the DNS server.
+    ///
+    /// If the domain is an alias for another domain, then the records of the canonical
+    /// domain are returned.
+    ///
+    /// This function **does not** make use of a cache.
+    pub(crate) fn resolve_records(
+        &self,
+        record_type: ResourceRecordType,
+    ) -> Result<Vec<Resource>, DNSError> {
+        let resolver = resolver::current_resolver();
+
+        self.resolve_records_with(record_type, |domain| resolver.query(domain, record_type))
+    }
+
+    /// Resolve all records of the given type, using `query` to obtain a message that either
+    /// answers the question for a domain or names its canonical domain
+    ///
+    /// `CNAME` records whose target is not part of the same message are followed by querying
+    /// the target, up to [MAX_RESOLUTION_STEPS] times.
+    fn resolve_records_with<F>(
+        &self,
+        record_type: ResourceRecordType,
+        mut query: F,
+    ) -> Result<Vec<Resource>, DNSError>
+    where
+        F: FnMut(&Domain) -> Result<Message, DNSError>,
+    {
+        let mut name = self.clone();
+
+        // All the domains we were redirected through so far
+        let mut chain = vec![];
 
-        // incrementally resolve segments
-        // www.ecosia.com will be resolved in the following order
-        // 1) com
-        // 2) ecosia.com
-        // 3) www.ecosia.com
         for _ in 0..MAX_RESOLUTION_STEPS {
-            let message = self.try_resolve_from(nameserver)?;
+            let message = query(&name)?;
 
-            // Check if the response contains our answer
-            if let Some((ip, ttl)) = message.get_answer(self) {
-                return Ok((ip, ttl));
+            if let Some(negative_answer) = message.negative_answer() {
+                return Err(DNSError::NonExistentDomain(negative_answer));
             }
 
-            // Insert any additional records provided by the server into our cache
-            message
-                .additional_records()
-                .iter()
-                .filter(|resource| resource.class == ResourceRecordClass::IN)
-                .for_each(|resource| {
-                    let referenced_ip = match resource.record {
-                        ResourceRecord::A { ipv4 } => Some(IpAddr::V4(ipv4)),
-                        ResourceRecord::AAAA { ipv6 } => Some(IpAddr::V6(ipv6)),
-                        _ => None,
-                    };
-
-                    if let Some(ip) = referenced_ip {
-                        DNS_CACHE.insert(resource.domain.clone(), ip, resource.time_to_live);
-                    }
-                });
-
-            // Check if the response contains the domain name of an authoritative nameserver
-            if let Some(ns_domain) = message.get_authority(self) {
-                // resolve that nameserver's domain and then
-                // continue trying to resolve from that ns
-                nameserver = DNS_CACHE.get(&ns_domain)?;
-            } else {
-                // We did not make any progress
+            let answers = message.get_answers(&name, record_type);
+            if !answers.is_empty() {
+                if !chain.is_empty() {
+                    log::debug!("Resolved {self:?} via the aliases {chain:?}");
+                }
+                return Ok(answers.into_iter().cloned().collect());
+            }
+
+            let Some(canonical_name) = message.canonical_name(&name) else {
+                return Err(DNSError::CouldNotResolve);
+            };
+
+            chain.push(name);
+            if chain.contains(&canonical_name) {
+                log::warn!("CNAME records for {self:?} form a loop: {chain:?}");
                 return Err(DNSError::CouldNotResolve);
             }
+
+            name = canonical_name;
         }
+
         Err(DNSError::MaxResolutionStepsExceeded)
     }
+}
 
-    fn try_resolve_from(&self, nameserver: IpAddr) -> Result<Message, DNSError> {
-        // Bind a UDP socket
-        let socket = UdpSocket::bind(UDP_SOCKET)?;
-        socket.connect((nameserver, DNS_PORT))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::Ipv4Addr;
+
+    /// Response to an `A` query for `www.example.com`, which is an alias for `example.com`
+    const CNAME_AND_A_RESPONSE: [u8; 63] = [
+        // Header
+        0x00, 0x00, 0x81, 0x80, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00,
+        // Question: www.example.com, type A, class IN
+        0x03, b'w', b'w', b'w', 0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o',
+        b'm', 0x00, 0x00, 0x01, 0x00, 0x01,
+        // Answer: pointer to www.example.com, type CNAME, class IN, TTL 300, pointer to example.com
+        0xc0, 0x0c, 0x00, 0x05, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2c, 0x00, 0x02, 0xc0, 0x10,
+        // Answer: pointer to example.com, type A, class IN, TTL 300, 4 bytes of data
+        0xc0, 0x10, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2c, 0x00, 0x04, 93, 184, 215, 14,
+    ];
+
+    /// Response to an `A` query for `www.example.com` which only contains the `CNAME` record
+    const CNAME_ONLY_RESPONSE: [u8; 47] = [
+        // Header
+        0x00, 0x00, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // Question: www.example.com, type A, class IN
+        0x03, b'w', b'w', b'w', 0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o',
+        b'm', 0x00, 0x00, 0x01, 0x00, 0x01,
+        // Answer: pointer to www.example.com, type CNAME, class IN, TTL 300, pointer to example.com
+        0xc0, 0x0c, 0x00, 0x05, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2c, 0x00, 0x02, 0xc0, 0x10,
+    ];
+
+    /// Response to an `A` query for `example.com`, claiming that it is an alias for `www.example.com`
+    const CNAME_LOOP_RESPONSE: [u8; 47] = [
+        // Header
+        0x00, 0x00, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // Question: example.com, type A, class IN
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0x00, 0x01,
+        0x00, 0x01,
+        // Answer: pointer to example.com, type CNAME, class IN, TTL 300, www + pointer to example.com
+        0xc0, 0x0c, 0x00, 0x05, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2c, 0x00, 0x06, 0x03, b'w', b'w',
+        b'w', 0xc0, 0x0c,
+    ];
+
+    fn parse(response: &[u8]) -> Message {
+        Message::read_from(&mut Reader::new(response)).expect("valid dns response")
+    }
 
-        // Send a DNS query
-        let message = Message::new(self);
-        let expected_id = message.id();
+    fn ip_addresses(resources: &[Resource]) -> Vec<IpAddr> {
+        resources
+            .iter()
+            .filter_map(|resource| resource.record.ip_address())
+            .collect()
+    }
 
-        let mut bytes = vec![0; message.size()];
-        message.write_to_buffer(&mut bytes);
-        socket.send(&bytes)?;
+    #[test]
+    fn cname_and_address_in_same_response() {
+        let message = parse(&CNAME_AND_A_RESPONSE);
+        let domain = Domain::new("www.example.com");
+        assert_eq!(
+            message.canonical_name(&domain),
+            Some(Domain::new("example.com"))
+        );
+
+        let mut queries = 0;
+        let resources = domain
+            .resolve_records_with(ResourceRecordType::A, |_| {
+                queries += 1;
+                Ok(parse(&CNAME_AND_A_RESPONSE))
+            })
+            .unwrap();
+
+        // The target of the alias is part of the response, so no second query is necessary
+        assert_eq!(queries, 1);
+        assert_eq!(
+            ip_addresses(&resources),
+            [IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14))]
+        );
+    }
 
-        // Read the DNS response
-        let mut response = [0; MAX_DATAGRAM_SIZE];
-        let response_length = socket.recv(&mut response)?;
+    #[test]
+    fn follow_cname_to_another_query() {
+        let domain = Domain::new("www.example.com");
+        let mut queried = vec![];
 
-        let mut reader = Reader::new(&response[..response_length]);
-        let parsed_message = Message::read_from(&mut reader)?;
+        let resources = domain
+            .resolve_records_with(ResourceRecordType::A, |name| {
+                queried.push(name.clone());
 
-        if parsed_message.id() != expected_id {
-            return Err(DNSError::UnexpectedID);
-        }
+                if *name == Domain::new("www.example.com") {
+                    Ok(parse(&CNAME_ONLY_RESPONSE))
+                } else {
+                    Ok(parse(&CNAME_AND_A_RESPONSE))
+                }
+            })
+            .unwrap();
+
+        assert_eq!(
+            queried,
+            [Domain::new("www.example.com"), Domain::new("example.com")]
+        );
+        assert_eq!(
+            ip_addresses(&resources),
+            [IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14))]
+        );
+    }
+
+    #[test]
+    fn cname_loop() {
+        let domain = Domain::new("www.example.com");
+        let mut queries = 0;
+
+        let result = domain.resolve_records_with(ResourceRecordType::A, |name| {
+            queries += 1;
+
+            if *name == Domain::new("www.example.com") {
+                Ok(parse(&CNAME_ONLY_RESPONSE))
+            } else {
+                Ok(parse(&CNAME_LOOP_RESPONSE))
+            }
+        });
 
-        Ok(parsed_message)
+        assert!(matches!(result, Err(DNSError::CouldNotResolve)));
+        assert_eq!(queries, 2);
     }
 }
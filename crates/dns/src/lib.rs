@@ -4,20 +4,25 @@ mod dns_cache;
 mod domain;
 pub mod message;
 mod reader;
+pub mod resolver;
 mod resource_type;
+mod srv;
 
 use crate::resource_type::{ResourceRecord, ResourceRecordClass};
 pub use dns_cache::DNS_CACHE;
-pub use domain::Domain;
+pub use domain::{AddressFamily, Domain};
 use error_derive::Error;
+pub use resource_type::ResourceRecordType;
+pub use srv::ServiceTarget;
 
 use std::{
     io,
-    net::{IpAddr, Ipv4Addr},
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
 };
 
 const MAX_DATAGRAM_SIZE: usize = 1024;
-const UDP_SOCKET: &str = "0.0.0.0:20000";
+const UDP_SOCKET: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 20000));
+const DNS_PORT: u16 = 53;
 const MAX_RESOLUTION_STEPS: usize = 5;
 
 /// The root server used to resolve domains.
@@ -32,6 +37,9 @@ pub enum DNSError {
     #[msg = "could not resolve"]
     CouldNotResolve,
 
+    #[msg = "domain does not exist"]
+    NonExistentDomain(message::NegativeAnswer),
+
     #[msg = "maximum number of resolution steps exceeded"]
     MaxResolutionStepsExceeded,
 
@@ -43,4 +51,10 @@ pub enum DNSError {
 
     #[msg = "domain too long"]
     DomainTooLong,
+
+    #[msg = "DNS over HTTPS request failed"]
+    Https(resolver::HttpsError),
+
+    #[msg = "DNS over HTTPS server responded with an error status"]
+    HttpStatus,
 }
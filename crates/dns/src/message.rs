@@ -2,8 +2,18 @@
 
 use sl_std::{rand::RNG, read::ReadExt};
 
-use crate::{domain::Domain, reader::Reader, DNSError, ResourceRecord, ResourceRecordClass};
-use std::{fmt, net::IpAddr, vec};
+use crate::{
+    domain::Domain, reader::Reader, resource_type::ResourceRecordType, DNSError, ResourceRecord,
+    ResourceRecordClass,
+};
+use std::{error, fmt, vec};
+
+/// The time a negative answer is cached for if the server did not include a `SOA` record
+const DEFAULT_NEGATIVE_TTL: u32 = 60;
+
+/// Negative answers are never cached for longer than this, as recommended by
+/// [RFC 2308](https://datatracker.ietf.org/doc/html/rfc2308#section-5)
+const MAX_NEGATIVE_TTL: u32 = 3 * 60 * 60;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum QueryType {
@@ -41,7 +51,7 @@ pub struct Header {
 #[derive(Clone, Debug)]
 pub struct Question {
     domain: Domain,
-    _query_type: QueryType,
+    record_type: ResourceRecordType,
     _query_class: (),
 }
 
@@ -49,11 +59,20 @@ pub struct Question {
 #[derive(Clone, Debug)]
 pub struct Resource {
     pub domain: Domain,
+    pub record_type: ResourceRecordType,
     pub record: ResourceRecord,
     pub class: ResourceRecordClass,
     pub time_to_live: u32,
 }
 
+/// A response stating that the queried domain does not exist
+///
+/// See [RFC 2308](https://datatracker.ietf.org/doc/html/rfc2308).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NegativeAnswer {
+    time_to_live: u32,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ResponseCode {
     /// No error condition
@@ -88,7 +107,7 @@ impl Header {
     #[must_use]
     pub fn new(num_questions: u16) -> Self {
         Self {
-            id: RNG::default().next_u16(),
+            id: RNG::from_entropy().next_u16(),
             flags: Flags::default(),
             num_questions,
             num_answers: 0x0000,
@@ -128,10 +147,10 @@ impl Header {
 
 impl Question {
     #[must_use]
-    pub fn new(domain: Domain) -> Self {
+    pub fn new(domain: Domain, record_type: ResourceRecordType) -> Self {
         Self {
             domain,
-            _query_type: QueryType::Standard,
+            record_type,
             _query_class: (),
         }
     }
@@ -147,7 +166,7 @@ impl Question {
 
         let mut ptr = encoded_domain.len();
 
-        bytes[ptr..ptr + 2].copy_from_slice(&1_u16.to_be_bytes());
+        bytes[ptr..ptr + 2].copy_from_slice(&u16::from(self.record_type).to_be_bytes());
         ptr += 2;
 
         bytes[ptr..ptr + 2].copy_from_slice(&1_u16.to_be_bytes());
@@ -158,13 +177,13 @@ impl Question {
     pub fn read_from(reader: &mut Reader<'_>) -> Result<Self, DNSError> {
         let domain = Domain::read_from(reader)?;
 
-        // FIXME: properly parse the type/class
-        let _query_type = reader.read_be_u16()?;
+        // FIXME: properly parse the class
+        let record_type = reader.read_be_u16()?.into();
         let _query_class = reader.read_be_u16()?;
 
         Ok(Self {
             domain,
-            _query_type: QueryType::Standard,
+            record_type,
             _query_class: (),
         })
     }
@@ -172,10 +191,10 @@ impl Question {
 
 impl Message {
     #[must_use]
-    pub fn new(domain: &Domain) -> Self {
+    pub fn new(domain: &Domain, record_type: ResourceRecordType) -> Self {
         Self {
             header: Header::new(1),
-            question: vec![Question::new(domain.clone())],
+            question: vec![Question::new(domain.clone(), record_type)],
             answer: vec![],
             authority: vec![],
             additional: vec![],
@@ -188,6 +207,26 @@ impl Message {
         self.header.id
     }
 
+    #[inline]
+    #[must_use]
+    pub fn with_id(mut self, id: u16) -> Self {
+        self.header.id = id;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn flags(&self) -> Flags {
+        self.header.flags
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_recursion_desired(mut self, recursion_desired: bool) -> Self {
+        self.header.flags = self.header.flags.set_recursion_desired(recursion_desired);
+        self
+    }
+
     #[must_use]
     pub fn size(&self) -> usize {
         16 + self.question.iter().map(|q| q.size()).sum::<usize>()
@@ -212,24 +251,64 @@ impl Message {
         ptr
     }
 
-    /// Returns a tuple `(resolved IP, TTL)`
-    pub fn get_answer(&self, domain: &Domain) -> Option<(IpAddr, u32)> {
-        for answer in self.answer.iter().chain(&self.additional) {
-            if answer.domain == *domain {
-                match answer.record {
-                    ResourceRecord::A { ipv4 } => {
-                        return Some((IpAddr::V4(ipv4), answer.time_to_live))
-                    },
-                    ResourceRecord::CNAME { ref alias } => {
-                        return self.get_answer(alias);
-                    },
-                    _ => {},
+    /// Returns all records of the given type that answer the question for `domain`
+    ///
+    /// `CNAME` records are followed, as long as the record they point to is also
+    /// contained in the message.
+    #[must_use]
+    pub fn get_answers(&self, domain: &Domain, record_type: ResourceRecordType) -> Vec<&Resource> {
+        let mut answers = vec![];
+
+        // Names that refer to the same domain, a CNAME loop must not make us loop forever
+        let mut names = vec![domain];
+        let mut index = 0;
+
+        while let Some(&name) = names.get(index) {
+            for answer in self.answer.iter().chain(&self.additional) {
+                if answer.domain != *name {
+                    continue;
+                }
+
+                if answer.record_type == record_type {
+                    answers.push(answer);
+                } else if let ResourceRecord::CNAME { alias } = &answer.record {
+                    if !names.contains(&alias) {
+                        names.push(alias);
+                    }
                 }
             }
+
+            index += 1;
         }
 
-        // Our question was not answered
-        None
+        answers
+    }
+
+    /// Follow the `CNAME` records for `domain` in this message and return the
+    /// last domain in the chain
+    ///
+    /// Returns `None` if the message contains no `CNAME` record for `domain`.
+    #[must_use]
+    pub fn canonical_name(&self, domain: &Domain) -> Option<Domain> {
+        let alias_of = |name: &Domain| {
+            self.answer
+                .iter()
+                .chain(&self.additional)
+                .find_map(|answer| match &answer.record {
+                    ResourceRecord::CNAME { alias } if answer.domain == *name => Some(alias),
+                    _ => None,
+                })
+        };
+
+        let mut chain = vec![domain];
+        while let Some(alias) = alias_of(chain[chain.len() - 1]) {
+            if chain.contains(&alias) {
+                break;
+            }
+            chain.push(alias);
+        }
+
+        (chain.len() > 1).then(|| chain[chain.len() - 1].clone())
     }
 
     pub fn get_authority(&self, _domain: &Domain) -> Option<Domain> {
@@ -243,6 +322,28 @@ impl Message {
         None
     }
 
+    /// Returns the negative answer contained in this message, if the server
+    /// told us that the domain does not exist
+    #[must_use]
+    pub fn negative_answer(&self) -> Option<NegativeAnswer> {
+        if self.flags().response_code() != ResponseCode::NameError {
+            return None;
+        }
+
+        // The negative answer may be cached for as long as both the SOA record
+        // and its minimum field allow
+        let time_to_live = self
+            .authority
+            .iter()
+            .find_map(|authority| match authority.record {
+                ResourceRecord::SOA { minimum, .. } => Some(authority.time_to_live.min(minimum)),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_NEGATIVE_TTL);
+
+        Some(NegativeAnswer::new(time_to_live))
+    }
+
     pub fn read_from(reader: &mut Reader<'_>) -> Result<Self, DNSError> {
         let header = Header::read_from(reader)?;
 
@@ -291,7 +392,7 @@ impl Resource {
         let length = reader.read_be_u16()? as u64;
 
         let position = reader.position();
-        let record = ResourceRecord::read_from(reader, rtype)?;
+        let record = ResourceRecord::read_from(reader, rtype, length)?;
         // Sometimes we don't read all the resource record data yet (because the specific
         // record is not supported for example)
         // In that case, we simply skip the data and move on
@@ -299,6 +400,7 @@ impl Resource {
 
         Ok(Self {
             domain,
+            record_type: rtype.into(),
             class,
             time_to_live: ttl,
             record,
@@ -468,3 +570,221 @@ impl fmt::Debug for Flags {
             .finish()
     }
 }
+
+impl NegativeAnswer {
+    #[must_use]
+    pub(crate) fn new(time_to_live: u32) -> Self {
+        Self {
+            time_to_live: time_to_live.min(MAX_NEGATIVE_TTL),
+        }
+    }
+
+    /// The number of seconds for which the domain should be considered nonexistent
+    #[must_use]
+    pub fn time_to_live(&self) -> u32 {
+        self.time_to_live
+    }
+}
+
+impl fmt::Display for NegativeAnswer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no such domain (valid for {}s)", self.time_to_live)
+    }
+}
+
+impl error::Error for NegativeAnswer {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::{IpAddr, Ipv6Addr};
+
+    /// Response to an `AAAA` query for `example.com`
+    const AAAA_RESPONSE: [u8; 57] = [
+        // Header
+        0x12, 0x34, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // Question: example.com, type AAAA, class IN
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0x00, 0x1c,
+        0x00, 0x01,
+        // Answer: pointer to example.com, type AAAA, class IN, TTL 21600, 16 bytes of data
+        0xc0, 0x0c, 0x00, 0x1c, 0x00, 0x01, 0x00, 0x00, 0x54, 0x60, 0x00, 0x10, 0x26, 0x06, 0x28,
+        0x00, 0x02, 0x20, 0x00, 0x01, 0x02, 0x48, 0x18, 0x93, 0x25, 0xc8, 0x19, 0x46,
+    ];
+
+    #[test]
+    fn parse_aaaa_response() {
+        let message =
+            Message::read_from(&mut Reader::new(&AAAA_RESPONSE)).expect("valid dns response");
+        assert_eq!(message.id(), 0x1234);
+
+        let domain = Domain::new("example.com");
+        let answers = message.get_answers(&domain, ResourceRecordType::AAAA);
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers[0].time_to_live, 21600);
+        assert_eq!(
+            answers[0].record.ip_address(),
+            Some(IpAddr::V6(Ipv6Addr::new(
+                0x2606, 0x2800, 0x220, 0x1, 0x248, 0x1893, 0x25c8, 0x1946
+            )))
+        );
+
+        assert!(message
+            .get_answers(&domain, ResourceRecordType::A)
+            .is_empty());
+    }
+
+    /// Response to an `MX` query for `gmail.com`, with the second exchange
+    /// using a compression pointer into the first one
+    const MX_RESPONSE: [u8; 87] = [
+        // Header
+        0xbe, 0xef, 0x81, 0x80, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00,
+        // Question: gmail.com, type MX, class IN
+        0x05, b'g', b'm', b'a', b'i', b'l', 0x03, b'c', b'o', b'm', 0x00, 0x00, 0x0f, 0x00, 0x01,
+        // Answer: pointer to gmail.com, type MX, class IN, TTL 3600, 32 bytes of data
+        0xc0, 0x0c, 0x00, 0x0f, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x20,
+        // Preference 20, alt1.gmail-smtp-in.l.google + pointer to com
+        0x00, 0x14, 0x04, b'a', b'l', b't', b'1', 0x0d, b'g', b'm', b'a', b'i', b'l', b'-', b's',
+        b'm', b't', b'p', b'-', b'i', b'n', 0x01, b'l', 0x06, b'g', b'o', b'o', b'g', b'l', b'e',
+        0xc0, 0x12,
+        // Answer: pointer to gmail.com, type MX, class IN, TTL 3600, 4 bytes of data
+        0xc0, 0x0c, 0x00, 0x0f, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x04,
+        // Preference 5, pointer to gmail-smtp-in.l.google.com
+        0x00, 0x05, 0xc0, 0x2e,
+    ];
+
+    #[test]
+    fn parse_mx_response() {
+        let message =
+            Message::read_from(&mut Reader::new(&MX_RESPONSE)).expect("valid dns response");
+
+        let domain = Domain::new("gmail.com");
+        let answers = message.get_answers(&domain, ResourceRecordType::MX);
+        assert_eq!(answers.len(), 2);
+
+        assert_eq!(
+            answers[0].record,
+            ResourceRecord::MX {
+                preference: 20,
+                exchange: Domain::new("alt1.gmail-smtp-in.l.google.com"),
+            }
+        );
+        assert_eq!(
+            answers[1].record,
+            ResourceRecord::MX {
+                preference: 5,
+                exchange: Domain::new("gmail-smtp-in.l.google.com"),
+            }
+        );
+    }
+
+    /// Response to a `TXT` query for `example.com`, with a record that is split
+    /// into two character-strings
+    const TXT_RESPONSE: [u8; 54] = [
+        // Header
+        0xbe, 0xef, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // Question: example.com, type TXT, class IN
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0x00, 0x10,
+        0x00, 0x01,
+        // Answer: pointer to example.com, type TXT, class IN, TTL 3600, 13 bytes of data
+        0xc0, 0x0c, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x0d,
+        // "v=spf1 " followed by "-all"
+        0x07, b'v', b'=', b's', b'p', b'f', b'1', b' ', 0x04, b'-', b'a', b'l', b'l',
+    ];
+
+    #[test]
+    fn parse_txt_response() {
+        let message =
+            Message::read_from(&mut Reader::new(&TXT_RESPONSE)).expect("valid dns response");
+
+        let domain = Domain::new("example.com");
+        let answers = message.get_answers(&domain, ResourceRecordType::TXT);
+        assert_eq!(answers.len(), 1);
+        assert_eq!(
+            answers[0].record,
+            ResourceRecord::TXT {
+                strings: vec![b"v=spf1 ".to_vec(), b"-all".to_vec()],
+            }
+        );
+    }
+
+    /// Response to an `SRV` query for `_xmpp-server._tcp.example.com`, with both
+    /// targets using a compression pointer to `example.com`
+    const SRV_RESPONSE: [u8; 99] = [
+        // Header
+        0xbe, 0xef, 0x81, 0x80, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00,
+        // Question: _xmpp-server._tcp.example.com, type SRV, class IN
+        0x0c, b'_', b'x', b'm', b'p', b'p', b'-', b's', b'e', b'r', b'v', b'e', b'r', 0x04, b'_',
+        b't', b'c', b'p', 0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm',
+        0x00, 0x00, 0x21, 0x00, 0x01,
+        // Answer: pointer to the question, type SRV, class IN, TTL 3600, 13 bytes of data
+        0xc0, 0x0c, 0x00, 0x21, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x0d,
+        // Priority 10, weight 60, port 5269, xmpp + pointer to example.com
+        0x00, 0x0a, 0x00, 0x3c, 0x14, 0x95, 0x04, b'x', b'm', b'p', b'p', 0xc0, 0x1e,
+        // Answer: pointer to the question, type SRV, class IN, TTL 3600, 15 bytes of data
+        0xc0, 0x0c, 0x00, 0x21, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x0f,
+        // Priority 20, weight 0, port 5269, backup + pointer to example.com
+        0x00, 0x14, 0x00, 0x00, 0x14, 0x95, 0x06, b'b', b'a', b'c', b'k', b'u', b'p', 0xc0, 0x1e,
+    ];
+
+    #[test]
+    fn parse_srv_response() {
+        let message =
+            Message::read_from(&mut Reader::new(&SRV_RESPONSE)).expect("valid dns response");
+
+        let domain = Domain::new("_xmpp-server._tcp.example.com");
+        let answers = message.get_answers(&domain, ResourceRecordType::SRV);
+        assert_eq!(answers.len(), 2);
+
+        assert_eq!(
+            answers[0].record,
+            ResourceRecord::SRV {
+                priority: 10,
+                weight: 60,
+                port: 5269,
+                target: Domain::new("xmpp.example.com"),
+            }
+        );
+        assert_eq!(
+            answers[1].record,
+            ResourceRecord::SRV {
+                priority: 20,
+                weight: 0,
+                port: 5269,
+                target: Domain::new("backup.example.com"),
+            }
+        );
+    }
+
+    /// `NXDOMAIN` response to an `A` query for `nonexistent.example.com`
+    const NXDOMAIN_RESPONSE: [u8; 91] = [
+        // Header, response code 3
+        0x12, 0x34, 0x81, 0x83, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // Question: nonexistent.example.com, type A, class IN
+        0x0b, b'n', b'o', b'n', b'e', b'x', b'i', b's', b't', b'e', b'n', b't', 0x07, b'e', b'x',
+        b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0x00, 0x01, 0x00, 0x01,
+        // Authority: pointer to example.com, type SOA, class IN, TTL 3600, 38 bytes of data
+        0xc0, 0x18, 0x00, 0x06, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x26,
+        // ns + pointer to example.com, hostmaster + pointer to example.com
+        0x02, b'n', b's', 0xc0, 0x18, 0x0a, b'h', b'o', b's', b't', b'm', b'a', b's', b't', b'e',
+        b'r', 0xc0, 0x18, // Serial, refresh, retry, expire, minimum 300
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x1c, 0x20, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x12, 0x75,
+        0x00, 0x00, 0x00, 0x01, 0x2c,
+    ];
+
+    #[test]
+    fn parse_nxdomain_response() {
+        let message =
+            Message::read_from(&mut Reader::new(&NXDOMAIN_RESPONSE)).expect("valid dns response");
+
+        assert_eq!(message.flags().response_code(), ResponseCode::NameError);
+
+        // The minimum field of the SOA record is smaller than its TTL
+        let negative_answer = message.negative_answer().expect("domain does not exist");
+        assert_eq!(negative_answer.time_to_live(), 300);
+
+        let existing =
+            Message::read_from(&mut Reader::new(&AAAA_RESPONSE)).expect("valid dns response");
+        assert!(existing.negative_answer().is_none());
+    }
+}
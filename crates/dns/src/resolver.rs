@@ -0,0 +1,550 @@
+//! Strategies for resolving domains
+//!
+//! By default, domains are resolved iteratively starting at a root server. Alternatively,
+//! queries can be sent to a recursive resolver over UDP or to a
+//! [DNS over HTTPS](https://datatracker.ietf.org/doc/html/rfc8484) server, which
+//! resolve them on our behalf.
+
+use std::{
+    error, fmt,
+    net::{SocketAddr, UdpSocket},
+    sync::{Arc, LazyLock, RwLock},
+    time::Duration,
+};
+
+use url::URL;
+
+use crate::{
+    message::{Message, ResponseCode},
+    reader::Reader,
+    DNSError, Domain, ResourceRecordClass, ResourceRecordType, DNS_CACHE, DNS_PORT,
+    MAX_DATAGRAM_SIZE, MAX_RESOLUTION_STEPS, ROOT_SERVER, UDP_SOCKET,
+};
+
+/// <https://datatracker.ietf.org/doc/html/rfc8484#section-6>
+pub const DNS_MESSAGE_MIME_TYPE: &str = "application/dns-message";
+
+/// How long to wait for a nameserver to respond before giving up on it
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+static RESOLVER: LazyLock<RwLock<Resolver>> = LazyLock::new(RwLock::default);
+
+/// Decides how [Domain::lookup] resolves domains that are not cached
+#[derive(Clone, Debug)]
+pub enum Resolver {
+    /// Send queries to nameservers over UDP
+    Udp(UdpResolver),
+
+    /// Send all queries to a DNS over HTTPS server
+    DnsOverHttps(DnsOverHttps),
+}
+
+/// Queries nameservers over UDP
+///
+/// The default configuration resolves domains iteratively, starting at a root server.
+#[derive(Clone, Debug)]
+pub struct UdpResolver {
+    /// The servers that queries are sent to, in order of preference
+    nameservers: Vec<SocketAddr>,
+
+    /// The local address that queries are sent from
+    bind_address: SocketAddr,
+
+    /// Whether the nameservers resolve domains on our behalf, instead of
+    /// referring us to other nameservers
+    is_recursive: bool,
+}
+
+/// Sends HTTPS requests on behalf of [DnsOverHttps]
+///
+/// This is implemented by the `http` crate, which itself depends on this crate.
+pub trait HttpsClient: Send + Sync {
+    /// Send a `POST` request with the given body to `url`
+    ///
+    /// Responses with an error status must be returned as [Ok], so that they can
+    /// be told apart from network errors.
+    fn post(
+        &self,
+        url: &URL,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<HttpsResponse, HttpsError>;
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpsResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Any error that prevented a DNS over HTTPS request from completing
+#[derive(Debug)]
+pub struct HttpsError(Box<dyn error::Error + Send + Sync>);
+
+/// A DNS over HTTPS server
+#[derive(Clone)]
+pub struct DnsOverHttps {
+    endpoint: URL,
+    client: Arc<dyn HttpsClient>,
+}
+
+/// Change the way domains are resolved from now on
+///
+/// Entries that are already cached are not affected.
+pub fn set_resolver(resolver: Resolver) {
+    *RESOLVER.write().expect("resolver lock was poisoned") = resolver;
+}
+
+#[must_use]
+pub(crate) fn current_resolver() -> Resolver {
+    RESOLVER.read().expect("resolver lock was poisoned").clone()
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::Udp(UdpResolver::default())
+    }
+}
+
+impl Resolver {
+    /// Obtain a message that either answers the question for `domain`, names
+    /// its canonical domain or states that it does not exist
+    pub(crate) fn query(
+        &self,
+        domain: &Domain,
+        record_type: ResourceRecordType,
+    ) -> Result<Message, DNSError> {
+        match self {
+            Self::Udp(resolver) => resolver.query(domain, record_type),
+            Self::DnsOverHttps(server) => server.query(domain, record_type),
+        }
+    }
+}
+
+impl Default for UdpResolver {
+    fn default() -> Self {
+        Self::iterative(vec![SocketAddr::new(ROOT_SERVER, DNS_PORT)])
+    }
+}
+
+impl UdpResolver {
+    /// Follow the chain of nameservers, starting at one of `root_servers`
+    #[must_use]
+    pub fn iterative(root_servers: Vec<SocketAddr>) -> Self {
+        Self {
+            nameservers: root_servers,
+            bind_address: UDP_SOCKET,
+            is_recursive: false,
+        }
+    }
+
+    /// Ask one of `nameservers` to resolve domains on our behalf
+    ///
+    /// This is how most resolvers configured by an operating system or a network
+    /// are meant to be used.
+    #[must_use]
+    pub fn recursive(nameservers: Vec<SocketAddr>) -> Self {
+        Self {
+            nameservers,
+            bind_address: UDP_SOCKET,
+            is_recursive: true,
+        }
+    }
+
+    /// Send queries from `bind_address`
+    ///
+    /// A port of `0` lets the operating system pick a free port for every query.
+    #[must_use]
+    pub fn with_bind_address(mut self, bind_address: SocketAddr) -> Self {
+        self.bind_address = bind_address;
+        self
+    }
+
+    #[must_use]
+    pub fn nameservers(&self) -> &[SocketAddr] {
+        &self.nameservers
+    }
+
+    #[must_use]
+    pub fn bind_address(&self) -> SocketAddr {
+        self.bind_address
+    }
+
+    #[must_use]
+    pub fn is_recursive(&self) -> bool {
+        self.is_recursive
+    }
+
+    /// Try all nameservers in order until one of them responds
+    pub(crate) fn query(
+        &self,
+        domain: &Domain,
+        record_type: ResourceRecordType,
+    ) -> Result<Message, DNSError> {
+        let mut last_error = DNSError::CouldNotResolve;
+
+        for &nameserver in &self.nameservers {
+            let result = if self.is_recursive {
+                self.query_nameserver(nameserver, domain, record_type)
+            } else {
+                self.query_iteratively(nameserver, domain, record_type)
+            };
+
+            match result {
+                Ok(message) => return Ok(message),
+                Err(error) => {
+                    log::debug!("Failed to query {nameserver} for {domain:?}: {error:?}");
+                    last_error = error;
+                },
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Query the chain of nameservers, starting at `root_server`, until one of them
+    /// answers the question for `domain`
+    fn query_iteratively(
+        &self,
+        root_server: SocketAddr,
+        domain: &Domain,
+        record_type: ResourceRecordType,
+    ) -> Result<Message, DNSError> {
+        let mut nameserver = root_server;
+
+        // incrementally resolve segments
+        // www.ecosia.com will be resolved in the following order
+        // 1) com
+        // 2) ecosia.com
+        // 3) www.ecosia.com
+        for _ in 0..MAX_RESOLUTION_STEPS {
+            let message = self.query_nameserver(nameserver, domain, record_type)?;
+
+            // Check if the response contains our answer, tells us that this domain is an alias
+            // or that it does not exist at all
+            if !message.get_answers(domain, record_type).is_empty()
+                || message.canonical_name(domain).is_some()
+                || message.negative_answer().is_some()
+            {
+                return Ok(message);
+            }
+
+            cache_additional_records(&message);
+
+            // Check if the response contains the domain name of an authoritative nameserver
+            if let Some(ns_domain) = message.get_authority(domain) {
+                // resolve that nameserver's domain and then
+                // continue trying to resolve from that ns
+                nameserver = SocketAddr::new(DNS_CACHE.get(&ns_domain)?, DNS_PORT);
+            } else {
+                // We did not make any progress
+                return Err(DNSError::CouldNotResolve);
+            }
+        }
+        Err(DNSError::MaxResolutionStepsExceeded)
+    }
+
+    fn query_nameserver(
+        &self,
+        nameserver: SocketAddr,
+        domain: &Domain,
+        record_type: ResourceRecordType,
+    ) -> Result<Message, DNSError> {
+        // Bind a UDP socket
+        let socket = UdpSocket::bind(self.bind_address)?;
+        socket.set_read_timeout(Some(QUERY_TIMEOUT))?;
+        socket.connect(nameserver)?;
+
+        // Send a DNS query
+        let message = Message::new(domain, record_type).with_recursion_desired(self.is_recursive);
+        let expected_id = message.id();
+
+        let mut bytes = vec![0; message.size()];
+        let length = message.write_to_buffer(&mut bytes);
+        socket.send(&bytes[..length])?;
+
+        // Read the DNS response
+        let mut response = [0; MAX_DATAGRAM_SIZE];
+        let response_length = socket.recv(&mut response)?;
+
+        let mut reader = Reader::new(&response[..response_length]);
+        let parsed_message = Message::read_from(&mut reader)?;
+
+        if parsed_message.id() != expected_id {
+            return Err(DNSError::UnexpectedID);
+        }
+
+        Ok(parsed_message)
+    }
+}
+
+impl HttpsError {
+    #[must_use]
+    pub fn new<E: Into<Box<dyn error::Error + Send + Sync>>>(error: E) -> Self {
+        Self(error.into())
+    }
+}
+
+impl fmt::Display for HttpsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl error::Error for HttpsError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(self.0.as_ref())
+    }
+}
+
+impl DnsOverHttps {
+    /// Use the server at `endpoint`, for example `https://cloudflare-dns.com/dns-query`
+    #[must_use]
+    pub fn new(endpoint: URL, client: Arc<dyn HttpsClient>) -> Self {
+        Self { endpoint, client }
+    }
+
+    #[must_use]
+    pub fn endpoint(&self) -> &URL {
+        &self.endpoint
+    }
+
+    /// Ask the server to resolve `domain` and return its response
+    ///
+    /// <https://datatracker.ietf.org/doc/html/rfc8484#section-4.1>
+    pub(crate) fn query(
+        &self,
+        domain: &Domain,
+        record_type: ResourceRecordType,
+    ) -> Result<Message, DNSError> {
+        // The server should resolve the domain for us. The id is zero to make the response cacheable.
+        let message = Message::new(domain, record_type)
+            .with_id(0)
+            .with_recursion_desired(true);
+
+        let mut bytes = vec![0; message.size()];
+        let length = message.write_to_buffer(&mut bytes);
+        bytes.truncate(length);
+
+        let response = self
+            .client
+            .post(&self.endpoint, DNS_MESSAGE_MIME_TYPE, bytes)?;
+
+        if !(200..300).contains(&response.status) {
+            log::warn!(
+                "DNS over HTTPS server {:?} responded with status {}",
+                self.endpoint,
+                response.status
+            );
+            return Err(DNSError::HttpStatus);
+        }
+
+        let has_correct_type = response.content_type.is_some_and(|content_type| {
+            content_type
+                .split(';')
+                .next()
+                .is_some_and(|essence| essence.trim().eq_ignore_ascii_case(DNS_MESSAGE_MIME_TYPE))
+        });
+        if !has_correct_type {
+            return Err(DNSError::InvalidResponse);
+        }
+
+        let message = Message::read_from(&mut Reader::new(&response.body))?;
+        cache_additional_records(&message);
+
+        match message.flags().response_code() {
+            // Nonexistent domains are cached, so they are not treated as an error here
+            ResponseCode::Ok | ResponseCode::NameError => Ok(message),
+            other => {
+                log::debug!("DNS over HTTPS server could not resolve {domain:?}: {other:?}");
+                Err(DNSError::CouldNotResolve)
+            },
+        }
+    }
+}
+
+/// Insert any additional records provided by a server into our cache
+fn cache_additional_records(message: &Message) {
+    message
+        .additional_records()
+        .iter()
+        .filter(|resource| resource.class == ResourceRecordClass::IN)
+        .for_each(|resource| {
+            if let Some(ip) = resource.record.ip_address() {
+                DNS_CACHE.insert(resource.domain.clone(), ip, resource.time_to_live);
+            }
+        });
+}
+
+impl fmt::Debug for DnsOverHttps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DnsOverHttps")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        sync::{mpsc, Mutex},
+        thread,
+    };
+
+    /// Response to an `A` query for `example.com`
+    const A_RESPONSE: [u8; 45] = [
+        // Header
+        0x00, 0x00, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        // Question: example.com, type A, class IN
+        0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0x00, 0x01,
+        0x00, 0x01,
+        // Answer: pointer to example.com, type A, class IN, TTL 300, 4 bytes of data
+        0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2c, 0x00, 0x04, 93, 184, 215, 14,
+    ];
+
+    /// Replies to every request with a fixed response and remembers the last request body
+    struct MockClient {
+        response: HttpsResponse,
+        last_request: Mutex<Option<(String, Vec<u8>)>>,
+    }
+
+    impl HttpsClient for MockClient {
+        fn post(
+            &self,
+            _url: &URL,
+            content_type: &str,
+            body: Vec<u8>,
+        ) -> Result<HttpsResponse, HttpsError> {
+            *self.last_request.lock().unwrap() = Some((content_type.to_string(), body));
+            Ok(self.response.clone())
+        }
+    }
+
+    fn mock_server(
+        status: u16,
+        content_type: &str,
+        body: &[u8],
+    ) -> (DnsOverHttps, Arc<MockClient>) {
+        let client = Arc::new(MockClient {
+            response: HttpsResponse {
+                status,
+                content_type: Some(content_type.to_string()),
+                body: body.to_vec(),
+            },
+            last_request: Mutex::default(),
+        });
+
+        let endpoint = "https://dns.example/dns-query".parse().unwrap();
+        (DnsOverHttps::new(endpoint, client.clone()), client)
+    }
+
+    #[test]
+    fn successful_query() {
+        let (resolver, client) = mock_server(200, DNS_MESSAGE_MIME_TYPE, &A_RESPONSE);
+        let domain = Domain::new("example.com");
+
+        let message = resolver.query(&domain, ResourceRecordType::A).unwrap();
+        let answers = message.get_answers(&domain, ResourceRecordType::A);
+        assert_eq!(answers.len(), 1);
+        assert_eq!(
+            answers[0].record.ip_address(),
+            Some(IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14)))
+        );
+
+        // The query is sent in wire format, with id zero and recursion desired
+        let (content_type, body) = client.last_request.lock().unwrap().take().unwrap();
+        assert_eq!(content_type, DNS_MESSAGE_MIME_TYPE);
+        assert_eq!(&body[..4], &[0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(&body[12..], &A_RESPONSE[12..29]);
+    }
+
+    #[test]
+    fn cache_additional_records_from_https() {
+        /// Like [A_RESPONSE], but with an additional `A` record for `doh.example.com`
+        const RESPONSE_WITH_ADDITIONAL: [u8; 65] = [
+            // Header
+            0x00, 0x00, 0x81, 0x80, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01,
+            // Question: example.com, type A, class IN
+            0x07, b'e', b'x', b'a', b'm', b'p', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0x00,
+            0x01, 0x00, 0x01,
+            // Answer: pointer to example.com, type A, class IN, TTL 300, 4 bytes of data
+            0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2c, 0x00, 0x04, 93, 184, 215,
+            14,
+            // Additional: doh.example.com, type A, class IN, TTL 300, 4 bytes of data
+            0x03, b'd', b'o', b'h', 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01, 0x2c,
+            0x00, 0x04, 10, 0, 0, 1,
+        ];
+
+        let (resolver, _) = mock_server(200, DNS_MESSAGE_MIME_TYPE, &RESPONSE_WITH_ADDITIONAL);
+        resolver
+            .query(&Domain::new("example.com"), ResourceRecordType::A)
+            .unwrap();
+
+        // The additional record is served from the cache, without querying any server
+        assert_eq!(
+            DNS_CACHE.get(&Domain::new("doh.example.com")).unwrap(),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))
+        );
+    }
+
+    #[test]
+    fn http_errors() {
+        let (resolver, _) = mock_server(503, "text/html", b"unavailable");
+        assert!(matches!(
+            resolver.query(&Domain::new("example.com"), ResourceRecordType::A),
+            Err(DNSError::HttpStatus)
+        ));
+
+        let (resolver, _) = mock_server(200, "text/html", &A_RESPONSE);
+        assert!(matches!(
+            resolver.query(&Domain::new("example.com"), ResourceRecordType::A),
+            Err(DNSError::InvalidResponse)
+        ));
+    }
+
+    /// Answers a single query with `response`, using the id of the query,
+    /// and sends the query it received to the returned channel
+    fn fake_responder(response: &'static [u8]) -> (SocketAddr, mpsc::Receiver<Vec<u8>>) {
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let address = socket.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut query = [0; MAX_DATAGRAM_SIZE];
+            let (length, client) = socket.recv_from(&mut query).unwrap();
+
+            let mut reply = response.to_vec();
+            reply[..2].copy_from_slice(&query[..2]);
+            socket.send_to(&reply, client).unwrap();
+
+            sender.send(query[..length].to_vec()).unwrap();
+        });
+
+        (address, receiver)
+    }
+
+    #[test]
+    fn udp_query() {
+        let (server, queries) = fake_responder(&A_RESPONSE);
+        let resolver = UdpResolver::recursive(vec![server])
+            .with_bind_address(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0));
+        let domain = Domain::new("example.com");
+
+        let message = resolver.query(&domain, ResourceRecordType::A).unwrap();
+        let answers = message.get_answers(&domain, ResourceRecordType::A);
+        assert_eq!(answers.len(), 1);
+        assert_eq!(
+            answers[0].record.ip_address(),
+            Some(IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14)))
+        );
+
+        // Recursive resolvers are asked to resolve the domain for us
+        let query = queries.recv().unwrap();
+        assert_eq!(&query[2..4], &[0x01, 0x00]);
+        assert_eq!(&query[12..], &A_RESPONSE[12..29]);
+    }
+}
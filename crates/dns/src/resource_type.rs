@@ -1,4 +1,7 @@
-use std::{io::Read, net};
+use std::{
+    io::Read,
+    net::{self, IpAddr},
+};
 
 use sl_std::read::ReadExt;
 
@@ -8,15 +11,21 @@ use crate::{domain::Domain, reader::Reader, DNSError};
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum ResourceRecord {
-    A { ipv4: net::Ipv4Addr },
-    AAAA { ipv6: net::Ipv6Addr },
+    A {
+        ipv4: net::Ipv4Addr,
+    },
+    AAAA {
+        ipv6: net::Ipv6Addr,
+    },
     AFSDB,
     APL,
     CAA,
     CDNSKEY,
     CDS,
     CERT,
-    CNAME { alias: Domain },
+    CNAME {
+        alias: Domain,
+    },
     CSYNC,
     DHCID,
     DLV,
@@ -32,9 +41,14 @@ pub enum ResourceRecord {
     KEY,
     KX,
     LOC,
-    MX,
+    MX {
+        preference: u16,
+        exchange: Domain,
+    },
     NAPTR,
-    NS { ns: Domain },
+    NS {
+        ns: Domain,
+    },
     NSEC,
     NSEC3,
     NSEC3PARAM,
@@ -44,22 +58,104 @@ pub enum ResourceRecord {
     RP,
     SIG,
     SMIMEA,
-    SOA { _ns: Domain, _mail: Domain },
-    SRV,
+    SOA {
+        _ns: Domain,
+        _mail: Domain,
+        _serial: u32,
+        _refresh: u32,
+        _retry: u32,
+        _expire: u32,
+        /// The TTL used for negative answers from this zone
+        minimum: u32,
+    },
+    SRV {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: Domain,
+    },
     SSHFP,
     SVCB,
     TA,
     TKEY,
     TLSA,
     TSIG,
-    TXT,
+    TXT {
+        /// The character-strings that make up the record, in the order in which they appear
+        ///
+        /// These are arbitrary bytes, not necessarily valid UTF-8.
+        strings: Vec<Vec<u8>>,
+    },
     URI,
     ZONEMD,
     UNKNOWN,
 }
 
+/// The type of a [ResourceRecord], as used in DNS questions
+///
+/// Only the types that we actually query are named explicitly, every other
+/// type is represented by its numeric value.
+///
+/// See <https://www.iana.org/assignments/dns-parameters/dns-parameters.xhtml#dns-parameters-4>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ResourceRecordType {
+    A,
+    NS,
+    CNAME,
+    SOA,
+    MX,
+    AAAA,
+    SRV,
+    TXT,
+    Other(u16),
+}
+
+impl From<u16> for ResourceRecordType {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => Self::A,
+            2 => Self::NS,
+            5 => Self::CNAME,
+            6 => Self::SOA,
+            15 => Self::MX,
+            16 => Self::TXT,
+            28 => Self::AAAA,
+            33 => Self::SRV,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl From<ResourceRecordType> for u16 {
+    fn from(value: ResourceRecordType) -> Self {
+        match value {
+            ResourceRecordType::A => 1,
+            ResourceRecordType::NS => 2,
+            ResourceRecordType::CNAME => 5,
+            ResourceRecordType::SOA => 6,
+            ResourceRecordType::MX => 15,
+            ResourceRecordType::AAAA => 28,
+            ResourceRecordType::SRV => 33,
+            ResourceRecordType::TXT => 16,
+            ResourceRecordType::Other(other) => other,
+        }
+    }
+}
+
 impl ResourceRecord {
-    pub fn read_from(reader: &mut Reader<'_>, rtype: u16) -> Result<Self, DNSError> {
+    /// Returns the address contained in the record, if it is an `A` or `AAAA` record
+    #[must_use]
+    pub fn ip_address(&self) -> Option<IpAddr> {
+        match self {
+            Self::A { ipv4 } => Some(IpAddr::V4(*ipv4)),
+            Self::AAAA { ipv6 } => Some(IpAddr::V6(*ipv6)),
+            _ => None,
+        }
+    }
+
+    /// Read the data of a record with the given type, which is `length` bytes long
+    pub fn read_from(reader: &mut Reader<'_>, rtype: u16, length: u64) -> Result<Self, DNSError> {
         let record = match rtype {
             1 => {
                 let mut buffer = [0; 4];
@@ -105,7 +201,10 @@ impl ResourceRecord {
             25 => Self::KEY,
             36 => Self::KX,
             29 => Self::LOC,
-            15 => Self::MX,
+            15 => Self::MX {
+                preference: reader.read_be_u16()?,
+                exchange: Domain::read_from(reader)?,
+            },
             35 => Self::NAPTR,
             2 => Self::NS {
                 ns: Domain::read_from(reader)?,
@@ -119,24 +218,44 @@ impl ResourceRecord {
             17 => Self::RP,
             24 => Self::SIG,
             53 => Self::SMIMEA,
-            6 => {
-                let ns = Domain::read_from(reader)?;
-                let mail = Domain::read_from(reader)?;
-
-                Self::SOA {
-                    _ns: ns,
-                    _mail: mail,
-                    // TODO missing fields
-                }
+            6 => Self::SOA {
+                _ns: Domain::read_from(reader)?,
+                _mail: Domain::read_from(reader)?,
+                _serial: reader.read_be_u32()?,
+                _refresh: reader.read_be_u32()?,
+                _retry: reader.read_be_u32()?,
+                _expire: reader.read_be_u32()?,
+                minimum: reader.read_be_u32()?,
+            },
+            33 => Self::SRV {
+                priority: reader.read_be_u16()?,
+                weight: reader.read_be_u16()?,
+                port: reader.read_be_u16()?,
+                target: Domain::read_from(reader)?,
             },
-            33 => Self::SRV,
             44 => Self::SSHFP,
             64 => Self::SVCB,
             32768 => Self::TA,
             249 => Self::TKEY,
             52 => Self::TLSA,
             250 => Self::TSIG,
-            16 => Self::TXT,
+            16 => {
+                // The data consists of one or more length-prefixed character-strings
+                let mut strings = vec![];
+                let mut remaining = length;
+                while remaining != 0 {
+                    let string_length = reader.read_be_u8()?;
+                    let mut string = vec![0; string_length as usize];
+                    reader.read_exact(&mut string)?;
+                    strings.push(string);
+
+                    remaining = remaining
+                        .checked_sub(1 + string_length as u64)
+                        .ok_or(DNSError::InvalidResponse)?;
+                }
+
+                Self::TXT { strings }
+            },
             256 => Self::URI,
             63 => Self::ZONEMD,
             _ => Self::UNKNOWN,
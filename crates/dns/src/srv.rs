@@ -0,0 +1,111 @@
+//! Ordering of `SRV` records, as described in [RFC 2782](https://datatracker.ietf.org/doc/html/rfc2782)
+
+use sl_std::rand::RNG;
+
+use crate::Domain;
+
+/// A host that provides a service, obtained from an `SRV` record
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServiceTarget {
+    /// Targets with a lower priority must be tried first
+    pub priority: u16,
+
+    /// The relative chance of selecting this target among targets with the same priority
+    pub weight: u16,
+
+    pub port: u16,
+    pub target: Domain,
+}
+
+/// Order targets in the order in which they should be contacted
+///
+/// Targets are sorted by priority. Within the same priority, targets are shuffled
+/// randomly, with the chance of a target coming first being proportional to its weight.
+#[must_use]
+pub(crate) fn order_targets(mut targets: Vec<ServiceTarget>, rng: &mut RNG) -> Vec<ServiceTarget> {
+    targets.sort_by_key(|target| target.priority);
+
+    let mut ordered = Vec::with_capacity(targets.len());
+    for group in targets.chunk_by_mut(|a, b| a.priority == b.priority) {
+        // > To select a target to be contacted next, arrange all SRV RRs (that have
+        // > not been ordered yet) in any order, except that all those with weight 0
+        // > are placed at the beginning of the list.
+        group.sort_by_key(|target| target.weight != 0);
+        let mut remaining = group.to_vec();
+
+        while !remaining.is_empty() {
+            // > Compute the sum of the weights of those RRs, and with each RR associate
+            // > the running sum in the selected order. Then choose a uniform random number
+            // > between 0 and the sum computed (inclusive), and select the RR whose running
+            // > sum value is the first in the selected order which is greater than or
+            // > equal to the random number selected.
+            let total_weight: u64 = remaining.iter().map(|target| target.weight as u64).sum();
+            let chosen_weight = rng.next_range(0, total_weight + 1);
+
+            let mut running_sum = 0;
+            let index = remaining
+                .iter()
+                .position(|target| {
+                    running_sum += target.weight as u64;
+                    running_sum >= chosen_weight
+                })
+                .expect("the running sum eventually reaches the total weight");
+
+            ordered.push(remaining.remove(index));
+        }
+    }
+
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(priority: u16, weight: u16, name: &str) -> ServiceTarget {
+        ServiceTarget {
+            priority,
+            weight,
+            port: 5269,
+            target: Domain::new(name),
+        }
+    }
+
+    #[test]
+    fn sorted_by_priority() {
+        let targets = vec![
+            target(20, 0, "c.example.com"),
+            target(10, 5, "a.example.com"),
+            target(30, 1, "d.example.com"),
+            target(10, 5, "b.example.com"),
+        ];
+
+        let ordered = order_targets(targets, &mut RNG::with_seed(42));
+        let priorities: Vec<u16> = ordered.iter().map(|target| target.priority).collect();
+        assert_eq!(priorities, [10, 10, 20, 30]);
+    }
+
+    #[test]
+    fn weighted_selection() {
+        let targets = vec![
+            target(10, 0, "never-first.example.com"),
+            target(10, 1, "rare.example.com"),
+            target(10, 9, "common.example.com"),
+        ];
+
+        let mut rng = RNG::with_seed(1234);
+        let mut times_first = [0; 3];
+        for _ in 0..1000 {
+            let ordered = order_targets(targets.clone(), &mut rng);
+            assert_eq!(ordered.len(), 3);
+
+            let index = targets.iter().position(|t| *t == ordered[0]).unwrap();
+            times_first[index] += 1;
+        }
+
+        // A target with weight zero is only chosen first if the random number is zero
+        assert!(times_first[0] < 200);
+        assert!(times_first[1] < times_first[2]);
+        assert!(times_first[2] > 700);
+    }
+}
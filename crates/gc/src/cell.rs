@@ -700,7 +700,7 @@ impl Drop for BorrowRefMut<'_> {
 }
 
 #[must_not_suspend = "holding a RefMut across suspend points can cause BorrowErrors"]
-pub struct RefMut<'b, T: ?Sized> {
+pub struct RefMut<'b, T: ?Sized + Trace> {
     // NB: we use a pointer instead of `&'b mut T` to avoid `noalias` violations, because a
     // `RefMut` argument doesn't hold exclusivity for its whole scope, only until it drops.
     value: NonNull<T>,
@@ -711,6 +711,18 @@ pub struct RefMut<'b, T: ?Sized> {
     marker: PhantomData<&'b mut T>,
 }
 
+impl<T: ?Sized + Trace> Drop for RefMut<'_, T> {
+    fn drop(&mut self) {
+        // The value was rooted when the borrow started, and any gc pointers that were
+        // inserted since then came from the stack. If the cell itself lives on the gc heap,
+        // they are no longer roots once the borrow ends.
+        if !self.borrow.borrow.get().is_rooted() {
+            // SAFETY: the value is accessible as long as we hold our borrow.
+            unsafe { self.value.as_ref() }.unroot();
+        }
+    }
+}
+
 impl<'b> BorrowRefMut<'b> {
     #[inline]
     fn new(borrow: &'b Cell<BorrowFlag>) -> Option<BorrowRefMut<'b>> {
@@ -725,7 +737,7 @@ impl<'b> BorrowRefMut<'b> {
     }
 }
 
-impl<T: ?Sized> Deref for RefMut<'_, T> {
+impl<T: ?Sized + Trace> Deref for RefMut<'_, T> {
     type Target = T;
 
     #[inline]
@@ -735,7 +747,7 @@ impl<T: ?Sized> Deref for RefMut<'_, T> {
     }
 }
 
-impl<T: ?Sized> DerefMut for RefMut<'_, T> {
+impl<T: ?Sized + Trace> DerefMut for RefMut<'_, T> {
     #[inline]
     fn deref_mut(&mut self) -> &mut T {
         // SAFETY: the value is accessible as long as we hold our borrow.
@@ -743,13 +755,13 @@ impl<T: ?Sized> DerefMut for RefMut<'_, T> {
     }
 }
 
-impl<T: ?Sized + fmt::Debug> fmt::Debug for RefMut<'_, T> {
+impl<T: ?Sized + Trace + fmt::Debug> fmt::Debug for RefMut<'_, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         (**self).fmt(f)
     }
 }
 
-impl<T: ?Sized + fmt::Display> fmt::Display for RefMut<'_, T> {
+impl<T: ?Sized + Trace + fmt::Display> fmt::Display for RefMut<'_, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         (**self).fmt(f)
     }
@@ -13,11 +13,28 @@ const COLLECT_IF_MEMORY_USAGE_ABOVE: usize = 0x1000;
 thread_local! {
     pub static HEAP: RefCell<Heap> = RefCell::new(Heap {
         bytes_allocated: 0,
+        live_objects: 0,
+        collections_run: 0,
         collect_if_memory_usage_above: COLLECT_IF_MEMORY_USAGE_ABOVE,
         head: None,
     });
 }
 
+/// A snapshot of the state of the current threads heap
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    /// The number of objects that are currently allocated, including garbage
+    /// that was not collected yet
+    pub live_objects: usize,
+
+    /// The size of all allocated objects
+    pub bytes_allocated: usize,
+
+    /// The number of garbage collections so far, including both automatic and
+    /// explicit ones
+    pub collections_run: usize,
+}
+
 /// Forces a garbage collection
 ///
 /// Returns the number of bytes that were freed
@@ -25,8 +42,29 @@ pub fn collect_garbage() -> usize {
     HEAP.with(|heap| heap.borrow_mut().collect_garbage())
 }
 
+#[must_use]
+pub fn heap_stats() -> HeapStats {
+    HEAP.with(|heap| {
+        let heap = heap.borrow();
+
+        HeapStats {
+            live_objects: heap.live_objects,
+            bytes_allocated: heap.bytes_allocated,
+            collections_run: heap.collections_run,
+        }
+    })
+}
+
+/// Collect garbage automatically whenever more than `bytes` are allocated
+/// on the current threads heap
+pub fn set_collection_threshold(bytes: usize) {
+    HEAP.with(|heap| heap.borrow_mut().collect_if_memory_usage_above = bytes);
+}
+
 pub(crate) struct Heap {
     bytes_allocated: usize,
+    live_objects: usize,
+    collections_run: usize,
     collect_if_memory_usage_above: usize,
 
     /// The most recently allocated gc node
@@ -41,7 +79,8 @@ impl Heap {
         let old_head = self.head.replace(node);
         node.as_ref().next.set(old_head);
 
-        self.bytes_allocated += mem::size_of_val(&node);
+        self.bytes_allocated += mem::size_of_val(node.as_ref());
+        self.live_objects += 1;
 
         if self.bytes_allocated > self.collect_if_memory_usage_above {
             self.collect_garbage();
@@ -53,6 +92,8 @@ impl Heap {
     /// Returns the number of bytes that were freed
     fn collect_garbage(&mut self) -> usize {
         log::debug!("Collecting garbage...");
+        self.collections_run += 1;
+
         // Mark phase
         // Iterate over all known GC objects and mark them (and all their children)
         // if they have one more roots
@@ -97,8 +138,10 @@ impl Heap {
 
         // Sweep Phase
         let mut total_freed_size = 0;
+        self.live_objects -= unmarked_nodes.len();
         while let Some(mut unmarked_node) = unmarked_nodes.pop() {
-            total_freed_size += mem::size_of_val(&unmarked_node.node);
+            // SAFETY: The node ptr is guaranteed to point to a valid node
+            total_freed_size += mem::size_of_val(unsafe { unmarked_node.node.as_ref() });
 
             // Remove the unmarked node from the linked list
             // SAFETY: The node ptr is guaranteed to point to a valid node
@@ -8,7 +8,7 @@ mod node;
 mod trace;
 
 pub use cell::{GcCell, Ref};
-pub use heap::collect_garbage;
+pub use heap::{collect_garbage, heap_stats, set_collection_threshold, HeapStats};
 use node::HeapNode;
 pub use trace::Trace;
 
@@ -33,6 +33,17 @@ where
     referenced_node: Cell<NonNull<HeapNode<T>>>,
 }
 
+/// Keeps the value behind a [Gc] alive until the guard is dropped
+///
+/// Created by [Gc::root_scope].
+#[must_use = "the value is only rooted as long as the guard is alive"]
+pub struct RootGuard<'a, T>
+where
+    T: 'static + ?Sized + Trace,
+{
+    gc: &'a Gc<T>,
+}
+
 impl<T: Trace> Clone for Gc<T> {
     fn clone(&self) -> Self {
         Self::node_mut(self).increment_root_count();
@@ -141,6 +152,16 @@ where
     pub fn mark(value: &Self) {
         Self::node(value).mark()
     }
+
+    /// Root the value for as long as the returned guard is alive
+    ///
+    /// Unlike [Gc::mark_as_root], this works regardless of whether the pointer
+    /// is already rooted, and the root is released even if the scope is left early.
+    pub fn root_scope(value: &Self) -> RootGuard<'_, T> {
+        Self::node_mut(value).increment_root_count();
+
+        RootGuard { gc: value }
+    }
 }
 
 impl<T> fmt::Debug for Gc<T>
@@ -172,6 +193,27 @@ where
     }
 }
 
+impl<T> Drop for RootGuard<'_, T>
+where
+    T: 'static + ?Sized + Trace,
+{
+    fn drop(&mut self) {
+        Gc::node_mut(self.gc).decrement_root_count();
+    }
+}
+
+impl<T> Deref for RootGuard<'_, T>
+where
+    T: 'static + Trace,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.gc
+    }
+}
+
 impl<T> Deref for Gc<T>
 where
     T: 'static + Trace,
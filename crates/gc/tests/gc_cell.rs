@@ -15,3 +15,18 @@ fn gc_cell() {
 
     assert_eq!(*container.borrow().1, 3);
 }
+
+#[test]
+fn reachable_through_gc_cell() {
+    let children: Gc<GcCell<Vec<Gc<i32>>>> = Gc::new(GcCell::new(vec![]));
+    children.borrow_mut().push(Gc::new(42));
+
+    // The child is only reachable through the cell, but it is not garbage
+    assert_eq!(gc::collect_garbage(), 0);
+    assert_eq!(*children.borrow()[0], 42);
+
+    // Once the cell is unreachable, the child is no longer rooted either
+    drop(children);
+    gc::collect_garbage();
+    assert_eq!(gc::heap_stats().live_objects, 0);
+}
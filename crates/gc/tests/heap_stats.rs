@@ -0,0 +1,32 @@
+use gc::Gc;
+
+#[test]
+fn automatic_collection() {
+    let before = gc::heap_stats();
+    assert_eq!(before.live_objects, 0);
+    assert_eq!(before.collections_run, 0);
+
+    let survivor = Gc::new(1);
+    let stats = gc::heap_stats();
+    assert_eq!(stats.live_objects, 1);
+    assert_ne!(stats.bytes_allocated, 0);
+
+    // Allocating many unused objects crosses the threshold
+    gc::set_collection_threshold(4 * stats.bytes_allocated);
+    for _ in 0..100 {
+        let _ = Gc::new(0);
+    }
+
+    let after = gc::heap_stats();
+    assert!(after.collections_run > 0);
+    assert!(after.live_objects < 100);
+
+    // Explicit collections are counted too and free the remaining garbage
+    gc::collect_garbage();
+    let after_collection = gc::heap_stats();
+    assert_eq!(after_collection.collections_run, after.collections_run + 1);
+    assert_eq!(after_collection.live_objects, 1);
+    assert_eq!(after_collection.bytes_allocated, stats.bytes_allocated);
+
+    assert_eq!(*survivor, 1);
+}
@@ -0,0 +1,20 @@
+use gc::Gc;
+
+#[test]
+fn root_guard() {
+    let value = Gc::new(5);
+
+    // Pretend that the value is only referenced from a place that the collector
+    // doesn't know about
+    unsafe { Gc::mark_as_no_root(&value) };
+
+    {
+        let guard = Gc::root_scope(&value);
+        assert_eq!(gc::collect_garbage(), 0);
+        assert_eq!(*guard, 5);
+    }
+
+    // Without the guard, nothing keeps the value alive
+    assert_ne!(gc::collect_garbage(), 0);
+    assert_eq!(gc::heap_stats().live_objects, 0);
+}
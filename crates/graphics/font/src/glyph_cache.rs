@@ -0,0 +1,172 @@
+//! Caches the outlines of recently rendered glyphs
+//!
+//! Decoding a glyph from the `glyf` table is comparatively expensive, and the same
+//! glyphs are rendered over and over again. Outlines are stored in font units, so one
+//! entry can be reused for every font size.
+
+use std::{collections::HashMap, mem, sync::Arc};
+
+use crate::{path::Operation, ttf_tables::cmap::GlyphID};
+
+/// The default upper limit for the memory used by cached outlines, in bytes
+const DEFAULT_BYTE_BUDGET: usize = 1 << 20;
+
+/// A least-recently-used cache for glyph outlines
+#[derive(Debug)]
+pub struct GlyphCache {
+    entries: HashMap<GlyphID, CacheEntry>,
+
+    /// The maximum number of bytes used by all cached outlines combined
+    byte_budget: usize,
+
+    /// The number of bytes used by all cached outlines combined
+    used_bytes: usize,
+
+    /// Incremented every time the cache is accessed, used to find the least recently used entry
+    clock: u64,
+
+    statistics: CacheStatistics,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStatistics {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    outline: Arc<[Operation]>,
+    last_used: u64,
+}
+
+impl CacheEntry {
+    #[inline]
+    #[must_use]
+    fn size_in_bytes(&self) -> usize {
+        mem::size_of_val(&*self.outline)
+    }
+}
+
+impl GlyphCache {
+    #[must_use]
+    pub fn new(byte_budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            byte_budget,
+            used_bytes: 0,
+            clock: 0,
+            statistics: CacheStatistics::default(),
+        }
+    }
+
+    /// Return the cached outline of a glyph, computing it if necessary
+    pub fn get_or_insert_with<F>(
+        &mut self,
+        glyph_id: GlyphID,
+        compute_outline: F,
+    ) -> Arc<[Operation]>
+    where
+        F: FnOnce() -> Vec<Operation>,
+    {
+        self.clock += 1;
+
+        if let Some(entry) = self.entries.get_mut(&glyph_id) {
+            self.statistics.hits += 1;
+            entry.last_used = self.clock;
+            return entry.outline.clone();
+        }
+
+        self.statistics.misses += 1;
+        let entry = CacheEntry {
+            outline: compute_outline().into(),
+            last_used: self.clock,
+        };
+        let outline = entry.outline.clone();
+
+        self.used_bytes += entry.size_in_bytes();
+        self.entries.insert(glyph_id, entry);
+        self.evict_until_within_budget();
+
+        outline
+    }
+
+    /// Remove the least recently used entries until the cache fits into its byte budget
+    fn evict_until_within_budget(&mut self) {
+        while self.byte_budget < self.used_bytes {
+            let Some(&least_recently_used) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(glyph_id, _)| glyph_id)
+            else {
+                break;
+            };
+
+            let entry = self
+                .entries
+                .remove(&least_recently_used)
+                .expect("entry exists");
+            self.used_bytes -= entry.size_in_bytes();
+        }
+    }
+
+    /// Remove all cached outlines
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.used_bytes = 0;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn statistics(&self) -> CacheStatistics {
+        self.statistics
+    }
+}
+
+impl Default for GlyphCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BYTE_BUDGET)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(length: usize) -> Vec<Operation> {
+        vec![Operation::MoveTo(math::Vec2D::new(0, 0)); length]
+    }
+
+    #[test]
+    fn hit_after_miss() {
+        let mut cache = GlyphCache::default();
+
+        let first = cache.get_or_insert_with(GlyphID::new(3), || outline(4));
+        let second = cache.get_or_insert_with(GlyphID::new(3), || unreachable!());
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.statistics(), CacheStatistics { hits: 1, misses: 1 });
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let entry_size = mem::size_of::<Operation>() * 4;
+        let mut cache = GlyphCache::new(2 * entry_size);
+
+        cache.get_or_insert_with(GlyphID::new(1), || outline(4));
+        cache.get_or_insert_with(GlyphID::new(2), || outline(4));
+
+        // Use the first glyph again, so the second one is evicted next
+        cache.get_or_insert_with(GlyphID::new(1), || outline(4));
+        cache.get_or_insert_with(GlyphID::new(3), || outline(4));
+        assert_eq!(cache.entries.len(), 2);
+
+        cache.get_or_insert_with(GlyphID::new(1), || outline(4));
+        cache.get_or_insert_with(GlyphID::new(2), || outline(4));
+        assert_eq!(cache.statistics(), CacheStatistics { hits: 2, misses: 4 });
+
+        cache.clear();
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -12,6 +12,7 @@
 )]
 
 mod description;
+mod glyph_cache;
 pub mod hinting;
 mod manager;
 pub mod path;
@@ -19,8 +20,10 @@ pub mod sources;
 mod stream;
 pub mod ttf;
 pub mod ttf_tables;
+pub mod woff;
 
 pub use description::{Family, Language, Properties, Style, Weight};
+pub use glyph_cache::CacheStatistics;
 pub use manager::{FontManager, SystemFont, SYSTEM_FONTS};
 pub use stream::{Readable, Stream};
-pub use ttf::Font;
+pub use ttf::{Font, FontMetrics, ScaledFontMetrics};
@@ -5,7 +5,7 @@ use std::{fs, io, path::PathBuf, sync::LazyLock};
 use crate::{
     sources::{FontStore, SystemSource},
     ttf::TTFParseError,
-    Family, Font, Language, Properties, Weight,
+    woff, Family, Font, Language, Properties, Weight,
 };
 
 #[derive(Clone, Debug)]
@@ -80,7 +80,11 @@ impl SystemFont {
     }
 
     pub fn try_load(&self) -> Result<Font, FontLoadError> {
-        let bytes = fs::read(&self.path)?;
+        let mut bytes = fs::read(&self.path)?;
+        if woff::is_woff(&bytes) {
+            bytes = woff::decode(&bytes)?;
+        }
+
         let loaded_font = Font::new(&bytes)?;
 
         Ok(loaded_font)
@@ -91,6 +95,7 @@ impl SystemFont {
 pub enum FontLoadError {
     IO(io::Error),
     TrueType(TTFParseError),
+    Woff(woff::Error),
 }
 
 impl From<io::Error> for FontLoadError {
@@ -99,6 +104,12 @@ impl From<io::Error> for FontLoadError {
     }
 }
 
+impl From<woff::Error> for FontLoadError {
+    fn from(value: woff::Error) -> Self {
+        Self::Woff(value)
+    }
+}
+
 impl From<TTFParseError> for FontLoadError {
     fn from(value: TTFParseError) -> Self {
         Self::TrueType(value)
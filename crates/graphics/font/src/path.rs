@@ -8,6 +8,18 @@ pub enum Operation {
     MoveTo(Vec2D<i32>),
 }
 
+impl Operation {
+    /// Move all points of the operation by `offset`
+    #[must_use]
+    pub fn translated(self, offset: Vec2D<i32>) -> Self {
+        match self {
+            Self::LineTo(p) => Self::LineTo(p + offset),
+            Self::QuadBezTo(p1, p2) => Self::QuadBezTo(p1 + offset, p2 + offset),
+            Self::MoveTo(p) => Self::MoveTo(p + offset),
+        }
+    }
+}
+
 pub struct PathReader<I: Iterator<Item = GlyphPoint>> {
     inner: I,
     last_on_curve_point: Option<Vec2D<i32>>,
@@ -5,20 +5,25 @@
 //! * <https://formats.kaitai.io/ttf/index.html>
 //! * <https://handmade.network/forums/articles/t/7330-implementing_a_font_reader_and_rasterizer_from_scratch%252C_part_1__ttf_font_reader>
 
-use std::{fmt, iter};
+use std::{
+    fmt, iter,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
+    glyph_cache::{CacheStatistics, GlyphCache},
     hinting::Interpreter,
     path::{Operation, PathConsumer, PathReader},
     ttf_tables::{
         cmap::{self, GlyphID},
         glyf::{self, CompoundGlyph, Glyph, GlyphPointIterator, Metrics},
-        head, hhea, hmtx, loca, maxp, name,
+        head, hhea, hmtx, kern, loca, maxp, name,
         offset::OffsetTable,
+        os2,
     },
 };
 
-const DEFAULT_FONT: &[u8; 168644] = include_bytes!(concat!(
+pub(crate) const DEFAULT_FONT: &[u8; 168644] = include_bytes!(concat!(
     env!("DOWNLOAD_DIR"),
     "/fonts/roboto/Roboto-Medium.ttf"
 ));
@@ -29,8 +34,10 @@ const LOCA_TAG: u32 = u32::from_be_bytes(*b"loca");
 const GLYF_TAG: u32 = u32::from_be_bytes(*b"glyf");
 const HHEA_TAG: u32 = u32::from_be_bytes(*b"hhea");
 const HMTX_TAG: u32 = u32::from_be_bytes(*b"hmtx");
+const KERN_TAG: u32 = u32::from_be_bytes(*b"kern");
 const MAXP_TAG: u32 = u32::from_be_bytes(*b"maxp");
 const NAME_TAG: u32 = u32::from_be_bytes(*b"name");
+const OS2_TAG: u32 = u32::from_be_bytes(*b"OS/2");
 const _VHEA_TAG: u32 = u32::from_be_bytes(*b"vhea");
 const PREP_TAG: u32 = u32::from_be_bytes(*b"prep");
 const FPGM_TAG: u32 = u32::from_be_bytes(*b"fpgm");
@@ -49,8 +56,13 @@ pub struct Font {
     format4: cmap::Format4,
     glyph_table: glyf::GlyphOutlineTable,
     hmtx_table: hmtx::HMTXTable,
+    kern_table: Option<kern::KernTable>,
     maxp_table: maxp::MaxPTable,
     name_table: name::NameTable,
+    metrics: FontMetrics,
+
+    /// Outlines of recently rendered glyphs, shared between all clones of the font
+    glyph_cache: Arc<Mutex<GlyphCache>>,
 
     /// A program that is run once the font is loaded and whenever its environment changes
     ///
@@ -118,6 +130,20 @@ impl Font {
             hhea_table.num_of_long_hor_metrics(),
         );
 
+        // The kerning table is optional, fonts without one are simply not kerned
+        let kern_table = offset_table.get_table(KERN_TAG).and_then(|kern_entry| {
+            kern::KernTable::new(&data[kern_entry.offset()..][..kern_entry.length()])
+                .inspect_err(|error| {
+                    log::warn!("Failed to parse kern table: {error:?}, ignoring it")
+                })
+                .ok()
+        });
+
+        let os2_table = offset_table.get_table(OS2_TAG).and_then(|os2_entry| {
+            os2::OS2Table::new(&data[os2_entry.offset()..][..os2_entry.length()])
+        });
+        let metrics = FontMetrics::new(&head_table, &hhea_table, os2_table.as_ref());
+
         let name_entry = offset_table
             .get_table(NAME_TAG)
             .ok_or(TTFParseError::MissingTable)?;
@@ -164,8 +190,11 @@ impl Font {
             format4,
             glyph_table,
             hmtx_table,
+            kern_table,
             maxp_table,
             name_table,
+            metrics,
+            glyph_cache: Arc::default(),
             control_value_program,
             interpreter,
             is_instructed,
@@ -219,6 +248,17 @@ impl Font {
         self.format4.get_glyph_id(codepoint)
     }
 
+    /// Return the kerning adjustment between two adjacent glyphs, in `FUnits`
+    ///
+    /// Fonts without a `kern` table always return `0`.
+    #[must_use]
+    pub fn kerning(&self, left: GlyphID, right: GlyphID) -> i16 {
+        self.kern_table
+            .as_ref()
+            .map(|kern_table| kern_table.kerning(left, right))
+            .unwrap_or_default()
+    }
+
     pub fn get_glyph(&self, glyph_id: GlyphID) -> Result<Glyph<'_>, TTFParseError> {
         // Any character that does not exist is mapped to index zero, which is defined to be the
         // missing character glyph
@@ -226,6 +266,13 @@ impl Font {
         Ok(glyph)
     }
 
+    /// Get the vertical metrics of the font, used for laying out lines of text
+    #[inline]
+    #[must_use]
+    pub fn metrics(&self) -> FontMetrics {
+        self.metrics
+    }
+
     /// Return the number of coordinate points per font size unit.
     /// This value is used to scale fonts, ie. when you render a font with
     /// size `17px`, one `em` equals `17px`.
@@ -271,7 +318,14 @@ impl Font {
         font_size: f32,
         text_offset: math::Vec2D,
     ) {
-        for glyph in RenderedGlyphIterator::new(self, text) {
+        let mut glyph_cache = self.glyph_cache.lock().expect("glyph cache was poisoned");
+
+        for glyph in GlyphPositionIterator::new(self, text) {
+            // The glyph is only decoded if its outline is not cached yet
+            let outline =
+                glyph_cache.get_or_insert_with(glyph.id, || self.decode_outline(glyph.id));
+            let glyph_position = math::Vec2D::new(glyph.x, glyph.y);
+
             let scale_point = |glyph_point: math::Vec2D<i32>| math::Vec2D {
                 x: (glyph_point.x as f32 * font_size) / self.units_per_em(),
                 y: font_size - (glyph_point.y as f32 * font_size) / self.units_per_em(),
@@ -279,19 +333,19 @@ impl Font {
 
             // Draw the outlines of the glyph on the rasterizer buffer
             // Note: all the coordinates in the path operations are relative to the glyph positiont;
-            for path_op in glyph.path_operations {
+            for path_op in outline.iter().copied() {
                 match path_op {
                     Operation::MoveTo(destination) => {
-                        renderer.move_to(scale_point(destination + glyph.position) + text_offset);
+                        renderer.move_to(scale_point(destination + glyph_position) + text_offset);
                     },
                     Operation::LineTo(destination) => {
                         let scaled_destination =
-                            scale_point(destination + glyph.position) + text_offset;
+                            scale_point(destination + glyph_position) + text_offset;
                         renderer.line_to(scaled_destination);
                     },
                     Operation::QuadBezTo(p1, p2) => {
-                        let scaled_p1 = scale_point(p1 + glyph.position) + text_offset;
-                        let scaled_p2 = scale_point(p2 + glyph.position) + text_offset;
+                        let scaled_p1 = scale_point(p1 + glyph_position) + text_offset;
+                        let scaled_p2 = scale_point(p2 + glyph_position) + text_offset;
                         renderer.quad_bez_to(scaled_p1, scaled_p2);
                     },
                 }
@@ -299,6 +353,49 @@ impl Font {
         }
     }
 
+    /// Decode the outline of a glyph, relative to the glyph position
+    ///
+    /// The components of compound glyphs are merged into a single outline.
+    fn decode_outline(&self, glyph_id: GlyphID) -> Vec<Operation> {
+        #[cfg(test)]
+        tests::OUTLINES_DECODED.with(|decoded| decoded.set(decoded.get() + 1));
+
+        let glyph = self
+            .get_glyph(glyph_id)
+            .expect("Font contains no glyph for glyph id");
+
+        match glyph {
+            Glyph::Empty => vec![],
+            Glyph::Simple(simple_glyph) => PathReader::new(simple_glyph.into_iter()).collect(),
+            Glyph::Compound(compound_glyph) => compound_glyph
+                .flat_map(|component| {
+                    let offset =
+                        math::Vec2D::new(component.x_offset as i32, component.y_offset as i32);
+                    self.decode_outline(component.glyph_id)
+                        .into_iter()
+                        .map(move |operation| operation.translated(offset))
+                })
+                .collect(),
+        }
+    }
+
+    /// Remove all cached glyph outlines, to free up memory
+    pub fn clear_glyph_cache(&self) {
+        self.glyph_cache
+            .lock()
+            .expect("glyph cache was poisoned")
+            .clear();
+    }
+
+    /// Return how often glyph outlines were served from the glyph cache
+    #[must_use]
+    pub fn glyph_cache_statistics(&self) -> CacheStatistics {
+        self.glyph_cache
+            .lock()
+            .expect("glyph cache was poisoned")
+            .statistics()
+    }
+
     pub fn render_as_svg(&self, text: &str, id_prefix: &str) -> String {
         let mut min_x = 0;
         let mut max_x = 0;
@@ -377,6 +474,98 @@ impl Font {
     }
 }
 
+/// Vertical metrics of a [Font], in `FUnits`
+///
+/// Use [FontMetrics::scale] to convert them to a given font size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FontMetrics {
+    /// Distance from the baseline to the top of the line
+    pub ascent: i16,
+
+    /// Distance from the baseline to the bottom of the line
+    ///
+    /// This value is usually negative, since it lies below the baseline.
+    pub descent: i16,
+
+    /// Additional space that should be added between two lines
+    pub line_gap: i16,
+
+    pub units_per_em: u16,
+
+    /// The height of uppercase letters above the baseline
+    ///
+    /// If the font does not specify this, the ascent is used instead, as recommended by
+    /// [CSS](https://drafts.csswg.org/css-values-4/#cap).
+    pub cap_height: i16,
+}
+
+/// [FontMetrics] scaled to a font size, in pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScaledFontMetrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+    pub cap_height: f32,
+}
+
+impl FontMetrics {
+    /// Prefers the typographic metrics from the `OS/2` table and falls back
+    /// to the `hhea` table for fonts that don't have one.
+    fn new(
+        head: &head::HeadTable,
+        hhea: &hhea::HHEATable<'_>,
+        os2: Option<&os2::OS2Table>,
+    ) -> Self {
+        let (ascent, descent, line_gap) = match os2 {
+            Some(os2) => (
+                os2.typo_ascender(),
+                os2.typo_descender(),
+                os2.typo_line_gap(),
+            ),
+            None => (hhea.ascent(), hhea.descent(), hhea.line_gap()),
+        };
+
+        let cap_height = os2.and_then(os2::OS2Table::cap_height).unwrap_or(ascent);
+
+        Self {
+            ascent,
+            descent,
+            line_gap,
+            units_per_em: head.units_per_em(),
+            cap_height,
+        }
+    }
+
+    /// The distance between the baselines of two consecutive lines, in `FUnits`
+    #[inline]
+    #[must_use]
+    pub fn line_height(&self) -> i32 {
+        self.ascent as i32 - self.descent as i32 + self.line_gap as i32
+    }
+
+    /// Convert the metrics to pixels for the given font size
+    #[must_use]
+    pub fn scale(&self, font_size: f32) -> ScaledFontMetrics {
+        let scale = |value: i16| value as f32 * font_size / self.units_per_em as f32;
+
+        ScaledFontMetrics {
+            ascent: scale(self.ascent),
+            descent: scale(self.descent),
+            line_gap: scale(self.line_gap),
+            cap_height: scale(self.cap_height),
+        }
+    }
+}
+
+impl ScaledFontMetrics {
+    /// The distance between the baselines of two consecutive lines, in pixels
+    #[inline]
+    #[must_use]
+    pub fn line_height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+}
+
 pub fn read_u16_at(data: &[u8], offset: usize) -> u16 {
     u16::from_be_bytes(data[offset..offset + 2].try_into().unwrap())
 }
@@ -405,6 +594,9 @@ struct GlyphPositionIterator<'font, 'text> {
     x: i32,
     y: i32,
     chars: std::str::Chars<'text>,
+
+    /// The previously emitted glyph, used for kerning
+    previous: Option<GlyphID>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -431,6 +623,10 @@ impl<'font, 'text> Iterator for GlyphPositionIterator<'font, 'text> {
             .get_glyph_id(c as u16)
             .unwrap_or(GlyphID::REPLACEMENT);
 
+        if let Some(previous) = self.previous.replace(id) {
+            self.x += self.font.kerning(previous, id) as i32;
+        }
+
         let horizontal_metrics = self.font.hmtx_table.get_metric_for(id);
         let x = self.x + horizontal_metrics.left_side_bearing() as i32;
         let y = self.y;
@@ -511,6 +707,7 @@ impl<'font, 'text> GlyphPositionIterator<'font, 'text> {
             x: 0,
             y: 0,
             chars: text.chars(),
+            previous: None,
         }
     }
 
@@ -543,3 +740,42 @@ impl fmt::Debug for Font {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    thread_local! {
+        /// The number of glyph outlines decoded on this thread
+        pub(super) static OUTLINES_DECODED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct DiscardPath;
+
+    impl PathConsumer for DiscardPath {
+        fn move_to(&mut self, _: math::Vec2D) {}
+        fn line_to(&mut self, _: math::Vec2D) {}
+        fn quad_bez_to(&mut self, _: math::Vec2D, _: math::Vec2D) {}
+    }
+
+    #[test]
+    fn cached_glyphs_are_not_decoded() {
+        let font = Font::fallback();
+        let render = || font.render("aaa", &mut DiscardPath, 16., math::Vec2D::new(0., 0.));
+
+        let decoded_before = OUTLINES_DECODED.with(Cell::get);
+        render();
+        let decoded_after_first_render = OUTLINES_DECODED.with(Cell::get);
+        assert!(decoded_before < decoded_after_first_render);
+
+        // Every glyph is now cached, so nothing needs to be decoded again
+        render();
+        assert_eq!(OUTLINES_DECODED.with(Cell::get), decoded_after_first_render);
+        assert_eq!(
+            font.glyph_cache_statistics(),
+            CacheStatistics { hits: 5, misses: 1 }
+        );
+    }
+}
@@ -4,7 +4,7 @@ use crate::ttf::{read_u16_at, read_u32_at};
 use std::{cmp::Ordering, fmt};
 
 /// Zero-cost wrapper around a `u16` for extra type safety.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct GlyphID(u16);
 
 impl GlyphID {
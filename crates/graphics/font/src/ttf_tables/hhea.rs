@@ -2,7 +2,7 @@
 //!
 //! Mostly just contains information for the [hmtx](super::hmtx) table.
 
-use crate::ttf::read_u16_at;
+use crate::ttf::{read_i16_at, read_u16_at};
 
 pub struct HHEATable<'a>(&'a [u8]);
 
@@ -11,6 +11,23 @@ impl<'a> HHEATable<'a> {
         Self(&data[offset..][..36])
     }
 
+    /// Distance from the baseline to the highest ascender, in `FUnits`
+    pub fn ascent(&self) -> i16 {
+        read_i16_at(self.0, 4)
+    }
+
+    /// Distance from the baseline to the lowest descender, in `FUnits`
+    ///
+    /// This value is usually negative.
+    pub fn descent(&self) -> i16 {
+        read_i16_at(self.0, 6)
+    }
+
+    /// Additional space between two lines, in `FUnits`
+    pub fn line_gap(&self) -> i16 {
+        read_i16_at(self.0, 8)
+    }
+
     pub fn num_of_long_hor_metrics(&self) -> usize {
         read_u16_at(self.0, 34) as usize
     }
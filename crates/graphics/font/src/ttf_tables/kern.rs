@@ -1,82 +1,209 @@
-use crate::ttf::{read_u16_at, read_u32_at};
+//! [Kerning](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kern.html) table
+//!
+//! Both the Microsoft (version `0`) and the Apple (version `0x00010000`) variants of the table
+//! are supported. Only horizontal format 0 subtables are used, all others are ignored.
 
-#[derive(Clone, Debug)]
-pub struct KernTable {}
+use std::collections::HashMap;
+
+use crate::ttf::{read_i16_at, read_u16_at, read_u32_at};
+
+use super::cmap::GlyphID;
+
+#[derive(Clone, Debug, Default)]
+pub struct KernTable {
+    /// Pairs of `(left << 16 | right, value)`, sorted by glyph pair
+    pairs: Vec<(u32, i16)>,
+}
 
 #[derive(Clone, Copy, Debug)]
 pub enum Error {
-    /// Expected version `0x00010000`
+    /// Expected version `0` or `0x00010000`
     InvalidVersion,
 
-    InvalidCoverage,
-
     UnexpectedEndOfTable,
 }
 
+/// Information from a subtable header that is relevant to us
+#[derive(Clone, Copy, Debug)]
+struct Coverage {
+    is_horizontal: bool,
+    is_cross_stream: bool,
+    is_variation: bool,
+    overrides_previous: bool,
+    format: u8,
+}
+
 impl KernTable {
     pub fn new(bytes: &[u8]) -> Result<Self, Error> {
-        const MAX_SUBTABLES_TO_RESERVE: usize = 16; // Ad-hoc
-
-        if bytes.len() < 8 {
+        if bytes.len() < 4 {
             return Err(Error::UnexpectedEndOfTable);
         }
 
-        // FIXME: Apparently older fonts sometimes represent these
-        //        first two fonts as 16 bit sometimes. Is this something
-        //        that we need to worry about in practice?
-        if read_u32_at(bytes, 0) != 0x00010000 {
-            return Err(Error::InvalidVersion);
+        // Values from all subtables, keyed by glyph pair
+        let mut pairs = HashMap::new();
+        match read_u16_at(bytes, 0) {
+            0 => {
+                // Microsoft variant
+                let n_subtables = read_u16_at(bytes, 2);
+
+                let mut cursor = 4;
+                for _ in 0..n_subtables {
+                    let remaining = bytes.get(cursor..).ok_or(Error::UnexpectedEndOfTable)?;
+                    if remaining.len() < 6 {
+                        return Err(Error::UnexpectedEndOfTable);
+                    }
+
+                    let length = read_u16_at(remaining, 2) as usize;
+                    let coverage = read_u16_at(remaining, 4);
+                    let coverage = Coverage {
+                        is_horizontal: coverage & 0x1 != 0,
+                        is_cross_stream: coverage & 0x4 != 0,
+                        is_variation: false,
+                        overrides_previous: coverage & 0x8 != 0,
+                        format: (coverage >> 8) as u8,
+                    };
+
+                    read_subtable(&remaining[6..], coverage, &mut pairs)?;
+                    cursor += length;
+                }
+            },
+            1 if read_u32_at(bytes, 0) == 0x00010000 => {
+                // Apple variant
+                if bytes.len() < 8 {
+                    return Err(Error::UnexpectedEndOfTable);
+                }
+                let n_subtables = read_u32_at(bytes, 4);
+
+                let mut cursor = 8;
+                for _ in 0..n_subtables {
+                    let remaining = bytes.get(cursor..).ok_or(Error::UnexpectedEndOfTable)?;
+                    if remaining.len() < 8 {
+                        return Err(Error::UnexpectedEndOfTable);
+                    }
+
+                    let length = read_u32_at(remaining, 0) as usize;
+                    let coverage = Coverage {
+                        is_horizontal: remaining[4] & 0x80 == 0,
+                        is_cross_stream: remaining[4] & 0x40 != 0,
+                        is_variation: remaining[4] & 0x20 != 0,
+                        overrides_previous: false,
+                        format: remaining[5],
+                    };
+
+                    read_subtable(&remaining[8..], coverage, &mut pairs)?;
+                    cursor += length;
+                }
+            },
+            _ => return Err(Error::InvalidVersion),
         }
 
-        let n_subtables = read_u32_at(bytes, 4) as usize;
-        let mut subtable_list = Vec::with_capacity(n_subtables.min(MAX_SUBTABLES_TO_RESERVE));
-
-        let mut cursor = 8;
-        for _ in 0..n_subtables {
-            let remaining = &bytes[cursor..];
-            if remaining.len() < 8 {
-                return Err(Error::UnexpectedEndOfTable);
-            }
-
-            let length = read_u32_at(remaining, 0) as usize;
-
-            let kind = match remaining[4] & !0x1F {
-                0x80 => Kind::Vertical,
-                0x40 => Kind::CrossStream,
-                0x20 => {
-                    let tuple_index = read_u16_at(remaining, 6);
-                    Kind::Variation { tuple_index }
-                },
-                _ => return Err(Error::InvalidCoverage),
-            };
-
-            let format = remaining[5];
-
-            let content = &remaining[8..length];
-
-            let subtable = Subtable {
-                kind,
-                format,
-                bytes: content,
-            };
-            cursor += length;
-            log::info!("subtable kind {:?}", subtable.format);
-            subtable_list.push(subtable);
-        }
-        panic!()
+        let mut pairs: Vec<(u32, i16)> = pairs.into_iter().collect();
+        pairs.sort_by_key(|(key, _)| *key);
+
+        Ok(Self { pairs })
+    }
+
+    /// Return the distance in `FUnits` that should be added between `left` and `right`
+    /// if they are placed next to each other
+    #[must_use]
+    pub fn kerning(&self, left: GlyphID, right: GlyphID) -> i16 {
+        let key = pair_key(left.numeric(), right.numeric());
+
+        self.pairs
+            .binary_search_by_key(&key, |(key, _)| *key)
+            .map(|index| self.pairs[index].1)
+            .unwrap_or_default()
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Kind {
-    Vertical,
-    CrossStream,
-    Variation { tuple_index: u16 },
+#[inline]
+#[must_use]
+fn pair_key(left: u16, right: u16) -> u32 {
+    (left as u32) << 16 | right as u32
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Subtable<'a> {
-    kind: Kind,
-    format: u8,
-    bytes: &'a [u8],
+fn read_subtable(
+    bytes: &[u8],
+    coverage: Coverage,
+    pairs: &mut HashMap<u32, i16>,
+) -> Result<(), Error> {
+    if !coverage.is_horizontal
+        || coverage.is_cross_stream
+        || coverage.is_variation
+        || coverage.format != 0
+    {
+        log::debug!("Skipping unsupported kerning subtable {coverage:?}");
+        return Ok(());
+    }
+
+    if bytes.len() < 8 {
+        return Err(Error::UnexpectedEndOfTable);
+    }
+
+    let n_pairs = read_u16_at(bytes, 0) as usize;
+    let pair_data = bytes
+        .get(8..8 + n_pairs * 6)
+        .ok_or(Error::UnexpectedEndOfTable)?;
+
+    for pair in pair_data.array_chunks::<6>() {
+        let key = pair_key(read_u16_at(pair, 0), read_u16_at(pair, 2));
+        let value = read_i16_at(pair, 4);
+
+        // Multiple subtables can contribute to the same pair, unless
+        // this subtable replaces the values of the previous ones
+        if coverage.overrides_previous {
+            pairs.insert(key, value);
+        } else {
+            pairs
+                .entry(key)
+                .and_modify(|previous| *previous = previous.saturating_add(value))
+                .or_insert(value);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn microsoft_format_0() {
+        #[rustfmt::skip]
+        let table = [
+            0x00, 0x00, 0x00, 0x01, // version 0, 1 subtable
+            0x00, 0x00, 0x00, 0x1A, 0x00, 0x01, // horizontal format 0 subtable
+            0x00, 0x02, 0x00, 0x0C, 0x00, 0x01, 0x00, 0x00, // two pairs
+            0x00, 0x04, 0x00, 0x05, 0xFF, 0xCE, // (4, 5) => -50
+            0x00, 0x05, 0x00, 0x04, 0x00, 0x0A, // (5, 4) => 10
+        ];
+
+        let kern = KernTable::new(&table).unwrap();
+
+        assert_eq!(kern.kerning(GlyphID::new(4), GlyphID::new(5)), -50);
+        assert_eq!(kern.kerning(GlyphID::new(5), GlyphID::new(4)), 10);
+        assert_eq!(kern.kerning(GlyphID::new(4), GlyphID::new(4)), 0);
+    }
+
+    #[test]
+    fn override_previous_subtable() {
+        #[rustfmt::skip]
+        let table = [
+            0x00, 0x00, 0x00, 0x02, // version 0, 2 subtables
+            0x00, 0x00, 0x00, 0x1A, 0x00, 0x01, // horizontal format 0 subtable
+            0x00, 0x02, 0x00, 0x0C, 0x00, 0x01, 0x00, 0x00, // two pairs
+            0x00, 0x04, 0x00, 0x05, 0xFF, 0xCE, // (4, 5) => -50
+            0x00, 0x05, 0x00, 0x04, 0x00, 0x0A, // (5, 4) => 10
+            0x00, 0x00, 0x00, 0x14, 0x00, 0x09, // horizontal format 0 subtable, overrides previous
+            0x00, 0x01, 0x00, 0x06, 0x00, 0x00, 0x00, 0x00, // one pair
+            0x00, 0x04, 0x00, 0x05, 0x00, 0x14, // (4, 5) => 20
+        ];
+
+        let kern = KernTable::new(&table).unwrap();
+
+        // Only the pairs defined by the overriding subtable are replaced
+        assert_eq!(kern.kerning(GlyphID::new(4), GlyphID::new(5)), 20);
+        assert_eq!(kern.kerning(GlyphID::new(5), GlyphID::new(4)), 10);
+    }
 }
@@ -5,9 +5,11 @@ pub mod glyf;
 pub mod head;
 pub mod hhea;
 pub mod hmtx;
+pub mod kern;
 pub mod loca;
 pub mod maxp;
 pub mod name;
 pub mod offset;
+pub mod os2;
 pub mod vhea;
 pub mod vmtx;
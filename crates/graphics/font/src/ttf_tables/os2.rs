@@ -0,0 +1,66 @@
+//! [OS/2](https://learn.microsoft.com/en-us/typography/opentype/spec/os2) table implementation
+//!
+//! Only the fields related to vertical metrics are parsed.
+
+use crate::ttf::{read_i16_at, read_u16_at};
+
+/// The `sCapHeight` field was added in version 2 of the table
+const MIN_VERSION_WITH_CAP_HEIGHT: u16 = 2;
+
+#[derive(Clone, Copy, Debug)]
+pub struct OS2Table {
+    typo_ascender: i16,
+    typo_descender: i16,
+    typo_line_gap: i16,
+    cap_height: Option<i16>,
+}
+
+impl OS2Table {
+    /// Returns `None` if the table is too short to contain the typographic metrics
+    #[must_use]
+    pub fn new(data: &[u8]) -> Option<Self> {
+        if data.len() < 78 {
+            return None;
+        }
+
+        let version = read_u16_at(data, 0);
+        let cap_height = if MIN_VERSION_WITH_CAP_HEIGHT <= version && 90 <= data.len() {
+            Some(read_i16_at(data, 88))
+        } else {
+            None
+        };
+
+        Some(Self {
+            typo_ascender: read_i16_at(data, 68),
+            typo_descender: read_i16_at(data, 70),
+            typo_line_gap: read_i16_at(data, 72),
+            cap_height,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn typo_ascender(&self) -> i16 {
+        self.typo_ascender
+    }
+
+    /// This value is usually negative
+    #[inline]
+    #[must_use]
+    pub fn typo_descender(&self) -> i16 {
+        self.typo_descender
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn typo_line_gap(&self) -> i16 {
+        self.typo_line_gap
+    }
+
+    /// The height of uppercase letters above the baseline, if specified
+    #[inline]
+    #[must_use]
+    pub fn cap_height(&self) -> Option<i16> {
+        self.cap_height
+    }
+}
@@ -0,0 +1,285 @@
+//! [WOFF](https://www.w3.org/TR/WOFF/) font container
+//!
+//! A WOFF file wraps the tables of a regular SFNT font (TrueType or OpenType), compressing
+//! each table individually. WOFF files are converted back to SFNT before being parsed.
+//!
+//! [WOFF2](https://www.w3.org/TR/WOFF2/) is not supported.
+
+use compression::zlib;
+
+use crate::ttf::{read_u16_at, read_u32_at};
+
+/// The `signature` field of a WOFF header
+pub const WOFF_SIGNATURE: [u8; 4] = *b"wOFF";
+
+const WOFF_HEADER_SIZE: usize = 44;
+const WOFF_TABLE_ENTRY_SIZE: usize = 20;
+
+const SFNT_HEADER_SIZE: usize = 12;
+const SFNT_TABLE_ENTRY_SIZE: usize = 16;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Error {
+    NotAWoff,
+    UnexpectedEOF,
+
+    /// A table is larger when compressed than when decompressed
+    InvalidTableLength,
+
+    /// The decompressed length of a table does not match the length declared in the table directory
+    MismatchedTableLength,
+
+    /// The number of tables is zero or too large to be described by an SFNT header
+    InvalidNumberOfTables,
+
+    Zlib(zlib::Error),
+}
+
+/// Return `true` if the data starts with the [WOFF signature](WOFF_SIGNATURE)
+#[must_use]
+pub fn is_woff(bytes: &[u8]) -> bool {
+    bytes.starts_with(&WOFF_SIGNATURE)
+}
+
+struct TableEntry {
+    tag: u32,
+    offset: usize,
+    compressed_length: usize,
+    original_length: usize,
+    original_checksum: u32,
+}
+
+/// Reconstruct the SFNT font contained in a WOFF file
+///
+/// The returned data can be passed to [Font::new](crate::Font::new).
+pub fn decode(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    if !is_woff(bytes) {
+        return Err(Error::NotAWoff);
+    }
+
+    if bytes.len() < WOFF_HEADER_SIZE {
+        return Err(Error::UnexpectedEOF);
+    }
+
+    let flavor = read_u32_at(bytes, 4);
+    let num_tables = read_u16_at(bytes, 12);
+
+    let table_directory = bytes
+        .get(WOFF_HEADER_SIZE..WOFF_HEADER_SIZE + num_tables as usize * WOFF_TABLE_ENTRY_SIZE)
+        .ok_or(Error::UnexpectedEOF)?;
+    let tables: Vec<TableEntry> = table_directory
+        .array_chunks::<WOFF_TABLE_ENTRY_SIZE>()
+        .map(|entry| TableEntry {
+            tag: read_u32_at(entry, 0),
+            offset: read_u32_at(entry, 4) as usize,
+            compressed_length: read_u32_at(entry, 8) as usize,
+            original_length: read_u32_at(entry, 12) as usize,
+            original_checksum: read_u32_at(entry, 16),
+        })
+        .collect();
+
+    // Write the SFNT header
+    // https://learn.microsoft.com/en-us/typography/opentype/spec/otff#table-directory
+    // The values are computed with u32 since they can overflow for malicious table counts
+    let entry_selector = num_tables
+        .checked_ilog2()
+        .ok_or(Error::InvalidNumberOfTables)?;
+    let search_range = (1_u32 << entry_selector) * SFNT_TABLE_ENTRY_SIZE as u32;
+    let range_shift = (num_tables as u32 * SFNT_TABLE_ENTRY_SIZE as u32)
+        .checked_sub(search_range)
+        .ok_or(Error::InvalidNumberOfTables)?;
+    let entry_selector = entry_selector as u16;
+    let search_range = u16::try_from(search_range).map_err(|_| Error::InvalidNumberOfTables)?;
+    let range_shift = u16::try_from(range_shift).map_err(|_| Error::InvalidNumberOfTables)?;
+
+    let mut sfnt = Vec::new();
+    sfnt.extend_from_slice(&flavor.to_be_bytes());
+    sfnt.extend_from_slice(&num_tables.to_be_bytes());
+    sfnt.extend_from_slice(&search_range.to_be_bytes());
+    sfnt.extend_from_slice(&entry_selector.to_be_bytes());
+    sfnt.extend_from_slice(&range_shift.to_be_bytes());
+
+    // The table data starts after the table directory, with every table aligned to four bytes.
+    // We first write the table directory and then fill in the table data.
+    let mut table_offset = SFNT_HEADER_SIZE + tables.len() * SFNT_TABLE_ENTRY_SIZE;
+    for table in &tables {
+        sfnt.extend_from_slice(&table.tag.to_be_bytes());
+        sfnt.extend_from_slice(&table.original_checksum.to_be_bytes());
+        let offset = u32::try_from(table_offset).map_err(|_| Error::InvalidTableLength)?;
+        sfnt.extend_from_slice(&offset.to_be_bytes());
+        sfnt.extend_from_slice(&(table.original_length as u32).to_be_bytes());
+
+        table_offset += table.original_length.next_multiple_of(4);
+    }
+
+    for table in &tables {
+        let data = bytes
+            .get(table.offset..)
+            .and_then(|data| data.get(..table.compressed_length))
+            .ok_or(Error::UnexpectedEOF)?;
+
+        match table.compressed_length.cmp(&table.original_length) {
+            std::cmp::Ordering::Less => {
+                let decompressed = zlib::decompress(data)?;
+                if decompressed.len() != table.original_length {
+                    return Err(Error::MismatchedTableLength);
+                }
+                sfnt.extend_from_slice(&decompressed);
+            },
+            std::cmp::Ordering::Equal => {
+                // The table is stored uncompressed
+                sfnt.extend_from_slice(data);
+            },
+            std::cmp::Ordering::Greater => return Err(Error::InvalidTableLength),
+        }
+
+        sfnt.resize(sfnt.len().next_multiple_of(4), 0);
+    }
+
+    Ok(sfnt)
+}
+
+impl From<zlib::Error> for Error {
+    fn from(value: zlib::Error) -> Self {
+        Self::Zlib(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{path::PathConsumer, ttf::DEFAULT_FONT, ttf_tables::offset::OffsetTable, Font};
+    use math::Vec2D;
+
+    /// Build a WOFF file from a list of `(tag, compressed data, original length, checksum)` tuples
+    fn make_woff(tables: &[(&[u8; 4], &[u8], usize, u32)]) -> Vec<u8> {
+        let mut woff = Vec::new();
+        woff.extend_from_slice(&WOFF_SIGNATURE);
+        woff.extend_from_slice(&0x00010000_u32.to_be_bytes());
+        woff.extend_from_slice(&0_u32.to_be_bytes()); // Length (unused)
+        woff.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        woff.resize(WOFF_HEADER_SIZE, 0);
+
+        let mut offset = WOFF_HEADER_SIZE + tables.len() * WOFF_TABLE_ENTRY_SIZE;
+        for (tag, data, original_length, checksum) in tables {
+            woff.extend_from_slice(*tag);
+            woff.extend_from_slice(&(offset as u32).to_be_bytes());
+            woff.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            woff.extend_from_slice(&(*original_length as u32).to_be_bytes());
+            woff.extend_from_slice(&checksum.to_be_bytes());
+            offset += data.len().next_multiple_of(4);
+        }
+
+        for (_, data, _, _) in tables {
+            woff.extend_from_slice(data);
+            woff.resize(woff.len().next_multiple_of(4), 0);
+        }
+
+        woff
+    }
+
+    #[test]
+    fn reconstruct_sfnt() {
+        // 64 zero bytes, compressed with zlib
+        let compressed = [120, 156, 99, 96, 160, 12, 0, 0, 0, 64, 0, 1];
+
+        let woff = make_woff(&[
+            (b"abcd", &compressed, 64, 0xDEADBEEF),
+            (b"efgh", b"hello", 5, 0xCAFEBABE),
+        ]);
+        let sfnt = decode(&woff).unwrap();
+
+        let mut expected = vec![
+            0x00, 0x01, 0x00, 0x00, // Flavor
+            0x00, 0x02, // Number of tables
+            0x00, 0x20, // Search range
+            0x00, 0x01, // Entry selector
+            0x00, 0x00, // Range shift
+        ];
+        expected.extend_from_slice(b"abcd");
+        expected.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF, 0, 0, 0, 44, 0, 0, 0, 64]);
+        expected.extend_from_slice(b"efgh");
+        expected.extend_from_slice(&[0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 108, 0, 0, 0, 5]);
+        expected.extend_from_slice(&[0; 64]);
+        expected.extend_from_slice(b"hello\0\0\0");
+
+        assert_eq!(sfnt, expected);
+    }
+
+    #[test]
+    fn invalid_woff() {
+        assert!(matches!(decode(b"OTTO"), Err(Error::NotAWoff)));
+
+        let woff = make_woff(&[(b"abcd", b"too long", 4, 0)]);
+        assert!(matches!(decode(&woff), Err(Error::InvalidTableLength)));
+
+        let woff = make_woff(&[]);
+        assert!(matches!(decode(&woff), Err(Error::InvalidNumberOfTables)));
+
+        // The table directory of a file claiming to contain 0xFFFF tables is truncated
+        let mut woff = make_woff(&[]);
+        woff[12..14].copy_from_slice(&u16::MAX.to_be_bytes());
+        assert!(matches!(decode(&woff), Err(Error::UnexpectedEOF)));
+    }
+
+    /// Records all path operations instead of drawing them
+    #[derive(Default)]
+    struct PathRecorder(Vec<(char, Vec<Vec2D>)>);
+
+    impl PathConsumer for PathRecorder {
+        fn move_to(&mut self, p: Vec2D) {
+            self.0.push(('M', vec![p]));
+        }
+
+        fn line_to(&mut self, p: Vec2D) {
+            self.0.push(('L', vec![p]));
+        }
+
+        fn quad_bez_to(&mut self, p1: Vec2D, p2: Vec2D) {
+            self.0.push(('Q', vec![p1, p2]));
+        }
+    }
+
+    #[test]
+    fn woff_renders_like_ttf() {
+        // Wrap the tables of the default font in a WOFF file, compressing those that benefit from it
+        let ttf = DEFAULT_FONT.as_slice();
+        let tables: Vec<_> = OffsetTable::new(ttf)
+            .tables()
+            .iter()
+            .map(|entry| {
+                let data = &ttf[entry.offset()..][..entry.length()];
+                let compressed = zlib::compress(data);
+                let stored = if compressed.len() < data.len() {
+                    compressed
+                } else {
+                    data.to_vec()
+                };
+                (
+                    entry.tag().to_be_bytes(),
+                    stored,
+                    data.len(),
+                    entry.checksum(),
+                )
+            })
+            .collect();
+        let tables: Vec<_> = tables
+            .iter()
+            .map(|(tag, data, length, checksum)| (tag, data.as_slice(), *length, *checksum))
+            .collect();
+        let woff = make_woff(&tables);
+
+        let from_ttf = Font::new(ttf).unwrap();
+        let from_woff = Font::new(&decode(&woff).unwrap()).unwrap();
+
+        let render = |font: &Font| {
+            let mut recorder = PathRecorder::default();
+            font.render("Hello, World!", &mut recorder, 16., Vec2D::new(0., 0.));
+            recorder.0
+        };
+
+        let expected = render(&from_ttf);
+        assert!(!expected.is_empty());
+        assert_eq!(render(&from_woff), expected);
+    }
+}
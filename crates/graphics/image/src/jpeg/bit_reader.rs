@@ -32,36 +32,35 @@ impl<'a> BitReader<'a> {
     /// Doesn't advance the internal reader
     pub fn peek_u16(&self) -> u16 {
         // We will at most need three bytes
-        let first_byte = self
-            .bytes
-            .get(self.byte_offset())
-            .copied()
-            .unwrap_or_default();
-        let second_byte = self
-            .bytes
-            .get(self.byte_offset() + 1)
-            .copied()
-            .unwrap_or_default();
-        let third_byte = self
-            .bytes
-            .get(self.byte_offset() + 1)
-            .copied()
-            .unwrap_or_default();
-
-        let mut result = (second_byte as u16) << 8 | (third_byte as u16);
-
-        let bits_from_first_byte = 8 - self.bit_offset();
-        let first_mask = if bits_from_first_byte == 8 {
-            u8::MAX
-        } else {
-            (1_u8 << bits_from_first_byte) - 1
-        };
-
-        // Put the bits from the first byte at the very front
-        result >>= bits_from_first_byte;
-        result |= ((first_byte & first_mask) as u16) << (16 - bits_from_first_byte);
-
-        result
+        let byte_at = |index| self.bytes.get(index).copied().unwrap_or_default();
+        let first_byte = byte_at(self.byte_offset());
+        let second_byte = byte_at(self.byte_offset() + 1);
+        let third_byte = byte_at(self.byte_offset() + 2);
+
+        let bits = u32::from_be_bytes([0, first_byte, second_byte, third_byte]);
+
+        // Drop the bits that were already consumed from the first byte
+        (bits >> (8 - self.bit_offset())) as u16
+    }
+
+    /// Skip the remaining bits in the current byte, if any
+    pub fn align_to_byte(&mut self) {
+        self.offset = self.offset.next_multiple_of(8);
+    }
+
+    /// Consume a `RST` marker, expecting the reader to be byte-aligned
+    ///
+    /// Returns `false` if there is no marker at the current position.
+    pub fn skip_restart_marker(&mut self, expected: u8) -> bool {
+        debug_assert_eq!(self.bit_offset(), 0);
+
+        let marker = self.bytes.get(self.byte_offset()..self.byte_offset() + 2);
+        if marker != Some(&[0xFF, 0xD0 + expected]) {
+            return false;
+        }
+
+        self.advance(16);
+        true
     }
 
     /// Consume up to 16 bits at once and sign-extend them
@@ -86,3 +85,33 @@ fn extend(v: u16, length: u8) -> i16 {
         v as i16
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peek_across_bytes() {
+        let mut reader = BitReader::new(&[0b1010_1100, 0b0011_1111, 0b0101_0000]);
+        assert_eq!(reader.peek_u16(), 0b1010_1100_0011_1111);
+
+        reader.advance(3);
+        assert_eq!(reader.peek_u16(), 0b0_1100_0011_1111_010);
+
+        reader.advance(13);
+        assert_eq!(reader.peek_u16(), 0b0101_0000_0000_0000);
+    }
+
+    #[test]
+    fn extend_values() {
+        // Table F.1
+        let mut reader = BitReader::new(&[0b0111_0000]);
+        assert_eq!(reader.get_bits_extended(3), -4);
+
+        let mut reader = BitReader::new(&[0b1000_0000]);
+        assert_eq!(reader.get_bits_extended(1), 1);
+
+        let mut reader = BitReader::new(&[0b0000_0000]);
+        assert_eq!(reader.get_bits_extended(1), -1);
+    }
+}
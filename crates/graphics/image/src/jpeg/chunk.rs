@@ -196,8 +196,8 @@ fn read_compressed_data(bytes: &[u8]) -> Result<(Vec<u8>, usize), Error> {
             if matches!(next_element, Chunk::RST_BEGIN..=Chunk::RST_END) {
                 // This is a restart marker, leave it as-is
             } else if next_element == 0x00 {
-                // This is a escaped 0xFF byte
-                compressed_data.extend(&bytes[pushed_until..index]);
+                // This is a escaped 0xFF byte, keep the 0xFF and drop the 0x00
+                compressed_data.extend(&bytes[pushed_until..=index]);
                 pushed_until = next_index + 1;
             } else {
                 // This is a marker after the end of the compressed data.
@@ -232,6 +232,6 @@ mod tests {
 
         // Data with escaped 0xFF bytes
         let with_escape = &[0xFF, 0x00, 0xAA, 0xFF, 0xD9];
-        assert_eq!(&read_compressed_data(with_escape).unwrap().0, &[0xAA]);
+        assert_eq!(&read_compressed_data(with_escape).unwrap().0, &[0xFF, 0xAA]);
     }
 }
@@ -1,11 +1,18 @@
 use crate::Rgbaf32;
 
 /// Converts from [YCbCr](https://en.wikipedia.org/wiki/YCbCr) to RGB
+///
+/// The components are expected to be in the range `[0, 255]`, as specified by
+/// [JFIF](https://www.w3.org/Graphics/JPEG/jfif3.pdf).
 #[must_use]
 pub fn ycbcr_to_rgb(y: f32, cb: f32, cr: f32) -> Rgbaf32 {
-    let red = cr * (2. - 2. * 0.299) + y;
-    let blue = cb * (2. - 2. * 0.114) + y;
-    let green = (y - 0.114 * blue - 0.299 * red) / 0.587;
+    let cb = cb - 128.;
+    let cr = cr - 128.;
 
-    Rgbaf32::rgb(red, green, blue)
+    let red = y + 1.402 * cr;
+    let green = y - 0.344136 * cb - 0.714136 * cr;
+    let blue = y + 1.772 * cb;
+
+    let normalize = |value: f32| (value / 255.).clamp(0., 1.);
+    Rgbaf32::rgb(normalize(red), normalize(green), normalize(blue))
 }
@@ -6,7 +6,6 @@ use super::quantization_table::QuantizationTable;
 use std::num::Wrapping;
 
 #[rustfmt::skip]
-#[allow(dead_code)] // Only needed for encoder
 pub const ORDER_TO_MATRIX_INDEX: [(i32, i32); 64] = [
     (0,0),
     (0,1), (1,0),         
@@ -25,7 +24,22 @@ pub const ORDER_TO_MATRIX_INDEX: [(i32, i32); 64] = [
     (7,7)
 ];
 
+/// Maps the position of a coefficient in zigzag order to its index in a row-major 8x8 matrix
+pub const ZIGZAG_TO_NATURAL_ORDER: [usize; 64] = {
+    let mut result = [0; 64];
+
+    let mut i = 0;
+    while i < 64 {
+        let (row, column) = ORDER_TO_MATRIX_INDEX[i];
+        result[i] = (row * 8 + column) as usize;
+        i += 1;
+    }
+
+    result
+};
+
 #[rustfmt::skip]
+#[allow(dead_code)] // Only needed for encoder
 pub const MATRIX_INDEX_TO_ORDER: [[usize; 8]; 8] = [
     [ 0,  1,  5,  6, 14, 15, 27, 28],
     [ 2,  4,  7, 13, 16, 26, 29, 42],
@@ -55,6 +69,7 @@ pub fn dequantize_and_perform_idct(
     for i in 0..8 {
         if coefficients[i + 8] == 0
             && coefficients[i + 16] == 0
+            && coefficients[i + 24] == 0
             && coefficients[i + 32] == 0
             && coefficients[i + 40] == 0
             && coefficients[i + 48] == 0
@@ -0,0 +1,167 @@
+//! Reads the orientation of an image from its [EXIF](https://www.cipa.jp/std/documents/e/DC-X008-Translation-2019-E.pdf) metadata
+
+use crate::Texture;
+
+/// Identifies an `APP1` segment that contains EXIF data
+const EXIF_HEADER: &[u8; 6] = b"Exif\0\0";
+
+const ORIENTATION_TAG: u16 = 0x0112;
+
+/// The `SHORT` field type, an unsigned 16 bit integer
+const TYPE_SHORT: u16 = 3;
+
+/// Describes how the stored pixels have to be transformed to display the image upright
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Orientation {
+    /// The pixels are stored upright
+    #[default]
+    Normal,
+
+    /// Mirrored along the vertical axis
+    FlipHorizontal,
+
+    Rotate180,
+
+    /// Mirrored along the horizontal axis
+    FlipVertical,
+
+    /// Mirrored along the diagonal from the top left to the bottom right corner
+    Transpose,
+
+    /// Rotated clockwise by 90 degrees
+    Rotate90,
+
+    /// Mirrored along the diagonal from the top right to the bottom left corner
+    Transverse,
+
+    /// Rotated clockwise by 270 degrees
+    Rotate270,
+}
+
+impl Orientation {
+    /// Returns `true` if the width and height of the image are swapped when the
+    /// orientation is applied
+    #[must_use]
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(
+            self,
+            Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270
+        )
+    }
+
+    /// Transform the stored pixels so that the image is upright
+    #[must_use]
+    pub fn apply(&self, texture: &Texture) -> Texture {
+        if *self == Self::Normal {
+            return texture.clone();
+        }
+
+        let (width, height) = (texture.width(), texture.height());
+        let (new_width, new_height) = if self.swaps_dimensions() {
+            (height, width)
+        } else {
+            (width, height)
+        };
+
+        let mut transformed = Texture::new(new_width, new_height);
+        for y in 0..new_height {
+            for x in 0..new_width {
+                let (source_x, source_y) = match self {
+                    Self::Normal => (x, y),
+                    Self::FlipHorizontal => (width - 1 - x, y),
+                    Self::Rotate180 => (width - 1 - x, height - 1 - y),
+                    Self::FlipVertical => (x, height - 1 - y),
+                    Self::Transpose => (y, x),
+                    Self::Rotate90 => (y, height - 1 - x),
+                    Self::Transverse => (width - 1 - y, height - 1 - x),
+                    Self::Rotate270 => (width - 1 - y, x),
+                };
+
+                transformed.set_pixel(x, y, texture.get_pixel(source_x, source_y));
+            }
+        }
+
+        transformed
+    }
+}
+
+impl TryFrom<u16> for Orientation {
+    type Error = ();
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Normal),
+            2 => Ok(Self::FlipHorizontal),
+            3 => Ok(Self::Rotate180),
+            4 => Ok(Self::FlipVertical),
+            5 => Ok(Self::Transpose),
+            6 => Ok(Self::Rotate90),
+            7 => Ok(Self::Transverse),
+            8 => Ok(Self::Rotate270),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Read the `Orientation` tag from the data of an `APP1` segment
+///
+/// Returns `None` if the segment does not contain EXIF data or the tag is missing.
+#[must_use]
+pub(super) fn read_orientation(data: &[u8]) -> Option<Orientation> {
+    let tiff = data.strip_prefix(EXIF_HEADER)?;
+
+    let is_little_endian = match tiff.get(..2)? {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+
+    let read_u16 = |offset: usize| {
+        let bytes = tiff.get(offset..offset + 2)?.try_into().ok()?;
+        if is_little_endian {
+            Some(u16::from_le_bytes(bytes))
+        } else {
+            Some(u16::from_be_bytes(bytes))
+        }
+    };
+    let read_u32 = |offset: usize| {
+        let bytes = tiff.get(offset..offset + 4)?.try_into().ok()?;
+        if is_little_endian {
+            Some(u32::from_le_bytes(bytes))
+        } else {
+            Some(u32::from_be_bytes(bytes))
+        }
+    };
+
+    if read_u16(2)? != 42 {
+        log::warn!("Invalid TIFF header in EXIF data");
+        return None;
+    }
+
+    // The orientation is stored in the first image file directory
+    let directory = read_u32(4)? as usize;
+    let num_entries = read_u16(directory)? as usize;
+
+    for index in 0..num_entries {
+        let entry = directory + 2 + index * 12;
+
+        if read_u16(entry)? != ORIENTATION_TAG {
+            continue;
+        }
+
+        if read_u16(entry + 2)? != TYPE_SHORT {
+            log::warn!("EXIF orientation tag has an invalid type");
+            return None;
+        }
+
+        // Values that fit into four bytes are stored inline
+        let value = read_u16(entry + 8)?;
+        let orientation = Orientation::try_from(value).ok();
+        if orientation.is_none() {
+            log::warn!("Invalid EXIF orientation: {value}");
+        }
+        return orientation;
+    }
+
+    None
+}
@@ -19,14 +19,31 @@ pub enum IsDifferential {
     No,
 }
 
-#[derive(Clone, Copy, Debug)]
+/// A single color component of an image, like `Y`, `Cb` or `Cr`
+///
+/// See Figure B.2
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Component {
+    /// The identifier used by scans to refer to this component
+    pub id: u8,
+
+    /// Horizontal sampling factor
+    pub horizontal_sampling: u8,
+
+    /// Vertical sampling factor
+    pub vertical_sampling: u8,
+
+    /// The quantization table used by this component
+    pub quantization_table: u8,
+}
+
+#[derive(Clone, Debug)]
 pub struct FrameHeader {
     pub entropy_coding: EntropyCoding,
     pub coding_scheme: CodingScheme,
     pub is_differential: IsDifferential,
 
     /// Sample precision in bits
-    #[allow(dead_code)] // FIXME: use this
     pub sample_precision: u8,
 
     /// The height of the image
@@ -38,7 +55,7 @@ pub struct FrameHeader {
     ///
     /// If there are multiple components then this is the width of the largest one.
     pub samples_per_line: u16,
-    pub num_image_components: u8,
+    pub components: Vec<Component>,
 }
 
 impl FrameHeader {
@@ -60,7 +77,27 @@ impl FrameHeader {
                 .try_into()
                 .expect("Slice is exactly two elements long"),
         );
-        let num_image_components = bytes[5];
+        let num_image_components = bytes[5] as usize;
+
+        let component_data = bytes
+            .get(6..6 + 3 * num_image_components)
+            .ok_or(Error::BadFrame)?;
+        let mut components = Vec::with_capacity(num_image_components);
+        for component in component_data.chunks_exact(3) {
+            let horizontal_sampling = component[1] >> 4;
+            let vertical_sampling = component[1] & 0xF;
+
+            if !(1..=4).contains(&horizontal_sampling) || !(1..=4).contains(&vertical_sampling) {
+                return Err(Error::BadFrame);
+            }
+
+            components.push(Component {
+                id: component[0],
+                horizontal_sampling,
+                vertical_sampling,
+                quantization_table: component[2],
+            });
+        }
 
         let header = Self {
             coding_scheme,
@@ -69,11 +106,29 @@ impl FrameHeader {
             sample_precision,
             number_of_lines,
             samples_per_line,
-            num_image_components,
+            components,
         };
 
         Ok(header)
     }
+
+    #[must_use]
+    pub fn max_horizontal_sampling(&self) -> u8 {
+        self.components
+            .iter()
+            .map(|component| component.horizontal_sampling)
+            .max()
+            .unwrap_or(1)
+    }
+
+    #[must_use]
+    pub fn max_vertical_sampling(&self) -> u8 {
+        self.components
+            .iter()
+            .map(|component| component.vertical_sampling)
+            .max()
+            .unwrap_or(1)
+    }
 }
 
 fn decode_subscript(subscript: u8) -> Result<(CodingScheme, EntropyCoding, IsDifferential), Error> {
@@ -14,7 +14,7 @@ pub struct HuffmanTables {
 /// Implemented as in <https://commandlinefanatic.com/cgi-bin/showarticle.cgi?article=art007>
 #[derive(Clone, Debug)]
 pub struct HuffmanTable {
-    lookup_table: Box<[HuffmanTableEntry; u16::MAX as usize]>,
+    lookup_table: Box<[HuffmanTableEntry; 1 << u16::BITS]>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -25,13 +25,12 @@ pub struct HuffmanLookupResult {
 
 impl HuffmanTable {
     fn insert_symbol(&mut self, code: u16, mask: NonZeroU16, symbol: u8) {
-        let base = code << mask.trailing_zeros();
-        let repeat_count = (1 << mask.trailing_zeros()) - 1;
+        // Every 16-bit sequence that starts with the code maps to the symbol
+        let base = (code as usize) << mask.trailing_zeros();
+        let repeat_count = 1 << mask.trailing_zeros();
 
         let entry = HuffmanTableEntry { mask, symbol };
-        for i in 0..repeat_count {
-            self.lookup_table[(base + i) as usize] = entry;
-        }
+        self.lookup_table[base..base + repeat_count].fill(entry);
     }
 
     #[must_use]
@@ -61,7 +60,7 @@ impl Default for HuffmanTable {
         };
 
         Self {
-            lookup_table: Box::new([initial_symbol; u16::MAX as usize]),
+            lookup_table: Box::new([initial_symbol; 1 << u16::BITS]),
         }
     }
 }
@@ -87,33 +86,56 @@ impl HuffmanTables {
             .ok_or(Error::UndefinedHuffmanTable)
     }
 
-    pub fn add_table(&mut self, bytes: &[u8]) -> Result<(), Error> {
-        // First byte is the table id
-        let table_id = *bytes.first().ok_or(Error::BadHuffmanTable)? as usize;
+    /// Parse all tables contained in a `DHT` chunk
+    pub fn add_tables(&mut self, mut bytes: &[u8]) -> Result<(), Error> {
+        // There might be multiple huffman tables stored after one another
+        while !bytes.is_empty() {
+            bytes = self.add_table(bytes)?;
+        }
 
-        if self.tables.len() <= table_id {
+        Ok(())
+    }
+
+    /// Parse a single huffman table, returning the remaining bytes
+    ///
+    /// See Figure B.7
+    fn add_table<'a>(&mut self, bytes: &'a [u8]) -> Result<&'a [u8], Error> {
+        // First byte is the table class (high nibble) and id (low nibble)
+        let tc_th = *bytes.first().ok_or(Error::BadHuffmanTable)?;
+        if 1 < tc_th >> 4 || 3 < tc_th & 0xF {
             return Err(Error::BadHuffmanTable);
         }
+        let table_id = tc_th as usize;
 
         // Next 16 bytes are the counts for each code length
         let mut counts = [0; 16];
         counts.copy_from_slice(bytes.get(1..17).ok_or(Error::BadHuffmanTable)?);
 
+        let num_symbols = counts.iter().map(|&count| count as usize).sum::<usize>();
+        let symbols = bytes
+            .get(17..17 + num_symbols)
+            .ok_or(Error::BadHuffmanTable)?;
+
         // Remaining bytes are the data values to be mapped
         // Build the Huffman map of (length, code) -> value
-        let mut bytes = bytes[17..].iter();
+        let mut symbols = symbols.iter();
 
-        let mut code: u16 = 0;
+        let mut code: u32 = 0;
         let mut table = HuffmanTable::default();
         for code_length in 1..=16 {
-            // This computes mask as an integer whose first code_length + 1 bits are 1 and 0 otherwise
+            // This computes mask as an integer whose first code_length bits are 1 and 0 otherwise
             let mask =
                 NonZeroU16::new(!((1 << (u16::BITS - code_length)) - 1)).expect("cannot be zero");
             let n_codes_with_this_length = counts[code_length as usize - 1];
 
             for _ in 0..n_codes_with_this_length {
-                let symbol = *bytes.next().ok_or(Error::BadHuffmanTable)?;
-                table.insert_symbol(code, mask, symbol);
+                // There are not enough codes of this length for all the symbols
+                if code >> code_length != 0 {
+                    return Err(Error::BadHuffmanTable);
+                }
+
+                let symbol = *symbols.next().expect("number of symbols was checked");
+                table.insert_symbol(code as u16, mask, symbol);
                 code += 1;
             }
             code <<= 1;
@@ -121,6 +143,34 @@ impl HuffmanTables {
 
         self.tables[table_id] = Some(table);
 
-        Ok(())
+        Ok(&bytes[17 + num_symbols..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_symbols() {
+        // Two codes of length 2 ("00" and "01"), one of length 3 ("100")
+        let mut counts = [0; 16];
+        counts[1] = 2;
+        counts[2] = 1;
+
+        let mut table = vec![0x01];
+        table.extend_from_slice(&counts);
+        table.extend_from_slice(&[0xA, 0xB, 0xC]);
+
+        let mut tables = HuffmanTables::default();
+        tables.add_tables(&table).unwrap();
+
+        let table = tables.get(0x01).unwrap();
+        let mut reader = BitReader::new(&[0b01_100_00_0]);
+        assert_eq!(table.lookup_code_from_reader(&mut reader), 0xB);
+        assert_eq!(table.lookup_code_from_reader(&mut reader), 0xC);
+        assert_eq!(table.lookup_code_from_reader(&mut reader), 0xA);
+        assert_eq!(reader.byte_offset(), 0);
+        assert_eq!(reader.bit_offset(), 7);
     }
 }
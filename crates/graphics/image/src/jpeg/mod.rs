@@ -10,6 +10,7 @@ mod bit_reader;
 mod chunk;
 mod colors;
 mod cosine_transform;
+mod exif;
 mod frame_header;
 mod huffman_table;
 mod quantization_table;
@@ -17,12 +18,23 @@ mod quantization_table;
 use bit_reader::BitReader;
 use chunk::{Chunk, Chunks};
 use huffman_table::HuffmanTables;
-use quantization_table::{QuantizationTable, QuantizationTables};
+use quantization_table::QuantizationTables;
 
-use crate::{jpeg::cosine_transform::dequantize_and_perform_idct, Texture};
+use crate::{
+    jpeg::cosine_transform::{dequantize_and_perform_idct, ZIGZAG_TO_NATURAL_ORDER},
+    Rgbaf32, Texture,
+};
 
 use self::frame_header::{CodingScheme, EntropyCoding, FrameHeader, IsDifferential};
 
+pub use exif::Orientation;
+
+/// The largest number of pixels in an image that we are willing to decode
+///
+/// The dimensions from the frame header are not backed by any data in the file,
+/// so a tiny file could otherwise make us allocate gigabytes of memory.
+const MAX_PIXELS: usize = 1 << 26;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Error {
     BadChunk,
@@ -43,16 +55,40 @@ pub enum Error {
     /// A `DQT` chunk failed to parse
     BadQuantizationTable,
 
+    /// The entropy-coded data following a `SOS` chunk is malformed
+    BadScan,
+
     /// Magnitude difference outside of valid range (0-11)
     ///
     /// See Figure F.1
     InvalidDcMagnitudeDifference,
 
+    /// The image is progressive or lossless, only baseline images can be decoded
+    UnsupportedCodingScheme,
+
+    /// The sampling factors of the components are not integer multiples of each others
+    UnsupportedSubsampling,
+
     /// A feature is not yet implemented
     Unsupported,
+
+    /// The frame is larger than [MAX_PIXELS] or could not be allocated
+    ImageTooLarge,
 }
 
+/// Decode the image, rotating and flipping it according to its EXIF orientation
+/// so that it is upright
 pub fn decode(bytes: &[u8]) -> Result<Texture, Error> {
+    let (texture, orientation) = decode_raw(bytes)?;
+    Ok(orientation.apply(&texture))
+}
+
+/// Decode the image without applying its EXIF orientation
+///
+/// The pixels are returned as they are stored in the file, along with the transformation
+/// that is required to display them upright. This is useful for callers that want to
+/// apply the orientation themselves, for example through CSS.
+pub fn decode_raw(bytes: &[u8]) -> Result<(Texture, Orientation), Error> {
     Decoder::decode(bytes)
 }
 
@@ -61,22 +97,50 @@ struct Decoder {
     huffman_tables: HuffmanTables,
     quantization_tables: QuantizationTables,
 
-    /// Mapping from image components to their used
-    /// quantization tables
-    quantization_table_mapping: [u8; 3],
+    /// The number of MCUs between two `RST` markers, or zero if
+    /// restart markers are not used
+    restart_interval: u16,
 
     /// The frame currently being decoded
     current_frame: Option<Frame>,
+
+    /// Read from the EXIF metadata, if present
+    orientation: Orientation,
 }
 
 #[derive(Clone)]
 struct Frame {
     header: FrameHeader,
-    texture: Texture,
+
+    /// The decoded samples for each component, in the same order as in the frame header
+    planes: Vec<Plane>,
+
+    /// Number of MCUs in an interleaved scan, horizontally
+    mcus_per_line: usize,
+
+    /// Number of MCUs in an interleaved scan, vertically
+    mcus_per_column: usize,
+}
+
+/// The samples of a single component
+///
+/// The dimensions are always a multiple of the block size, so
+/// there might be additional samples outside of the image.
+#[derive(Clone, Debug)]
+struct Plane {
+    width: usize,
+    samples: Vec<u8>,
+}
+
+struct ScanComponent {
+    /// Index of the component in the frame header
+    index: usize,
+    dc_table: u8,
+    ac_table: u8,
 }
 
 impl Decoder {
-    fn decode(bytes: &[u8]) -> Result<Texture, Error> {
+    fn decode(bytes: &[u8]) -> Result<(Texture, Orientation), Error> {
         let mut chunks = Chunks::new(bytes);
         let mut decoder = Self::default();
 
@@ -92,6 +156,11 @@ impl Decoder {
             match chunk {
                 Chunk::EndOfImage => break,
                 Chunk::Comment(_) => {},
+                Chunk::ApplicationSpecific { subscript: 1, data } => {
+                    if let Some(orientation) = exif::read_orientation(data) {
+                        decoder.orientation = orientation;
+                    }
+                },
                 Chunk::ApplicationSpecific { .. } => {},
                 Chunk::StartOfFrame { subscript, data } => {
                     if decoder.current_frame.is_some() {
@@ -102,12 +171,17 @@ impl Decoder {
 
                     decoder.process_frame(subscript, data)?;
                 },
-                Chunk::DefineHuffmanTable(huffman_table) => {
-                    decoder.huffman_tables.add_table(huffman_table)?;
+                Chunk::DefineHuffmanTable(huffman_tables) => {
+                    decoder.huffman_tables.add_tables(huffman_tables)?;
                 },
                 Chunk::DefineQuantizationTable(quantization_table) => {
                     decoder.quantization_tables.add_tables(quantization_table)?;
                 },
+                Chunk::DefineRestartInterval(data) => {
+                    // See Figure B.9
+                    let interval: [u8; 2] = data.try_into().map_err(|_| Error::BadChunk)?;
+                    decoder.restart_interval = u16::from_be_bytes(interval);
+                },
                 Chunk::StartOfScan { header, scan } => {
                     decoder.decode_scan(header, scan)?;
                 },
@@ -120,55 +194,121 @@ impl Decoder {
             return Err(Error::IncompleteImage);
         };
 
-        Ok(frame.texture)
+        Ok((frame.into_texture(), decoder.orientation))
     }
 
     fn process_frame(&mut self, subscript: u8, bytes: &[u8]) -> Result<(), Error> {
         let frame_header = FrameHeader::new(subscript, bytes)?;
 
         if frame_header.is_differential == IsDifferential::Yes {
-            log::warn!("Differential encodings are not implemented")
+            log::error!("Differential encodings are not implemented");
+            return Err(Error::Unsupported);
         }
 
         if frame_header.entropy_coding == EntropyCoding::Arithmetic {
-            log::warn!("Arithmetic entropy encodings are not implemented")
+            log::error!("Arithmetic entropy encodings are not implemented");
+            return Err(Error::Unsupported);
         }
 
         if frame_header.coding_scheme != CodingScheme::SequentialDiscreteCosineTransform {
-            log::warn!(
+            log::error!(
                 "Lossless/Progressive coding schemes are not implemented (image uses {:?})",
                 frame_header.coding_scheme
             );
+            return Err(Error::UnsupportedCodingScheme);
         }
 
-        let texture = Texture::new(
-            frame_header.samples_per_line as usize,
-            frame_header.number_of_lines as usize,
-        );
+        if frame_header.sample_precision != 8 {
+            log::error!(
+                "Images with a sample precision of {} bits are not supported",
+                frame_header.sample_precision
+            );
+            return Err(Error::Unsupported);
+        }
 
-        let frame = Frame {
-            header: frame_header,
-            texture,
-        };
-        self.current_frame = Some(frame);
+        if frame_header.number_of_lines == 0 || frame_header.samples_per_line == 0 {
+            log::error!("FIXME: Implement DNL chunks");
+            return Err(Error::Unsupported);
+        }
 
         // Read the image components
-        if frame_header.num_image_components != 3 {
+        if !matches!(frame_header.components.len(), 1 | 3) {
             log::error!(
-                "Image has {} components but we only understand images with 3 components (YCbCr)",
-                frame_header.num_image_components
-            )
+                "Image has {} components but we only understand images with 1 (Grayscale) or 3 components (YCbCr)",
+                frame_header.components.len()
+            );
+            return Err(Error::Unsupported);
         }
 
-        let mut bytes = bytes[6..].iter();
-        for component_id in 0..frame_header.num_image_components {
-            let _id = *bytes.next().ok_or(Error::BadFrame)?;
-            let _sampling_factor = *bytes.next().ok_or(Error::BadFrame)?;
-            let used_quantization_table = *bytes.next().ok_or(Error::BadFrame)?;
+        // Section B.2.2
+        let blocks_per_mcu: u8 = frame_header
+            .components
+            .iter()
+            .map(|component| component.horizontal_sampling * component.vertical_sampling)
+            .sum();
+        if frame_header.components.len() != 1 && 10 < blocks_per_mcu {
+            return Err(Error::BadFrame);
+        }
 
-            self.quantization_table_mapping[component_id as usize] = used_quantization_table;
+        let max_horizontal_sampling = frame_header.max_horizontal_sampling();
+        let max_vertical_sampling = frame_header.max_vertical_sampling();
+        for component in &frame_header.components {
+            if max_horizontal_sampling % component.horizontal_sampling != 0
+                || max_vertical_sampling % component.vertical_sampling != 0
+            {
+                log::error!(
+                    "Cannot upsample component with sampling factors {}x{} to {}x{}",
+                    component.horizontal_sampling,
+                    component.vertical_sampling,
+                    max_horizontal_sampling,
+                    max_vertical_sampling
+                );
+                return Err(Error::UnsupportedSubsampling);
+            }
+        }
+
+        let num_pixels = (frame_header.samples_per_line as usize)
+            .checked_mul(frame_header.number_of_lines as usize)
+            .filter(|&num_pixels| num_pixels <= MAX_PIXELS);
+        if num_pixels.is_none() {
+            log::error!(
+                "Refusing to decode image of size {}x{}",
+                frame_header.samples_per_line,
+                frame_header.number_of_lines
+            );
+            return Err(Error::ImageTooLarge);
         }
 
+        let mcus_per_line =
+            (frame_header.samples_per_line as usize).div_ceil(8 * max_horizontal_sampling as usize);
+        let mcus_per_column =
+            (frame_header.number_of_lines as usize).div_ceil(8 * max_vertical_sampling as usize);
+
+        let planes = frame_header
+            .components
+            .iter()
+            .map(|component| {
+                let width = mcus_per_line * component.horizontal_sampling as usize * 8;
+                let height = mcus_per_column * component.vertical_sampling as usize * 8;
+                let num_samples = width.checked_mul(height).ok_or(Error::ImageTooLarge)?;
+
+                let mut samples = Vec::new();
+                samples
+                    .try_reserve_exact(num_samples)
+                    .map_err(|_| Error::ImageTooLarge)?;
+                samples.resize(num_samples, 0);
+
+                Ok(Plane { width, samples })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        self.current_frame = Some(Frame {
+            header: frame_header,
+            planes,
+            mcus_per_line,
+            mcus_per_column,
+        });
+
         Ok(())
     }
 
@@ -185,91 +325,121 @@ impl Decoder {
             return Err(Error::BadChunk);
         };
 
-        struct ScanComponentData {
-            #[allow(dead_code)] // FIXME: use this
-            component_selector: u8,
-            ac_table: u8,
-            dc_table: u8,
-        }
-
-        let mut component_data = vec![];
+        let mut scan_components = vec![];
         for i in 0..num_components as usize {
             let component_selector = *header.get(1 + i * 2).ok_or(Error::BadChunk)?;
             let td_ta = header.get(2 + i * 2).ok_or(Error::BadChunk)?;
 
-            let dc_table = td_ta >> 4;
-            let ac_table = td_ta & 0xF;
-
-            let component = ScanComponentData {
-                component_selector,
-                ac_table,
-                dc_table,
+            let Some(index) = frame
+                .header
+                .components
+                .iter()
+                .position(|component| component.id == component_selector)
+            else {
+                log::error!("Scan references undefined component {component_selector}");
+                return Err(Error::BadChunk);
             };
 
-            component_data.push(component);
+            scan_components.push(ScanComponent {
+                index,
+                dc_table: td_ta >> 4,
+                ac_table: td_ta & 0xF,
+            });
         }
 
-        // Start of spectral or predictor selection
-        let ss = *header
-            .get(1 + num_components as usize * 2)
-            .ok_or(Error::BadChunk)?;
-        _ = ss;
+        if scan_components.is_empty() {
+            return Err(Error::BadChunk);
+        }
 
-        // End of spectral or predictor selection
-        let se = *header
-            .get(2 + num_components as usize * 2)
+        // Start of spectral or predictor selection, end of spectral or predictor selection
+        // and successive approximation bit positions.
+        // These are fixed for sequential images
+        let selection = header
+            .get(1 + num_components as usize * 2..4 + num_components as usize * 2)
             .ok_or(Error::BadChunk)?;
-        _ = se;
+        if selection != [0, 63, 0] {
+            log::error!("Invalid spectral selection for sequential image: {selection:?}");
+            return Err(Error::BadChunk);
+        }
 
-        let ah_al = *header
-            .get(3 + num_components as usize * 2)
-            .ok_or(Error::BadChunk)?;
-        _ = ah_al;
+        // Non-interleaved scans contain the blocks of a single component, in
+        // which case a MCU is exactly one block
+        let is_interleaved = scan_components.len() != 1;
+        let (mcus_per_line, mcus_per_column) = if is_interleaved {
+            (frame.mcus_per_line, frame.mcus_per_column)
+        } else {
+            let component = frame.header.components[scan_components[0].index];
+            let width = (frame.header.samples_per_line as usize
+                * component.horizontal_sampling as usize)
+                .div_ceil(frame.header.max_horizontal_sampling() as usize);
+            let height = (frame.header.number_of_lines as usize
+                * component.vertical_sampling as usize)
+                .div_ceil(frame.header.max_vertical_sampling() as usize);
+
+            (width.div_ceil(8), height.div_ceil(8))
+        };
 
         // Decode the actual scan data
         let mut bit_reader = BitReader::new(&scan);
-
-        // (Matrix, Coefficient) for each component
-        let mut component_matrices = vec![([0; 64], 0); num_components as usize];
-        for y in 0..frame.header.number_of_lines / 8 {
-            for x in 0..frame.header.samples_per_line / 8 {
-                // Update component matrices for this MCU
-                for (i, (matrix, coefficient)) in component_matrices.iter_mut().enumerate() {
-                    let used_quantization_table = self
-                        .quantization_tables
-                        .get(self.quantization_table_mapping[i])?;
-
-                    let mut coefficients = [0; 64];
-                    decode_coefficients(
-                        &mut coefficients,
-                        &mut bit_reader,
-                        component_data[i].dc_table,
-                        component_data[i].ac_table,
-                        used_quantization_table,
-                        &self.huffman_tables,
-                        coefficient,
-                    )?;
-
-                    // De-quantize the coefficients
-                    dequantize_and_perform_idct(&coefficients, used_quantization_table, matrix)
+        let mut dc_predictions = vec![0; scan_components.len()];
+        let mut next_restart_marker = 0;
+
+        for mcu_index in 0..mcus_per_line * mcus_per_column {
+            // Section F.2.1.3
+            if self.restart_interval != 0
+                && mcu_index != 0
+                && mcu_index % self.restart_interval as usize == 0
+            {
+                bit_reader.align_to_byte();
+                if !bit_reader.skip_restart_marker(next_restart_marker) {
+                    log::error!("Expected RST{next_restart_marker} marker after MCU {mcu_index}");
+                    return Err(Error::BadScan);
                 }
 
-                // Write the decoded block to the texture
-                for block_offset_y in 0..8 {
-                    for block_offset_x in 0..8 {
-                        let index =
-                            cosine_transform::MATRIX_INDEX_TO_ORDER[block_offset_x][block_offset_y];
-
-                        let luminance = component_matrices[0].0[index];
-                        let cr = component_matrices[1].0[index];
-                        let cb = component_matrices[2].0[index];
+                next_restart_marker = (next_restart_marker + 1) % 8;
+                dc_predictions.fill(0);
+            }
 
-                        let color = colors::ycbcr_to_rgb(luminance as f32, cb as f32, cr as f32);
+            let mcu_x = mcu_index % mcus_per_line;
+            let mcu_y = mcu_index / mcus_per_line;
+
+            for (scan_component, dc_prediction) in scan_components.iter().zip(&mut dc_predictions) {
+                let component = frame.header.components[scan_component.index];
+                let quantization_table =
+                    self.quantization_tables.get(component.quantization_table)?;
+
+                let (blocks_per_line, blocks_per_column) = if is_interleaved {
+                    (
+                        component.horizontal_sampling as usize,
+                        component.vertical_sampling as usize,
+                    )
+                } else {
+                    (1, 1)
+                };
+
+                for block_y in 0..blocks_per_column {
+                    for block_x in 0..blocks_per_line {
+                        let mut coefficients = [0; 64];
+                        decode_coefficients(
+                            &mut coefficients,
+                            &mut bit_reader,
+                            scan_component.dc_table,
+                            scan_component.ac_table,
+                            &self.huffman_tables,
+                            dc_prediction,
+                        )?;
+
+                        let mut samples = [0; 64];
+                        dequantize_and_perform_idct(
+                            &coefficients,
+                            quantization_table,
+                            &mut samples,
+                        );
 
-                        frame.texture.set_pixel(
-                            x as usize * 8 + block_offset_x,
-                            y as usize * 8 + block_offset_y,
-                            color,
+                        frame.planes[scan_component.index].write_block(
+                            (mcu_x * blocks_per_line + block_x) * 8,
+                            (mcu_y * blocks_per_column + block_y) * 8,
+                            &samples,
                         );
                     }
                 }
@@ -280,12 +450,57 @@ impl Decoder {
     }
 }
 
+impl Frame {
+    /// Upsample all components to the size of the image and convert them to RGB
+    fn into_texture(self) -> Texture {
+        let width = self.header.samples_per_line as usize;
+        let height = self.header.number_of_lines as usize;
+        let max_horizontal_sampling = self.header.max_horizontal_sampling() as usize;
+        let max_vertical_sampling = self.header.max_vertical_sampling() as usize;
+
+        let sample = |component_index: usize, x: usize, y: usize| {
+            let component = self.header.components[component_index];
+            let plane = &self.planes[component_index];
+
+            let x = x * component.horizontal_sampling as usize / max_horizontal_sampling;
+            let y = y * component.vertical_sampling as usize / max_vertical_sampling;
+            plane.samples[y * plane.width + x] as f32
+        };
+
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let color = if self.planes.len() == 1 {
+                    Rgbaf32::grayscale(sample(0, x, y) / 255.)
+                } else {
+                    colors::ycbcr_to_rgb(sample(0, x, y), sample(1, x, y), sample(2, x, y))
+                };
+
+                data.push(color);
+            }
+        }
+
+        Texture::from_data(data, width, height)
+    }
+}
+
+impl Plane {
+    fn write_block(&mut self, x: usize, y: usize, block: &[u8; 64]) {
+        for (row_index, row) in block.chunks_exact(8).enumerate() {
+            let start = (y + row_index) * self.width + x;
+            self.samples[start..start + 8].copy_from_slice(row);
+        }
+    }
+}
+
+/// Decode the coefficients of a single block, in natural order
+///
+/// See Section F.2.2
 fn decode_coefficients(
     coefficients: &mut [i16; 64],
     reader: &mut BitReader<'_>,
     dc_table: u8,
     ac_table: u8,
-    quantization_table: &QuantizationTable,
     huffman_tables: &HuffmanTables,
     dc_coefficient: &mut i16,
 ) -> Result<(), Error> {
@@ -302,35 +517,208 @@ fn decode_coefficients(
         },
     };
 
-    *dc_coefficient += magnitude_difference;
+    *dc_coefficient = dc_coefficient.wrapping_add(magnitude_difference);
 
     // Assign DC
-    coefficients[0] = *dc_coefficient * quantization_table[0] as i16;
+    coefficients[0] = *dc_coefficient;
 
     // Assign AC values
+    // F.2.2.2
+    let ac_table = huffman_tables.get(ac_table + 16)?;
     let mut ac_index = 1;
     while ac_index < 64 {
-        let code = huffman_tables
-            .get(ac_table + 16)?
-            .lookup_code_from_reader(reader);
+        let code = ac_table.lookup_code_from_reader(reader);
 
-        if code == 0 {
+        // End of block
+        if code == 0x00 {
             break;
         }
 
         let n_ac_codes_to_skip = code >> 4;
         let length_of_v = code & 0xF;
 
-        ac_index += n_ac_codes_to_skip;
-
-        let ac_coefficient = reader.get_bits_extended(length_of_v);
+        // Codes with a length of zero are only allowed as "End of block" (0x00) or "Zero run length" (0xF0)
+        if length_of_v == 0 && code != 0xF0 {
+            return Err(Error::BadScan);
+        }
 
-        if ac_index < 64 {
-            coefficients[ac_index as usize] =
-                ac_coefficient * quantization_table[ac_index as usize] as i16;
-            ac_index += 1;
+        ac_index += n_ac_codes_to_skip as usize;
+        if 63 < ac_index {
+            return Err(Error::BadScan);
         }
+
+        coefficients[ZIGZAG_TO_NATURAL_ORDER[ac_index]] = reader.get_bits_extended(length_of_v);
+        ac_index += 1;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Small baseline images with image-specific huffman tables and
+    // quantization tables that are all ones
+    const QUADRANTS: [u8; 346] = [
+        0xff, 0xd8, 0xff, 0xdb, 0x00, 0x43, 0x00, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0xff, 0xdb, 0x00, 0x43,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0xff, 0xc0, 0x00, 0x11, 0x08, 0x00, 0x10, 0x00, 0x10, 0x03,
+        0x01, 0x22, 0x00, 0x02, 0x11, 0x01, 0x03, 0x11, 0x01, 0xff, 0xc4, 0x00, 0x16, 0x00, 0x01,
+        0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x0a, 0x0b, 0x09, 0xff, 0xc4, 0x00, 0x14, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xc4, 0x00, 0x38, 0x01,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x11, 0x00, 0x00, 0x03, 0x04, 0x0b, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x15, 0x16, 0x17, 0x09, 0x14, 0x18, 0x45, 0x05, 0x07, 0x13, 0x19,
+        0x23, 0x25, 0x43, 0x47, 0x64, 0x65, 0x67, 0x28, 0xff, 0xda, 0x00, 0x0c, 0x03, 0x01, 0x00,
+        0x02, 0x11, 0x03, 0x11, 0x00, 0x3f, 0x00, 0xc6, 0x12, 0x4b, 0x03, 0xb5, 0xc3, 0xc6, 0x99,
+        0x30, 0xc6, 0x1c, 0x91, 0x3f, 0xa8, 0x8e, 0x47, 0x25, 0x23, 0x64, 0xcb, 0xc1, 0xc5, 0xe2,
+        0x17, 0xae, 0x53, 0x8f, 0x8f, 0x83, 0x9c, 0x57, 0x77, 0x5d, 0x7b, 0x7c, 0x13, 0x60, 0xeb,
+        0x2e, 0xfa, 0x38, 0x77, 0xc9, 0x61, 0xb2, 0x1b, 0x15, 0xb9, 0x92, 0xc4, 0x74, 0x58, 0x93,
+        0x2e, 0x05, 0x56, 0x17, 0x0b, 0x89, 0x67, 0x7a, 0x2e, 0x3b, 0x2b, 0x0b, 0xcc, 0x7f, 0xff,
+        0xd9,
+    ];
+
+    const GRAYSCALE_BANDS: [u8; 162] = [
+        0xff, 0xd8, 0xff, 0xdb, 0x00, 0x43, 0x00, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0xff, 0xc0, 0x00, 0x0b,
+        0x08, 0x00, 0x07, 0x00, 0x1e, 0x01, 0x01, 0x11, 0x00, 0xff, 0xc4, 0x00, 0x16, 0x00, 0x01,
+        0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x09, 0x0a, 0x0b, 0xff, 0xc4, 0x00, 0x14, 0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xdd, 0x00, 0x04, 0x00,
+        0x01, 0xff, 0xda, 0x00, 0x08, 0x01, 0x01, 0x00, 0x00, 0x3f, 0x00, 0xcf, 0xfd, 0xff, 0xd0,
+        0x29, 0xdf, 0xff, 0xd1, 0x54, 0x1f, 0xff, 0xd2, 0xbf, 0x87, 0xff, 0xd9,
+    ];
+
+    const PROGRESSIVE: [u8; 84] = [
+        0xff, 0xd8, 0xff, 0xdb, 0x00, 0x43, 0x00, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0xff, 0xc2, 0x00, 0x0b,
+        0x08, 0x00, 0x08, 0x00, 0x08, 0x01, 0x01, 0x11, 0x00,
+    ];
+
+    /// Returns the pixel as 8-bit RGB
+    fn rgb_at(texture: &Texture, x: usize, y: usize) -> [u8; 3] {
+        let pixel = texture.get_pixel(x, y);
+        [pixel.red(), pixel.green(), pixel.blue()].map(|c| (c * 255.).round() as u8)
+    }
+
+    fn assert_close(actual: [u8; 3], expected: [u8; 3]) {
+        let close = actual
+            .iter()
+            .zip(expected)
+            .all(|(&a, e)| a.abs_diff(e) <= 2);
+        assert!(close, "expected {expected:?}, found {actual:?}");
+    }
+
+    #[test]
+    fn decode_subsampled_color() {
+        // 4:2:0 chroma subsampling, with a red, green, blue and white quadrant
+        let texture = decode(&QUADRANTS).unwrap();
+        assert_eq!(texture.width(), 16);
+        assert_eq!(texture.height(), 16);
+
+        for (x, y) in [(0, 0), (7, 7), (3, 5)] {
+            assert_close(rgb_at(&texture, x, y), [255, 0, 0]);
+            assert_close(rgb_at(&texture, x + 8, y), [0, 255, 0]);
+            assert_close(rgb_at(&texture, x, y + 8), [0, 0, 255]);
+            assert_close(rgb_at(&texture, x + 8, y + 8), [255, 255, 255]);
+        }
+    }
+
+    #[test]
+    fn decode_grayscale_with_restart_markers() {
+        // Four vertical bands, with a restart marker after every block.
+        // The width and height are not multiples of the block size.
+        let texture = decode(&GRAYSCALE_BANDS).unwrap();
+        assert_eq!(texture.width(), 30);
+        assert_eq!(texture.height(), 7);
+
+        for y in 0..7 {
+            for x in 0..30 {
+                let value = [0, 85, 170, 255][x / 8];
+                assert_close(rgb_at(&texture, x, y), [value; 3]);
+            }
+        }
+    }
+
+    /// Insert an `APP1` segment with the given EXIF orientation after the `SOI` marker
+    fn with_orientation(jpeg: &[u8], orientation: u16) -> Vec<u8> {
+        #[rustfmt::skip]
+        let tiff = [
+            // Big endian, first directory at offset 8
+            b'M', b'M', 0x00, 0x2a, 0x00, 0x00, 0x00, 0x08,
+            // One entry: orientation, type SHORT, count 1
+            0x00, 0x01, 0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01,
+            (orientation >> 8) as u8, orientation as u8, 0x00, 0x00,
+            // No further directories
+            0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let length = (2 + 6 + tiff.len()) as u16;
+        let mut result = jpeg[..2].to_vec();
+        result.extend_from_slice(&[0xff, 0xe1]);
+        result.extend_from_slice(&length.to_be_bytes());
+        result.extend_from_slice(b"Exif\0\0");
+        result.extend_from_slice(&tiff);
+        result.extend_from_slice(&jpeg[2..]);
+        result
+    }
+
+    #[test]
+    fn apply_exif_orientation() {
+        // The bands are stored horizontally, but the image should be rotated clockwise
+        let rotated = with_orientation(&GRAYSCALE_BANDS, 6);
+
+        let (raw, orientation) = decode_raw(&rotated).unwrap();
+        assert_eq!(orientation, Orientation::Rotate90);
+        assert_eq!((raw.width(), raw.height()), (30, 7));
+
+        let texture = decode(&rotated).unwrap();
+        assert_eq!((texture.width(), texture.height()), (7, 30));
+        for y in 0..30 {
+            for x in 0..7 {
+                let value = [0, 85, 170, 255][y / 8];
+                assert_close(rgb_at(&texture, x, y), [value; 3]);
+            }
+        }
+
+        // Images without EXIF data are left as-is
+        let (_, orientation) = decode_raw(&GRAYSCALE_BANDS).unwrap();
+        assert_eq!(orientation, Orientation::Normal);
+    }
+
+    #[test]
+    fn reject_huge_frame() {
+        // A baseline frame header claiming to be 65535x65535 pixels, without any scans
+        #[rustfmt::skip]
+        let huge = [
+            0xff, 0xd8,
+            0xff, 0xc0, 0x00, 0x0b, 0x08, 0xff, 0xff, 0xff, 0xff, 0x01, 0x01, 0x11, 0x00,
+            0xff, 0xd9,
+        ];
+
+        assert!(matches!(decode(&huge), Err(Error::ImageTooLarge)));
+    }
+
+    #[test]
+    fn reject_progressive() {
+        assert!(matches!(
+            decode(&PROGRESSIVE),
+            Err(Error::UnsupportedCodingScheme)
+        ));
+    }
+}
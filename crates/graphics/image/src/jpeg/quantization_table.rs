@@ -1,6 +1,6 @@
 use sl_std::{bytestream::ByteStream, safe_casts::Plain};
 
-use super::Error;
+use super::{cosine_transform::ZIGZAG_TO_NATURAL_ORDER, Error};
 
 const MAX_QUANTIZATION_TABLES: usize = 4;
 
@@ -16,12 +16,14 @@ enum Precision {
     U16,
 }
 
+/// Quantization values in natural (row-major) order
 pub type QuantizationTable = [u16; 64];
 
 impl QuantizationTables {
     pub fn get(&self, index: u8) -> Result<&QuantizationTable, Error> {
-        self.tables[index as usize]
-            .as_ref()
+        self.tables
+            .get(index as usize)
+            .and_then(Option::as_ref)
             .ok_or(Error::UndefinedQuantizationTable)
     }
 
@@ -31,7 +33,7 @@ impl QuantizationTables {
         // There might be multiple quantization tables stored after one another
         while !byte_stream.is_empty() {
             let pq_tq = byte_stream.next_byte().ok_or(Error::BadChunk)?;
-            let precision = match pq_tq & 0xF0 {
+            let precision = match pq_tq >> 4 {
                 0 => Precision::U8,
                 1 => Precision::U16,
                 _ => return Err(Error::BadQuantizationTable),
@@ -77,7 +79,17 @@ impl QuantizationTables {
                 return Err(Error::ZeroInQuantizationTable);
             }
 
-            self.tables[destination] = Some(quantization_table);
+            // The values are stored in zigzag order, but we want them in the same order as the
+            // decoded coefficients
+            let mut table_in_natural_order = [0; 64];
+            for (&value, &index) in quantization_table
+                .iter()
+                .zip(ZIGZAG_TO_NATURAL_ORDER.iter())
+            {
+                table_in_natural_order[index] = value;
+            }
+
+            self.tables[destination] = Some(table_in_natural_order);
         }
 
         Ok(())
@@ -5,4 +5,4 @@ pub mod jpeg;
 pub mod png;
 mod texture;
 
-pub use texture::{AccessMode, Rgbaf32, Texture};
+pub use texture::{AccessMode, Rgbaf32, ScaleFilter, Texture};
@@ -0,0 +1,273 @@
+//! Decodes [animated PNG](https://www.w3.org/TR/png/#apng-frame-based-animation) files
+
+use std::time::Duration;
+
+use crate::{texture::Rgbaf32, Texture};
+
+use super::{
+    chunks::{
+        fctl::{BlendOp, DisposeOp},
+        FrameControl,
+    },
+    decode_image_data, parse, Error,
+};
+
+/// The frames of an animated png
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub frames: Vec<Frame>,
+
+    /// The number of times the animation should be played, `0` means forever
+    pub loop_count: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Frame {
+    /// The contents of the whole canvas while the frame is displayed
+    pub texture: Texture,
+
+    /// The time for which the frame should be displayed
+    pub delay: Duration,
+
+    /// The region of the canvas that the frame is rendered to and how it
+    /// is composited
+    pub control: FrameControl,
+}
+
+/// Decode all frames of an animated png
+///
+/// A png without animation control chunk decodes to a single frame that is
+/// displayed forever.
+pub fn decode_animation(bytes: &[u8]) -> Result<Animation, Error> {
+    let png = parse(bytes)?;
+    let canvas_width = png.image_header.width as usize;
+    let canvas_height = png.image_header.height as usize;
+
+    let Some(animation_control) = png.animation_control.filter(|_| !png.frames.is_empty()) else {
+        let texture = decode_image_data(&png, canvas_width, canvas_height, &png.idat)?;
+        let frame = Frame {
+            texture,
+            delay: Duration::ZERO,
+            control: FrameControl {
+                sequence_number: 0,
+                width: png.image_header.width,
+                height: png.image_header.height,
+                x_offset: 0,
+                y_offset: 0,
+                delay_num: 0,
+                delay_den: 0,
+                dispose_op: DisposeOp::None,
+                blend_op: BlendOp::Source,
+            },
+        };
+
+        return Ok(Animation {
+            frames: vec![frame],
+            loop_count: 0,
+        });
+    };
+
+    if animation_control.num_frames as usize != png.frames.len() {
+        log::warn!(
+            "acTL chunk announces {} frames, found {}",
+            animation_control.num_frames,
+            png.frames.len()
+        );
+    }
+
+    let mut canvas = Texture::new(canvas_width, canvas_height);
+    let mut frames = Vec::with_capacity(png.frames.len());
+
+    for (index, (control, data)) in png.frames.iter().enumerate() {
+        let frame_width = control.width as usize;
+        let frame_height = control.height as usize;
+        let x_offset = control.x_offset as usize;
+        let y_offset = control.y_offset as usize;
+
+        if canvas_width < x_offset + frame_width || canvas_height < y_offset + frame_height {
+            log::warn!("Frame {index} extends past the boundaries of the image");
+            return Err(Error::FrameOutsideOfCanvas);
+        }
+
+        let uses_default_image = index == 0 && png.default_image_is_first_frame;
+        let data = if uses_default_image { &png.idat } else { data };
+        let frame_texture = decode_image_data(&png, frame_width, frame_height, data)?;
+
+        let mut dispose_op = control.dispose_op;
+        if index == 0 && dispose_op == DisposeOp::Previous {
+            // There is no previous frame, so the region is cleared instead
+            dispose_op = DisposeOp::Background;
+        }
+
+        // The region is only saved if it has to be restored later
+        let previous_region = (dispose_op == DisposeOp::Previous)
+            .then(|| copy_region(&canvas, x_offset, y_offset, frame_width, frame_height));
+
+        for y in 0..frame_height {
+            for x in 0..frame_width {
+                let source = frame_texture.get_pixel(x, y);
+                let pixel = canvas.pixel_data_mut(x_offset + x, y_offset + y);
+
+                *pixel = match control.blend_op {
+                    BlendOp::Source => source,
+                    BlendOp::Over => pixel.blend(source),
+                };
+            }
+        }
+
+        frames.push(Frame {
+            texture: canvas.clone(),
+            delay: control.delay(),
+            control: *control,
+        });
+
+        match dispose_op {
+            DisposeOp::None => {},
+            DisposeOp::Background => {
+                for y in y_offset..y_offset + frame_height {
+                    for x in x_offset..x_offset + frame_width {
+                        canvas.set_pixel(x, y, Rgbaf32::BLANK);
+                    }
+                }
+            },
+            DisposeOp::Previous => {
+                let previous_region =
+                    previous_region.expect("region is saved if it has to be restored");
+                for y in 0..frame_height {
+                    for x in 0..frame_width {
+                        canvas.set_pixel(
+                            x_offset + x,
+                            y_offset + y,
+                            previous_region.get_pixel(x, y),
+                        );
+                    }
+                }
+            },
+        }
+    }
+
+    Ok(Animation {
+        frames,
+        loop_count: animation_control.num_plays,
+    })
+}
+
+fn copy_region(texture: &Texture, x: usize, y: usize, width: usize, height: usize) -> Texture {
+    let data = (0..width * height)
+        .map(|i| texture.get_pixel(x + i % width, y + i / width))
+        .collect();
+
+    Texture::from_data(data, width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use compression::zlib;
+
+    use crate::png::{chunks::ihdr::ImageType, encode::write_chunk, Filter, PNG_HEADER};
+
+    fn frame_control(
+        sequence_number: u32,
+        (width, height): (u32, u32),
+        (x_offset, y_offset): (u32, u32),
+        (delay_num, delay_den): (u16, u16),
+        blend_op: u8,
+    ) -> Vec<u8> {
+        let mut data = vec![];
+        for value in [sequence_number, width, height, x_offset, y_offset] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(&delay_num.to_be_bytes());
+        data.extend_from_slice(&delay_den.to_be_bytes());
+
+        // Dispose op none
+        data.extend_from_slice(&[0, blend_op]);
+        data
+    }
+
+    /// Compress unfiltered scanlines of rgba pixels
+    fn image_data(scanlines: &[&[u8]]) -> Vec<u8> {
+        let mut data = vec![];
+        for scanline in scanlines {
+            data.push(Filter::None as u8);
+            data.extend_from_slice(scanline);
+        }
+        zlib::compress(&data)
+    }
+
+    fn rgba_at(texture: &Texture, x: usize, y: usize) -> [u8; 4] {
+        let pixel = texture.get_pixel(x, y);
+        [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            .map(|value| (value * 255.).round() as u8)
+    }
+
+    /// A 2x1 image that starts out red and then has a half transparent
+    /// blue pixel composited over its right half
+    fn two_frame_animation() -> Vec<u8> {
+        let mut png = PNG_HEADER.to_vec();
+
+        let mut image_header = vec![];
+        image_header.extend_from_slice(&2_u32.to_be_bytes());
+        image_header.extend_from_slice(&1_u32.to_be_bytes());
+        image_header.extend_from_slice(&[8, ImageType::TrueColorWithAlpha.into(), 0, 0, 0]);
+        write_chunk(&mut png, b"IHDR", &image_header);
+
+        // Two frames, played three times
+        write_chunk(&mut png, b"acTL", &[0, 0, 0, 2, 0, 0, 0, 3]);
+
+        // The default image is the first frame
+        let first_frame = frame_control(0, (2, 1), (0, 0), (1, 10), 0);
+        write_chunk(&mut png, b"fcTL", &first_frame);
+        let red = [0xFF, 0x00, 0x00, 0xFF];
+        write_chunk(&mut png, b"IDAT", &image_data(&[&[red, red].concat()]));
+
+        let second_frame = frame_control(1, (1, 1), (1, 0), (20, 0), 1);
+        write_chunk(&mut png, b"fcTL", &second_frame);
+        let mut frame_data = 2_u32.to_be_bytes().to_vec();
+        frame_data.extend(image_data(&[&[0x00, 0x00, 0xFF, 0x80]]));
+        write_chunk(&mut png, b"fdAT", &frame_data);
+
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn two_frames() {
+        let animation = decode_animation(&two_frame_animation()).unwrap();
+        assert_eq!(animation.loop_count, 3);
+        assert_eq!(animation.frames.len(), 2);
+
+        let first_frame = &animation.frames[0];
+        assert_eq!(first_frame.delay, Duration::from_millis(100));
+        assert_eq!(
+            rgba_at(&first_frame.texture, 0, 0),
+            [0xFF, 0x00, 0x00, 0xFF]
+        );
+        assert_eq!(
+            rgba_at(&first_frame.texture, 1, 0),
+            [0xFF, 0x00, 0x00, 0xFF]
+        );
+
+        // The second frame only covers the right pixel and is blended with the first one
+        let second_frame = &animation.frames[1];
+        assert_eq!(second_frame.delay, Duration::from_millis(200));
+        assert_eq!(second_frame.control.x_offset, 1);
+        assert_eq!(
+            rgba_at(&second_frame.texture, 0, 0),
+            [0xFF, 0x00, 0x00, 0xFF]
+        );
+        assert_eq!(
+            rgba_at(&second_frame.texture, 1, 0),
+            [0x7F, 0x00, 0x80, 0xFF]
+        );
+    }
+
+    #[test]
+    fn default_image_of_animation() {
+        // Decoders that don't support animations display the default image
+        let texture = crate::png::decode(&two_frame_animation()).unwrap();
+        assert_eq!(rgba_at(&texture, 1, 0), [0xFF, 0x00, 0x00, 0xFF]);
+    }
+}
@@ -0,0 +1,33 @@
+//! [acTL](https://www.w3.org/TR/png/#acTL-chunk) chunk
+
+#[derive(Clone, Copy, Debug)]
+pub struct AnimationControl {
+    /// The number of frames in the animation
+    pub num_frames: u32,
+
+    /// The number of times the animation should be played, `0` means forever
+    pub num_plays: u32,
+}
+
+impl AnimationControl {
+    #[must_use]
+    pub fn new(data: &[u8]) -> Option<Self> {
+        if data.len() != 8 {
+            log::warn!("acTL length must be exactly 8 bytes, found {}", data.len());
+            return None;
+        }
+
+        let num_frames = u32::from_be_bytes(data[0..4].try_into().unwrap());
+        let num_plays = u32::from_be_bytes(data[4..8].try_into().unwrap());
+
+        if num_frames == 0 {
+            log::warn!("acTL chunk must specify at least one frame");
+            return None;
+        }
+
+        Some(Self {
+            num_frames,
+            num_plays,
+        })
+    }
+}
@@ -0,0 +1,124 @@
+//! [fcTL](https://www.w3.org/TR/png/#fcTL-chunk) chunk
+
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug)]
+pub enum FrameControlError {
+    IncorrectNumberOfBytes,
+    EmptyFrame,
+    UnknownDisposeOp,
+    UnknownBlendOp,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FrameControl {
+    pub sequence_number: u32,
+    pub width: u32,
+    pub height: u32,
+    pub x_offset: u32,
+    pub y_offset: u32,
+    pub delay_num: u16,
+    pub delay_den: u16,
+    pub dispose_op: DisposeOp,
+    pub blend_op: BlendOp,
+}
+
+/// Describes what happens to the frame region after the frame was displayed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisposeOp {
+    /// The canvas is left as-is
+    None,
+
+    /// The frame region is cleared to fully transparent black
+    Background,
+
+    /// The frame region is reverted to what it was before the frame was rendered
+    Previous,
+}
+
+/// Describes how the frame is rendered onto the canvas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendOp {
+    /// The frame replaces the contents of the frame region
+    Source,
+
+    /// The frame is alpha-composited on top of the frame region
+    Over,
+}
+
+impl FrameControl {
+    pub fn new(data: &[u8]) -> Result<Self, FrameControlError> {
+        if data.len() != 26 {
+            log::warn!("fcTL length must be exactly 26 bytes, found {}", data.len());
+            return Err(FrameControlError::IncorrectNumberOfBytes);
+        }
+
+        let read_u32 =
+            |offset: usize| u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap());
+        let read_u16 =
+            |offset: usize| u16::from_be_bytes(data[offset..offset + 2].try_into().unwrap());
+
+        let width = read_u32(4);
+        let height = read_u32(8);
+        if width == 0 || height == 0 {
+            log::warn!("fcTL chunk describes an empty frame ({width}x{height})");
+            return Err(FrameControlError::EmptyFrame);
+        }
+
+        Ok(Self {
+            sequence_number: read_u32(0),
+            width,
+            height,
+            x_offset: read_u32(12),
+            y_offset: read_u32(16),
+            delay_num: read_u16(20),
+            delay_den: read_u16(22),
+            dispose_op: data[24].try_into()?,
+            blend_op: data[25].try_into()?,
+        })
+    }
+
+    /// The time for which the frame should be displayed
+    #[must_use]
+    pub fn delay(&self) -> Duration {
+        // A denominator of zero means that the delay is given in hundredths of a second
+        let denominator = if self.delay_den == 0 {
+            100
+        } else {
+            self.delay_den
+        };
+
+        Duration::from_secs_f64(self.delay_num as f64 / denominator as f64)
+    }
+}
+
+impl TryFrom<u8> for DisposeOp {
+    type Error = FrameControlError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Background),
+            2 => Ok(Self::Previous),
+            _ => {
+                log::warn!("Unknown dispose op: {value}");
+                Err(FrameControlError::UnknownDisposeOp)
+            },
+        }
+    }
+}
+
+impl TryFrom<u8> for BlendOp {
+    type Error = FrameControlError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Source),
+            1 => Ok(Self::Over),
+            _ => {
+                log::warn!("Unknown blend op: {value}");
+                Err(FrameControlError::UnknownBlendOp)
+            },
+        }
+    }
+}
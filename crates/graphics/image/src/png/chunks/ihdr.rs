@@ -104,6 +104,18 @@ impl ImageType {
     }
 }
 
+impl From<ImageType> for u8 {
+    fn from(value: ImageType) -> Self {
+        match value {
+            ImageType::GrayScale => 0,
+            ImageType::TrueColor => 2,
+            ImageType::IndexedColor => 3,
+            ImageType::GrayScaleWithAlpha => 4,
+            ImageType::TrueColorWithAlpha => 6,
+        }
+    }
+}
+
 impl TryFrom<u8> for ImageType {
     type Error = ImageHeaderError;
 
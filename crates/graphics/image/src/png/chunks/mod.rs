@@ -1,9 +1,13 @@
+pub mod actl;
 pub mod chrm;
+pub mod fctl;
 pub mod idat;
 pub mod ihdr;
 pub mod plte;
 
+pub use actl::AnimationControl;
 pub use chrm::Chromacities;
+pub use fctl::FrameControl;
 pub use idat::ImageData;
 pub use ihdr::ImageHeader;
 pub use plte::Palette;
@@ -1,29 +1,29 @@
 //! [PLTE](https://www.w3.org/TR/png/#11PLTE) chunk
 
-use std::ops::Index;
-
 use crate::texture::Rgbaf32;
 
-const PALETTE_MAX_SIZE: usize = 256 * 3;
+/// The maximum number of colors in a palette
+const PALETTE_MAX_SIZE: usize = 256;
 
 #[derive(Clone, Copy, Debug)]
 pub enum PaletteError {
     InvalidPaletteSize,
     TooLong,
+
+    /// The `tRNS` chunk contains more alpha values than there are palette entries
+    TooManyAlphaValues,
 }
 
 #[derive(Clone, Debug)]
 pub struct Palette {
-    /// We always store 256 colors (the maximum), even if the actual
-    /// size of the palette is less, to not have to worry about out of bounds errors
-    colors: Box<[Rgbaf32; PALETTE_MAX_SIZE]>,
+    colors: Vec<Rgbaf32>,
 }
 
 impl Palette {
     pub fn new(bytes: &[u8]) -> Result<Self, PaletteError> {
         let color_values = bytes.array_chunks::<3>();
 
-        if !color_values.remainder().is_empty() {
+        if !color_values.remainder().is_empty() || color_values.len() == 0 {
             return Err(PaletteError::InvalidPaletteSize);
         }
 
@@ -31,23 +31,33 @@ impl Palette {
             return Err(PaletteError::TooLong);
         }
 
-        // [T; PALETTE_MAX_SIZE] does not implement Default:
-        // https://rust-lang.github.io/project-const-generics/vision/status_quo/array_default.html
-        let mut colors = Box::new([Rgbaf32::default(); PALETTE_MAX_SIZE]);
-
-        for (slot, [r, g, b]) in colors.iter_mut().zip(color_values) {
-            *slot = Rgbaf32::rgb(*r as f32 / 255., *g as f32 / 255., *b as f32 / 255.);
-        }
+        let colors = color_values
+            .map(|[r, g, b]| Rgbaf32::rgb(*r as f32 / 255., *g as f32 / 255., *b as f32 / 255.))
+            .collect();
 
         let palette = Self { colors };
         Ok(palette)
     }
-}
 
-impl Index<u8> for Palette {
-    type Output = Rgbaf32;
+    /// Apply the contents of a [tRNS](https://www.w3.org/TR/png/#11tRNS) chunk
+    ///
+    /// The `n`-th byte is the alpha value of the `n`-th palette entry. Entries without
+    /// an alpha value remain fully opaque.
+    pub fn apply_transparency(&mut self, alpha_values: &[u8]) -> Result<(), PaletteError> {
+        if alpha_values.len() > self.colors.len() {
+            return Err(PaletteError::TooManyAlphaValues);
+        }
+
+        for (color, alpha) in self.colors.iter_mut().zip(alpha_values) {
+            color.set_alpha(*alpha as f32 / 255.);
+        }
+
+        Ok(())
+    }
 
-    fn index(&self, index: u8) -> &Self::Output {
-        &self.colors[index as usize]
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: u8) -> Option<Rgbaf32> {
+        self.colors.get(index as usize).copied()
     }
 }
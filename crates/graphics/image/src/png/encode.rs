@@ -0,0 +1,208 @@
+//! Encodes a [Texture] as a PNG file
+
+use compression::zlib;
+use hash::Crc32Hasher;
+
+use crate::{texture::Rgbaf32, Texture};
+
+use super::{chunks::ihdr::ImageType, Error, Filter, PNG_HEADER};
+
+/// Encode the texture as a PNG file with the given color type
+///
+/// Every channel is stored with a bit depth of `8`. Channels that are not part
+/// of the color type are dropped, for example encoding a texture as
+/// [ImageType::TrueColor] discards its alpha values.
+///
+/// # Errors
+/// Returns [Error::NotImplemented] for [ImageType::IndexedColor], since that would require
+/// computing a palette.
+pub fn encode(texture: &Texture, image_type: ImageType) -> Result<Vec<u8>, Error> {
+    if image_type == ImageType::IndexedColor {
+        return Err(Error::NotImplemented);
+    }
+
+    let width = u32::try_from(texture.width()).map_err(|_| Error::NotImplemented)?;
+    let height = u32::try_from(texture.height()).map_err(|_| Error::NotImplemented)?;
+
+    let mut png = PNG_HEADER.to_vec();
+
+    // https://www.w3.org/TR/png/#11IHDR
+    let mut image_header = Vec::with_capacity(13);
+    image_header.extend_from_slice(&width.to_be_bytes());
+    image_header.extend_from_slice(&height.to_be_bytes());
+    image_header.push(8); // Bit depth
+    image_header.push(image_type.into());
+    image_header.push(0); // Compression method
+    image_header.push(0); // Filter method
+    image_header.push(0); // Interlace method (none)
+    write_chunk(&mut png, b"IHDR", &image_header);
+
+    let pixel_width = image_type.pixel_width();
+    let scanline_width = texture.width() * pixel_width;
+    let mut filtered = Vec::with_capacity(texture.height() * (scanline_width + 1));
+
+    let mut previous_scanline = vec![0; scanline_width];
+    let mut scanline = Vec::with_capacity(scanline_width);
+    for y in 0..texture.height() {
+        scanline.clear();
+        for x in 0..texture.width() {
+            push_pixel(texture.get_pixel(x, y), image_type, &mut scanline);
+        }
+
+        filter_scanline(&scanline, &previous_scanline, pixel_width, &mut filtered);
+        std::mem::swap(&mut scanline, &mut previous_scanline);
+    }
+
+    write_chunk(&mut png, b"IDAT", &zlib::compress(&filtered));
+    write_chunk(&mut png, b"IEND", &[]);
+
+    Ok(png)
+}
+
+pub(super) fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    let length = u32::try_from(data.len()).expect("chunk is too large");
+
+    let mut hasher = Crc32Hasher::default();
+    hasher.write(chunk_type);
+    hasher.write(data);
+
+    png.extend_from_slice(&length.to_be_bytes());
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&hasher.finish().to_be_bytes());
+}
+
+fn push_pixel(pixel: Rgbaf32, image_type: ImageType, scanline: &mut Vec<u8>) {
+    let to_byte = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+
+    let red = to_byte(pixel.red());
+    let green = to_byte(pixel.green());
+    let blue = to_byte(pixel.blue());
+    let alpha = to_byte(pixel.alpha());
+
+    let gray = || to_byte(pixel.luminance());
+
+    match image_type {
+        ImageType::GrayScale => scanline.push(gray()),
+        ImageType::GrayScaleWithAlpha => scanline.extend_from_slice(&[gray(), alpha]),
+        ImageType::TrueColor => scanline.extend_from_slice(&[red, green, blue]),
+        ImageType::TrueColorWithAlpha => scanline.extend_from_slice(&[red, green, blue, alpha]),
+        ImageType::IndexedColor => unreachable!("indexed color images cannot be encoded"),
+    }
+}
+
+/// Filter a scanline and append it (including the filter type) to the output
+///
+/// The filter is chosen with the "minimum sum of absolute differences" heuristic from
+/// <https://www.w3.org/TR/png/#12Filter-selection>.
+fn filter_scanline(
+    scanline: &[u8],
+    previous_scanline: &[u8],
+    pixel_width: usize,
+    output: &mut Vec<u8>,
+) {
+    let apply = |filter: Filter| -> Vec<u8> {
+        (0..scanline.len())
+            .map(|i| {
+                let a = if i < pixel_width {
+                    0
+                } else {
+                    scanline[i - pixel_width]
+                };
+                let b = previous_scanline[i];
+
+                match filter {
+                    Filter::None => scanline[i],
+                    Filter::Sub => scanline[i].wrapping_sub(a),
+                    Filter::Up => scanline[i].wrapping_sub(b),
+                    _ => unreachable!("filter is never used by the encoder"),
+                }
+            })
+            .collect()
+    };
+
+    // Interpret the filtered bytes as signed values, so that small negative
+    // differences are considered to be small too
+    let cost = |filtered: &[u8]| -> u64 {
+        filtered
+            .iter()
+            .map(|&byte| (byte as i8).unsigned_abs() as u64)
+            .sum()
+    };
+
+    let (filter, filtered) = [Filter::None, Filter::Sub, Filter::Up]
+        .map(|filter| (filter, apply(filter)))
+        .into_iter()
+        .min_by_key(|(_, filtered)| cost(filtered))
+        .expect("there are multiple filters");
+
+    output.push(filter as u8);
+    output.extend(filtered);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::png::decode;
+
+    fn gradient(width: usize, height: usize) -> Texture {
+        let data = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                Rgbaf32::rgba(
+                    x as f32 / width as f32,
+                    y as f32 / height as f32,
+                    ((x * y) % 256) as f32 / 255.,
+                    (x % 2) as f32,
+                )
+            })
+            .collect();
+
+        Texture::from_data(data, width, height)
+    }
+
+    fn to_bytes(pixel: Rgbaf32) -> [u8; 4] {
+        [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            .map(|value| (value * 255.).round() as u8)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let texture = gradient(17, 9);
+
+        for image_type in [
+            ImageType::TrueColorWithAlpha,
+            ImageType::TrueColor,
+            ImageType::GrayScale,
+        ] {
+            let png = encode(&texture, image_type).unwrap();
+            let decoded = decode(&png).unwrap();
+
+            assert_eq!(decoded.width(), texture.width());
+            assert_eq!(decoded.height(), texture.height());
+
+            for (original, decoded) in texture.data().iter().zip(decoded.data()) {
+                let [r, g, b, a] = to_bytes(*original);
+                let expected = match image_type {
+                    ImageType::TrueColorWithAlpha => [r, g, b, a],
+                    ImageType::TrueColor => [r, g, b, 255],
+                    _ => {
+                        let mut bytes = Vec::new();
+                        push_pixel(*original, ImageType::GrayScale, &mut bytes);
+                        [bytes[0], bytes[0], bytes[0], 255]
+                    },
+                };
+
+                assert_eq!(to_bytes(*decoded), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn indexed_color_is_not_supported() {
+        assert!(matches!(
+            encode(&gradient(1, 1), ImageType::IndexedColor),
+            Err(Error::NotImplemented)
+        ));
+    }
+}
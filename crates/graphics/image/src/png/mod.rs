@@ -1,9 +1,11 @@
-//! Implements a [PNG](https://www.w3.org/TR/png) decoder
+//! Implements a [PNG](https://www.w3.org/TR/png) decoder and encoder
 
 // The chunk types don't necessarily start with uppercase characters and renaming them would be silly
 // #![allow(non_upper_case_globals)]
 
+mod animation;
 pub mod chunks;
+mod encode;
 
 use std::{
     fs,
@@ -19,6 +21,9 @@ use crate::{texture::Rgbaf32, Texture};
 
 use self::chunks::ihdr::ImageType;
 
+pub use animation::{decode_animation, Animation, Frame};
+pub use encode::encode;
+
 pub(crate) const PNG_HEADER: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
 
 #[derive(Debug)]
@@ -30,11 +35,21 @@ pub enum Error {
     InvalidIHDRChunk(chunks::ihdr::ImageHeaderError),
     InvalidcHRMChunk,
     InvalidPLTEChunk(chunks::plte::PaletteError),
+    InvalidtRNSChunk(chunks::plte::PaletteError),
     NonConsecutiveIDATChunk,
+    InvalidacTLChunk,
+    InvalidfcTLChunk(chunks::fctl::FrameControlError),
+    InvalidfdATChunk,
+    /// The sequence numbers of `fcTL` and `fdAT` chunks are not consecutive
+    InvalidSequenceNumber,
+    /// A frame of an animation extends past the boundaries of the image
+    FrameOutsideOfCanvas,
     /// Expected the length of the decompressed zlib stream to be a multiple of the scanline width plus the filter byte
     MismatchedDecompressedZlibSize,
     UnknownFilterType,
     IndexedImageWithoutPalette,
+    /// A pixel references a color that is not part of the palette
+    PaletteIndexOutOfRange,
     NotImplemented,
     IncorrectLengthOfImageData,
     ZLib(zlib::Error),
@@ -76,10 +91,19 @@ pub enum Chunk {
     sTER,
     tEXt,
     tIME,
-    tRNS,
+    /// Transparency
+    ///
+    /// The meaning of the contained data depends on the [ImageType].
+    tRNS(Vec<u8>),
     zTXt,
     /// Background
     bKGD,
+    /// Animation Control
+    acTL(chunks::AnimationControl),
+    /// Frame Control
+    fcTL(chunks::FrameControl),
+    /// Frame Data, along with its sequence number
+    fdAT(u32, chunks::ImageData),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -89,7 +113,39 @@ enum ParserStage {
     AfterIDAT,
 }
 
+/// The chunks of a png file that are relevant for decoding it
+struct ParsedPng {
+    image_header: chunks::ImageHeader,
+
+    /// The concatenated contents of all `IDAT` chunks
+    idat: Vec<u8>,
+
+    /// The palette, with transparency information already applied
+    palette: Option<chunks::Palette>,
+
+    animation_control: Option<chunks::AnimationControl>,
+
+    /// The frames of an animated png
+    ///
+    /// If the `IDAT` chunks are part of the animation, then the first frame
+    /// contains no data and the contents of [ParsedPng::idat] should be used instead.
+    frames: Vec<(chunks::FrameControl, Vec<u8>)>,
+
+    default_image_is_first_frame: bool,
+}
+
 pub(crate) fn decode(bytes: &[u8]) -> Result<Texture, Error> {
+    let png = parse(bytes)?;
+
+    decode_image_data(
+        &png,
+        png.image_header.width as usize,
+        png.image_header.height as usize,
+        &png.idat,
+    )
+}
+
+fn parse(bytes: &[u8]) -> Result<ParsedPng, Error> {
     let mut reader = Cursor::new(bytes);
 
     let mut signature = [0; 8];
@@ -106,12 +162,15 @@ pub(crate) fn decode(bytes: &[u8]) -> Result<Texture, Error> {
         log::warn!("Expected IHDR chunk, found {ihdr_chunk:?}");
         return Err(Error::ExpectedIHDR);
     };
-    let image_width = image_header.width as usize;
-    let image_height = image_header.height as usize;
 
     let mut parser_stage = ParserStage::BeforeIDAT;
     let mut idat = vec![];
     let mut palette = None;
+    let mut transparency = None;
+    let mut animation_control = None;
+    let mut frames: Vec<(chunks::FrameControl, Vec<u8>)> = vec![];
+    let mut default_image_is_first_frame = false;
+    let mut next_sequence_number = 0;
 
     // Read all the PNG chunks in the fule
     loop {
@@ -132,13 +191,77 @@ pub(crate) fn decode(bytes: &[u8]) -> Result<Texture, Error> {
                 idat.extend(data.bytes());
             },
             Chunk::PLTE(plte) => palette = Some(plte),
+            Chunk::tRNS(data) => transparency = Some(data),
+            Chunk::acTL(control) if parser_stage == ParserStage::BeforeIDAT => {
+                animation_control = Some(control)
+            },
+            Chunk::fcTL(control) => {
+                if control.sequence_number != next_sequence_number {
+                    return Err(Error::InvalidSequenceNumber);
+                }
+                next_sequence_number += 1;
+
+                // A frame control chunk before the image data makes the default image
+                // the first frame of the animation
+                if parser_stage == ParserStage::BeforeIDAT {
+                    default_image_is_first_frame = true;
+                }
+                frames.push((control, vec![]));
+            },
+            Chunk::fdAT(sequence_number, data) => {
+                if sequence_number != next_sequence_number {
+                    return Err(Error::InvalidSequenceNumber);
+                }
+                next_sequence_number += 1;
+
+                let Some((_, frame_data)) = frames.last_mut() else {
+                    log::warn!("fdAT chunk without preceding fcTL chunk");
+                    return Err(Error::InvalidfdATChunk);
+                };
+                frame_data.extend(data.bytes());
+            },
             _ => {},
         }
     }
 
-    let decompressed_body = zlib::decompress(&idat)?;
+    // Truecolor and grayscale images may contain a suggested palette, but their
+    // tRNS chunk describes a single transparent color instead of palette alpha values
+    if image_header.image_type == ImageType::IndexedColor {
+        if let (Some(palette), Some(alpha_values)) = (&mut palette, transparency) {
+            palette
+                .apply_transparency(&alpha_values)
+                .map_err(Error::InvalidtRNSChunk)?;
+        }
+    }
+
+    Ok(ParsedPng {
+        image_header,
+        idat,
+        palette,
+        animation_control,
+        frames,
+        default_image_is_first_frame,
+    })
+}
 
-    let scanline_width = image_width * image_header.image_type.pixel_width();
+/// Decode a zlib-compressed image of the given size, using the
+/// format described by the png
+fn decode_image_data(
+    png: &ParsedPng,
+    image_width: usize,
+    image_height: usize,
+    compressed: &[u8],
+) -> Result<Texture, Error> {
+    let image_header = png.image_header;
+    let decompressed_body = zlib::decompress(compressed)?;
+
+    // Samples with a bit depth of less than 8 are packed into bytes, but each scanline starts
+    // at a byte boundary
+    let bits_per_pixel = image_header.image_type.pixel_width() * image_header.bit_depth as usize;
+    let scanline_width = (image_width * bits_per_pixel).div_ceil(8);
+
+    // Filters operate on complete bytes
+    let filter_width = bits_per_pixel.div_ceil(8);
 
     // NOTE: need to add 1 here because each scanline also contains a byte specifying a filter type
     if decompressed_body.len() % (scanline_width + 1) != 0 {
@@ -155,9 +278,21 @@ pub(crate) fn decode(bytes: &[u8]) -> Result<Texture, Error> {
         &decompressed_body,
         &mut image_data,
         scanline_width,
-        image_header.image_type.pixel_width(),
+        filter_width,
     )?;
 
+    if image_header.bit_depth < 8 {
+        image_data = unpack_samples(&image_data, image_width, image_header.bit_depth);
+
+        // Scale the samples to the full range of a byte (palette indices are left as-is)
+        if image_header.image_type == ImageType::GrayScale {
+            let max_value = (1 << image_header.bit_depth) - 1;
+            for sample in &mut image_data {
+                *sample = (*sample as usize * 255 / max_value) as u8;
+            }
+        }
+    }
+
     let mut texture_data = vec![Rgbaf32::default(); image_width * image_height];
     match image_header.image_type {
         ImageType::GrayScale => {
@@ -213,13 +348,15 @@ pub(crate) fn decode(bytes: &[u8]) -> Result<Texture, Error> {
                 return Err(Error::IncorrectLengthOfImageData);
             }
 
-            let Some(palette) = palette else {
+            let Some(palette) = &png.palette else {
                 log::error!("Cannot decode indexed color image without palette table");
                 return Err(Error::IndexedImageWithoutPalette);
             };
 
             for (texture_pixel, reference) in texture_data.iter_mut().zip(image_data) {
-                *texture_pixel = palette[reference];
+                *texture_pixel = palette
+                    .get(reference)
+                    .ok_or(Error::PaletteIndexOutOfRange)?;
             }
         },
     };
@@ -309,9 +446,23 @@ fn read_chunk<R: Read>(reader: &mut R) -> Result<Chunk, Error> {
         b"sTER" => Chunk::sTER,
         b"tEXt" => Chunk::tEXt,
         b"tIME" => Chunk::tIME,
-        b"tRNS" => Chunk::tRNS,
+        b"tRNS" => Chunk::tRNS(data),
         b"zTXt" => Chunk::zTXt,
         b"bKGD" => Chunk::bKGD,
+        b"acTL" => {
+            Chunk::acTL(chunks::AnimationControl::new(&data).ok_or(Error::InvalidacTLChunk)?)
+        },
+        b"fcTL" => Chunk::fcTL(chunks::FrameControl::new(&data).map_err(Error::InvalidfcTLChunk)?),
+        b"fdAT" => {
+            if length < 4 {
+                log::error!("fdAT chunk must contain a sequence number");
+                return Err(Error::InvalidfdATChunk);
+            }
+
+            let sequence_number = u32::from_be_bytes(data[0..4].try_into().unwrap());
+            data.drain(..4);
+            Chunk::fdAT(sequence_number, chunks::ImageData::new(data))
+        },
         unknown_chunk_type => {
             // Any chunk that we don't know about is not critical (since only IHDR, IDAT, PLTE and IEND are critical)
             log::info!(
@@ -327,6 +478,27 @@ fn read_chunk<R: Read>(reader: &mut R) -> Result<Chunk, Error> {
     Ok(chunk)
 }
 
+/// Expand samples with a bit depth of less than 8 to one byte each
+///
+/// Any bits that are left over at the end of a scanline are ignored.
+fn unpack_samples(packed: &[u8], image_width: usize, bit_depth: u8) -> Vec<u8> {
+    let samples_per_byte = 8 / bit_depth as usize;
+    let scanline_width = image_width.div_ceil(samples_per_byte);
+    let mask = (1 << bit_depth) - 1;
+
+    let mut unpacked = Vec::with_capacity(packed.len() * samples_per_byte);
+    for scanline in packed.chunks_exact(scanline_width) {
+        for x in 0..image_width {
+            // The leftmost sample is stored in the most significant bits
+            let byte = scanline[x / samples_per_byte];
+            let shift = 8 - bit_depth as usize * (x % samples_per_byte + 1);
+            unpacked.push((byte >> shift) & mask);
+        }
+    }
+
+    unpacked
+}
+
 /// Apply one of the filter specified in <https://www.w3.org/TR/png/#9-table91> to a scanline
 fn apply_filters(
     from: &[u8],
@@ -444,7 +616,7 @@ fn paeth(a: u8, b: u8, c: u8) -> u8 {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Filter {
     None = 0,
     Sub = 1,
@@ -479,3 +651,138 @@ impl From<io::Error> for Error {
         Self::IO(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a non-interlaced png from the given chunks and unfiltered scanlines
+    fn make_png(
+        width: u32,
+        height: u32,
+        bit_depth: u8,
+        image_type: ImageType,
+        chunks: &[(&[u8; 4], &[u8])],
+        scanlines: &[&[u8]],
+    ) -> Vec<u8> {
+        let mut png = PNG_HEADER.to_vec();
+
+        let mut image_header = vec![];
+        image_header.extend_from_slice(&width.to_be_bytes());
+        image_header.extend_from_slice(&height.to_be_bytes());
+        image_header.extend_from_slice(&[bit_depth, image_type.into(), 0, 0, 0]);
+        encode::write_chunk(&mut png, b"IHDR", &image_header);
+
+        for (chunk_type, data) in chunks {
+            encode::write_chunk(&mut png, chunk_type, data);
+        }
+
+        let mut image_data = vec![];
+        for scanline in scanlines {
+            image_data.push(Filter::None as u8);
+            image_data.extend_from_slice(scanline);
+        }
+        encode::write_chunk(&mut png, b"IDAT", &zlib::compress(&image_data));
+        encode::write_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+
+    fn rgba_at(texture: &Texture, x: usize, y: usize) -> [u8; 4] {
+        let pixel = texture.get_pixel(x, y);
+        [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            .map(|value| (value * 255.).round() as u8)
+    }
+
+    #[test]
+    fn indexed_color_with_transparency() {
+        let palette = [0xFF, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00, 0xFF];
+
+        // Red is fully transparent, green is half transparent and blue has no alpha value
+        let alpha_values = [0x00, 0x80];
+
+        // 2 bits per index, the rows are [0, 1, 2] and [2, 1, 0]
+        let png = make_png(
+            3,
+            2,
+            2,
+            ImageType::IndexedColor,
+            &[(b"PLTE", &palette), (b"tRNS", &alpha_values)],
+            &[&[0b00_01_10_00], &[0b10_01_00_00]],
+        );
+
+        let texture = decode(&png).unwrap();
+        assert_eq!(rgba_at(&texture, 0, 0), [0xFF, 0x00, 0x00, 0x00]);
+        assert_eq!(rgba_at(&texture, 1, 0), [0x00, 0xFF, 0x00, 0x80]);
+        assert_eq!(rgba_at(&texture, 2, 0), [0x00, 0x00, 0xFF, 0xFF]);
+        assert_eq!(rgba_at(&texture, 0, 1), [0x00, 0x00, 0xFF, 0xFF]);
+        assert_eq!(rgba_at(&texture, 1, 1), [0x00, 0xFF, 0x00, 0x80]);
+        assert_eq!(rgba_at(&texture, 2, 1), [0xFF, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn indexed_color_bit_depths() {
+        let palette: Vec<u8> = (0..=255).flat_map(|i| [i, i, 255 - i]).collect();
+        let indices = [3, 0, 1, 2, 1, 3, 0, 2, 2, 1];
+
+        for bit_depth in [1, 2, 4, 8] {
+            let mask = ((1_u16 << bit_depth) - 1) as u8;
+
+            let mut scanline = vec![0; (indices.len() * bit_depth as usize).div_ceil(8)];
+            for (x, index) in indices.iter().enumerate() {
+                let bit_offset = x * bit_depth as usize;
+                scanline[bit_offset / 8] |=
+                    (index & mask) << (8 - bit_depth - (bit_offset % 8) as u8);
+            }
+
+            let png = make_png(
+                indices.len() as u32,
+                1,
+                bit_depth,
+                ImageType::IndexedColor,
+                &[(b"PLTE", &palette)],
+                &[&scanline],
+            );
+
+            let texture = decode(&png).unwrap();
+            for (x, index) in indices.iter().enumerate() {
+                let index = index & mask;
+                assert_eq!(rgba_at(&texture, x, 0), [index, index, 255 - index, 255]);
+            }
+        }
+    }
+
+    #[test]
+    fn index_outside_of_palette() {
+        let png = make_png(
+            1,
+            1,
+            8,
+            ImageType::IndexedColor,
+            &[(b"PLTE", &[0x00, 0x00, 0x00])],
+            &[&[1]],
+        );
+
+        assert!(matches!(decode(&png), Err(Error::PaletteIndexOutOfRange)));
+    }
+
+    #[test]
+    fn truecolor_with_suggested_palette() {
+        // The tRNS chunk of truecolor images contains a single color, which is longer
+        // than the palette and must not be applied to it
+        let png = make_png(
+            1,
+            1,
+            8,
+            ImageType::TrueColor,
+            &[
+                (b"PLTE", &[0x00, 0x00, 0x00]),
+                (b"tRNS", &[0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF]),
+            ],
+            &[&[0x10, 0x20, 0x30]],
+        );
+
+        let texture = decode(&png).unwrap();
+        assert_eq!(rgba_at(&texture, 0, 0), [0x10, 0x20, 0x30, 0xFF]);
+    }
+}
@@ -65,6 +65,32 @@ impl Rgbaf32 {
         self.channels[3] = alpha;
     }
 
+    /// The relative luminance of the color, ignoring alpha
+    ///
+    /// Uses the weights from <https://www.w3.org/TR/png/#13Decoder-conversions>.
+    #[inline]
+    #[must_use]
+    pub fn luminance(&self) -> f32 {
+        0.2126 * self.red() + 0.7152 * self.green() + 0.0722 * self.blue()
+    }
+
+    /// Replace the color channels with the [luminance](Self::luminance), keeping alpha as-is
+    #[inline]
+    #[must_use]
+    pub fn to_grayscale(&self) -> Self {
+        Self::grayscale_with_alpha(self.luminance(), self.alpha())
+    }
+
+    /// Linearly interpolate every channel between `self` (at `t = 0`) and `other` (at `t = 1`)
+    #[inline]
+    #[must_use]
+    fn interpolate(&self, other: Self, t: f32) -> Self {
+        let channels =
+            std::array::from_fn(|i| self.channels[i] + (other.channels[i] - self.channels[i]) * t);
+
+        Self { channels }
+    }
+
     /// Blend another color on top of `self`
     #[must_use]
     pub fn blend(&self, other: Self) -> Self {
@@ -84,9 +110,16 @@ impl Rgbaf32 {
             return Self::BLANK;
         }
 
-        let red = other.red() * other.alpha() + self.red() * (1. - other.alpha());
-        let green = other.green() * other.alpha() + self.green() * (1. - other.alpha());
-        let blue = other.blue() * other.alpha() + self.blue() * (1. - other.alpha());
+        // The channels are not premultiplied, so the contribution of each color
+        // has to be weighted by its alpha and then normalized again
+        let self_weight = self.alpha() * (1. - other.alpha());
+        let blend_channel = |source: f32, destination: f32| {
+            (source * other.alpha() + destination * self_weight) / new_alpha
+        };
+
+        let red = blend_channel(other.red(), self.red());
+        let green = blend_channel(other.green(), self.green());
+        let blue = blend_channel(other.blue(), self.blue());
 
         let channels = [red, green, blue, new_alpha];
 
@@ -107,6 +140,17 @@ pub enum AccessMode {
     Clamp,
 }
 
+/// Describes how pixel values are computed when [resizing](Texture::resize) a texture
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScaleFilter {
+    /// Use the value of the closest source pixel
+    #[default]
+    Nearest,
+
+    /// Interpolate between the four closest source pixels
+    Bilinear,
+}
+
 impl Texture {
     #[must_use]
     pub const fn from_data(data: Vec<Rgbaf32>, width: usize, height: usize) -> Self {
@@ -139,27 +183,78 @@ impl Texture {
         Self::from_data(vec![], 0, 0)
     }
 
-    pub fn resize(&self, width: usize, height: usize) -> Self {
+    /// Scale the texture to the given dimensions
+    #[must_use]
+    pub fn resize(&self, width: usize, height: usize, filter: ScaleFilter) -> Self {
         let mut result = Self::new(width, height);
 
         if self.width() == 0 || self.height() == 0 {
             return result;
         }
 
-        // Nearest neighbor
         let height_ratio = self.height as f32 / height as f32;
         let width_ratio = self.width as f32 / width as f32;
 
-        for y in 0..height {
-            let nearest_y = (y as f32 * height_ratio).floor() as usize;
-            for x in 0..width {
-                let nearest_x = (x as f32 * width_ratio).floor() as usize;
-                result.set_pixel(x, y, self.get_pixel(nearest_x, nearest_y));
-            }
+        match filter {
+            ScaleFilter::Nearest => {
+                for y in 0..height {
+                    let nearest_y = (y as f32 * height_ratio).floor() as usize;
+                    for x in 0..width {
+                        let nearest_x = (x as f32 * width_ratio).floor() as usize;
+                        result.set_pixel(x, y, self.get_pixel(nearest_x, nearest_y));
+                    }
+                }
+            },
+            ScaleFilter::Bilinear => {
+                // Maps the center of a destination pixel to a (fractional) source pixel, clamped to the
+                // edge of the source texture
+                let source_position = |position: usize, ratio: f32, max: usize| {
+                    let position =
+                        ((position as f32 + 0.5) * ratio - 0.5).clamp(0., (max - 1) as f32);
+                    let lower = position.floor() as usize;
+                    let upper = (lower + 1).min(max - 1);
+                    (lower, upper, position - lower as f32)
+                };
+
+                for y in 0..height {
+                    let (y0, y1, weight_y) = source_position(y, height_ratio, self.height);
+                    for x in 0..width {
+                        let (x0, x1, weight_x) = source_position(x, width_ratio, self.width);
+
+                        let top = self
+                            .get_pixel(x0, y0)
+                            .interpolate(self.get_pixel(x1, y0), weight_x);
+                        let bottom = self
+                            .get_pixel(x0, y1)
+                            .interpolate(self.get_pixel(x1, y1), weight_x);
+                        result.set_pixel(x, y, top.interpolate(bottom, weight_y));
+                    }
+                }
+            },
         }
 
         result
     }
+
+    /// Convert every pixel in the texture to grayscale
+    ///
+    /// See [Rgbaf32::to_grayscale] for details.
+    #[must_use]
+    pub fn to_grayscale(&self) -> Self {
+        let data = self.data.iter().map(Rgbaf32::to_grayscale).collect();
+        Self::from_data(data, self.width, self.height)
+    }
+
+    /// Make every pixel in the texture fully opaque
+    #[must_use]
+    pub fn to_opaque(&self) -> Self {
+        let data = self
+            .data
+            .iter()
+            .map(|pixel| Rgbaf32::rgb(pixel.red(), pixel.green(), pixel.blue()))
+            .collect();
+        Self::from_data(data, self.width, self.height)
+    }
 }
 
 impl Texture {
@@ -311,3 +406,87 @@ impl From<jpeg::Error> for Error {
         Self::Jpeg(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_to_grayscale() {
+        let texture = Texture::from_data(
+            vec![
+                Rgbaf32::rgb(1., 0., 0.),
+                Rgbaf32::rgb(0., 1., 0.),
+                Rgbaf32::rgba(0., 0., 1., 0.5),
+                Rgbaf32::rgb(1., 1., 1.),
+            ],
+            2,
+            2,
+        );
+
+        let grayscale = texture.to_grayscale();
+        assert_eq!(grayscale.width(), 2);
+        assert_eq!(grayscale.height(), 2);
+
+        let expected = [(0.2126, 1.), (0.7152, 1.), (0.0722, 0.5), (1., 1.)];
+        for (pixel, (value, alpha)) in grayscale.data().iter().zip(expected) {
+            for channel in [pixel.red(), pixel.green(), pixel.blue()] {
+                assert!((channel - value).abs() < 1e-6);
+            }
+            assert_eq!(pixel.alpha(), alpha);
+        }
+    }
+
+    #[test]
+    fn convert_to_opaque() {
+        let texture = Texture::from_data(vec![Rgbaf32::rgba(0.25, 0.5, 0.75, 0.)], 1, 1);
+        let pixel = texture.to_opaque().get_pixel(0, 0);
+
+        assert_eq!(
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()],
+            [0.25, 0.5, 0.75, 1.]
+        );
+    }
+
+    #[test]
+    fn upscale_nearest() {
+        let texture = Texture::from_data(
+            vec![
+                Rgbaf32::rgb(1., 0., 0.),
+                Rgbaf32::rgb(0., 1., 0.),
+                Rgbaf32::rgb(0., 0., 1.),
+                Rgbaf32::rgb(1., 1., 1.),
+            ],
+            2,
+            2,
+        );
+
+        let scaled = texture.resize(6, 4, ScaleFilter::Nearest);
+        assert_eq!(scaled.width(), 6);
+        assert_eq!(scaled.height(), 4);
+
+        for y in 0..4 {
+            for x in 0..6 {
+                let expected = texture.get_pixel(x / 3, y / 2);
+                assert_eq!(scaled.get_pixel(x, y).channels, expected.channels);
+            }
+        }
+    }
+
+    #[test]
+    fn scale_bilinear() {
+        let texture =
+            Texture::from_data(vec![Rgbaf32::grayscale(0.), Rgbaf32::grayscale(1.)], 2, 1);
+
+        // The outermost pixels are clamped to the edges of the texture
+        let scaled = texture.resize(4, 3, ScaleFilter::Bilinear);
+        for y in 0..3 {
+            let row: Vec<f32> = (0..4).map(|x| scaled.get_pixel(x, y).red()).collect();
+            assert_eq!(row, [0., 0.25, 0.75, 1.]);
+        }
+
+        let downscaled = Texture::new(7, 5).resize(3, 2, ScaleFilter::Bilinear);
+        assert_eq!(downscaled.width(), 3);
+        assert_eq!(downscaled.height(), 2);
+    }
+}
@@ -0,0 +1,151 @@
+//! <https://drafts.fxtf.org/compositing/#blending>
+
+use image::Rgbaf32;
+
+/// Controls how the colors of a [Layer](crate::Layer) are combined with the colors below it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Simply draw the layer on top of its backdrop
+    #[default]
+    Normal,
+
+    /// Multiply the colors, which always results in a darker color
+    Multiply,
+
+    /// The inverse of [BlendMode::Multiply], which always results in a lighter color
+    Screen,
+
+    /// [Multiply](BlendMode::Multiply) or [Screen](BlendMode::Screen), depending on the backdrop
+    Overlay,
+
+    /// Pick the darker of the two colors
+    Darken,
+
+    /// Pick the lighter of the two colors
+    Lighten,
+}
+
+impl BlendMode {
+    /// <https://drafts.fxtf.org/compositing/#blendingseparable>
+    #[must_use]
+    fn blend_channel(&self, backdrop: f32, source: f32) -> f32 {
+        match self {
+            Self::Normal => source,
+            Self::Multiply => backdrop * source,
+            Self::Screen => backdrop + source - backdrop * source,
+            Self::Overlay => {
+                // Hard light, with the roles of source and backdrop swapped
+                if backdrop <= 0.5 {
+                    Self::Multiply.blend_channel(source, 2. * backdrop)
+                } else {
+                    Self::Screen.blend_channel(source, 2. * backdrop - 1.)
+                }
+            },
+            Self::Darken => backdrop.min(source),
+            Self::Lighten => backdrop.max(source),
+        }
+    }
+
+    /// Blend `source` on top of `backdrop`, followed by source-over compositing
+    ///
+    /// <https://drafts.fxtf.org/compositing/#generalformula>
+    #[must_use]
+    pub fn blend(&self, backdrop: Rgbaf32, source: Rgbaf32) -> Rgbaf32 {
+        if *self == Self::Normal {
+            return backdrop.blend(source);
+        }
+
+        let alpha = source.alpha() + backdrop.alpha() * (1. - source.alpha());
+        if alpha == 0. {
+            return Rgbaf32::BLANK;
+        }
+
+        let blend_channel = |backdrop_channel: f32, source_channel: f32| {
+            // Where the backdrop is transparent, the source color is used as-is
+            let blended = (1. - backdrop.alpha()) * source_channel
+                + backdrop.alpha() * self.blend_channel(backdrop_channel, source_channel);
+
+            // Source-over on premultiplied values, converted back to straight alpha
+            let premultiplied = blended * source.alpha()
+                + backdrop_channel * backdrop.alpha() * (1. - source.alpha());
+            premultiplied / alpha
+        };
+
+        Rgbaf32::rgba(
+            blend_channel(backdrop.red(), source.red()),
+            blend_channel(backdrop.green(), source.green()),
+            blend_channel(backdrop.blue(), source.blue()),
+            alpha,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_color_eq(actual: Rgbaf32, expected: Rgbaf32) {
+        let channels = |color: Rgbaf32| [color.red(), color.green(), color.blue(), color.alpha()];
+
+        for (actual_channel, expected_channel) in
+            channels(actual).into_iter().zip(channels(expected))
+        {
+            assert!(
+                (actual_channel - expected_channel).abs() < 1e-5,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn multiply() {
+        let backdrop = Rgbaf32::rgb(0.2, 0.5, 0.8);
+
+        // Multiplying with white leaves the backdrop unchanged
+        let white = Rgbaf32::rgb(1., 1., 1.);
+        assert_color_eq(BlendMode::Multiply.blend(backdrop, white), backdrop);
+
+        // Multiplying with black always yields black
+        let black = Rgbaf32::rgb(0., 0., 0.);
+        assert_color_eq(BlendMode::Multiply.blend(backdrop, black), black);
+    }
+
+    #[test]
+    fn transparent_backdrop() {
+        // Without a backdrop there is nothing to blend with
+        let source = Rgbaf32::rgba(0.3, 0.6, 0.9, 0.5);
+
+        for blend_mode in [
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+            BlendMode::Darken,
+            BlendMode::Lighten,
+        ] {
+            assert_color_eq(blend_mode.blend(Rgbaf32::BLANK, source), source);
+        }
+    }
+
+    #[test]
+    fn separable_modes() {
+        let backdrop = Rgbaf32::rgb(0.25, 0.75, 0.5);
+        let source = Rgbaf32::rgb(0.5, 0.5, 0.5);
+
+        assert_color_eq(
+            BlendMode::Screen.blend(backdrop, source),
+            Rgbaf32::rgb(0.625, 0.875, 0.75),
+        );
+        assert_color_eq(
+            BlendMode::Overlay.blend(backdrop, source),
+            Rgbaf32::rgb(0.25, 0.75, 0.5),
+        );
+        assert_color_eq(
+            BlendMode::Darken.blend(backdrop, source),
+            Rgbaf32::rgb(0.25, 0.5, 0.5),
+        );
+        assert_color_eq(
+            BlendMode::Lighten.blend(backdrop, source),
+            Rgbaf32::rgb(0.5, 0.75, 0.5),
+        );
+    }
+}
@@ -66,3 +66,45 @@ impl Composition {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Path, Source};
+    use math::{Color, Vec2D};
+
+    #[test]
+    fn half_opacity_layer() {
+        let outline = Path::rect(Vec2D::new(0., 0.), Vec2D::new(4., 4.));
+        let mut composition = Composition::default();
+
+        composition
+            .get_or_insert_layer(0)
+            .with_outline(outline.clone())
+            .with_source(Source::Solid(Color::BLUE));
+
+        composition
+            .get_or_insert_layer(1)
+            .with_outline(outline)
+            .with_source(Source::Solid(Color::RED))
+            .set_opacity(0.5);
+
+        let mut texture = Texture::new(4, 4);
+        composition.render_to(&mut texture);
+
+        let pixel = texture.get_pixel(1, 1);
+        assert!((pixel.red() - 0.5).abs() < 0.01);
+        assert_eq!(pixel.green(), 0.);
+        assert!((pixel.blue() - 0.5).abs() < 0.01);
+        assert!((pixel.alpha() - 1.).abs() < 0.01);
+    }
+
+    #[test]
+    fn opacity_is_clamped() {
+        let mut layer = Layer::default();
+
+        assert_eq!(layer.set_opacity(2.).opacity(), 1.);
+        assert_eq!(layer.set_opacity(-1.).opacity(), 0.);
+        assert_eq!(layer.set_opacity(f32::NAN).opacity(), 0.);
+    }
+}
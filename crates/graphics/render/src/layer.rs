@@ -1,7 +1,7 @@
-use image::{AccessMode, Rgbaf32, Texture};
+use image::{AccessMode, Rgbaf32, ScaleFilter, Texture};
 use math::{AffineTransform, Angle, Color, Rectangle, Vec2D};
 
-use crate::{FlattenedPathPoint, Mask, Path, Rasterizer};
+use crate::{BlendMode, FlattenedPathPoint, Mask, Path, Rasterizer};
 
 #[derive(Clone, Debug)]
 pub enum Source {
@@ -30,6 +30,12 @@ pub struct Layer {
 
     /// Controls whether or not a [Layer]'s contents should be rendered to the screen
     pub is_enabled: bool,
+
+    /// The opacity of the whole layer, between `0` (transparent) and `1` (opaque)
+    opacity: f32,
+
+    /// How the layer is combined with the layers below it
+    blend_mode: BlendMode,
     needs_flattening: bool,
     flattened_outline: Vec<FlattenedPathPoint>,
 }
@@ -49,13 +55,47 @@ impl Layer {
         self
     }
 
+    /// Set the opacity of the layer
+    ///
+    /// The opacity is applied to the layer as a whole, on top of the
+    /// alpha channel of its source. Values outside of `[0, 1]` are clamped.
+    #[inline]
+    pub fn set_opacity(&mut self, opacity: f32) -> &mut Self {
+        // NaN is treated as fully transparent
+        self.opacity = if opacity.is_nan() {
+            0.
+        } else {
+            opacity.clamp(0., 1.)
+        };
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Set the [BlendMode] that is used when drawing the layer on top of its backdrop
+    #[inline]
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) -> &mut Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
     /// Draw text to the layer. This replaces any existing paths within
     /// the layer
     #[inline]
     pub fn text(
         &mut self,
         text: &str,
-        fontface: font::Font,
+        fontface: &font::Font,
         font_size: f32,
         offset: Vec2D,
     ) -> &mut Self {
@@ -173,7 +213,14 @@ impl Layer {
                 .resize(outline_extent.width(), outline_extent.height());
 
             // Compose the mask onto the buffer
-            compose(texture, mask, &resized_source, outline_extent.top_left());
+            compose(
+                texture,
+                mask,
+                &resized_source,
+                outline_extent.top_left(),
+                self.opacity,
+                self.blend_mode,
+            );
         }
     }
 }
@@ -185,6 +232,8 @@ impl Default for Layer {
             source: Source::default(),
             transform: AffineTransform::identity(),
             is_enabled: true,
+            opacity: 1.,
+            blend_mode: BlendMode::default(),
             needs_flattening: true,
             flattened_outline: vec![],
         }
@@ -199,13 +248,20 @@ impl Source {
                 texture,
                 access_mode,
             } => Self::Texture {
-                texture: texture.resize(width, height),
+                texture: texture.resize(width, height, ScaleFilter::Nearest),
                 access_mode: *access_mode,
             },
         }
     }
 }
-fn compose(destination: &mut Texture, mask: Mask, source: &Source, offset: Vec2D<usize>) {
+fn compose(
+    destination: &mut Texture,
+    mask: Mask,
+    source: &Source,
+    offset: Vec2D<usize>,
+    layer_opacity: f32,
+    blend_mode: BlendMode,
+) {
     if offset.x < destination.width() && offset.y < destination.height() {
         // Don't draw out of bounds
         let available_space = Vec2D::new(
@@ -216,7 +272,7 @@ fn compose(destination: &mut Texture, mask: Mask, source: &Source, offset: Vec2D
             Source::Solid(color) => {
                 for x in 0..mask.width().min(available_space.x) {
                     for y in 0..mask.height().min(available_space.y) {
-                        let opacity = mask.opacity_at(x, y).abs().min(1.);
+                        let opacity = mask.opacity_at(x, y).abs().min(1.) * layer_opacity;
 
                         let color = Rgbaf32::rgba(
                             color.red() as f32 / 255.,
@@ -226,7 +282,7 @@ fn compose(destination: &mut Texture, mask: Mask, source: &Source, offset: Vec2D
                         );
 
                         let previous_color = destination.get_pixel(x + offset.x, y + offset.y);
-                        let computed_color = previous_color.blend(color);
+                        let computed_color = blend_mode.blend(previous_color, color);
                         destination.set_pixel(x + offset.x, y + offset.y, computed_color);
                     }
                 }
@@ -237,7 +293,7 @@ fn compose(destination: &mut Texture, mask: Mask, source: &Source, offset: Vec2D
             } => {
                 for x in 0..mask.width().min(available_space.x) {
                     for y in 0..mask.height().min(available_space.y) {
-                        let opacity = mask.opacity_at(x, y).abs().min(1.);
+                        let opacity = mask.opacity_at(x, y).abs().min(1.) * layer_opacity;
                         let mut texture_pixel = texture.get(x, y, *access_mode);
 
                         // Adjust the alpha value of the texture according to the mask
@@ -245,7 +301,7 @@ fn compose(destination: &mut Texture, mask: Mask, source: &Source, offset: Vec2D
                         texture_pixel.set_alpha(texture_alpha * opacity);
 
                         let previous_color = destination.get_pixel(x + offset.x, y + offset.y);
-                        let computed_color = previous_color.blend(texture_pixel);
+                        let computed_color = blend_mode.blend(previous_color, texture_pixel);
                         destination.set_pixel(x + offset.x, y + offset.y, computed_color);
                     }
                 }
@@ -14,12 +14,18 @@
 #![feature(array_windows)]
 #![feature(portable_simd)]
 
+mod blend_mode;
 mod composition;
 mod layer;
 mod path;
 mod rasterizer;
+mod stroke;
+mod text;
 
+pub use blend_mode::BlendMode;
 pub use composition::Composition;
 pub use layer::{Layer, Source};
 pub use path::{FlattenedPathPoint, Path};
 pub use rasterizer::{Mask, Rasterizer};
+pub use stroke::{DashPattern, StrokeStyle};
+pub use text::draw_text;
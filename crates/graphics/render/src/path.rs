@@ -133,6 +133,10 @@ impl Path {
 
         let mut current_point = self.start;
 
+        if !self.commands.is_empty() {
+            flattened_path.push(FlattenedPathPoint::new(self.start, false));
+        }
+
         for &command in &self.commands {
             match command {
                 PathCommand::Move(point) => {
@@ -0,0 +1,264 @@
+//! Stroking of [Paths](Path), optionally using a dash pattern
+//!
+//! The stroke outline is made up of one rectangle per (flattened) line segment. Segments
+//! use butt caps and are not joined in any special way.
+
+use math::Vec2D;
+
+use crate::{FlattenedPathPoint, Path};
+
+/// The error tolerance used when flattening the curves of a path before stroking it
+const FLATTEN_TOLERANCE: f32 = 0.01;
+
+/// Describes how the outline of a [Path] should be stroked
+#[derive(Clone, Debug)]
+pub struct StrokeStyle {
+    /// The width of the stroke, centered on the path
+    pub width: f32,
+
+    /// If set, only the "on" sections of the pattern are stroked
+    pub dash_pattern: Option<DashPattern>,
+}
+
+/// An alternating sequence of visible ("on") and invisible ("off") lengths
+///
+/// <https://svgwg.org/svg2-draft/painting.html#StrokeDashing>
+#[derive(Clone, Debug)]
+pub struct DashPattern {
+    /// Always contains an even number of elements
+    lengths: Vec<f32>,
+
+    /// How far into the pattern each contour starts, always smaller than the total length of the pattern
+    offset: f32,
+}
+
+impl DashPattern {
+    /// Create a new dash pattern
+    ///
+    /// Like in SVG, a pattern with an odd number of lengths is repeated once to make it even,
+    /// so `[5, 3, 2]` is equivalent to `[5, 3, 2, 5, 3, 2]`.
+    ///
+    /// Returns `None` if the pattern is empty, contains negative or non-finite lengths or
+    /// only consists of zeros. The path should be stroked without dashes in this case.
+    #[must_use]
+    pub fn new(lengths: &[f32], offset: f32) -> Option<Self> {
+        if lengths
+            .iter()
+            .any(|&length| length < 0. || !length.is_finite())
+        {
+            return None;
+        }
+
+        if lengths.iter().sum::<f32>() == 0. || !offset.is_finite() {
+            return None;
+        }
+
+        let mut lengths = lengths.to_vec();
+        if lengths.len() % 2 == 1 {
+            lengths.extend_from_within(..);
+        }
+
+        let offset = offset.rem_euclid(lengths.iter().sum());
+
+        Some(Self { lengths, offset })
+    }
+
+    /// Find the dash at which the pattern starts, and how much of it is left
+    fn initial_state(&self) -> (usize, f32) {
+        let mut offset = self.offset;
+        let mut index = 0;
+
+        while offset >= self.lengths[index] {
+            offset -= self.lengths[index];
+            index = (index + 1) % self.lengths.len();
+        }
+
+        (index, self.lengths[index] - offset)
+    }
+}
+
+impl Path {
+    /// Compute a path that covers the stroke of `self`
+    ///
+    /// The returned path should be filled to draw the stroke.
+    #[must_use]
+    pub fn stroke(&self, style: &StrokeStyle) -> Self {
+        let mut flattened_path = vec![];
+        self.flatten(FLATTEN_TOLERANCE, &mut flattened_path);
+
+        let polylines: Vec<Vec<Vec2D>> = match &style.dash_pattern {
+            Some(pattern) => contours(&flattened_path)
+                .flat_map(|contour| dash_contour(&contour, pattern))
+                .collect(),
+            None => contours(&flattened_path).collect(),
+        };
+
+        let half_width = style.width / 2.;
+        let mut outline = Self::empty();
+        for [from, to] in polylines
+            .iter()
+            .flat_map(|polyline| polyline.array_windows())
+        {
+            let direction = *to - *from;
+            let length = direction.magnitude();
+            if length == 0. {
+                continue;
+            }
+
+            let normal = Vec2D::new(-direction.y, direction.x) * (half_width / length);
+            outline = outline
+                .move_to(*from + normal)
+                .line_to(*to + normal)
+                .line_to(*to - normal)
+                .line_to(*from - normal)
+                .line_to(*from + normal);
+        }
+
+        outline
+    }
+}
+
+/// Split a flattened path into its individual contours
+fn contours(flattened_path: &[FlattenedPathPoint]) -> impl Iterator<Item = Vec<Vec2D>> + '_ {
+    flattened_path
+        .chunk_by(|_, next| next.connected)
+        .map(|contour| contour.iter().map(|point| point.coordinates).collect())
+}
+
+/// Split a contour into the sections that are "on" according to the dash pattern
+///
+/// The pattern continues across the segments of the contour. If the contour is closed
+/// and both starts and ends within a dash then these two dashes are merged into one.
+fn dash_contour(contour: &[Vec2D], pattern: &DashPattern) -> Vec<Vec<Vec2D>> {
+    let Some(&first_point) = contour.first() else {
+        return vec![];
+    };
+
+    let (mut index, mut remaining) = pattern.initial_state();
+    let starts_on = index % 2 == 0;
+    let mut is_on = starts_on;
+
+    let mut dashes = vec![];
+    let mut current_dash = if is_on { vec![first_point] } else { vec![] };
+
+    for [from, to] in contour.array_windows() {
+        let segment_length = (*to - *from).magnitude();
+        let mut traveled = 0.;
+
+        // Toggle the dashes that end within this segment
+        while segment_length - traveled > remaining {
+            traveled += remaining;
+
+            let point = from.lerp(*to, traveled / segment_length);
+            current_dash.push(point);
+            if is_on {
+                dashes.push(std::mem::take(&mut current_dash));
+            }
+
+            index = (index + 1) % pattern.lengths.len();
+            remaining = pattern.lengths[index];
+            is_on = !is_on;
+        }
+
+        remaining -= segment_length - traveled;
+        if is_on {
+            current_dash.push(*to);
+        }
+    }
+
+    let is_closed = contour.len() > 2 && contour.last() == Some(&first_point);
+    if is_on {
+        if is_closed && starts_on && !dashes.is_empty() {
+            // The last dash wraps around into the first one
+            let first_dash = dashes.remove(0);
+            current_dash.extend_from_slice(&first_dash[1..]);
+        }
+
+        dashes.push(current_dash);
+    }
+
+    // Zero-length dashes don't contribute to the stroke
+    dashes.retain(|dash| dash.len() >= 2);
+    dashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_points_eq(actual: &[Vec2D], expected: &[Vec2D]) {
+        assert_eq!(actual.len(), expected.len());
+
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (*actual - *expected).magnitude() < 1e-4,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn dashed_line() {
+        let line = [Vec2D::new(0., 0.), Vec2D::new(100., 0.)];
+
+        // 0-10, 15-25, ..., 90-100
+        let pattern = DashPattern::new(&[10., 5.], 0.).unwrap();
+        let dashes = dash_contour(&line, &pattern);
+        assert_eq!(dashes.len(), 7);
+        assert_points_eq(&dashes[1], &[Vec2D::new(15., 0.), Vec2D::new(25., 0.)]);
+
+        // 0-5, 10-20, ..., 85-95
+        let pattern = DashPattern::new(&[10., 5.], 5.).unwrap();
+        let dashes = dash_contour(&line, &pattern);
+        assert_eq!(dashes.len(), 7);
+        assert_points_eq(&dashes[0], &[Vec2D::new(0., 0.), Vec2D::new(5., 0.)]);
+
+        // Odd patterns are repeated, so this is 0-20, 30-50, 70-80
+        let pattern = DashPattern::new(&[20., 10., 20.], 0.).unwrap();
+        let dashes = dash_contour(&line, &pattern);
+        assert_eq!(dashes.len(), 3);
+        assert_points_eq(&dashes[2], &[Vec2D::new(70., 0.), Vec2D::new(80., 0.)]);
+    }
+
+    #[test]
+    fn pattern_continues_across_segments() {
+        let polyline = [Vec2D::new(0., 0.), Vec2D::new(6., 0.), Vec2D::new(6., 6.)];
+
+        let pattern = DashPattern::new(&[8., 2.], 0.).unwrap();
+        let dashes = dash_contour(&polyline, &pattern);
+
+        assert_eq!(dashes.len(), 2);
+        assert_points_eq(
+            &dashes[0],
+            &[Vec2D::new(0., 0.), Vec2D::new(6., 0.), Vec2D::new(6., 2.)],
+        );
+        assert_points_eq(&dashes[1], &[Vec2D::new(6., 4.), Vec2D::new(6., 6.)]);
+    }
+
+    #[test]
+    fn closed_contour_merges_dashes() {
+        let square = [
+            Vec2D::new(0., 0.),
+            Vec2D::new(10., 0.),
+            Vec2D::new(10., 10.),
+            Vec2D::new(0., 10.),
+            Vec2D::new(0., 0.),
+        ];
+
+        // The last dash (38-40) connects to the first one (0-3)
+        let pattern = DashPattern::new(&[5., 5.], 2.).unwrap();
+        let dashes = dash_contour(&square, &pattern);
+        assert_eq!(dashes.len(), 4);
+        assert_points_eq(
+            &dashes[3],
+            &[Vec2D::new(0., 2.), Vec2D::new(0., 0.), Vec2D::new(3., 0.)],
+        );
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert!(DashPattern::new(&[], 0.).is_none());
+        assert!(DashPattern::new(&[0., 0.], 0.).is_none());
+        assert!(DashPattern::new(&[5., -1.], 0.).is_none());
+    }
+}
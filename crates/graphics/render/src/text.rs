@@ -0,0 +1,52 @@
+//! Drawing text onto [Layers](Layer)
+
+use font::Font;
+use math::{Color, Vec2D};
+
+use crate::{Layer, Source};
+
+/// Draw a line of text onto a [Layer], replacing its previous outline and source
+///
+/// The glyphs are laid out from left to right, starting at `origin` (the top left corner of the line).
+/// Characters that are not present in the font are drawn as the `.notdef` glyph.
+pub fn draw_text<'layer>(
+    layer: &'layer mut Layer,
+    font: &Font,
+    text: &str,
+    origin: Vec2D,
+    font_size: f32,
+    color: Color,
+) -> &'layer mut Layer {
+    layer
+        .text(text, font, font_size, origin)
+        .with_source(Source::Solid(color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Composition;
+
+    use image::Texture;
+
+    #[test]
+    fn draw_simple_text() {
+        let font = Font::fallback();
+        let mut composition = Composition::default();
+
+        draw_text(
+            composition.get_or_insert_layer(0),
+            &font,
+            "Hi",
+            Vec2D::new(0., 0.),
+            32.,
+            Color::BLACK,
+        );
+
+        let mut texture = Texture::new(64, 40);
+        composition.render_to(&mut texture);
+
+        let coverage: f32 = texture.data().iter().map(|pixel| pixel.alpha()).sum();
+        assert!(coverage > 0.);
+    }
+}
@@ -0,0 +1,60 @@
+//! Lets the [dns] crate resolve domains over HTTPS ([RFC 8484](https://datatracker.ietf.org/doc/html/rfc8484))
+
+use std::sync::Arc;
+
+use dns::resolver::{self, DnsOverHttps, HttpsClient, HttpsError, HttpsResponse, Resolver};
+use url::URL;
+
+use crate::{request::HTTPError, Header, Request};
+
+/// Sends DNS over HTTPS queries using [Request]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DnsOverHttpsClient;
+
+/// Resolve all domains that are not cached using the DNS over HTTPS server at `endpoint`
+pub fn use_dns_over_https(endpoint: URL) {
+    let server = DnsOverHttps::new(endpoint, Arc::new(DnsOverHttpsClient));
+    resolver::set_resolver(Resolver::DnsOverHttps(server));
+}
+
+impl HttpsClient for DnsOverHttpsClient {
+    fn post(
+        &self,
+        url: &URL,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> Result<HttpsResponse, HttpsError> {
+        // Plain http requests resolve their host through the dns crate, which
+        // would send us right back here
+        if url.scheme().as_str() != "https" {
+            return Err(HttpsError::new(
+                "DNS over HTTPS endpoint must be a https url",
+            ));
+        }
+
+        let mut request = Request::post(url, body);
+        request
+            .headers_mut()
+            .set(Header::CONTENT_TYPE, content_type.to_string());
+        request
+            .headers_mut()
+            .set(Header::ACCEPT, content_type.to_string());
+
+        match request.send() {
+            Ok(response) => Ok(HttpsResponse {
+                status: response.status().numeric(),
+                content_type: response
+                    .headers()
+                    .get(Header::CONTENT_TYPE)
+                    .map(str::to_string),
+                body: response.into_body(),
+            }),
+            Err(HTTPError::Status(status)) => Ok(HttpsResponse {
+                status: status.numeric(),
+                content_type: None,
+                body: vec![],
+            }),
+            Err(error) => Err(HttpsError::new(error)),
+        }
+    }
+}
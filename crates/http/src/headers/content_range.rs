@@ -0,0 +1,63 @@
+//! Utilities to understand the value of the `content-range` header
+//!
+//! <https://www.rfc-editor.org/rfc/rfc9110#name-content-range>
+
+use std::ops::RangeInclusive;
+
+/// The range of bytes that is contained in a partial response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentRange {
+    /// The offset of the first byte in the response
+    pub first_byte: u64,
+
+    /// The offset of the last byte in the response (inclusive)
+    pub last_byte: u64,
+
+    /// The size of the whole resource, if known
+    pub complete_length: Option<u64>,
+}
+
+impl ContentRange {
+    /// Parse a header value like `bytes 0-499/1234` or `bytes 500-999/*`
+    ///
+    /// Unsatisfied ranges (`bytes */1234`) and units other than `bytes` are rejected.
+    #[must_use]
+    pub fn parse(header_value: &str) -> Option<Self> {
+        let (unit, range) = header_value.trim().split_once(' ')?;
+        if !unit.eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+
+        let (range, complete_length) = range.trim_start().split_once('/')?;
+        let (first_byte, last_byte) = range.split_once('-')?;
+        let first_byte: u64 = first_byte.parse().ok()?;
+        let last_byte: u64 = last_byte.parse().ok()?;
+
+        let complete_length = match complete_length {
+            "*" => None,
+            length => Some(length.parse().ok()?),
+        };
+
+        // A range is invalid if the last byte is before the first one or outside the resource
+        if last_byte < first_byte || complete_length.is_some_and(|length| length <= last_byte) {
+            return None;
+        }
+
+        Some(Self {
+            first_byte,
+            last_byte,
+            complete_length,
+        })
+    }
+
+    #[must_use]
+    pub fn bytes(&self) -> RangeInclusive<u64> {
+        self.first_byte..=self.last_byte
+    }
+
+    /// The number of bytes in the range
+    #[must_use]
+    pub fn num_bytes(&self) -> u64 {
+        self.last_byte - self.first_byte + 1
+    }
+}
@@ -1,6 +1,7 @@
 //! <https://www.rfc-editor.org/rfc/rfc2616#section-4.2>
 
 mod cache_control;
+mod content_range;
 mod utils;
 mod value;
 
@@ -8,6 +9,7 @@ use std::collections::HashMap;
 
 use self::cache_control::CacheControlIterator;
 
+pub use content_range::ContentRange;
 pub use value::Header;
 
 /// Thin wrapper around a [HashMap] to provide case-insensitive
@@ -36,6 +38,10 @@ impl Headers {
         self.internal.insert(header, value);
     }
 
+    pub fn remove(&mut self, header: Header) -> Option<String> {
+        self.internal.remove(&header)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (&Header, &str)> {
         self.internal
             .iter()
@@ -50,6 +56,14 @@ impl Headers {
 
         CacheControlIterator::new(header)
     }
+
+    /// Parse the value of the `Content-Range` header
+    ///
+    /// Returns `None` if the header is not present or invalid.
+    #[must_use]
+    pub fn content_range(&self) -> Option<ContentRange> {
+        ContentRange::parse(self.get(Header::CONTENT_RANGE)?)
+    }
 }
 
 #[cfg(test)]
@@ -77,4 +91,43 @@ mod tests {
         assert_eq!(directives.next(), Some(CacheControlDirective::NoCache));
         assert!(directives.next().is_none());
     }
+
+    #[test]
+    fn content_range() {
+        let mut headers = Headers::default();
+        assert_eq!(headers.content_range(), None);
+
+        headers.set(Header::CONTENT_RANGE, "bytes 0-499/1234".to_string());
+        assert_eq!(
+            headers.content_range(),
+            Some(ContentRange {
+                first_byte: 0,
+                last_byte: 499,
+                complete_length: Some(1234),
+            })
+        );
+        assert_eq!(headers.content_range().unwrap().num_bytes(), 500);
+
+        headers.set(Header::CONTENT_RANGE, "bytes 500-999/*".to_string());
+        assert_eq!(
+            headers.content_range(),
+            Some(ContentRange {
+                first_byte: 500,
+                last_byte: 999,
+                complete_length: None,
+            })
+        );
+
+        for invalid in [
+            "bytes */1234",
+            "bytes 500-499/1234",
+            "bytes 0-1234/1234",
+            "pages 1-2/3",
+            "bytes 0-/10",
+            "bytes 0-9",
+        ] {
+            headers.set(Header::CONTENT_RANGE, invalid.to_string());
+            assert_eq!(headers.content_range(), None, "{invalid}");
+        }
+    }
 }
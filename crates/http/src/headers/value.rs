@@ -500,6 +500,23 @@ impl Header {
     /// [MDN](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Sec-Purpose)
     pub const SEC_PURPOSE: Self = Self::Defined(DefinedHeader::SecPurpose);
 
+    /// Sent by the client during the WebSocket opening handshake, contains a random nonce
+    /// that the server uses to prove that it received the handshake.
+    ///
+    /// [MDN](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Sec-WebSocket-Key)
+    pub const SEC_WEBSOCKET_KEY: Self = Self::Defined(DefinedHeader::SecWebSocketKey);
+
+    /// Sent by the server during the WebSocket opening handshake, derived from the
+    /// `Sec-WebSocket-Key` of the client.
+    ///
+    /// [MDN](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Sec-WebSocket-Accept)
+    pub const SEC_WEBSOCKET_ACCEPT: Self = Self::Defined(DefinedHeader::SecWebSocketAccept);
+
+    /// The version of the WebSocket protocol that the client wants to use.
+    ///
+    /// [MDN](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Sec-WebSocket-Version)
+    pub const SEC_WEBSOCKET_VERSION: Self = Self::Defined(DefinedHeader::SecWebSocketVersion);
+
     /// A request header sent in preemptive request to fetch() a resource during service
     /// worker boot. The value, which is set with `NavigationPreloadManager.setHeaderValue()`,
     /// can be used to inform a server that a different resource should be returned than in a
@@ -754,6 +771,9 @@ impl Header {
         "sec-fetch-user" => Self::SEC_FETCH_USER,
         "sec-fetch-dest" => Self::SEC_FETCH_DEST,
         "sec-purpose" => Self::SEC_PURPOSE,
+        "sec-websocket-key" => Self::SEC_WEBSOCKET_KEY,
+        "sec-websocket-accept" => Self::SEC_WEBSOCKET_ACCEPT,
+        "sec-websocket-version" => Self::SEC_WEBSOCKET_VERSION,
         "service-worker-navigation-preload" => Self::SERVICE_WORKER_NAVIGATION_PRELOAD,
         "report-to" => Self::REPORT_TO,
         "transfer-encoding" => Self::TRANSFER_ENCODING,
@@ -1012,6 +1032,15 @@ pub enum DefinedHeader {
     /// Refer to [Header::SEC_PURPOSE] for documentation
     SecPurpose,
 
+    /// Refer to [Header::SEC_WEBSOCKET_KEY] for documentation
+    SecWebSocketKey,
+
+    /// Refer to [Header::SEC_WEBSOCKET_ACCEPT] for documentation
+    SecWebSocketAccept,
+
+    /// Refer to [Header::SEC_WEBSOCKET_VERSION] for documentation
+    SecWebSocketVersion,
+
     /// Refer to [Header::SERVICE_WORKER_NAVIGATION_PRELOAD] for documentation
     ServiceWorkerNavigationPreload,
 
@@ -1,10 +1,15 @@
 use std::{
-    net::TcpStream,
+    io::{self, BufReader},
+    net::{SocketAddr, TcpStream},
     sync::{Arc, OnceLock},
 };
 
-use crate::request::HTTPError;
+use crate::{
+    request::{HTTPError, HTTP_NEWLINE},
+    response, StatusCode,
+};
 
+/// The trust anchors that server certificates are verified against
 static CERTIFICATE_STORE: OnceLock<Arc<rustls::RootCertStore>> = OnceLock::new();
 
 const TLS_PORT: u16 = 443;
@@ -20,11 +25,39 @@ fn root_certificates() -> Arc<rustls::RootCertStore> {
         .clone()
 }
 
+/// Use a custom set of trust anchors for all https connections
+///
+/// By default, the [Mozilla root certificates](webpki_roots) are trusted.
+/// This must be called before the first https connection is established. Afterwards,
+/// the trust anchors can no longer be changed and the rejected `store` is returned as an error.
+pub fn set_root_certificates(
+    store: rustls::RootCertStore,
+) -> Result<(), Arc<rustls::RootCertStore>> {
+    CERTIFICATE_STORE.set(Arc::new(store))
+}
+
+/// Establish a TLS connection with a server
+///
+/// The certificate chain presented by the server is verified by rustls during the handshake:
+/// Every certificate must be signed by its issuer, up to one of the [root certificates](set_root_certificates),
+/// all of them must be valid at the current time and the end-entity certificate must be issued for `domain_name`.
+/// If any of these checks fail, the handshake is aborted with a [rustls::Error::InvalidCertificate].
+///
+/// If a `proxy` is provided, then the connection is tunneled through it and the TLS
+/// handshake happens directly with the server.
 pub(crate) fn establish_connection(
     domain_name: String,
     port: Option<u16>,
+    proxy: Option<SocketAddr>,
 ) -> Result<rustls::StreamOwned<rustls::ClientConnection, TcpStream>, HTTPError> {
-    let socket = TcpStream::connect((domain_name.as_str(), port.unwrap_or(TLS_PORT)))?;
+    let port = port.unwrap_or(TLS_PORT);
+    let socket = match proxy {
+        Some(proxy) => {
+            log::info!("Tunneling https connection to {domain_name}:{port} via {proxy}");
+            open_tunnel(TcpStream::connect(proxy)?, &domain_name, port)?
+        },
+        None => TcpStream::connect((domain_name.as_str(), port))?,
+    };
     let server_name = rustls::pki_types::ServerName::try_from(domain_name).expect("invalid domain");
 
     let config = rustls::ClientConfig::builder()
@@ -35,3 +68,124 @@ pub(crate) fn establish_connection(
     let stream = rustls::StreamOwned::new(client, socket);
     Ok(stream)
 }
+
+/// Ask a proxy to open a tunnel to `host:port` on the given connection
+///
+/// Once this function returns, all data written to the stream is forwarded to the host.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9110#name-connect>
+fn open_tunnel<S: io::Read + io::Write>(
+    mut stream: S,
+    host: &str,
+    port: u16,
+) -> Result<S, HTTPError> {
+    write!(
+        stream,
+        "CONNECT {host}:{port} HTTP/1.1{HTTP_NEWLINE}Host: {host}:{port}{HTTP_NEWLINE}{HTTP_NEWLINE}"
+    )?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let (status, _) = response::receive_status_and_headers(&mut reader)?;
+
+    // Any 2xx response indicates that the tunnel was established
+    if status == StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+        log::error!("Proxy requires authentication to connect to {host}:{port}");
+        return Err(HTTPError::ProxyAuthenticationRequired);
+    } else if !status.is_success() {
+        log::error!("Proxy refused to connect to {host}:{port} ({status:?})");
+        return Err(HTTPError::ProxyTunnel);
+    }
+
+    // The server does not send anything before the TLS handshake starts, so if the proxy
+    // sent additional data then it would be lost inside the buffer
+    if !reader.buffer().is_empty() {
+        return Err(HTTPError::InvalidResponse);
+    }
+
+    Ok(reader.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A connection to a proxy that answers with a fixed response
+    struct MockProxy {
+        response: io::Cursor<&'static [u8]>,
+        request: Vec<u8>,
+    }
+
+    impl MockProxy {
+        fn new(response: &'static [u8]) -> Self {
+            Self {
+                response: io::Cursor::new(response),
+                request: vec![],
+            }
+        }
+    }
+
+    impl io::Read for MockProxy {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.response.read(buf)
+        }
+    }
+
+    impl io::Write for MockProxy {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.request.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn root_certificates_cannot_be_replaced() {
+        assert!(set_root_certificates(rustls::RootCertStore::empty()).is_ok());
+
+        let mut store = rustls::RootCertStore::empty();
+        store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let rejected = set_root_certificates(store).unwrap_err();
+
+        // The caller's store is handed back, the one in use is unchanged
+        assert_eq!(rejected.len(), webpki_roots::TLS_SERVER_ROOTS.len());
+        assert!(root_certificates().is_empty());
+    }
+
+    #[test]
+    fn tunnel_established() {
+        let proxy = MockProxy::new(b"HTTP/1.1 200 Connection Established\r\nVia: proxy\r\n\r\n");
+
+        let proxy = open_tunnel(proxy, "example.com", 443).unwrap();
+        assert_eq!(
+            proxy.request,
+            b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn tunnel_refused() {
+        let proxy = MockProxy::new(
+            b"HTTP/1.1 407 Proxy Authentication Required\r\nContent-Length: 0\r\n\r\n",
+        );
+        assert!(matches!(
+            open_tunnel(proxy, "example.com", 443),
+            Err(HTTPError::ProxyAuthenticationRequired)
+        ));
+
+        let proxy = MockProxy::new(b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n");
+        assert!(matches!(
+            open_tunnel(proxy, "example.com", 443),
+            Err(HTTPError::ProxyTunnel)
+        ));
+
+        // The proxy closes the connection before sending a complete response
+        let proxy = MockProxy::new(b"HTTP/1.1 200 OK\r\n");
+        assert!(matches!(
+            open_tunnel(proxy, "example.com", 443),
+            Err(HTTPError::IO(_))
+        ));
+    }
+}
@@ -2,13 +2,18 @@
 //!
 //! [Specifications](https://developer.mozilla.org/en-US/docs/Web/HTTP/Resources_and_specifications)
 
+mod dns_over_https;
 mod headers;
 mod https;
+pub mod multipart;
 pub mod request;
 mod response;
 mod status_code;
+pub mod websocket;
 
-pub use headers::{Header, Headers};
+pub use dns_over_https::{use_dns_over_https, DnsOverHttpsClient};
+pub use headers::{ContentRange, Header, Headers};
+pub use https::set_root_certificates;
 pub use request::Request;
-pub use response::Response;
+pub use response::{Response, ServedRange};
 pub use status_code::StatusCode;
@@ -0,0 +1,247 @@
+//! Multipart bodies
+//!
+//! Request bodies with the `multipart/form-data` content type are built by [MultipartBody]
+//! (<https://www.rfc-editor.org/rfc/rfc7578>), responses to multi-range requests with
+//! the `multipart/byteranges` content type are split by [Response::byte_ranges](crate::Response::byte_ranges)
+//! (<https://www.rfc-editor.org/rfc/rfc9110#name-multipart-byteranges>).
+
+use sl_std::{bytestream::ByteStream, rand::RNG};
+
+use crate::{request::HTTP_NEWLINE, response::parse_header_line, ContentRange, Header};
+
+/// The number of random characters in a generated boundary
+const BOUNDARY_RANDOM_LENGTH: usize = 24;
+
+/// Builds a `multipart/form-data` body from a list of form fields
+#[derive(Clone, Debug)]
+pub struct MultipartBody {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartBody {
+    /// Create an empty body with a random boundary
+    #[must_use]
+    pub fn new() -> Self {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+        let mut rng = RNG::from_entropy();
+        let random_part: String = (0..BOUNDARY_RANDOM_LENGTH)
+            .map(|_| ALPHABET[rng.next_range(0, ALPHABET.len() as u64) as usize] as char)
+            .collect();
+
+        Self::with_boundary(format!("----StormlichtFormBoundary{random_part}"))
+    }
+
+    /// Create an empty body with the given boundary
+    ///
+    /// The boundary must not occur anywhere within the values that are added later.
+    #[must_use]
+    pub fn with_boundary(boundary: String) -> Self {
+        Self {
+            boundary,
+            body: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The value of the `Content-Type` header for this body
+    #[must_use]
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// Append a field with a text value
+    pub fn add_field(&mut self, name: &str, value: &str) {
+        self.body.extend_from_slice(
+            format!(
+                "--{boundary}{HTTP_NEWLINE}\
+                Content-Disposition: form-data; name=\"{name}\"{HTTP_NEWLINE}\
+                {HTTP_NEWLINE}\
+                {value}{HTTP_NEWLINE}",
+                boundary = self.boundary,
+                name = escape_name(name),
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Append the closing delimiter and return the serialized body
+    #[must_use]
+    pub fn finish(mut self) -> Vec<u8> {
+        self.body
+            .extend_from_slice(format!("--{}--{HTTP_NEWLINE}", self.boundary).as_bytes());
+        self.body
+    }
+}
+
+impl Default for MultipartBody {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Escape a field name for use within a quoted `Content-Disposition` parameter
+///
+/// <https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#multipart-form-data>
+fn escape_name(name: &str) -> String {
+    name.replace('\n', "%0A")
+        .replace('\r', "%0D")
+        .replace('"', "%22")
+}
+
+/// Extract the boundary from a `multipart/byteranges` content type
+///
+/// Returns `None` if the content type is not `multipart/byteranges`.
+pub(crate) fn byteranges_boundary(content_type: &str) -> Option<&str> {
+    let mut parameters = content_type.split(';');
+    let essence = parameters.next()?.trim();
+    if !essence.eq_ignore_ascii_case("multipart/byteranges") {
+        return None;
+    }
+
+    parameters.find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Split a `multipart/byteranges` body into its parts
+///
+/// Every part must have a valid `Content-Range` header that matches the length of its content.
+pub(crate) fn parse_byteranges(
+    body: &[u8],
+    boundary: &str,
+) -> Option<Vec<(ContentRange, Vec<u8>)>> {
+    // Every delimiter except for the first one is preceded by a newline,
+    // which is not part of the content of the previous part
+    let delimiter = format!("{HTTP_NEWLINE}--{boundary}");
+    let first_delimiter = &delimiter[HTTP_NEWLINE.len()..];
+
+    let mut stream = ByteStream::new(body);
+    if stream.remaining().starts_with(first_delimiter.as_bytes()) {
+        stream.advance(first_delimiter.len());
+    } else {
+        // Skip the preamble
+        stream.read_until(delimiter.as_bytes())?;
+    }
+
+    let mut parts = vec![];
+
+    // The close delimiter has two trailing hyphens, anything after it is ignored
+    while !stream.remaining().starts_with(b"--") {
+        // Skip any whitespace after the delimiter
+        stream.read_until(HTTP_NEWLINE.as_bytes())?;
+
+        let mut content_range = None;
+        loop {
+            let header_line = stream.read_until(HTTP_NEWLINE.as_bytes())?;
+
+            // The headers are terminated by an empty line
+            if header_line.is_empty() {
+                break;
+            }
+
+            let (header, value) = parse_header_line(header_line).ok()?;
+            if header == Header::CONTENT_RANGE {
+                content_range = Some(ContentRange::parse(&value)?);
+            }
+        }
+
+        let content_range = content_range?;
+        let content = stream.read_until(delimiter.as_bytes())?;
+        if content.len() as u64 != content_range.num_bytes() {
+            return None;
+        }
+
+        parts.push((content_range, content.to_vec()));
+    }
+
+    Some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_fields() {
+        let mut body = MultipartBody::with_boundary("boundary".to_string());
+        body.add_field("name", "value");
+        body.add_field("quote\"d", "two\r\nlines");
+
+        assert_eq!(
+            body.content_type(),
+            "multipart/form-data; boundary=boundary"
+        );
+        assert_eq!(
+            String::from_utf8(body.finish()).unwrap(),
+            "--boundary\r\n\
+            Content-Disposition: form-data; name=\"name\"\r\n\
+            \r\n\
+            value\r\n\
+            --boundary\r\n\
+            Content-Disposition: form-data; name=\"quote%22d\"\r\n\
+            \r\n\
+            two\r\nlines\r\n\
+            --boundary--\r\n"
+        );
+    }
+
+    #[test]
+    fn random_boundaries() {
+        let a = MultipartBody::new();
+        let b = MultipartBody::new();
+
+        assert_ne!(a.boundary(), b.boundary());
+        assert!(a.boundary().is_ascii());
+    }
+
+    #[test]
+    fn byteranges_boundary_from_content_type() {
+        assert_eq!(
+            byteranges_boundary("multipart/byteranges; boundary=3d6b6a416f9b5"),
+            Some("3d6b6a416f9b5")
+        );
+        assert_eq!(
+            byteranges_boundary("Multipart/ByteRanges;boundary=\"quoted\""),
+            Some("quoted")
+        );
+        assert_eq!(
+            byteranges_boundary("multipart/form-data; boundary=boundary"),
+            None
+        );
+        assert_eq!(byteranges_boundary("multipart/byteranges"), None);
+    }
+
+    #[test]
+    fn invalid_byteranges() {
+        // Length of the content does not match the Content-Range
+        assert!(parse_byteranges(
+            b"--b\r\nContent-Range: bytes 0-9/20\r\n\r\nhello\r\n--b--\r\n",
+            "b"
+        )
+        .is_none());
+
+        // Missing close delimiter
+        assert!(
+            parse_byteranges(b"--b\r\nContent-Range: bytes 0-4/20\r\n\r\nhello", "b").is_none()
+        );
+
+        // The preamble is ignored
+        assert_eq!(
+            parse_byteranges(
+                b"preamble\r\n--b\r\nContent-Range: bytes 0-4/20\r\n\r\nhello\r\n--b--\r\n",
+                "b"
+            )
+            .map(|parts| parts.len()),
+            Some(1)
+        );
+    }
+}
@@ -3,14 +3,18 @@
 use std::io::{BufRead, BufReader, Read};
 
 use compression::{brotli, gzip, zlib};
-use sl_std::{ascii, iter::MultiElementSplit};
+use sl_std::{ascii, bytestream::ByteStream};
 
 use crate::{
-    request::{Context, HTTPError, HTTP_NEWLINE},
+    multipart,
+    request::{Context, HTTPError, Method, HTTP_NEWLINE},
     status_code::StatusCode,
-    Header, Headers,
+    ContentRange, Header, Headers,
 };
 
+/// The maximum number of bytes that are reserved for a response body before any of it was received
+const MAX_INITIAL_BODY_CAPACITY: usize = 64 * 1024;
+
 /// Like [BufReader::read_until], except the needle may have arbitrary length
 fn read_until<R: std::io::Read>(
     reader: &mut BufReader<R>,
@@ -34,16 +38,150 @@ fn read_until<R: std::io::Read>(
             None => {
                 result.extend(reader.buffer());
                 reader.consume(reader.capacity());
-                reader.fill_buf()?;
+
+                if reader.fill_buf()?.is_empty() {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
             },
         }
     }
 }
 
+/// Read the status line and the headers of a response, leaving the body unconsumed
+pub(crate) fn receive_status_and_headers<R: std::io::Read>(
+    reader: &mut BufReader<R>,
+) -> Result<(StatusCode, Headers), HTTPError> {
+    let needle = b"\r\n\r\n";
+    let header_bytes = read_until(reader, needle)?;
+
+    let mut response_lines = ByteStream::new(&header_bytes);
+
+    let mut status_line_words = response_lines
+        .read_line()
+        .ok_or(HTTPError::InvalidResponse)?
+        .split(|&b| b == b' ')
+        .filter(|word| !word.is_empty());
+
+    if !matches!(status_line_words.next(), Some(b"HTTP/1.1")) {
+        return Err(HTTPError::InvalidResponse);
+    }
+
+    // Parse status code
+    let status: StatusCode =
+        std::str::from_utf8(status_line_words.next().ok_or(HTTPError::InvalidResponse)?)
+            .map_err(|_| HTTPError::InvalidResponse)?
+            .parse()
+            .map_err(|_| HTTPError::InvalidResponse)?;
+
+    // What follows is a textual description of the error code ("OK" for 200) - we don't care about that
+
+    // Parse the response headers
+    let mut headers = Headers::default();
+    while let Some(header_line) = response_lines.read_line() {
+        // An empty header indicates the end of the list of headers
+        if header_line.is_empty() {
+            break;
+        }
+
+        let (header, value) = parse_header_line(header_line)?;
+        headers.set(header, value);
+    }
+
+    Ok((status, headers))
+}
+
+/// Parse a single `name: value` line of a header (or trailer) section
+pub(crate) fn parse_header_line(header_line: &[u8]) -> Result<(Header, String), HTTPError> {
+    let separator = header_line
+        .iter()
+        .position(|&elem| elem == b':')
+        .ok_or(HTTPError::InvalidResponse)?;
+
+    let key = &header_line[..separator];
+    let value = &header_line[separator + 1..];
+
+    // FIXME: Find a way not to clone the header here
+    let header_name = ascii::Str::from_bytes(key)
+        .ok_or(HTTPError::InvalidResponse)?
+        .trim()
+        .to_lowercase();
+    let header = Header::from_lowercase_str(&header_name);
+    let value = std::str::from_utf8(value)
+        .map_err(|_| HTTPError::InvalidResponse)?
+        .trim()
+        .to_owned();
+
+    Ok((header, value))
+}
+
+/// Read the trailer section that follows the last chunk of a chunked body
+///
+/// If the response announced its trailer fields using the `Trailer` header then
+/// all other fields are discarded.
+///
+/// <https://datatracker.ietf.org/doc/html/rfc9112#name-chunked-trailer-section>
+fn receive_trailers<R: std::io::Read>(
+    reader: &mut BufReader<R>,
+    headers: &Headers,
+) -> Result<Headers, HTTPError> {
+    let announced_fields: Option<Vec<String>> = headers.get(Header::TRAILER).map(|trailer| {
+        trailer
+            .split(',')
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect()
+    });
+
+    let mut trailers = Headers::default();
+    loop {
+        let line = read_until(reader, HTTP_NEWLINE.as_bytes())?;
+        let line = &line[..line.len() - HTTP_NEWLINE.len()];
+
+        // The trailer section is terminated by an empty line
+        if line.is_empty() {
+            break;
+        }
+
+        let (header, value) = parse_header_line(line)?;
+        let is_announced = announced_fields.as_ref().map_or(true, |fields| {
+            fields
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(header.as_str()))
+        });
+
+        if is_announced {
+            trailers.set(header, value);
+        } else {
+            log::warn!(
+                "Ignoring trailer field {:?} that was not announced",
+                header.as_str()
+            );
+        }
+    }
+
+    Ok(trailers)
+}
+
+/// The part of a resource that is contained in a [Response]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServedRange {
+    /// The whole resource was sent, either because no range was requested
+    /// or because the server ignored it
+    Full,
+
+    /// Only the given range of the resource was sent (`206 Partial Content`)
+    Partial(ContentRange),
+}
+
 #[derive(Clone, Debug)]
 pub struct Response {
     pub status: StatusCode,
     pub headers: Headers,
+
+    /// Fields from the trailer section of a chunked body
+    ///
+    /// These are kept separate from the headers, since they are received after the body
+    /// and must not override any header fields.
+    pub trailers: Headers,
     pub body: Vec<u8>,
     context: Context,
 }
@@ -59,11 +197,61 @@ impl Response {
         &self.headers
     }
 
+    #[must_use]
+    pub fn trailers(&self) -> &Headers {
+        &self.trailers
+    }
+
     #[must_use]
     pub fn status(&self) -> StatusCode {
         self.status
     }
 
+    /// Determine which part of the resource is contained in the body
+    ///
+    /// Returns `None` for a `206 Partial Content` response without a valid `Content-Range` header.
+    #[must_use]
+    pub fn content_range(&self) -> Option<ServedRange> {
+        if self.status != StatusCode::PARTIAL_CONTENT {
+            return Some(ServedRange::Full);
+        }
+
+        self.headers.content_range().map(ServedRange::Partial)
+    }
+
+    /// Split the body of a `206 Partial Content` response into the ranges it contains
+    ///
+    /// When multiple ranges were [requested](crate::Request::with_ranges), the server may send a
+    /// `multipart/byteranges` body with one part per range. Otherwise, the whole body
+    /// makes up a single range.
+    ///
+    /// Returns `None` if the response is not partial or the body is malformed.
+    #[must_use]
+    pub fn byte_ranges(&self) -> Option<Vec<(ContentRange, Vec<u8>)>> {
+        if self.status != StatusCode::PARTIAL_CONTENT {
+            return None;
+        }
+
+        let boundary = self
+            .headers
+            .get(Header::CONTENT_TYPE)
+            .and_then(multipart::byteranges_boundary);
+
+        match boundary {
+            Some(boundary) => {
+                let parts = multipart::parse_byteranges(&self.body, boundary);
+                if parts.is_none() {
+                    log::warn!("Invalid multipart/byteranges body");
+                }
+                parts
+            },
+            None => {
+                let content_range = self.headers.content_range()?;
+                Some(vec![(content_range, self.body.clone())])
+            },
+        }
+    }
+
     #[must_use]
     pub fn body(&self) -> &[u8] {
         &self.body
@@ -84,66 +272,16 @@ impl Response {
     ) -> Result<Self, HTTPError> {
         // TODO all of this is very insecure - we blindly trust the size in Transfer-Encoding: chunked,
         // no timeouts, stuff like that.
-        let needle = b"\r\n\r\n";
-        let header_bytes = read_until(reader, needle)?;
-
-        let mut response_lines =
-            MultiElementSplit::new(&header_bytes, |w: &[u8; 2]| w == HTTP_NEWLINE.as_bytes());
-
-        let mut status_line_words = response_lines
-            .next()
-            .ok_or(HTTPError::InvalidResponse)?
-            .split(|&b| b == b' ')
-            .filter(|word| !word.is_empty());
-
-        if !matches!(status_line_words.next(), Some(b"HTTP/1.1")) {
-            return Err(HTTPError::InvalidResponse);
-        }
-
-        // Parse status code
-        let status: StatusCode =
-            std::str::from_utf8(status_line_words.next().ok_or(HTTPError::InvalidResponse)?)
-                .map_err(|_| HTTPError::InvalidResponse)?
-                .parse()
-                .map_err(|_| HTTPError::InvalidResponse)?;
-
-        // What follows is a textual description of the error code ("OK" for 200) - we don't care about that
-
-        // Parse the response headers
-        let mut headers = Headers::default();
-        for header_line in response_lines {
-            // An empty header indicates the end of the list of headers
-            if header_line.is_empty() {
-                break;
-            }
-
-            let separator = header_line
-                .iter()
-                .position(|&elem| elem == b':')
-                .ok_or(HTTPError::InvalidResponse)?;
-
-            let key = &header_line[..separator];
-            let value = &header_line[separator + 1..];
-
-            // FIXME: Find a way not to clone the header here
-            let header_name = ascii::Str::from_bytes(key)
-                .ok_or(HTTPError::InvalidResponse)?
-                .trim()
-                .to_lowercase();
-            let header = Header::from_lowercase_str(&header_name);
-            headers.set(
-                header,
-                std::str::from_utf8(value)
-                    .map_err(|_| HTTPError::InvalidResponse)?
-                    .trim()
-                    .to_owned(),
-            );
-        }
+        let (status, headers) = receive_status_and_headers(reader)?;
 
-        if !status.allowed_to_have_body() {
+        // Responses to HEAD requests include the headers that a GET request would have caused,
+        // like Content-Length, but no body
+        // https://www.rfc-editor.org/rfc/rfc9110#name-head
+        if !status.allowed_to_have_body() || context.method == Method::Head {
             return Ok(Self {
                 status,
                 headers,
+                trailers: Headers::default(),
                 body: vec![],
                 context,
             });
@@ -151,6 +289,7 @@ impl Response {
 
         // Anything after the headers is the actual response body
         // The length of the body depends on the headers that were sent
+        let mut trailers = Headers::default();
         let mut body: Vec<u8> = if let Some(transfer_encoding) =
             headers.get(Header::TRANSFER_ENCODING)
         {
@@ -170,6 +309,7 @@ impl Response {
 
                         if size == 0 {
                             // > The chunked transfer coding is complete when a chunk with a chunk-size of zero is received.
+                            trailers = receive_trailers(reader, &headers)?;
                             break;
                         }
 
@@ -197,12 +337,26 @@ impl Response {
                 },
             }
         } else if let Some(content_length) = headers.get(Header::CONTENT_LENGTH) {
-            // Reserve enough space for the content inside the response body
             let content_length: usize =
                 str::parse(content_length).map_err(|_| HTTPError::InvalidResponse)?;
-            let mut buffer = vec![0; content_length];
 
-            reader.read_exact(&mut buffer)?;
+            // Reserve space for the response body, but don't trust the server with
+            // huge allocations. The buffer grows as more data arrives.
+            let mut buffer = Vec::with_capacity(content_length.min(MAX_INITIAL_BODY_CAPACITY));
+
+            // Anything after the body is left on the stream
+            reader
+                .take(content_length as u64)
+                .read_to_end(&mut buffer)?;
+
+            if buffer.len() != content_length {
+                log::warn!(
+                    "Connection closed after {} of {content_length} bytes",
+                    buffer.len()
+                );
+                return Err(HTTPError::IncompleteBody);
+            }
+
             buffer
         } else {
             log::warn!("Neither Transfer-Encoding nor Content-Length were provided, we don't know how to decode the body!");
@@ -233,8 +387,189 @@ impl Response {
         Ok(Self {
             status,
             headers,
+            trailers,
             body,
             context,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::*;
+
+    fn context(method: Method) -> Context {
+        Context::new("http://example.com".parse().unwrap(), method)
+    }
+
+    #[test]
+    fn head_response_has_no_body() {
+        // The body would be 100 bytes long, but the server does not send it
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n",
+        ));
+
+        let response = Response::receive(&mut reader, context(Method::Head)).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(Header::CONTENT_LENGTH), Some("100"));
+        assert!(response.body().is_empty());
+    }
+
+    #[test]
+    fn status_without_body() {
+        for status in ["100", "204", "304"] {
+            let http = format!("HTTP/1.1 {status} Status\r\nContent-Length: 5\r\n\r\nhello");
+            let mut reader = BufReader::new(Cursor::new(http.into_bytes()));
+
+            let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+            assert!(response.body().is_empty());
+        }
+    }
+
+    #[test]
+    fn partial_content() {
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 2-4/10\r\nContent-Length: 3\r\n\r\ncde",
+        ));
+
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(response.body(), b"cde");
+        assert_eq!(
+            response.content_range(),
+            Some(ServedRange::Partial(ContentRange {
+                first_byte: 2,
+                last_byte: 4,
+                complete_length: Some(10),
+            }))
+        );
+
+        // The server ignored the range and sent the whole resource
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabcdefghij",
+        ));
+
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(response.content_range(), Some(ServedRange::Full));
+    }
+
+    #[test]
+    fn incomplete_body() {
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\nonly a few bytes",
+        ));
+
+        assert!(matches!(
+            Response::receive(&mut reader, context(Method::Get)),
+            Err(HTTPError::IncompleteBody)
+        ));
+    }
+
+    #[test]
+    fn bytes_after_body_are_not_consumed() {
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhelloHTTP/1.1 204 No Content\r\n\r\n",
+        ));
+
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(response.body(), b"hello");
+
+        // The next response on the same connection can still be read
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let mut remaining = vec![];
+        reader.read_to_end(&mut remaining).unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn chunked_body_with_trailers() {
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: Server-Timing\r\nServer-Timing: header\r\n\r\n\
+              5\r\nhello\r\n6\r\n world\r\n0\r\nServer-Timing: total;dur=12\r\nX-Unannounced: 1\r\n\r\n\
+              HTTP/1.1 204 No Content\r\n\r\n",
+        ));
+
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(response.body(), b"hello world");
+        assert_eq!(
+            response.trailers().get(Header::SERVER_TIMING),
+            Some("total;dur=12")
+        );
+        assert_eq!(response.trailers().iter().count(), 1);
+
+        // Trailers don't override the headers
+        assert_eq!(
+            response.headers().get(Header::SERVER_TIMING),
+            Some("header")
+        );
+
+        // The whole trailer section was consumed
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[test]
+    fn multipart_byteranges() {
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 206 Partial Content\r\n\
+              Content-Type: multipart/byteranges; boundary=\"THIS_STRING_SEPARATES\"\r\n\
+              Content-Length: 207\r\n\r\n\
+              --THIS_STRING_SEPARATES\r\n\
+              Content-Type: text/plain\r\n\
+              Content-Range: bytes 0-4/20\r\n\
+              \r\n\
+              hello\r\n\
+              --THIS_STRING_SEPARATES\r\n\
+              Content-Type: text/plain\r\n\
+              Content-Range: bytes 10-14/20\r\n\
+              \r\n\
+              world\r\n\
+              --THIS_STRING_SEPARATES--\r\n",
+        ));
+
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(response.content_range(), None);
+        assert_eq!(
+            response.byte_ranges(),
+            Some(vec![
+                (
+                    ContentRange {
+                        first_byte: 0,
+                        last_byte: 4,
+                        complete_length: Some(20),
+                    },
+                    b"hello".to_vec()
+                ),
+                (
+                    ContentRange {
+                        first_byte: 10,
+                        last_byte: 14,
+                        complete_length: Some(20),
+                    },
+                    b"world".to_vec()
+                ),
+            ])
+        );
+
+        // Single range responses consist of a single part
+        let mut reader = BufReader::new(Cursor::new(
+            b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 2-4/10\r\nContent-Length: 3\r\n\r\ncde",
+        ));
+
+        let response = Response::receive(&mut reader, context(Method::Get)).unwrap();
+        assert_eq!(
+            response.byte_ranges(),
+            Some(vec![(
+                ContentRange {
+                    first_byte: 2,
+                    last_byte: 4,
+                    complete_length: Some(10),
+                },
+                b"cde".to_vec()
+            )])
+        );
+    }
+}
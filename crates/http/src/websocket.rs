@@ -0,0 +1,235 @@
+//! The opening handshake of the WebSocket protocol ([RFC 6455](https://www.rfc-editor.org/rfc/rfc6455))
+//!
+//! Once the handshake has completed, the connection is handed back to the caller,
+//! who is responsible for exchanging frames with the server.
+
+use std::{
+    io::{self, BufReader},
+    net::{SocketAddr, TcpStream},
+};
+
+use hash::{HashAlgorithm, Sha1};
+use sl_std::{base64, rand::RNG};
+use url::{Host, URL};
+
+use crate::{
+    https,
+    request::{HTTPError, Request},
+    response, Header, Headers, StatusCode,
+};
+
+/// Appended to the `Sec-WebSocket-Key` of the client before hashing it
+///
+/// <https://www.rfc-editor.org/rfc/rfc6455#section-1.3>
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The only version of the protocol defined by RFC 6455
+const WEBSOCKET_VERSION: &str = "13";
+
+/// A bidirectional byte stream that a WebSocket connection can be established on
+pub trait Stream: io::Read + io::Write {}
+
+impl<S> Stream for S where S: io::Read + io::Write {}
+
+/// Connect to a `ws://` or `wss://` url and perform the opening handshake
+///
+/// The returned reader may already contain data that the server sent after
+/// the handshake, so it must be used for all subsequent reads.
+pub fn connect(url: &URL) -> Result<BufReader<Box<dyn Stream>>, HTTPError> {
+    let http_scheme = match url.scheme().as_str() {
+        "ws" => "http",
+        "wss" => "https",
+        _ => return Err(HTTPError::NonWebSocketURL),
+    };
+
+    // The handshake is a regular http request, so build the equivalent http url
+    let serialized = url.serialize(url::ExcludeFragment::Yes);
+    let http_url: URL = format!(
+        "{http_scheme}{}",
+        &serialized.as_str()[url.scheme().len()..]
+    )
+    .parse()
+    .map_err(|_| HTTPError::NonWebSocketURL)?;
+
+    // Special urls always have a host, so this only fails for empty hosts
+    let host = http_url.host().ok_or(HTTPError::NonWebSocketURL)?;
+    let port = http_url.port();
+
+    let stream: Box<dyn Stream> = match (http_scheme, host) {
+        ("http", Host::Domain(host) | Host::OpaqueHost(host)) => {
+            let ip = dns::Domain::new(host.as_str())
+                .lookup()
+                .map_err(HTTPError::DNS)?;
+            Box::new(TcpStream::connect(SocketAddr::new(ip, port.unwrap_or(80)))?)
+        },
+        ("http", Host::Ip(ip)) => Box::new(TcpStream::connect((*ip, port.unwrap_or(80)))?),
+        ("https", Host::Domain(host) | Host::OpaqueHost(host)) => {
+            Box::new(https::establish_connection(host.to_string(), port, None)?)
+        },
+        ("https", Host::Ip(ip)) => {
+            Box::new(https::establish_connection(ip.to_string(), port, None)?)
+        },
+        _ => return Err(HTTPError::NonWebSocketURL),
+    };
+
+    let mut request = Request::get(&http_url);
+    handshake(stream, &mut request)
+}
+
+/// Perform the opening handshake on an established connection
+///
+/// The request is sent on `stream` after the handshake headers were added to it.
+/// If the server accepts the upgrade then the connection is returned, ready to exchange frames.
+pub fn handshake<S: io::Read + io::Write>(
+    stream: S,
+    request: &mut Request,
+) -> Result<BufReader<S>, HTTPError> {
+    // The key is a randomly selected 16-byte nonce
+    // https://www.rfc-editor.org/rfc/rfc6455#section-4.1
+    let mut nonce = [0; 16];
+    RNG::from_entropy().fill_bytes(&mut nonce);
+    let key = base64::b64encode(&nonce).to_string();
+
+    handshake_with_key(stream, request, &key)
+}
+
+fn handshake_with_key<S: io::Read + io::Write>(
+    mut stream: S,
+    request: &mut Request,
+    key: &str,
+) -> Result<BufReader<S>, HTTPError> {
+    let headers = request.headers_mut();
+    headers.set(Header::UPGRADE, "websocket".to_string());
+    headers.set(Header::CONNECTION, "Upgrade".to_string());
+    headers.set(Header::SEC_WEBSOCKET_KEY, key.to_string());
+    headers.set(Header::SEC_WEBSOCKET_VERSION, WEBSOCKET_VERSION.to_string());
+
+    request.write_to(&mut stream)?;
+
+    let mut reader = BufReader::new(stream);
+    let (status, headers) = response::receive_status_and_headers(&mut reader)?;
+
+    if status != StatusCode::SWITCHING_PROTOCOLS {
+        log::error!("Server refused websocket upgrade ({status:?})");
+        return Err(HTTPError::WebSocketHandshake);
+    }
+
+    if !is_valid_handshake_response(&headers, key) {
+        log::error!("Server sent an invalid websocket handshake response");
+        return Err(HTTPError::WebSocketHandshake);
+    }
+
+    Ok(reader)
+}
+
+/// <https://www.rfc-editor.org/rfc/rfc6455#section-4.1> (client requirements, steps 2 to 4)
+#[must_use]
+fn is_valid_handshake_response(headers: &Headers, key: &str) -> bool {
+    let is_websocket_upgrade = headers
+        .get(Header::UPGRADE)
+        .is_some_and(|upgrade| upgrade.trim().eq_ignore_ascii_case("websocket"));
+
+    let is_connection_upgrade = headers.get(Header::CONNECTION).is_some_and(|connection| {
+        connection
+            .split(',')
+            .any(|option| option.trim().eq_ignore_ascii_case("upgrade"))
+    });
+
+    let is_key_accepted = headers
+        .get(Header::SEC_WEBSOCKET_ACCEPT)
+        .is_some_and(|accept| accept.trim() == accept_key(key));
+
+    is_websocket_upgrade && is_connection_upgrade && is_key_accepted
+}
+
+/// Compute the `Sec-WebSocket-Accept` value that the server must respond with for a given key
+#[must_use]
+pub fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::default();
+    hasher.update(key.as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+
+    base64::b64encode(&hasher.finish()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    /// Example from <https://www.rfc-editor.org/rfc/rfc6455#section-1.3>
+    const SPEC_KEY: &str = "dGhlIHNhbXBsZSBub25jZQ==";
+
+    /// A connection to a server that answers with a fixed response
+    struct MockServer {
+        response: io::Cursor<&'static [u8]>,
+        request: Vec<u8>,
+    }
+
+    impl MockServer {
+        fn new(response: &'static [u8]) -> Self {
+            Self {
+                response: io::Cursor::new(response),
+                request: vec![],
+            }
+        }
+    }
+
+    impl io::Read for MockServer {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.response.read(buf)
+        }
+    }
+
+    impl io::Write for MockServer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.request.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn accept_key_from_spec() {
+        assert_eq!(accept_key(SPEC_KEY), "s3pPLMBiTxaQ9EYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn successful_handshake() {
+        let server = MockServer::new(
+            b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+              Sec-WebSocket-Accept: s3pPLMBiTxaQ9EYGzzhZRbK+xOo=\r\n\r\n\x81\x02hi",
+        );
+        let mut request = Request::get(&"http://example.com/chat".parse().unwrap());
+
+        let mut reader = handshake_with_key(server, &mut request, SPEC_KEY).unwrap();
+
+        let sent = String::from_utf8(reader.get_ref().request.clone()).unwrap();
+        assert!(sent.starts_with("GET /chat HTTP/1.1\r\n"));
+        assert!(sent.contains("upgrade: websocket\r\n"));
+        assert!(sent.contains(&format!("sec-websocket-key: {SPEC_KEY}\r\n")));
+        assert!(sent.contains("sec-websocket-version: 13\r\n"));
+
+        // Frames sent right after the handshake are not lost
+        let mut remaining = vec![];
+        reader.read_to_end(&mut remaining).unwrap();
+        assert_eq!(remaining, b"\x81\x02hi");
+    }
+
+    #[test]
+    fn wrong_accept_key() {
+        let server = MockServer::new(
+            b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+              Sec-WebSocket-Accept: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        );
+        let mut request = Request::get(&"http://example.com/chat".parse().unwrap());
+
+        assert!(matches!(
+            handshake_with_key(server, &mut request, SPEC_KEY),
+            Err(HTTPError::WebSocketHandshake)
+        ));
+    }
+}
@@ -1,12 +1,24 @@
 use libc::{
     accept, bind, close, cmsghdr, connect, iovec, listen, msghdr, recvmsg, sa_family_t, sendmsg,
-    sockaddr, sockaddr_un, socket, socketpair, socklen_t, strncpy, unlink, AF_UNIX, CMSG_DATA,
-    CMSG_FIRSTHDR, CMSG_LEN, CMSG_SPACE, SCM_RIGHTS, SOCK_STREAM, SOL_SOCKET,
+    shutdown, sockaddr, sockaddr_un, socket, socketpair, socklen_t, strncpy, unlink, AF_UNIX,
+    CMSG_DATA, CMSG_FIRSTHDR, CMSG_LEN, CMSG_SPACE, MSG_TRUNC, SCM_RIGHTS, SHUT_WR, SOCK_DGRAM,
+    SOCK_STREAM, SOL_SOCKET,
 };
 use std::{ffi, io, mem, ptr};
 
 pub struct FileDescriptor(ffi::c_int);
 
+/// The identity of the process on the other end of an [IpcClient]
+///
+/// The values are captured by the kernel when the connection is established.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeerCred {
+    pub pid: libc::pid_t,
+    pub uid: libc::uid_t,
+    pub gid: libc::gid_t,
+}
+
 /// A very short-lived ipc server that only serves to
 /// share a `fd` between two processes
 ///
@@ -92,6 +104,19 @@ impl IpcSetupServer {
 
         Ok(client)
     }
+
+    /// Accept an incoming connection from [IpcClient::connect_datagram]
+    ///
+    /// The connection is only used to hand one end of a datagram socket pair
+    /// to the peer, the other end is returned.
+    pub fn accept_datagram(&self) -> Result<IpcClient, io::Error> {
+        let setup_connection = self.accept()?;
+        let (ours, theirs) = IpcClient::pair_datagram()?;
+
+        setup_connection.send_fd(theirs.fd)?;
+
+        Ok(ours)
+    }
 }
 
 pub struct IpcClient {
@@ -99,12 +124,25 @@ pub struct IpcClient {
 }
 
 impl IpcClient {
+    /// Create a pair of connected stream sockets
     pub fn pair() -> io::Result<(Self, Self)> {
+        Self::pair_with_type(SOCK_STREAM)
+    }
+
+    /// Create a pair of connected datagram sockets
+    ///
+    /// Message boundaries are preserved: every call to [IpcClient::send_bytes] sends
+    /// one datagram and every call to [IpcClient::recv_bytes] receives exactly one.
+    pub fn pair_datagram() -> io::Result<(Self, Self)> {
+        Self::pair_with_type(SOCK_DGRAM)
+    }
+
+    fn pair_with_type(socket_type: ffi::c_int) -> io::Result<(Self, Self)> {
         let mut fds = [0; 2];
         let status = unsafe {
             socketpair(
                 AF_UNIX,
-                SOCK_STREAM,
+                socket_type,
                 0,
                 &mut fds as *mut _ as *mut ffi::c_int,
             )
@@ -140,6 +178,16 @@ impl IpcClient {
         Ok(client)
     }
 
+    /// Connect to an [IpcSetupServer] that calls [IpcSetupServer::accept_datagram]
+    ///
+    /// The returned client uses a datagram socket, see [IpcClient::pair_datagram].
+    pub fn connect_datagram() -> io::Result<Self> {
+        let setup_connection = Self::connect()?;
+        let fd = setup_connection.receive_fd()?;
+
+        Ok(Self { fd: fd.0 })
+    }
+
     pub fn send_bytes(&self, bytes: &mut [u8]) -> io::Result<()> {
         let mut io_vec = iovec {
             iov_base: bytes.as_mut_ptr() as *mut _ as *mut ffi::c_void,
@@ -165,7 +213,15 @@ impl IpcClient {
         Ok(())
     }
 
-    pub fn recv_bytes(&self, buf: &mut [u8]) -> io::Result<()> {
+    /// Receive bytes from the peer
+    ///
+    /// Returns the number of bytes that were written to `buf`. On stream sockets, a return value
+    /// of `0` means that the peer has closed its side of the connection
+    /// (see [IpcClient::shutdown_write]).
+    ///
+    /// On datagram sockets, exactly one datagram is received. If it does not fit into `buf`,
+    /// the rest of it is discarded and an error is returned.
+    pub fn recv_bytes(&self, buf: &mut [u8]) -> io::Result<usize> {
         let mut io_vec = iovec {
             iov_base: buf.as_mut_ptr() as *mut _ as *mut ffi::c_void,
             iov_len: buf.len(),
@@ -187,9 +243,65 @@ impl IpcClient {
             return Err(io::Error::last_os_error());
         }
 
+        if message.msg_flags & MSG_TRUNC != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "datagram did not fit into the buffer",
+            ));
+        }
+
+        Ok(status as usize)
+    }
+
+    /// Signal to the peer that no more data will be sent
+    ///
+    /// The peer observes a `0` byte read once it has consumed all pending data. Receiving
+    /// data from the peer is still possible afterwards.
+    pub fn shutdown_write(&self) -> io::Result<()> {
+        let status = unsafe { shutdown(self.fd, SHUT_WR) };
+        if status == -1 {
+            log::error!("Failed to shut down socket for writing");
+            return Err(io::Error::last_os_error());
+        }
+
         Ok(())
     }
 
+    /// Look up the credentials of the connected peer using `SO_PEERCRED`
+    ///
+    /// This is only available on Linux.
+    #[cfg(target_os = "linux")]
+    pub fn peer_credentials(&self) -> io::Result<PeerCred> {
+        let mut credentials = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut length = mem::size_of::<libc::ucred>() as socklen_t;
+
+        let status = unsafe {
+            libc::getsockopt(
+                self.fd,
+                SOL_SOCKET,
+                libc::SO_PEERCRED,
+                &mut credentials as *mut _ as *mut ffi::c_void,
+                &mut length,
+            )
+        };
+        if status == -1 {
+            log::error!("Failed to query peer credentials");
+            return Err(io::Error::last_os_error());
+        }
+
+        let peer_credentials = PeerCred {
+            pid: credentials.pid,
+            uid: credentials.uid,
+            gid: credentials.gid,
+        };
+
+        Ok(peer_credentials)
+    }
+
     pub fn send_fd(&self, fd: ffi::c_int) -> io::Result<()> {
         // We're only sending control messages, not regular data
         let mut io_vec = iovec {
@@ -303,4 +415,82 @@ mod tests {
 
         assert_eq!(send_buf, recv_buf);
     }
+
+    #[test]
+    fn half_close() {
+        let (a, b) = IpcClient::pair().unwrap();
+
+        a.send_bytes(&mut [1, 2, 3]).unwrap();
+        a.shutdown_write().unwrap();
+
+        // Read until the end of the stream
+        let mut received = vec![];
+        let mut recv_buf = [0; 2];
+        loop {
+            let n = b.recv_bytes(&mut recv_buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            received.extend_from_slice(&recv_buf[..n]);
+        }
+        assert_eq!(received, [1, 2, 3]);
+
+        // The other direction is still open
+        b.send_bytes(&mut [4]).unwrap();
+        let mut reply = [0; 1];
+        assert_eq!(a.recv_bytes(&mut reply).unwrap(), 1);
+        assert_eq!(reply, [4]);
+
+        // Sending on the closed side fails
+        assert!(a.send_bytes(&mut [5]).is_err());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn peer_credentials() {
+        let (a, _b) = IpcClient::pair().unwrap();
+
+        // Both ends of the pair belong to the current process
+        let credentials = a.peer_credentials().unwrap();
+        assert_eq!(credentials.pid, std::process::id() as libc::pid_t);
+        assert_eq!(credentials.uid, unsafe { libc::getuid() });
+        assert_eq!(credentials.gid, unsafe { libc::getgid() });
+    }
+
+    #[test]
+    fn datagram_boundaries() {
+        let (a, b) = IpcClient::pair_datagram().unwrap();
+
+        a.send_bytes(&mut [1, 2, 3]).unwrap();
+        a.send_bytes(&mut [4, 5]).unwrap();
+
+        // Each datagram is received on its own, even though the buffer could hold both
+        let mut recv_buf = [0; 8];
+        assert_eq!(b.recv_bytes(&mut recv_buf).unwrap(), 3);
+        assert_eq!(recv_buf[..3], [1, 2, 3]);
+
+        assert_eq!(b.recv_bytes(&mut recv_buf).unwrap(), 2);
+        assert_eq!(recv_buf[..2], [4, 5]);
+
+        // Datagrams that don't fit are reported instead of being silently truncated
+        a.send_bytes(&mut [6; 16]).unwrap();
+        assert!(b.recv_bytes(&mut recv_buf).is_err());
+    }
+
+    #[test]
+    fn send_fd_over_datagram_socket() {
+        let (a, b) = IpcClient::pair_datagram().unwrap();
+        let (inner_a, inner_b) = IpcClient::pair().unwrap();
+
+        a.send_fd(inner_a.fd).unwrap();
+        let received = IpcClient {
+            fd: b.receive_fd().unwrap().0,
+        };
+
+        // The received descriptor refers to the same socket as the one we sent
+        received.send_bytes(&mut [7]).unwrap();
+        let mut recv_buf = [0; 1];
+        assert_eq!(inner_b.recv_bytes(&mut recv_buf).unwrap(), 1);
+        assert_eq!(recv_buf, [7]);
+    }
 }
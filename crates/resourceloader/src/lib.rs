@@ -58,6 +58,14 @@ impl PendingLoad {
             .receive_blocking()
             .expect("Failed to receive response")
     }
+
+    /// Return the result of the load if it has completed, without blocking
+    ///
+    /// Once this returned a completion, all further calls return `None`.
+    #[must_use]
+    pub fn poll(&mut self) -> Option<LoadCompletion> {
+        self.receiver.try_receive()
+    }
 }
 
 impl ResourceThreadHandle {
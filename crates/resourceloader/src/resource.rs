@@ -26,7 +26,7 @@ pub enum ResourceLoadError {
     HTTP(HTTPError),
 
     #[msg = "invalid base64"]
-    Base64(base64::Error),
+    Base64(base64::DecodeError),
 
     #[msg = "unsupported url scheme"]
     UnsupportedScheme,
@@ -96,6 +96,16 @@ impl Resource {
                 // Fetch the file via http
                 let mut request = http::request::Request::get(url);
 
+                // Credentials embedded in the url are sent using basic authentication
+                if url.includes_credentials() {
+                    let username = url::percent_decode(url.username());
+                    let password = url::percent_decode(url.password());
+                    request = request.with_basic_auth(
+                        &String::from_utf8_lossy(&username),
+                        &String::from_utf8_lossy(&password),
+                    );
+                }
+
                 if let Some(proxy) = dbg!(SETTINGS.proxy) {
                     request.set_proxy(proxy);
                 }
@@ -106,9 +116,9 @@ impl Resource {
             },
             "file" => {
                 // Fetch the file from the local filesystem
-                let data = match url.as_file_path() {
-                    Ok(path) => fs::read(path)?,
-                    Err(_) => {
+                let data = match url.to_file_path() {
+                    Some(path) => fs::read(path)?,
+                    None => {
                         log::error!(
                             "Failed to load {}: Invalid file path for current platform",
                             url.serialize(url::ExcludeFragment::Yes)
@@ -151,7 +161,11 @@ impl Resource {
                 }
 
                 let data = if is_b64 {
-                    base64::b64decode(data)?
+                    let mut decoder = base64::Base64Decoder::new(base64::Alphabet::Standard);
+                    let mut decoded = vec![];
+                    decoder.update(data.as_bytes(), &mut decoded)?;
+                    decoder.finish(&mut decoded)?;
+                    decoded
                 } else {
                     url::percent_decode(data).to_vec()
                 };
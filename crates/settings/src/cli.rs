@@ -1,7 +1,7 @@
-use std::net;
+use std::{fmt::Write, net, path::PathBuf};
 use url::URL;
 
-use crate::Settings;
+use crate::{LogFormat, Settings};
 
 #[derive(clap::Parser, Debug)]
 #[command(name = "Stormlicht", version, about="A modern browser engine", long_about = None)]
@@ -20,9 +20,94 @@ pub struct Arguments {
     /// Proxy for http requests
     #[arg(long, value_parser = parse_socketaddr)]
     proxy: Option<net::SocketAddr>,
+
+    /// Write log messages to a file instead of stderr
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    log_file: Option<PathBuf>,
+
+    /// Format of log messages
+    #[arg(long, value_enum)]
+    log_format: Option<LogFormat>,
+}
+
+/// Shells that [Arguments::completions] can generate scripts for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
 }
 
 impl Arguments {
+    /// Generate a script that completes the command line options of stormlicht
+    #[must_use]
+    pub fn completions(shell: Shell) -> String {
+        let mut command = <Self as clap::CommandFactory>::command();
+
+        // Adds the implicit "--help" and "--version" flags
+        command.build();
+
+        let binary_name = command.get_name().to_lowercase();
+        let options = command
+            .get_arguments()
+            .filter(|argument| !argument.is_positional())
+            .collect::<Vec<_>>();
+
+        let mut script = String::new();
+        match shell {
+            Shell::Bash => {
+                let words = options
+                    .iter()
+                    .flat_map(|option| {
+                        let long = option.get_long().map(|long| format!("--{long}"));
+                        let short = option.get_short().map(|short| format!("-{short}"));
+                        long.into_iter().chain(short)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                _ = writeln!(script, "_{binary_name}() {{");
+                _ = writeln!(script, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"");
+                _ = writeln!(script, "    if [[ \"$cur\" == -* ]]; then");
+                _ = writeln!(
+                    script,
+                    "        COMPREPLY=($(compgen -W \"{words}\" -- \"$cur\"))"
+                );
+                _ = writeln!(script, "    fi");
+                _ = writeln!(script, "}}");
+                _ = writeln!(
+                    script,
+                    "complete -o default -F _{binary_name} {binary_name}"
+                );
+            },
+            Shell::Zsh => {
+                _ = writeln!(script, "#compdef {binary_name}");
+                _ = writeln!(script);
+                _ = writeln!(script, "_arguments \\");
+                for option in &options {
+                    let help = option
+                        .get_help()
+                        .map(|help| help.to_string().replace(['[', ']'], ""))
+                        .unwrap_or_default();
+
+                    match (option.get_short(), option.get_long()) {
+                        (Some(short), Some(long)) => {
+                            _ = writeln!(
+                                script,
+                                "    '(-{short} --{long})'{{-{short},--{long}}}'[{help}]' \\"
+                            )
+                        },
+                        (Some(short), None) => _ = writeln!(script, "    '-{short}[{help}]' \\"),
+                        (None, Some(long)) => _ = writeln!(script, "    '--{long}[{help}]' \\"),
+                        (None, None) => {},
+                    }
+                }
+                _ = writeln!(script, "    '*:: :_files'");
+            },
+        }
+
+        script
+    }
+
     pub(crate) fn update_settings(self, settings: &mut Settings) {
         settings.disable_javascript = settings.disable_javascript;
 
@@ -33,6 +118,14 @@ impl Arguments {
         if let Some(proxy) = self.proxy {
             settings.proxy = Some(proxy);
         }
+
+        if let Some(log_file) = self.log_file {
+            settings.log_file = Some(log_file);
+        }
+
+        if let Some(log_format) = self.log_format {
+            settings.log_format = log_format;
+        }
     }
 }
 
@@ -44,3 +137,25 @@ fn parse_socketaddr(s: &str) -> Result<net::SocketAddr, String> {
     s.parse()
         .map_err(|e: <net::SocketAddr as std::str::FromStr>::Err| format!("{e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bash_completions() {
+        let script = Arguments::completions(Shell::Bash);
+
+        for option in [
+            "--disable-javascript",
+            "--proxy",
+            "--log-file",
+            "--log-format",
+            "--help",
+            "--version",
+        ] {
+            assert!(script.contains(option), "{option} is missing");
+        }
+        assert!(script.contains("complete -o default -F _stormlicht stormlicht"));
+    }
+}
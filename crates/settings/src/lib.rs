@@ -2,7 +2,9 @@
 
 mod cli;
 
-use std::{net, sync::LazyLock};
+pub use cli::Shell;
+
+use std::{net, path::PathBuf, sync::LazyLock};
 
 use clap::Parser;
 use url::URL;
@@ -26,6 +28,22 @@ pub struct Settings {
 
     /// Proxy for networking
     pub proxy: Option<net::SocketAddr>,
+
+    /// File that log messages are written to, instead of stderr
+    pub log_file: Option<PathBuf>,
+
+    pub log_format: LogFormat,
+}
+
+/// How log messages are formatted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+
+    /// One json object per message
+    Json,
 }
 
 impl Settings {
@@ -39,6 +57,12 @@ impl Settings {
 
         settings
     }
+
+    /// Generate a script for the given shell that completes command line options
+    #[must_use]
+    pub fn completions(shell: Shell) -> String {
+        cli::Arguments::completions(shell)
+    }
 }
 
 impl Default for Settings {
@@ -47,6 +71,8 @@ impl Default for Settings {
             disable_javascript: false,
             url: WELCOME_PAGE.parse().expect("welcome page is a valid url"),
             proxy: None,
+            log_file: None,
+            log_format: LogFormat::default(),
         }
     }
 }
@@ -242,6 +242,46 @@ impl Str {
         pattern.is_prefix_of(self)
     }
 
+    /// Checks that two strings are an ASCII case-insensitive match.
+    ///
+    /// Same as `to_lowercase(a) == to_lowercase(b)`, but without allocating and
+    /// copying temporaries. Only the letters 'A' to 'Z' are folded, all other
+    /// characters must match exactly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(ascii_char)]
+    /// # use sl_std::ascii;
+    /// let s: &ascii::Str = "Content-Length".try_into().unwrap();
+    ///
+    /// assert!(s.eq_ignore_ascii_case(ascii!("content-length")));
+    /// assert!(!s.eq_ignore_ascii_case(ascii!("content_length")));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn eq_ignore_ascii_case(&self, other: &Self) -> bool {
+        self.as_bytes().eq_ignore_ascii_case(other.as_bytes())
+    }
+
+    /// Checks whether the string starts with `prefix`, ignoring ASCII case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(ascii_char)]
+    /// # use sl_std::ascii;
+    /// let s: &ascii::Str = "<!DOCTYPE html>".try_into().unwrap();
+    ///
+    /// assert!(s.starts_with_ignore_ascii_case(ascii!("<!doctype")));
+    /// assert!(!s.starts_with_ignore_ascii_case(ascii!("<!doctype html> ")));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn starts_with_ignore_ascii_case(&self, prefix: &Self) -> bool {
+        self.len() >= prefix.len() && self[..prefix.len()].eq_ignore_ascii_case(prefix)
+    }
+
     /// Returns a string slice with leading and trailing whitespace removed.
     ///
     /// 'Whitespace' is defined according to the terms of the [WhatWG spec](https://infra.spec.whatwg.org/#ascii-whitespace).
@@ -716,3 +756,35 @@ where
 }
 
 impl<'a, P> FusedIterator for SplitIterator<'a, P> where P: super::Pattern<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::ascii;
+
+    #[test]
+    fn eq_ignore_ascii_case() {
+        assert!(ascii!("DOCTYPE").eq_ignore_ascii_case(ascii!("doctype")));
+        assert!(ascii!("DocType").eq_ignore_ascii_case(ascii!("dOCtYPE")));
+        assert!(ascii!("").eq_ignore_ascii_case(ascii!("")));
+        assert!(!ascii!("doctype").eq_ignore_ascii_case(ascii!("doctypes")));
+
+        // Non-alphabetic characters that differ only in the case bit (0x20) must not match
+        assert!(!ascii!("@").eq_ignore_ascii_case(ascii!("`")));
+        assert!(!ascii!("[").eq_ignore_ascii_case(ascii!("{")));
+        assert!(!ascii!("^").eq_ignore_ascii_case(ascii!("~")));
+        assert!(!ascii!("1").eq_ignore_ascii_case(ascii!("\x11")));
+        assert!(!ascii!("a-b").eq_ignore_ascii_case(ascii!("A\rB")));
+    }
+
+    #[test]
+    fn starts_with_ignore_ascii_case() {
+        let s = ascii!("Content-Type: text/html");
+
+        assert!(s.starts_with_ignore_ascii_case(ascii!("content-type")));
+        assert!(s.starts_with_ignore_ascii_case(ascii!("CONTENT-TYPE:")));
+        assert!(s.starts_with_ignore_ascii_case(ascii!("")));
+        assert!(s.starts_with_ignore_ascii_case(s));
+        assert!(!s.starts_with_ignore_ascii_case(ascii!("content_type")));
+        assert!(!ascii!("Con").starts_with_ignore_ascii_case(ascii!("content")));
+    }
+}
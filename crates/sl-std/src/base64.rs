@@ -1,3 +1,5 @@
+use std::fmt;
+
 use error_derive::Error;
 
 use crate::ascii;
@@ -69,6 +71,54 @@ const BASE64_CHARS: [ascii::Char; 64] = [
     ascii::Char::Solidus,
 ];
 
+/// Like [BASE64_CHARS], except that `+` and `/` are replaced with `-` and `_`
+/// so the result can be used in urls and filenames.
+///
+/// Refer to <https://www.rfc-editor.org/rfc/rfc4648#section-5>
+const URL_SAFE_BASE64_CHARS: [ascii::Char; 64] = {
+    let mut chars = BASE64_CHARS;
+    chars[62] = ascii::Char::HyphenMinus;
+    chars[63] = ascii::Char::LowLine;
+    chars
+};
+
+/// The set of symbols used to represent data
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Alphabet {
+    /// Uses `+` and `/`
+    #[default]
+    Standard,
+
+    /// Uses `-` and `_`
+    UrlSafe,
+}
+
+/// Whether or not encoded data should be padded with `=` to a multiple of four symbols
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Padding {
+    #[default]
+    Include,
+    Omit,
+}
+
+impl Alphabet {
+    #[must_use]
+    const fn symbols(&self) -> &'static [ascii::Char; 64] {
+        match self {
+            Self::Standard => &BASE64_CHARS,
+            Self::UrlSafe => &URL_SAFE_BASE64_CHARS,
+        }
+    }
+
+    #[must_use]
+    fn index_of(&self, symbol: ascii::Char) -> Option<u32> {
+        self.symbols()
+            .iter()
+            .position(|&c| c == symbol)
+            .map(|index| index as u32)
+    }
+}
+
 #[derive(Clone, Copy, Debug, Error)]
 pub enum Error {
     #[msg = "illegal character"]
@@ -81,50 +131,108 @@ pub enum Error {
     InvalidPadding,
 }
 
-pub fn b64decode(base64: &ascii::Str) -> Result<Vec<u8>, Error> {
-    if base64.len() % 4 != 0 {
-        return Err(Error::InvalidLength);
+/// An [Error] encountered by a [Base64Decoder], along with the position of the offending symbol
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeError {
+    /// The offset of the offending symbol from the start of the input
+    pub offset: usize,
+    pub error: Error,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.error, self.offset)
     }
+}
 
-    // Let's not worry about this edge case later on
-    if base64.is_empty() {
-        return Ok(vec![]);
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
     }
+}
 
-    let padding = if base64[base64.len() - 1] == ascii::Char::EqualsSign {
-        if base64[base64.len() - 2] == ascii::Char::EqualsSign {
-            2
-        } else {
-            1
-        }
-    } else {
-        0
-    };
+/// Encode data using the standard alphabet, including padding
+#[must_use]
+pub fn b64encode(data: &[u8]) -> ascii::String {
+    encode_with(data, Alphabet::Standard, Padding::Include)
+}
 
-    let mut data = Vec::with_capacity((base64.len() / 4) * 3 - padding);
-    let mut buffer: u32 = 0;
-    let mut iter = 0;
+/// Encode data using the url-safe alphabet, without padding
+///
+/// This is the encoding used by JSON Web Tokens, among others.
+#[must_use]
+pub fn b64encode_url_safe(data: &[u8]) -> ascii::String {
+    encode_with(data, Alphabet::UrlSafe, Padding::Omit)
+}
 
-    for &symbol in base64.chars() {
-        if symbol == ascii::Char::EqualsSign {
-            // End of data
-            match iter {
-                2 => {
-                    data.push((buffer >> 4) as u8);
-                },
-                3 => {
-                    data.push((buffer >> 10) as u8);
-                    data.push((buffer >> 2) as u8);
-                },
-                _ => return Err(Error::InvalidPadding),
+#[must_use]
+pub fn encode_with(data: &[u8], alphabet: Alphabet, padding: Padding) -> ascii::String {
+    let symbols = alphabet.symbols();
+    let mut base64 = ascii::String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let mut buffer = [0; 4];
+        buffer[1..][..chunk.len()].copy_from_slice(chunk);
+        let buffer = u32::from_be_bytes(buffer);
+
+        // Every input byte contributes to (at most) two output symbols
+        let num_symbols = chunk.len() + 1;
+        for i in 0..num_symbols {
+            let index = (buffer >> (18 - 6 * i)) & 0b111111;
+            base64.push(symbols[index as usize]);
+        }
+
+        if padding == Padding::Include {
+            for _ in num_symbols..4 {
+                base64.push(ascii::Char::EqualsSign);
             }
-            break;
         }
+    }
 
-        let index = BASE64_CHARS
-            .iter()
-            .position(|&c| c == symbol)
-            .ok_or(Error::IllegalCharacter)? as u32;
+    base64
+}
+
+/// Decode data using the standard alphabet
+///
+/// Padding is optional.
+pub fn b64decode(base64: &ascii::Str) -> Result<Vec<u8>, Error> {
+    decode_with(base64, Alphabet::Standard)
+}
+
+/// Decode data using the url-safe alphabet
+///
+/// Padding is optional.
+pub fn b64decode_url_safe(base64: &ascii::Str) -> Result<Vec<u8>, Error> {
+    decode_with(base64, Alphabet::UrlSafe)
+}
+
+pub fn decode_with(base64: &ascii::Str, alphabet: Alphabet) -> Result<Vec<u8>, Error> {
+    let symbols = base64.chars();
+    let padding = symbols
+        .iter()
+        .rev()
+        .take(2)
+        .take_while(|&&c| c == ascii::Char::EqualsSign)
+        .count();
+
+    // If the data is padded then the padding needs to be complete
+    if padding != 0 && symbols.len() % 4 != 0 {
+        return Err(Error::InvalidPadding);
+    }
+
+    let symbols = &symbols[..symbols.len() - padding];
+
+    // A single symbol does not encode a full byte
+    if symbols.len() % 4 == 1 {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut data = Vec::with_capacity((symbols.len() * 3) / 4);
+    let mut buffer: u32 = 0;
+    let mut iter = 0;
+
+    for &symbol in symbols {
+        let index = alphabet.index_of(symbol).ok_or(Error::IllegalCharacter)?;
         buffer = (buffer << 6) | index;
         iter += 1;
 
@@ -135,12 +243,158 @@ pub fn b64decode(base64: &ascii::Str) -> Result<Vec<u8>, Error> {
         }
     }
 
+    // Flush the last (incomplete) group
+    match iter {
+        0 => {},
+        2 => {
+            data.push((buffer >> 4) as u8);
+        },
+        3 => {
+            data.push((buffer >> 10) as u8);
+            data.push((buffer >> 2) as u8);
+        },
+        _ => unreachable!("length is not 1 mod 4"),
+    }
+
     Ok(data)
 }
 
+/// Incrementally decodes base64 data that is supplied in chunks
+///
+/// Chunk boundaries may fall anywhere, including in the middle of a
+/// group of four symbols. Like [decode_with], padding is optional.
+///
+/// # Example
+/// ```
+/// # use sl_std::base64::{Alphabet, Base64Decoder};
+/// let mut decoder = Base64Decoder::new(Alphabet::Standard);
+/// let mut data = vec![];
+/// decoder.update(b"Zm9v", &mut data).unwrap();
+/// decoder.update(b"Ym", &mut data).unwrap();
+/// decoder.update(b"Fy", &mut data).unwrap();
+/// decoder.finish(&mut data).unwrap();
+///
+/// assert_eq!(data, b"foobar");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Base64Decoder {
+    alphabet: Alphabet,
+
+    /// The symbols of the current (incomplete) group
+    buffer: u32,
+
+    /// The number of symbols in `buffer`
+    num_symbols: usize,
+
+    /// The number of `=` symbols that were encountered
+    padding: usize,
+
+    /// The number of input bytes consumed so far
+    offset: usize,
+}
+
+impl Base64Decoder {
+    #[must_use]
+    pub const fn new(alphabet: Alphabet) -> Self {
+        Self {
+            alphabet,
+            buffer: 0,
+            num_symbols: 0,
+            padding: 0,
+            offset: 0,
+        }
+    }
+
+    /// Decode a chunk of input, appending the decoded bytes to `output`
+    ///
+    /// Symbols that do not form a complete group are kept until
+    /// the next call to [update](Self::update) or [finish](Self::finish).
+    pub fn update(&mut self, chunk: &[u8], output: &mut Vec<u8>) -> Result<(), DecodeError> {
+        output.reserve((chunk.len() * 3) / 4);
+
+        for &byte in chunk {
+            self.consume(byte, output)
+                .map_err(|error| self.error_at_current_offset(error))?;
+            self.offset += 1;
+        }
+
+        Ok(())
+    }
+
+    fn consume(&mut self, byte: u8, output: &mut Vec<u8>) -> Result<(), Error> {
+        let symbol = ascii::Char::from_u8(byte).ok_or(Error::IllegalCharacter)?;
+
+        if symbol == ascii::Char::EqualsSign {
+            // Padding can only be used to complete a group of at least two symbols
+            self.padding += 1;
+            if self.num_symbols < 2 || 4 < self.num_symbols + self.padding {
+                return Err(Error::InvalidPadding);
+            }
+            return Ok(());
+        }
+
+        // No data may follow after the padding
+        if self.padding != 0 {
+            return Err(Error::InvalidPadding);
+        }
+
+        let index = self
+            .alphabet
+            .index_of(symbol)
+            .ok_or(Error::IllegalCharacter)?;
+        self.buffer = (self.buffer << 6) | index;
+        self.num_symbols += 1;
+
+        if self.num_symbols == 4 {
+            output.extend_from_slice(&self.buffer.to_be_bytes().as_slice()[1..]);
+            self.num_symbols = 0;
+            self.buffer = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Signal the end of the input, flushing any remaining data to `output`
+    pub fn finish(self, output: &mut Vec<u8>) -> Result<(), DecodeError> {
+        // If the data is padded then the padding needs to be complete
+        if self.padding != 0 && self.num_symbols + self.padding != 4 {
+            return Err(self.error_at_current_offset(Error::InvalidPadding));
+        }
+
+        // Flush the last (incomplete) group
+        match self.num_symbols {
+            0 => {},
+            1 => {
+                // A single symbol does not encode a full byte
+                return Err(self.error_at_current_offset(Error::InvalidLength));
+            },
+            2 => {
+                output.push((self.buffer >> 4) as u8);
+            },
+            3 => {
+                output.push((self.buffer >> 10) as u8);
+                output.push((self.buffer >> 2) as u8);
+            },
+            _ => unreachable!("complete groups are flushed immediately"),
+        }
+
+        Ok(())
+    }
+
+    #[must_use]
+    fn error_at_current_offset(&self, error: Error) -> DecodeError {
+        DecodeError {
+            offset: self.offset,
+            error,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::b64decode;
+    use std::assert_matches::assert_matches;
+
+    use super::*;
 
     #[test]
     fn decode() {
@@ -149,4 +403,124 @@ mod tests {
 
         assert_eq!(b64decode(encoded).unwrap(), decoded);
     }
+
+    #[test]
+    fn encode() {
+        assert_eq!(b64encode(b""), "");
+        assert_eq!(b64encode(b"f"), "Zg==");
+        assert_eq!(b64encode(b"fo"), "Zm8=");
+        assert_eq!(b64encode(b"foo"), "Zm9v");
+        assert_eq!(b64encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode_with(b"fo", Alphabet::Standard, Padding::Omit), "Zm8");
+    }
+
+    #[test]
+    fn url_safe_alphabet() {
+        let data = [0xfb, 0xff, 0xbf];
+
+        assert_eq!(b64encode(&data), "+/+/");
+        assert_eq!(b64encode_url_safe(&data), "-_-_");
+        assert_eq!(
+            encode_with(&data[..2], Alphabet::UrlSafe, Padding::Include),
+            "-_8="
+        );
+
+        assert_eq!(b64decode_url_safe(ascii!("-_-_")).unwrap(), data);
+        assert!(b64decode_url_safe(ascii!("+/+/")).is_err());
+        assert!(b64decode(ascii!("-_-_")).is_err());
+    }
+
+    #[test]
+    fn optional_padding() {
+        assert_eq!(b64decode(ascii!("Zm8=")).unwrap(), b"fo");
+        assert_eq!(b64decode(ascii!("Zm8")).unwrap(), b"fo");
+        assert_eq!(b64decode(ascii!("Zg==")).unwrap(), b"f");
+        assert_eq!(b64decode(ascii!("Zg")).unwrap(), b"f");
+
+        assert_matches!(b64decode(ascii!("Zg=")), Err(Error::InvalidPadding));
+        assert_matches!(b64decode(ascii!("Zm9vY")), Err(Error::InvalidLength));
+        assert_matches!(b64decode(ascii!("Zg==Zg==")), Err(Error::IllegalCharacter));
+    }
+
+    #[test]
+    fn decode_in_chunks() {
+        let encoded = b"UG9seWZvbiB6d2l0c2NoZXJuZCBhw59lbiBNw6R4Y2hlbnMgVsO2Z2VsIFLDvGJlbiwgSm9naHVydCB1bmQgUXVhcms=";
+        let decoded = "Polyfon zwitschernd aßen Mäxchens Vögel Rüben, Joghurt und Quark".as_bytes();
+
+        for chunk_size in 1..=7 {
+            let mut decoder = Base64Decoder::new(Alphabet::Standard);
+            let mut data = vec![];
+            for chunk in encoded.chunks(chunk_size) {
+                decoder.update(chunk, &mut data).unwrap();
+            }
+            decoder.finish(&mut data).unwrap();
+
+            assert_eq!(data, decoded, "chunk size {chunk_size}");
+        }
+
+        // Split right between the data and the padding
+        let mut decoder = Base64Decoder::new(Alphabet::Standard);
+        let mut data = vec![];
+        decoder.update(b"Zg", &mut data).unwrap();
+        decoder.update(b"=", &mut data).unwrap();
+        decoder.update(b"=", &mut data).unwrap();
+        decoder.finish(&mut data).unwrap();
+        assert_eq!(data, b"f");
+    }
+
+    #[test]
+    fn decoder_errors() {
+        fn decode(chunks: &[&[u8]]) -> Result<Vec<u8>, DecodeError> {
+            let mut decoder = Base64Decoder::new(Alphabet::Standard);
+            let mut data = vec![];
+            for chunk in chunks {
+                decoder.update(chunk, &mut data)?;
+            }
+            decoder.finish(&mut data)?;
+            Ok(data)
+        }
+
+        assert_matches!(
+            decode(&[b"Zm9v", b"Y!Fy"]),
+            Err(DecodeError {
+                offset: 5,
+                error: Error::IllegalCharacter
+            })
+        );
+        assert_matches!(
+            decode(&[b"Zg=", b"=Zg"]),
+            Err(DecodeError {
+                offset: 4,
+                error: Error::InvalidPadding
+            })
+        );
+        assert_matches!(
+            decode(&[b"Z", b"="]),
+            Err(DecodeError {
+                offset: 1,
+                error: Error::InvalidPadding
+            })
+        );
+        assert_matches!(
+            decode(&[b"Zm8", b"=="]),
+            Err(DecodeError {
+                offset: 4,
+                error: Error::InvalidPadding
+            })
+        );
+        assert_matches!(
+            decode(&[b"Zg="]),
+            Err(DecodeError {
+                offset: 3,
+                error: Error::InvalidPadding
+            })
+        );
+        assert_matches!(
+            decode(&[b"Zm9v", b"Y"]),
+            Err(DecodeError {
+                offset: 5,
+                error: Error::InvalidLength
+            })
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use std::{iter, mem, ops};
+use std::{cmp, iter, mem, ops};
 
 cfg_match! {
     cfg(target_pointer_width = "64") => {
@@ -121,7 +121,10 @@ impl BigNum {
 
             assert_eq!(carry, 0);
 
-            result = result + Digit::from(chunk.iter().fold(0, |acc, &digit| acc * radix + digit));
+            result = result
+                + chunk.iter().fold(0, |acc, &digit| {
+                    acc * Digit::from(radix) + Digit::from(digit)
+                });
         }
 
         result
@@ -194,6 +197,84 @@ impl BigNum {
 
         Self::from_digits(digits)
     }
+
+    /// Serialize the number as big-endian bytes, without leading zeros
+    ///
+    /// The number `0` is serialized as an empty vector.
+    #[must_use]
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let bytes: Vec<u8> = self
+            .nonzero_digits()
+            .iter()
+            .rev()
+            .flat_map(|digit| digit.to_be_bytes())
+            .collect();
+
+        let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+        bytes[leading_zeros..].to_vec()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.digits().iter().all(|&digit| digit == 0)
+    }
+
+    /// The number of bits required to represent the number
+    #[must_use]
+    pub fn bit_length(&self) -> usize {
+        let digits = self.nonzero_digits();
+        let most_significant = digits[digits.len() - 1];
+
+        (digits.len() - 1) * Digit::BITS as usize
+            + (Digit::BITS - most_significant.leading_zeros()) as usize
+    }
+
+    #[must_use]
+    fn bit(&self, index: usize) -> bool {
+        let digit = self
+            .digits()
+            .get(index / Digit::BITS as usize)
+            .copied()
+            .unwrap_or_default();
+
+        digit & (1 << (index % Digit::BITS as usize)) != 0
+    }
+
+    /// Compute `self^exponent mod modulus`
+    ///
+    /// # Panics
+    /// This function panics if `modulus` is zero.
+    #[must_use]
+    pub fn pow_mod(&self, exponent: &Self, modulus: &Self) -> Self {
+        let base = self % modulus;
+        let mut result = &Self::from_digits(vec![1]) % modulus;
+
+        // Square-and-multiply, starting with the most significant bit of the exponent
+        for index in (0..exponent.bit_length()).rev() {
+            result = &(&result * &result) % modulus;
+
+            if exponent.bit(index) {
+                result = &(&result * &base) % modulus;
+            }
+        }
+
+        result
+    }
+
+    /// Shift the number one bit to the left and set the least significant bit to `bit`
+    fn shift_in_bit(&mut self, bit: bool) {
+        let mut carry = Digit::from(bit);
+        for digit in self.digits_mut() {
+            let new_carry = *digit >> (Digit::BITS - 1);
+            *digit = (*digit << 1) | carry;
+            carry = new_carry;
+        }
+
+        if carry != 0 {
+            self.digits.push(carry);
+        }
+    }
 }
 
 impl ops::Add for BigNum {
@@ -239,18 +320,19 @@ impl ops::Add<Digit> for BigNum {
     type Output = Self;
 
     fn add(mut self, other: Digit) -> Self::Output {
-        let mut carry = false;
+        // Only the least significant digit is added to, after that we only propagate the carry
+        let mut to_add = other;
         for digit in self.digits.iter_mut() {
-            (*digit, carry) = digit.carrying_add(other, carry);
+            let carry;
+            (*digit, carry) = digit.overflowing_add(to_add);
 
             if !carry {
                 return self;
             }
+            to_add = 1;
         }
 
-        if carry {
-            self.digits.push(1);
-        }
+        self.digits.push(to_add);
 
         self
     }
@@ -290,6 +372,107 @@ impl PartialEq for BigNum {
     }
 }
 
+impl Eq for BigNum {}
+
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigNum {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        let (a, b) = (self.nonzero_digits(), other.nonzero_digits());
+
+        // Compare the number of digits first, then the digits themselves starting with the most significant one
+        a.len()
+            .cmp(&b.len())
+            .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+    }
+}
+
+impl ops::SubAssign<&BigNum> for BigNum {
+    /// # Panics
+    /// This function panics if `other` is larger than `self`.
+    fn sub_assign(&mut self, other: &BigNum) {
+        assert!(*self >= *other, "attempt to subtract with overflow");
+
+        let mut borrow = false;
+        for (d1, &d2) in self
+            .digits
+            .iter_mut()
+            .zip(other.digits().iter().chain(iter::repeat(&0)))
+        {
+            (*d1, borrow) = d1.borrowing_sub(d2, borrow);
+        }
+
+        debug_assert!(!borrow);
+        self.compact();
+    }
+}
+
+impl ops::Sub for BigNum {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self::Output {
+        self -= &other;
+        self
+    }
+}
+
+impl ops::Mul for &BigNum {
+    type Output = BigNum;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let (a, b) = (self.nonzero_digits(), other.nonzero_digits());
+        let mut digits = vec![0; a.len() + b.len()];
+
+        // Schoolbook multiplication, the intermediate results always fit into a BigDigit
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry: BigDigit = 0;
+            for (j, &y) in b.iter().enumerate() {
+                carry += BigDigit::from(x) * BigDigit::from(y) + BigDigit::from(digits[i + j]);
+                digits[i + j] = carry as Digit;
+                carry >>= Digit::BITS;
+            }
+            digits[i + b.len()] = carry as Digit;
+        }
+
+        let mut result = BigNum::from_digits(digits);
+        result.compact();
+        result
+    }
+}
+
+impl ops::Rem for &BigNum {
+    type Output = BigNum;
+
+    /// # Panics
+    /// This function panics if `modulus` is zero.
+    fn rem(self, modulus: Self) -> Self::Output {
+        assert!(
+            !modulus.is_zero(),
+            "attempt to calculate the remainder with a divisor of zero"
+        );
+
+        if self < modulus {
+            return self.clone();
+        }
+
+        // Binary long division, we only care about the remainder
+        let mut remainder = BigNum::zero();
+        for index in (0..self.bit_length()).rev() {
+            remainder.shift_in_bit(self.bit(index));
+
+            if remainder >= *modulus {
+                remainder -= modulus;
+            }
+        }
+
+        remainder
+    }
+}
+
 // Takes an ascii string and converts it to a sequence of digits in the given
 // radix and removes leading zeros So `"01_23F"` in base 16 becomes `[1, 2, 3, 15]`.
 //
@@ -349,6 +532,61 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_sub() {
+        assert_eq!(
+            bignum!(0x100000000000000000000000000000000) - bignum!(1),
+            bignum!(0xffffffffffffffffffffffffffffffff)
+        );
+        assert_eq!(bignum!(123) - bignum!(123), BigNum::zero());
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(
+            &bignum!(0xffffffffffffffffffffffffffffffff) * &bignum!(0xffffffffffffffff),
+            bignum!(0xfffffffffffffffeffffffffffffffff0000000000000001)
+        );
+        assert_eq!(&bignum!(123) * &BigNum::zero(), BigNum::zero());
+    }
+
+    #[test]
+    fn test_rem() {
+        assert_eq!(
+            &bignum!(123456789012345678901234567890) % &bignum!(987654321),
+            bignum!(574845669)
+        );
+        assert_eq!(&bignum!(5) % &bignum!(7), bignum!(5));
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(
+            bignum!(4).pow_mod(&bignum!(13), &bignum!(497)),
+            bignum!(445)
+        );
+        assert_eq!(
+            bignum!(0xdeadbeefcafe).pow_mod(
+                &bignum!(65537),
+                &bignum!(0xfffffffffffffffffffffffffffffff1)
+            ),
+            bignum!(5956917826997594417627151245292546779)
+        );
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(bignum!(0x100000000000000000) > bignum!(0xffffffffffffffff));
+        assert!(bignum!(2) < bignum!(3));
+        assert_eq!(bignum!(0).cmp(&BigNum::zero()), cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_to_be_bytes() {
+        assert_eq!(bignum!(0xdeadbeef).to_be_bytes(), [0xde, 0xad, 0xbe, 0xef]);
+        assert!(BigNum::zero().to_be_bytes().is_empty());
+    }
+
     #[test]
     fn test_from_be_bytes() {
         assert_eq!(
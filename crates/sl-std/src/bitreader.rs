@@ -5,7 +5,23 @@ use error_derive::Error;
 pub struct BitReader<'a> {
     bytes: &'a [u8],
     pub byte_ptr: usize,
+
+    /// The number of bits within the current byte that were already consumed
     pub bit_ptr: u8,
+    bit_order: BitOrder,
+}
+
+/// The order in which the bits within a byte are consumed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The least significant bit of a byte is read first and values
+    /// are packed starting with their least significant bit, as in deflate
+    #[default]
+    LeastSignificantFirst,
+
+    /// The most significant bit of a byte is read first and values
+    /// are packed starting with their most significant bit, as in JPEG
+    MostSignificantFirst,
 }
 
 // this enum might grow once we add streaming (ie the reader wraps a Read instance)
@@ -37,14 +53,31 @@ pub(crate) fn mask(from: u8, to: u8) -> u8 {
 }
 
 impl<'a> BitReader<'a> {
+    /// Create a reader that consumes the least significant bits first
     pub fn new(source: &'a [u8]) -> Self {
+        Self::with_bit_order(source, BitOrder::LeastSignificantFirst)
+    }
+
+    pub fn with_bit_order(source: &'a [u8], bit_order: BitOrder) -> Self {
         Self {
             bytes: source,
             byte_ptr: 0,
             bit_ptr: 0,
+            bit_order,
         }
     }
 
+    #[must_use]
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// The number of bits that can still be read
+    #[must_use]
+    pub fn remaining_bits(&self) -> usize {
+        (self.bytes.len().saturating_sub(self.byte_ptr) * 8).saturating_sub(self.bit_ptr as usize)
+    }
+
     pub fn align_to_byte_boundary(&mut self) {
         if self.bit_ptr != 0 {
             self.bit_ptr = 0;
@@ -53,7 +86,7 @@ impl<'a> BitReader<'a> {
     }
 
     pub fn read_bytes(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
-        if !self.bit_ptr == 0 {
+        if self.bit_ptr != 0 {
             return Err(Error::UnalignedRead);
         } else if self.byte_ptr + buffer.len() > self.bytes.len() {
             return Err(Error::UnexpectedEOF);
@@ -77,9 +110,13 @@ impl<'a> BitReader<'a> {
         self.read_bits::<u8>(1).map(|val| val == 1)
     }
 
+    /// Read `bits_to_read` bits
+    ///
+    /// If there are not enough bits left, then [Error::UnexpectedEOF] is returned and
+    /// no bits are consumed.
     pub fn read_bits<T: From<u8> + std::ops::BitOrAssign<T> + std::ops::Shl<u8, Output = T>>(
         &mut self,
-        mut bits_to_read: u8,
+        bits_to_read: u8,
     ) -> Result<T, Error>
     where
         u8: Into<T>,
@@ -88,6 +125,29 @@ impl<'a> BitReader<'a> {
             return Err(Error::TooLargeRead);
         }
 
+        if self.remaining_bits() < bits_to_read as usize {
+            return Err(Error::UnexpectedEOF);
+        }
+
+        if bits_to_read == 0 {
+            return Ok(T::from(0));
+        }
+
+        let result = match self.bit_order {
+            BitOrder::LeastSignificantFirst => self.read_bits_lsb_first(bits_to_read),
+            BitOrder::MostSignificantFirst => self.read_bits_msb_first(bits_to_read),
+        };
+
+        Ok(result)
+    }
+
+    fn read_bits_lsb_first<T: From<u8> + std::ops::BitOrAssign<T> + std::ops::Shl<u8, Output = T>>(
+        &mut self,
+        mut bits_to_read: u8,
+    ) -> T
+    where
+        u8: Into<T>,
+    {
         let mut bits_available_from_current_byte = 8 - self.bit_ptr;
 
         let mut result = T::from(0);
@@ -117,13 +177,52 @@ impl<'a> BitReader<'a> {
             self.byte_ptr += 1;
         }
 
-        Ok(result)
+        result
+    }
+
+    fn read_bits_msb_first<T: From<u8> + std::ops::BitOrAssign<T> + std::ops::Shl<u8, Output = T>>(
+        &mut self,
+        mut bits_to_read: u8,
+    ) -> T
+    where
+        u8: Into<T>,
+    {
+        let mut result = T::from(0);
+        let mut is_first_chunk = true;
+
+        while bits_to_read != 0 {
+            // The bits that were not consumed yet are the least significant ones
+            let bits_available_from_current_byte = 8 - self.bit_ptr;
+            let newly_read_bits = bits_to_read.min(bits_available_from_current_byte);
+
+            let unread_bits = self.bytes[self.byte_ptr] & mask(0, bits_available_from_current_byte);
+            let chunk = unread_bits >> (bits_available_from_current_byte - newly_read_bits);
+
+            // Shifting the (still empty) result by the width of T would overflow
+            if is_first_chunk {
+                result = chunk.into();
+                is_first_chunk = false;
+            } else {
+                result = result << newly_read_bits;
+                result |= chunk.into();
+            }
+
+            bits_to_read -= newly_read_bits;
+            self.bit_ptr += newly_read_bits;
+
+            if self.bit_ptr == 8 {
+                self.bit_ptr = 0;
+                self.byte_ptr += 1;
+            }
+        }
+
+        result
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::BitReader;
+    use super::{BitOrder, BitReader, Error};
 
     #[test]
     fn test_bitreader() {
@@ -134,4 +233,80 @@ mod tests {
         assert_eq!(reader.read_bits::<u8>(8), Ok(0b00111001));
         assert_eq!(reader.read_bits::<u8>(4), Ok(0b0011));
     }
+
+    #[test]
+    fn least_significant_bit_first() {
+        let bytes = [0b1010_1100, 0b0111_0001, 0b1111_0000];
+        let mut reader = BitReader::new(&bytes);
+
+        assert_eq!(reader.read_single_bit(), Ok(false));
+        assert_eq!(reader.read_bits::<u8>(3), Ok(0b110));
+
+        // Read across a byte boundary
+        assert_eq!(reader.read_bits::<u16>(7), Ok(0b001_1010));
+        assert_eq!(reader.read_bits::<u32>(13), Ok(0b1_1110_0000_1110));
+        assert_eq!(reader.remaining_bits(), 0);
+    }
+
+    #[test]
+    fn most_significant_bit_first() {
+        let bytes = [0b1010_1100, 0b0111_0001, 0b1111_0000];
+        let mut reader = BitReader::with_bit_order(&bytes, BitOrder::MostSignificantFirst);
+
+        assert_eq!(reader.read_single_bit(), Ok(true));
+        assert_eq!(reader.read_bits::<u8>(3), Ok(0b010));
+
+        // Read across a byte boundary
+        assert_eq!(reader.read_bits::<u16>(7), Ok(0b1100_011));
+        assert_eq!(reader.read_bits::<u8>(8), Ok(0b1_0001_111));
+        assert_eq!(reader.read_bits::<u8>(5), Ok(0b1_0000));
+        assert_eq!(reader.remaining_bits(), 0);
+
+        let bytes = [0xAB, 0xCD, 0xEF, 0x12];
+        let mut reader = BitReader::with_bit_order(&bytes, BitOrder::MostSignificantFirst);
+        assert_eq!(reader.read_bits::<u32>(32), Ok(0xABCDEF12));
+    }
+
+    #[test]
+    fn align_to_byte_boundary() {
+        let bytes = [0b1111_0000, 0b1010_1010];
+
+        let mut reader = BitReader::new(&bytes);
+        reader.read_bits::<u8>(2).unwrap();
+        reader.align_to_byte_boundary();
+        assert_eq!(reader.read_bits::<u8>(4), Ok(0b1010));
+
+        let mut reader = BitReader::with_bit_order(&bytes, BitOrder::MostSignificantFirst);
+        reader.align_to_byte_boundary();
+        assert_eq!(reader.read_bits::<u8>(4), Ok(0b1111));
+        reader.align_to_byte_boundary();
+        assert_eq!(reader.read_bits::<u8>(4), Ok(0b1010));
+    }
+
+    #[test]
+    fn unexpected_eof() {
+        let bytes = [0xFF, 0xFF];
+
+        for bit_order in [
+            BitOrder::LeastSignificantFirst,
+            BitOrder::MostSignificantFirst,
+        ] {
+            let mut reader = BitReader::with_bit_order(&bytes, bit_order);
+
+            assert_eq!(reader.read_bits::<u32>(17), Err(Error::UnexpectedEOF));
+
+            // Failed reads don't consume any bits
+            assert_eq!(reader.read_bits::<u16>(12), Ok(0xFFF));
+            assert_eq!(reader.read_bits::<u8>(5), Err(Error::UnexpectedEOF));
+            assert_eq!(reader.read_bits::<u8>(4), Ok(0xF));
+            assert_eq!(reader.read_bits::<u8>(0), Ok(0));
+            assert_eq!(reader.read_single_bit(), Err(Error::UnexpectedEOF));
+
+            reader.align_to_byte_boundary();
+            assert_eq!(reader.read_bytes(&mut [0]), Err(Error::UnexpectedEOF));
+        }
+
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(reader.read_bits::<u8>(9), Err(Error::TooLargeRead));
+    }
 }
@@ -2,6 +2,8 @@
 
 use std::io;
 
+use error_derive::Error;
+
 #[derive(Clone, Copy)]
 /// Provides a [Cursor](std::io::Cursor) equivalent without [io::Error](std::io::Error)
 pub struct ByteStream<'a> {
@@ -9,6 +11,12 @@ pub struct ByteStream<'a> {
     cursor: usize,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum Error {
+    #[msg = "attempting to seek past the end of the stream"]
+    SeekOutOfBounds,
+}
+
 macro_rules! next_int {
     ($primitive: ty, $len: expr, $be_function: ident, $le_function: ident) => {
         #[must_use]
@@ -67,6 +75,51 @@ impl<'a> ByteStream<'a> {
         self.cursor = cursor;
     }
 
+    /// Move the cursor to an absolute offset within the stream
+    ///
+    /// Unlike [set_cursor](Self::set_cursor), this fails if the offset is past the end of the stream.
+    /// Seeking to exactly the end of the stream is allowed.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::bytestream::{ByteStream, Error};
+    ///
+    /// let mut byte_stream = ByteStream::new(b"foobar");
+    ///
+    /// assert!(byte_stream.seek_to(3).is_ok());
+    /// assert_eq!(byte_stream.remaining(), b"bar");
+    ///
+    /// assert_eq!(byte_stream.seek_to(7), Err(Error::SeekOutOfBounds));
+    /// assert_eq!(byte_stream.cursor(), 3);
+    /// ```
+    pub fn seek_to(&mut self, offset: usize) -> Result<(), Error> {
+        if self.bytes.len() < offset {
+            return Err(Error::SeekOutOfBounds);
+        }
+
+        self.cursor = offset;
+        Ok(())
+    }
+
+    /// Return the next `n` bytes without advancing the cursor
+    ///
+    /// Returns `None` if there are less than `n` bytes remaining.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::bytestream::ByteStream;
+    ///
+    /// let mut byte_stream = ByteStream::new(b"foobar");
+    ///
+    /// assert_eq!(byte_stream.peek(3), Some(&b"foo"[..]));
+    /// assert_eq!(byte_stream.peek(7), None);
+    /// assert_eq!(byte_stream.remaining(), b"foobar");
+    /// ```
+    #[must_use]
+    pub fn peek(&self, n: usize) -> Option<&'a [u8]> {
+        self.bytes.get(self.cursor..)?.get(..n)
+    }
+
     #[inline]
     #[must_use]
     pub fn next_chunk<const N: usize>(&mut self) -> Option<[u8; N]> {
@@ -85,6 +138,115 @@ impl<'a> ByteStream<'a> {
         Some(chunk)
     }
 
+    /// Consume bytes as long as they match a predicate
+    ///
+    /// Returns the consumed bytes, which might be empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::bytestream::ByteStream;
+    ///
+    /// let mut byte_stream = ByteStream::new(b"123abc");
+    ///
+    /// assert_eq!(byte_stream.take_while(u8::is_ascii_digit), b"123");
+    /// assert_eq!(byte_stream.remaining(), b"abc");
+    /// ```
+    pub fn take_while<P>(&mut self, mut predicate: P) -> &'a [u8]
+    where
+        P: FnMut(&u8) -> bool,
+    {
+        let remaining = self.bytes.get(self.cursor..).unwrap_or_default();
+        let length = remaining
+            .iter()
+            .position(|byte| !predicate(byte))
+            .unwrap_or(remaining.len());
+
+        self.cursor += length;
+        &remaining[..length]
+    }
+
+    /// Consume bytes up to (but not including) the first occurence of `delimiter`
+    ///
+    /// If the delimiter does not occur within the remaining bytes, then nothing is
+    /// consumed and `None` is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::bytestream::ByteStream;
+    ///
+    /// let mut byte_stream = ByteStream::new(b"Host: example.com\r\n");
+    ///
+    /// assert_eq!(byte_stream.take_until(b": "), Some(&b"Host"[..]));
+    /// assert_eq!(byte_stream.remaining(), b": example.com\r\n");
+    /// assert_eq!(byte_stream.take_until(b"\n\n"), None);
+    /// ```
+    pub fn take_until(&mut self, delimiter: &[u8]) -> Option<&'a [u8]> {
+        let remaining = self.bytes.get(self.cursor..).unwrap_or_default();
+
+        let length = if delimiter.is_empty() {
+            0
+        } else {
+            remaining
+                .windows(delimiter.len())
+                .position(|window| window == delimiter)?
+        };
+
+        self.cursor += length;
+        Some(&remaining[..length])
+    }
+
+    /// Consume bytes up to and including the first occurence of `delimiter`
+    ///
+    /// Returns the bytes before the delimiter. If the delimiter does not occur within
+    /// the remaining bytes, then nothing is consumed and `None` is returned.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::bytestream::ByteStream;
+    ///
+    /// let mut byte_stream = ByteStream::new(b"Host: example.com\r\n");
+    ///
+    /// assert_eq!(byte_stream.read_until(b": "), Some(&b"Host"[..]));
+    /// assert_eq!(byte_stream.read_until(b"\r\n"), Some(&b"example.com"[..]));
+    /// assert!(byte_stream.is_empty());
+    /// ```
+    pub fn read_until(&mut self, delimiter: &[u8]) -> Option<&'a [u8]> {
+        let bytes = self.take_until(delimiter)?;
+        self.cursor += delimiter.len();
+        Some(bytes)
+    }
+
+    /// Consume a line that is terminated by either `\n` or `\r\n`
+    ///
+    /// The line terminator is not part of the returned line. Unlike with
+    /// [read_until](Self::read_until), the last line of the stream does not need to be terminated.
+    /// `None` is only returned once the stream is exhausted.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::bytestream::ByteStream;
+    ///
+    /// let mut byte_stream = ByteStream::new(b"foo\r\nbar\nbaz");
+    ///
+    /// assert_eq!(byte_stream.read_line(), Some(&b"foo"[..]));
+    /// assert_eq!(byte_stream.read_line(), Some(&b"bar"[..]));
+    /// assert_eq!(byte_stream.read_line(), Some(&b"baz"[..]));
+    /// assert_eq!(byte_stream.read_line(), None);
+    /// ```
+    pub fn read_line(&mut self) -> Option<&'a [u8]> {
+        if let Some(line) = self.read_until(b"\n") {
+            return Some(line.strip_suffix(b"\r").unwrap_or(line));
+        }
+
+        let remaining = self.bytes.get(self.cursor..).unwrap_or_default();
+        if remaining.is_empty() {
+            return None;
+        }
+
+        self.cursor += remaining.len();
+        Some(remaining)
+    }
+
     #[must_use]
     pub fn next_byte(&mut self) -> Option<u8> {
         let byte = self.remaining().first().copied();
@@ -135,3 +297,126 @@ impl<'a> io::Seek for ByteStream<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ByteStream, Error};
+
+    #[test]
+    fn take_while() {
+        let mut byte_stream = ByteStream::new(b"aaab");
+
+        // No match at all
+        assert_eq!(byte_stream.take_while(|&b| b == b'b'), b"");
+        assert_eq!(byte_stream.cursor(), 0);
+
+        assert_eq!(byte_stream.take_while(|&b| b == b'a'), b"aaa");
+        assert_eq!(byte_stream.cursor(), 3);
+
+        // Match until the end of the stream
+        assert_eq!(byte_stream.take_while(|_| true), b"b");
+        assert!(byte_stream.is_empty());
+        assert_eq!(byte_stream.take_while(|_| true), b"");
+
+        let mut past_the_end = ByteStream::new(b"a");
+        past_the_end.advance(10);
+        assert_eq!(past_the_end.take_while(|_| true), b"");
+    }
+
+    #[test]
+    fn take_until() {
+        let mut byte_stream = ByteStream::new(b"key=value;rest");
+
+        assert_eq!(byte_stream.take_until(b"="), Some(&b"key"[..]));
+        byte_stream.advance(1);
+
+        // The delimiter is not consumed
+        assert_eq!(byte_stream.take_until(b";"), Some(&b"value"[..]));
+        assert_eq!(byte_stream.take_until(b";"), Some(&b""[..]));
+        byte_stream.advance(1);
+
+        // No match at all
+        assert_eq!(byte_stream.take_until(b";"), None);
+        assert_eq!(byte_stream.remaining(), b"rest");
+
+        // The delimiter is at the very end
+        assert_eq!(byte_stream.take_until(b"st"), Some(&b"re"[..]));
+        assert_eq!(byte_stream.take_until(b"stt"), None);
+        assert_eq!(byte_stream.take_until(b""), Some(&b""[..]));
+    }
+
+    #[test]
+    fn read_until() {
+        let mut byte_stream = ByteStream::new(b"\r\nGET / HTTP/1.1\r\n\r\nbody");
+
+        // The delimiter is at the very start
+        assert_eq!(byte_stream.read_until(b"\r\n"), Some(&b""[..]));
+
+        // The delimiter is consumed
+        assert_eq!(byte_stream.read_until(b" "), Some(&b"GET"[..]));
+        assert_eq!(byte_stream.read_until(b"\r\n"), Some(&b"/ HTTP/1.1"[..]));
+        assert_eq!(byte_stream.read_until(b"\r\n"), Some(&b""[..]));
+
+        // No match at all
+        assert_eq!(byte_stream.read_until(b"\r\n"), None);
+        assert_eq!(byte_stream.remaining(), b"body");
+
+        // The delimiter is at the very end
+        assert_eq!(byte_stream.read_until(b"dy"), Some(&b"bo"[..]));
+        assert!(byte_stream.is_empty());
+        assert_eq!(byte_stream.read_until(b"dy"), None);
+    }
+
+    #[test]
+    fn read_line() {
+        let mut byte_stream = ByteStream::new(b"\nfirst\r\n\r\nsecond\r");
+
+        assert_eq!(byte_stream.read_line(), Some(&b""[..]));
+        assert_eq!(byte_stream.read_line(), Some(&b"first"[..]));
+        assert_eq!(byte_stream.read_line(), Some(&b""[..]));
+
+        // The last line is returned even though it is not terminated
+        assert_eq!(byte_stream.read_line(), Some(&b"second\r"[..]));
+        assert_eq!(byte_stream.read_line(), None);
+    }
+
+    #[test]
+    fn peek_then_read() {
+        let mut byte_stream = ByteStream::new(&[0x12, 0x34, 0x56, 0x78, 0x9A]);
+
+        let peeked = byte_stream.peek(4).unwrap();
+        assert_eq!(byte_stream.cursor(), 0);
+        assert_eq!(
+            byte_stream.next_be_u32(),
+            Some(u32::from_be_bytes(peeked.try_into().unwrap()))
+        );
+
+        assert_eq!(byte_stream.peek(0), Some(&[][..]));
+        assert_eq!(byte_stream.peek(1), Some(&[0x9A][..]));
+        assert_eq!(byte_stream.peek(2), None);
+        assert_eq!(byte_stream.next_byte(), Some(0x9A));
+        assert_eq!(byte_stream.peek(1), None);
+
+        byte_stream.advance(10);
+        assert_eq!(byte_stream.peek(0), None);
+    }
+
+    #[test]
+    fn seek_out_of_bounds() {
+        let mut byte_stream = ByteStream::new(b"table");
+
+        assert_eq!(byte_stream.seek_to(2), Ok(()));
+        assert_eq!(byte_stream.remaining(), b"ble");
+
+        assert_eq!(byte_stream.seek_to(5), Ok(()));
+        assert!(byte_stream.is_empty());
+
+        // A failed seek does not move the cursor
+        assert_eq!(byte_stream.seek_to(6), Err(Error::SeekOutOfBounds));
+        assert_eq!(byte_stream.seek_to(usize::MAX), Err(Error::SeekOutOfBounds));
+        assert_eq!(byte_stream.cursor(), 5);
+
+        assert_eq!(byte_stream.seek_to(0), Ok(()));
+        assert_eq!(byte_stream.remaining(), b"table");
+    }
+}
@@ -41,6 +41,10 @@ impl Year {
     pub const fn is_leap_year(&self) -> bool {
         self.0 % 4 == 0 && (self.0 % 100 != 0 || self.0 % 400 == 0)
     }
+
+    pub const fn value(&self) -> YearRange {
+        self.0
+    }
 }
 
 impl Month {
@@ -66,6 +70,17 @@ impl Month {
         Self(index)
     }
 
+    /// Return the numeric index of the month (0 based)
+    pub const fn index(&self) -> u8 {
+        self.0
+    }
+
+    /// Parse the abbreviated name of a month, as returned by [Month::name]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let index = (0..consts::MONTHS_PER_YEAR as u8).find(|&i| Self(i).name() == name)?;
+        Some(Self(index))
+    }
+
     /// Return the number of days in the month
     pub const fn num_days(&self, is_leap_year: bool) -> u64 {
         if is_leap_year {
@@ -167,18 +182,16 @@ impl Date {
     /// Return number of days since [Date::UNIX].
     ///
     /// <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>
-    pub const fn days_since_unix(&self) -> u64 {
-        let era = self.year.div_euclid(400) as usize;
+    pub const fn days_since_unix(&self) -> i64 {
+        let era = self.year.div_euclid(400) as i64;
 
         // Offset into the 400 year cycle, in years
-        let era_year = self.year.rem_euclid(400) as usize;
+        let era_year = self.year.rem_euclid(400) as i64;
 
-        let day_of_year = (153 * self.month as usize + 2) / 5 + self.day as usize - 1;
+        let day_of_year = (153 * self.month as i64 + 2) / 5 + self.day as i64 - 1;
         let day_of_era = era_year * 365 + era_year / 4 - era_year / 100 + day_of_year;
 
-        let days_since_unix = era * consts::DAYS_PER_400_YEARS + day_of_era - 719468;
-
-        days_since_unix as u64
+        era * consts::DAYS_PER_400_YEARS as i64 + day_of_era - 719468
     }
 
     pub const fn year(&self) -> Year {
@@ -195,6 +208,11 @@ impl Date {
         Month::from_internal(self.month)
     }
 
+    /// Return the day of the month (1 based)
+    pub const fn day(&self) -> u8 {
+        self.day
+    }
+
     pub const fn weekday(&self) -> Weekday {
         let days_since_unix = self.days_since_unix();
         let index = (days_since_unix + 4).rem_euclid(7);
@@ -210,6 +228,15 @@ impl Date {
 
         Self { year, month, day }
     }
+
+    /// Like [Date::from_ymd], but returns [None] if the day does not exist within the month
+    pub const fn try_from_ymd(year: Year, month: Month, day: u8) -> Option<Self> {
+        if day == 0 || month.num_days(year.is_leap_year()) < day as u64 {
+            return None;
+        }
+
+        Some(Self::from_ymd(year, month, day))
+    }
 }
 
 /// <https://howardhinnant.github.io/date_algorithms.html#Computing%20month%20from%20day-of-year>
@@ -226,7 +253,7 @@ const fn month_from_day_of_year(day: i32) -> i32 {
 const fn days_in_month_leap_year(month: u8) -> u64 {
     const N_DAYS_IN_MONTH_LEAP_YEAR: [u64; consts::MONTHS_PER_YEAR] =
         [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-    N_DAYS_IN_MONTH_LEAP_YEAR[(month - 1) as usize]
+    N_DAYS_IN_MONTH_LEAP_YEAR[month as usize]
 }
 
 /// <https://howardhinnant.github.io/date_algorithms.html#last_day_of_month_common_year>
@@ -234,7 +261,7 @@ const fn days_in_month_leap_year(month: u8) -> u64 {
 const fn days_in_month_common_year(month: u8) -> u64 {
     const N_DAYS_IN_MONTH_COMMON_YEAR: [u64; consts::MONTHS_PER_YEAR] =
         [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
-    N_DAYS_IN_MONTH_COMMON_YEAR[(month - 1) as usize]
+    N_DAYS_IN_MONTH_COMMON_YEAR[month as usize]
 }
 #[cfg(test)]
 mod tests {
@@ -259,6 +286,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn days_in_month() {
+        assert_eq!(Month::JANUARY.num_days(false), 31);
+        assert_eq!(Month::FEBRUARY.num_days(false), 28);
+        assert_eq!(Month::FEBRUARY.num_days(true), 29);
+        assert_eq!(Month::DECEMBER.num_days(true), 31);
+
+        assert!(Date::try_from_ymd(Year(2024), Month::FEBRUARY, 29).is_some());
+        assert!(Date::try_from_ymd(Year(2023), Month::FEBRUARY, 29).is_none());
+        assert!(Date::try_from_ymd(Year(2023), Month::APRIL, 0).is_none());
+    }
+
+    #[test]
+    fn days_before_unix() {
+        let date = Date::from_ymd(Year(1969), Month::DECEMBER, 31);
+        assert_eq!(date.days_since_unix(), -1);
+        assert_eq!(Date::new_from_days_since_unix(-1), date);
+    }
+
     #[test]
     fn month() {
         const MONTHS: [(i32, i32); consts::MONTHS_PER_YEAR] = [
@@ -38,6 +38,11 @@ pub enum ParseError {
     MissingHour,
     MissingMinute,
     IncorrectWeekday,
+    InvalidOffset,
+    MissingOffset,
+
+    /// The input does not match the expected format
+    UnexpectedCharacter,
 }
 
 impl Weekday {
@@ -77,15 +82,29 @@ impl DateTime {
 
     #[must_use]
     pub const fn from_unix_timestamp(seconds: u64) -> Self {
-        let days = seconds / consts::SECONDS_PER_DAY as u64;
-        let seconds = seconds % consts::SECONDS_PER_DAY as u64;
+        Self::from_signed_unix_timestamp(seconds as i64)
+    }
+
+    #[must_use]
+    const fn from_signed_unix_timestamp(seconds: i64) -> Self {
+        let days = seconds.div_euclid(consts::SECONDS_PER_DAY as i64);
+        let seconds = seconds.rem_euclid(consts::SECONDS_PER_DAY as i64);
 
         let date = Date::new_from_days_since_unix(days as i32);
-        let time = Time::new_from_n_seconds_since_midnight(seconds);
+        let time = Time::new_from_n_seconds_since_midnight(seconds as u64);
 
         Self { date, time }
     }
 
+    /// Return the number of seconds since `Jan 1, 1970 00:00:00 UTC`
+    ///
+    /// This is negative for points in time before that.
+    #[must_use]
+    pub const fn unix_timestamp(&self) -> i64 {
+        self.date.days_since_unix() * consts::SECONDS_PER_DAY as i64
+            + self.time.seconds_since_midnight() as i64
+    }
+
     pub fn from_ymd_hms(
         year: u64,
         month: u8,
@@ -107,4 +126,312 @@ impl DateTime {
     pub fn time(&self) -> Time {
         self.time
     }
+
+    /// Parse a timestamp as defined in [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339#section-5.6)
+    ///
+    /// Times with a numeric offset are converted to UTC. Fractions of a second are ignored
+    /// and leap seconds are treated as the last second of the minute.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::datetime::DateTime;
+    /// let a = DateTime::parse_rfc3339("1985-04-12T23:20:50.52Z").unwrap();
+    /// let b = DateTime::parse_rfc3339("1985-04-12T19:20:50-04:00").unwrap();
+    ///
+    /// assert_eq!(a, b);
+    /// assert_eq!(a.format_rfc3339(), "1985-04-12T23:20:50Z");
+    /// ```
+    pub fn parse_rfc3339(input: &str) -> Result<Self, ParseError> {
+        let mut cursor = input;
+
+        // full-date = date-fullyear "-" date-month "-" date-mday
+        let year = parse_digits(&mut cursor, 4).ok_or(ParseError::InvalidYear)?;
+        expect(&mut cursor, "-")?;
+        let month = parse_digits(&mut cursor, 2).ok_or(ParseError::InvalidMonth)?;
+        expect(&mut cursor, "-")?;
+        let day = parse_digits(&mut cursor, 2).ok_or(ParseError::InvalidDay)?;
+
+        // The "T" may be replaced by a space, refer to the note in section 5.6
+        if !["T", "t", " "]
+            .into_iter()
+            .any(|separator| expect(&mut cursor, separator).is_ok())
+        {
+            return Err(ParseError::MissingTime);
+        }
+
+        // partial-time = time-hour ":" time-minute ":" time-second [time-secfrac]
+        let (hour, minute, second) = parse_hms(&mut cursor)?;
+        if expect(&mut cursor, ".").is_ok() {
+            let num_digits = cursor.bytes().take_while(u8::is_ascii_digit).count();
+            if num_digits == 0 {
+                return Err(ParseError::InvalidSecond);
+            }
+            cursor = &cursor[num_digits..];
+        }
+
+        // time-offset = "Z" / time-numoffset
+        let offset = match cursor.as_bytes().first() {
+            Some(b'Z' | b'z') => {
+                cursor = &cursor[1..];
+                0
+            },
+            Some(&sign @ (b'+' | b'-')) => {
+                cursor = &cursor[1..];
+                let hours = parse_digits(&mut cursor, 2).ok_or(ParseError::InvalidOffset)?;
+                expect(&mut cursor, ":").map_err(|_| ParseError::InvalidOffset)?;
+                let minutes = parse_digits(&mut cursor, 2).ok_or(ParseError::InvalidOffset)?;
+
+                if 23 < hours || 59 < minutes {
+                    return Err(ParseError::InvalidOffset);
+                }
+
+                let offset = (hours as i64 * consts::SECONDS_PER_HOUR as i64)
+                    + (minutes as i64 * consts::SECONDS_PER_MINUTE as i64);
+                if sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            },
+            _ => return Err(ParseError::MissingOffset),
+        };
+
+        if !cursor.is_empty() {
+            return Err(ParseError::UnexpectedCharacter);
+        }
+
+        let local_time = Self::from_parts(year, month, day, hour, minute, second)?;
+        Ok(Self::from_signed_unix_timestamp(
+            local_time.unix_timestamp() - offset,
+        ))
+    }
+
+    /// Parse a date in the `IMF-fixdate` format preferred by
+    /// [RFC 7231](https://www.rfc-editor.org/rfc/rfc7231#section-7.1.1.1)
+    ///
+    /// The obsolete `rfc850-date` and `asctime-date` formats are rejected, in particular
+    /// that means that two-digit years are not accepted.
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::datetime::DateTime;
+    /// let date = DateTime::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    ///
+    /// assert_eq!(date.format_rfc3339(), "1994-11-06T08:49:37Z");
+    /// ```
+    pub fn parse_http_date(input: &str) -> Result<Self, ParseError> {
+        let mut cursor = input;
+
+        // day-name "," SP
+        let weekday_name = cursor.get(..3).ok_or(ParseError::InvalidWeekday)?;
+        Weekday::from_rfc822(weekday_name)?;
+        cursor = &cursor[3..];
+        expect(&mut cursor, ", ")?;
+
+        // date1 = day SP month SP year
+        let day = parse_digits(&mut cursor, 2).ok_or(ParseError::MissingDay)?;
+        expect(&mut cursor, " ")?;
+        let month_name = cursor.get(..3).ok_or(ParseError::MissingMonth)?;
+        let month = Month::from_name(month_name).ok_or(ParseError::InvalidMonth)?;
+        cursor = &cursor[3..];
+        expect(&mut cursor, " ")?;
+        let year = parse_digits(&mut cursor, 4).ok_or(ParseError::InvalidYear)?;
+        expect(&mut cursor, " ")?;
+
+        // time-of-day = hour ":" minute ":" second
+        let (hour, minute, second) = parse_hms(&mut cursor)?;
+
+        // The timezone is always GMT
+        expect(&mut cursor, " GMT")?;
+        if !cursor.is_empty() {
+            return Err(ParseError::UnexpectedCharacter);
+        }
+
+        let datetime = Self::from_parts(year, month.index() as u64 + 1, day, hour, minute, second)?;
+
+        if datetime.date.weekday().name() != weekday_name {
+            return Err(ParseError::IncorrectWeekday);
+        }
+
+        Ok(datetime)
+    }
+
+    /// Format the date as a [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339#section-5.6) timestamp in UTC
+    #[must_use]
+    pub fn format_rfc3339(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.date.year().value(),
+            self.date.month().index() + 1,
+            self.date.day(),
+            self.time.hour(),
+            self.time.minute(),
+            self.time.second()
+        )
+    }
+
+    /// Validates the individual components of a date
+    ///
+    /// `month` is 1 based.
+    fn from_parts(
+        year: u64,
+        month: u64,
+        day: u64,
+        hour: u64,
+        minute: u64,
+        second: u64,
+    ) -> Result<Self, ParseError> {
+        if !(1..=consts::MONTHS_PER_YEAR as u64).contains(&month) {
+            return Err(ParseError::InvalidMonth);
+        }
+
+        let date = Date::try_from_ymd(
+            Year::new(year as YearRange),
+            Month::from_index(month as u8 - 1),
+            u8::try_from(day).map_err(|_| ParseError::InvalidDay)?,
+        )
+        .ok_or(ParseError::InvalidDay)?;
+
+        if 23 < hour {
+            return Err(ParseError::InvalidHour);
+        }
+
+        if 59 < minute {
+            return Err(ParseError::InvalidMinute);
+        }
+
+        // Leap seconds can't be represented, so we pretend they are the last second of the minute
+        let second = match second {
+            0..=59 => second,
+            60 => 59,
+            _ => return Err(ParseError::InvalidSecond),
+        };
+
+        let time = Time::from_hms(hour, minute, second).expect("time was validated");
+        Ok(Self { date, time })
+    }
+}
+
+/// Parses `hour ":" minute ":" second`, where each component is exactly two digits
+fn parse_hms(cursor: &mut &str) -> Result<(u64, u64, u64), ParseError> {
+    let hour = parse_digits(cursor, 2).ok_or(ParseError::MissingHour)?;
+    expect(cursor, ":")?;
+    let minute = parse_digits(cursor, 2).ok_or(ParseError::MissingMinute)?;
+    expect(cursor, ":")?;
+    let second = parse_digits(cursor, 2).ok_or(ParseError::InvalidSecond)?;
+
+    Ok((hour, minute, second))
+}
+
+/// Consumes exactly `n` ascii digits from the input
+fn parse_digits(cursor: &mut &str, n: usize) -> Option<u64> {
+    let digits = cursor.get(..n)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    *cursor = &cursor[n..];
+    digits.parse().ok()
+}
+
+fn expect(cursor: &mut &str, expected: &str) -> Result<(), ParseError> {
+    *cursor = cursor
+        .strip_prefix(expected)
+        .ok_or(ParseError::UnexpectedCharacter)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc3339_offsets() {
+        let utc = DateTime::parse_rfc3339("1996-12-20T00:39:57Z").unwrap();
+        let with_offset = DateTime::parse_rfc3339("1996-12-19T16:39:57-08:00").unwrap();
+
+        assert_eq!(utc, with_offset);
+        assert_eq!(utc.unix_timestamp(), 851_042_397);
+        assert_eq!(
+            DateTime::parse_rfc3339("1970-01-01T01:00:00+01:00")
+                .unwrap()
+                .unix_timestamp(),
+            0
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1970-01-01 00:00:00.000z"),
+            Ok(DateTime::from_unix_timestamp(0))
+        );
+    }
+
+    #[test]
+    fn rfc3339_leap_second() {
+        let leap_second = DateTime::parse_rfc3339("1990-12-31T23:59:60Z").unwrap();
+        assert_eq!(leap_second.format_rfc3339(), "1990-12-31T23:59:59Z");
+
+        assert_eq!(
+            DateTime::parse_rfc3339("1990-12-31T23:59:61Z"),
+            Err(ParseError::InvalidSecond)
+        );
+    }
+
+    #[test]
+    fn rfc3339_invalid() {
+        assert_eq!(
+            DateTime::parse_rfc3339("96-12-19T16:39:57Z"),
+            Err(ParseError::InvalidYear)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-02-30T16:39:57Z"),
+            Err(ParseError::InvalidDay)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-13-01T16:39:57Z"),
+            Err(ParseError::InvalidMonth)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-12-19T16:39:57"),
+            Err(ParseError::MissingOffset)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-12-19T16:39:57+0800"),
+            Err(ParseError::InvalidOffset)
+        );
+        assert_eq!(
+            DateTime::parse_rfc3339("1996-12-19T16:39:57Z "),
+            Err(ParseError::UnexpectedCharacter)
+        );
+    }
+
+    #[test]
+    fn http_date() {
+        let date = DateTime::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(date.unix_timestamp(), 784_111_777);
+
+        assert_eq!(
+            DateTime::parse_http_date("Mon, 06 Nov 1994 08:49:37 GMT"),
+            Err(ParseError::IncorrectWeekday)
+        );
+        assert_eq!(
+            DateTime::parse_http_date("Foo, 06 Nov 1994 08:49:37 GMT"),
+            Err(ParseError::InvalidWeekday)
+        );
+        assert_eq!(
+            DateTime::parse_http_date("Sun, 06 Nov 1994 08:49:37 UTC"),
+            Err(ParseError::UnexpectedCharacter)
+        );
+    }
+
+    #[test]
+    fn http_date_rejects_obsolete_formats() {
+        // rfc850-date, with a two-digit year
+        assert!(DateTime::parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT").is_err());
+        assert_eq!(
+            DateTime::parse_http_date("Sun, 06 Nov 94 08:49:37 GMT"),
+            Err(ParseError::InvalidYear)
+        );
+
+        // asctime-date
+        assert!(DateTime::parse_http_date("Sun Nov  6 08:49:37 1994").is_err());
+    }
 }
@@ -31,4 +31,22 @@ impl Time {
             second,
         })
     }
+
+    pub const fn hour(&self) -> u64 {
+        self.hour
+    }
+
+    pub const fn minute(&self) -> u64 {
+        self.minute
+    }
+
+    pub const fn second(&self) -> u64 {
+        self.second
+    }
+
+    pub const fn seconds_since_midnight(&self) -> u64 {
+        self.hour * consts::SECONDS_PER_HOUR as u64
+            + self.minute * consts::SECONDS_PER_MINUTE as u64
+            + self.second
+    }
 }
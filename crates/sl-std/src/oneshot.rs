@@ -179,6 +179,31 @@ impl<T> Drop for Sender<T> {
 }
 
 impl<T> Receiver<T> {
+    /// Take the message out of the channel, without blocking
+    ///
+    /// Returns `None` if there is no message yet, the sender disconnected
+    /// or the message has already been received.
+    pub fn try_receive(&mut self) -> Option<T> {
+        // SAFETY: The Sender is never going to deallocate the channel while
+        //         we're alive.
+        let channel = unsafe { self.channel.as_ref() };
+
+        match channel.state.load(Ordering::Acquire) {
+            WAITING_FOR_RECEIVER => {
+                // SAFETY: We're only in this state if the Sender set a message for us
+                let message = unsafe { channel.take_message() };
+
+                // The sender is gone, so we can pretend that it was dropped.
+                // That way, the channel is freed once we are dropped.
+                channel.state.store(DONE, Ordering::Release);
+
+                Some(message)
+            },
+            INITIAL | DONE => None,
+            _ => unreachable!(),
+        }
+    }
+
     pub fn receive_blocking(self) -> Result<T, ReceiveError> {
         // SAFETY: The Sender is never going to deallocate the channel while
         //         we're alive.
@@ -357,6 +382,16 @@ mod tests {
         assert_eq!(msg, 42);
     }
 
+    #[test]
+    fn try_receive() {
+        let (sender, mut receiver) = Channel::create();
+
+        assert_eq!(receiver.try_receive(), None);
+        sender.send(42_u8).unwrap();
+        assert_eq!(receiver.try_receive(), Some(42));
+        assert_eq!(receiver.try_receive(), None);
+    }
+
     #[test]
     fn drop_receiver() {
         let (sender, receiver) = Channel::create();
@@ -162,9 +162,12 @@ pub fn punycode_decode(input: &ascii::Str) -> Result<String, PunyCodeError> {
         let mut k = BASE;
         loop {
             let code_point = codepoints.next().ok_or(PunyCodeError::IntegerOverflow)?;
+
+            // Digits are case-insensitive
+            let code_point = code_point.to_u8().to_ascii_lowercase();
             let digit = DIGITS
                 .iter()
-                .position(|d| d == code_point)
+                .position(|d| d.to_u8() == code_point)
                 .ok_or(PunyCodeError::InvalidPunycode)? as u32;
 
             i = i
@@ -219,6 +222,41 @@ pub fn idna_encode(input: &str) -> Result<String, PunyCodeError> {
     }
 }
 
+/// Decode all the punycode-encoded (`xn--`) labels within a domain name
+///
+/// Labels without the `xn--` prefix are returned unchanged.
+///
+/// # Example
+/// ```
+/// # use sl_std::punycode::idna_decode;
+/// assert_eq!(idna_decode("www.xn--bcher-kva.example").unwrap(), "www.bücher.example");
+/// ```
+pub fn idna_decode(input: &str) -> Result<String, PunyCodeError> {
+    let mut output = String::with_capacity(input.len());
+
+    for (index, label) in input.split('.').enumerate() {
+        if index != 0 {
+            output.push('.');
+        }
+
+        let encoded = label
+            .get(..4)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("xn--"))
+            .map(|_| &label[4..]);
+
+        match encoded {
+            Some(encoded) => {
+                let encoded = ascii::Str::from_bytes(encoded.as_bytes())
+                    .ok_or(PunyCodeError::InvalidPunycode)?;
+                output.push_str(&punycode_decode(encoded)?);
+            },
+            None => output.push_str(label),
+        }
+    }
+
+    Ok(output)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -381,4 +419,43 @@ mod tests {
             PURE_ASCII_ENCODED
         );
     }
+
+    #[test]
+    fn uppercase_digits() {
+        let encoded = ascii::Str::from_bytes(b"EGBPDAJ6BU4BXFGEHFVWXN").unwrap();
+        assert_eq!(punycode_decode(encoded).unwrap(), ARABIC);
+    }
+
+    #[test]
+    fn test_idna_decode() {
+        assert_eq!(
+            idna_decode("xn--bcher-kva.example").unwrap(),
+            "bücher.example"
+        );
+        assert_eq!(
+            idna_decode("XN--bcher-KVA.example").unwrap(),
+            "bücher.example"
+        );
+        assert_eq!(idna_decode("example.com").unwrap(), "example.com");
+        assert_eq!(idna_decode("xn--").unwrap(), "");
+
+        let domain = format!(
+            "{}.{}",
+            idna_encode(JAPANESE).unwrap(),
+            idna_encode(KOREAN).unwrap()
+        );
+        assert_eq!(
+            idna_decode(&domain).unwrap(),
+            format!("{JAPANESE}.{KOREAN}")
+        );
+
+        assert_eq!(
+            idna_decode("xn--ü.example"),
+            Err(PunyCodeError::InvalidPunycode)
+        );
+        assert_eq!(
+            idna_decode("xn--99999999999.example"),
+            Err(PunyCodeError::IntegerOverflow)
+        );
+    }
 }
@@ -1,11 +1,43 @@
 //! Random Number Generation
 
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
 /// Implements the [XOR-Shift Algorithm](https://en.wikipedia.org/wiki/Xorshift)
+///
+/// # Note
+/// This generator is **not** cryptographically secure.
+#[derive(Clone, Debug)]
 pub struct RNG {
     state: u64,
 }
 
 impl RNG {
+    /// The seed used if no other seed is provided
+    const DEFAULT_SEED: u64 = 0xcafebabedeadbeef;
+
+    /// Create a generator that always produces the same sequence for a given seed
+    ///
+    /// Since an all-zero state would cause the generator to only ever produce zeros,
+    /// a seed of `0` is replaced with a fixed non-zero value.
+    #[must_use]
+    pub const fn with_seed(seed: u64) -> Self {
+        let state = if seed == 0 { Self::DEFAULT_SEED } else { seed };
+
+        Self { state }
+    }
+
+    /// Create a generator with a seed provided by the operating system
+    #[must_use]
+    pub fn from_entropy() -> Self {
+        // The keys of a RandomState are obtained from the operating system
+        let seed = RandomState::new().build_hasher().finish();
+
+        Self::with_seed(seed)
+    }
+
     pub fn next_u64(&mut self) -> u64 {
         self.state ^= self.state << 13;
         self.state ^= self.state >> 7;
@@ -24,12 +56,186 @@ impl RNG {
     pub fn next_u8(&mut self) -> u8 {
         (self.next_u64() & u8::MAX as u64) as u8
     }
+
+    /// Fill the buffer with random bytes
+    ///
+    /// # Example
+    /// ```
+    /// # use sl_std::rand::RNG;
+    /// let mut nonce = [0; 12];
+    /// RNG::from_entropy().fill_bytes(&mut nonce);
+    /// ```
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        let mut chunks = buffer.chunks_exact_mut(8);
+        for chunk in &mut chunks {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+
+        let tail = chunks.into_remainder();
+        if !tail.is_empty() {
+            let bytes = self.next_u64().to_le_bytes();
+            tail.copy_from_slice(&bytes[..tail.len()]);
+        }
+    }
+
+    /// Return a uniformly distributed number in the range `[min, max)`
+    ///
+    /// # Panics
+    /// This function panics if `max <= min`.
+    pub fn next_range(&mut self, min: u64, max: u64) -> u64 {
+        assert!(min < max, "range {min}..{max} is empty");
+
+        let range = max - min;
+
+        // Reject values from the last incomplete multiple of range, to avoid
+        // biasing the result towards small numbers
+        let zone = u64::MAX - (u64::MAX - range + 1) % range;
+        loop {
+            let value = self.next_u64();
+            if value <= zone {
+                return min + value % range;
+            }
+        }
+    }
+
+    /// Return a uniformly distributed number in the range `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        // An f64 has 53 bits of precision, so use the 53 most significant bits
+        const SCALE: f64 = 1. / (1_u64 << 53) as f64;
+
+        (self.next_u64() >> 11) as f64 * SCALE
+    }
+}
+
+/// Shuffle the elements of a slice in place
+///
+/// Uses the [Fisher-Yates](https://en.wikipedia.org/wiki/Fisher%E2%80%93Yates_shuffle) algorithm,
+/// so every permutation is equally likely.
+///
+/// # Example
+/// ```
+/// # use sl_std::rand::{shuffle, RNG};
+/// let mut numbers = [1, 2, 3, 4, 5];
+/// shuffle(&mut RNG::with_seed(42), &mut numbers);
+/// ```
+pub fn shuffle<T>(rng: &mut RNG, slice: &mut [T]) {
+    for i in (1..slice.len()).rev() {
+        let j = rng.next_range(0, i as u64 + 1) as usize;
+        slice.swap(i, j);
+    }
+}
+
+/// Pick a uniformly distributed element from a slice
+///
+/// Returns `None` if the slice is empty.
+pub fn choose<'a, T>(rng: &mut RNG, slice: &'a [T]) -> Option<&'a T> {
+    if slice.is_empty() {
+        return None;
+    }
+
+    let index = rng.next_range(0, slice.len() as u64) as usize;
+    Some(&slice[index])
 }
 
 impl Default for RNG {
     fn default() -> Self {
-        Self {
-            state: 0xcafebabedeadbeef,
+        Self::with_seed(Self::DEFAULT_SEED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{choose, shuffle, RNG};
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = RNG::with_seed(42);
+        let mut b = RNG::with_seed(42);
+        let mut c = RNG::with_seed(43);
+
+        for _ in 0..16 {
+            let value = a.next_u64();
+            assert_eq!(value, b.next_u64());
+            assert_ne!(value, c.next_u64());
+        }
+    }
+
+    #[test]
+    fn zero_seed() {
+        let mut rng = RNG::with_seed(0);
+
+        assert_ne!(rng.next_u64(), 0);
+        assert_ne!(rng.next_u64(), 0);
+    }
+
+    #[test]
+    fn fill_bytes() {
+        let mut a = [0; 21];
+        let mut b = [0; 21];
+
+        RNG::with_seed(42).fill_bytes(&mut a);
+        RNG::with_seed(42).fill_bytes(&mut b);
+        assert_eq!(a, b);
+
+        // The bytes are the little-endian representation of consecutive numbers
+        let mut rng = RNG::with_seed(42);
+        assert_eq!(a[..8], rng.next_u64().to_le_bytes());
+        assert_eq!(a[8..16], rng.next_u64().to_le_bytes());
+        assert_eq!(a[16..], rng.next_u64().to_le_bytes()[..5]);
+
+        let mut empty = [];
+        rng.fill_bytes(&mut empty);
+    }
+
+    #[test]
+    fn range() {
+        let mut rng = RNG::with_seed(1234);
+
+        for _ in 0..1000 {
+            let value = rng.next_range(10, 15);
+            assert!((10..15).contains(&value));
+
+            let value = rng.next_f64();
+            assert!((0. ..1.).contains(&value));
+        }
+
+        assert_eq!(rng.next_range(7, 8), 7);
+        assert!((0..u64::MAX).contains(&rng.next_range(0, u64::MAX)));
+    }
+
+    #[test]
+    fn shuffle_is_deterministic_permutation() {
+        let original: Vec<u32> = (0..50).collect();
+
+        let mut a = original.clone();
+        let mut b = original.clone();
+        shuffle(&mut RNG::with_seed(42), &mut a);
+        shuffle(&mut RNG::with_seed(42), &mut b);
+        assert_eq!(a, b);
+        assert_ne!(a, original);
+
+        // No element was lost or duplicated
+        a.sort_unstable();
+        assert_eq!(a, original);
+
+        // Trivial slices are left as-is
+        let mut empty: [u32; 0] = [];
+        shuffle(&mut RNG::default(), &mut empty);
+        let mut single = [1];
+        shuffle(&mut RNG::default(), &mut single);
+        assert_eq!(single, [1]);
+    }
+
+    #[test]
+    fn choose_element() {
+        let mut rng = RNG::with_seed(1234);
+        let options = [1, 2, 3];
+
+        for _ in 0..100 {
+            let chosen = choose(&mut rng, &options).unwrap();
+            assert!(options.contains(chosen));
         }
+
+        assert_eq!(choose::<u32>(&mut rng, &[]), None);
     }
 }
@@ -1,6 +1,9 @@
 //! Implements a [circular buffer](https://en.wikipedia.org/wiki/Circular_buffer) which can hold a fixed number of items.
 
-use std::{iter::FusedIterator, mem};
+use std::{
+    iter::{self, FusedIterator},
+    mem, ops, slice,
+};
 
 /// A circular buffer capable of storing up to `N` items at once
 #[derive(Debug)]
@@ -145,15 +148,29 @@ impl<T, const N: usize> RingBuffer<T, N> {
     }
 
     /// Push an element to the buffer, possibly overwriting the oldest existing element
-    pub fn push_overwriting(&mut self, element: T) {
+    ///
+    /// If the buffer was full, the overwritten element is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sl_std::ring_buffer::RingBuffer;
+    /// let mut buffer: RingBuffer<usize, 2> = RingBuffer::default();
+    ///
+    /// assert_eq!(buffer.push_overwriting(1), None);
+    /// assert_eq!(buffer.push_overwriting(2), None);
+    /// assert_eq!(buffer.push_overwriting(3), Some(1));
+    /// ```
+    pub fn push_overwriting(&mut self, element: T) -> Option<T> {
         // Make space for the element if necessary
-        if self.is_full() {
-            // Note that we can't simply overwrite the element, as we might need
-            // to run the drop implementation for the element
-            let _ = self.pop_front();
-        }
+        let overwritten = if self.is_full() {
+            self.pop_front()
+        } else {
+            None
+        };
 
         self.push(element);
+        overwritten
     }
 
     /// Try to push an element to the buffer
@@ -195,14 +212,83 @@ impl<T, const N: usize> RingBuffer<T, N> {
         Some(element)
     }
 
+    /// Return the elements in the buffer as a pair of slices, ordered from oldest to newest
+    ///
+    /// The second slice is only non-empty if the elements wrap around the end of the underlying array.
+    #[must_use]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (first, second) = self.initialized_ranges();
+
+        // SAFETY:
+        // All elements within the ranges are initialized and MaybeUninit<T> has the same layout as T
+        unsafe {
+            (
+                &*(&self.elements[first] as *const [mem::MaybeUninit<T>] as *const [T]),
+                &*(&self.elements[second] as *const [mem::MaybeUninit<T>] as *const [T]),
+            )
+        }
+    }
+
+    /// Like [as_slices](RingBuffer::as_slices), except that the elements are mutable
+    #[must_use]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.initialized_ranges();
+
+        // The second range always ends before the first range starts
+        let (head, tail) = self.elements.split_at_mut(first.start);
+
+        // SAFETY:
+        // All elements within the ranges are initialized and MaybeUninit<T> has the same layout as T
+        unsafe {
+            (
+                &mut *(&mut tail[..first.len()] as *mut [mem::MaybeUninit<T>] as *mut [T]),
+                &mut *(&mut head[second] as *mut [mem::MaybeUninit<T>] as *mut [T]),
+            )
+        }
+    }
+
+    /// Return the indices of all initialized elements, ordered from oldest to newest
+    fn initialized_ranges(&self) -> (ops::Range<usize>, ops::Range<usize>) {
+        if self.is_empty() {
+            (0..0, 0..0)
+        } else if self.read_head < self.write_head {
+            (self.read_head..self.write_head, 0..0)
+        } else {
+            (self.read_head..N, 0..self.write_head)
+        }
+    }
+
+    /// Iterate over the elements in the buffer, from oldest to newest, without removing them
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sl_std::ring_buffer::RingBuffer;
+    /// let mut buffer: RingBuffer<usize, 3> = RingBuffer::from([1, 2, 3]);
+    /// buffer.push_overwriting(4);
+    ///
+    /// assert!(buffer.iter().eq(&[2, 3, 4]));
+    /// ```
     #[inline]
     #[must_use]
     pub const fn iter(&self) -> RingBufferIterator<'_, T, N> {
         RingBufferIterator {
-            current_index: 0,
+            front: 0,
+            back: self.len(),
             ring_buffer: self,
         }
     }
+
+    /// Like [iter](RingBuffer::iter), except that the elements are mutable
+    #[inline]
+    #[must_use]
+    pub fn iter_mut(&mut self) -> RingBufferIteratorMut<'_, T> {
+        let (first, second) = self.as_mut_slices();
+
+        RingBufferIteratorMut {
+            inner: first.iter_mut().chain(second.iter_mut()),
+        }
+    }
 }
 
 impl<T, const N: usize> Drop for RingBuffer<T, N> {
@@ -246,9 +332,31 @@ where
     }
 }
 
+impl<'a, T, const N: usize> IntoIterator for &'a RingBuffer<T, N> {
+    type Item = &'a T;
+    type IntoIter = RingBufferIterator<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut RingBuffer<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = RingBufferIteratorMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct RingBufferIterator<'a, T, const N: usize> {
-    current_index: usize,
+    /// Offset of the next element to be returned from the front, relative to the oldest element
+    front: usize,
+
+    /// Offset one past the next element to be returned from the back
+    back: usize,
     ring_buffer: &'a RingBuffer<T, N>,
 }
 
@@ -256,25 +364,66 @@ impl<'a, T, const N: usize> Iterator for RingBufferIterator<'a, T, N> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let element = self.ring_buffer.peek_front(self.current_index);
-        self.current_index += 1;
+        if self.front == self.back {
+            return None;
+        }
+
+        let element = self.ring_buffer.peek_front(self.front);
+        self.front += 1;
         element
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let elements_left = self.ring_buffer.len() - self.current_index;
+        let elements_left = self.back - self.front;
         (elements_left, Some(elements_left))
     }
 }
 
+impl<'a, T, const N: usize> DoubleEndedIterator for RingBufferIterator<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        self.ring_buffer.peek_front(self.back)
+    }
+}
+
 impl<'a, T, const N: usize> FusedIterator for RingBufferIterator<'a, T, N> {}
 
 impl<'a, T, const N: usize> ExactSizeIterator for RingBufferIterator<'a, T, N> {
     fn len(&self) -> usize {
-        self.ring_buffer.len() - self.current_index
+        self.back - self.front
+    }
+}
+
+pub struct RingBufferIteratorMut<'a, T> {
+    inner: iter::Chain<slice::IterMut<'a, T>, slice::IterMut<'a, T>>,
+}
+
+impl<'a, T> Iterator for RingBufferIteratorMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
+impl<'a, T> DoubleEndedIterator for RingBufferIteratorMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, T> FusedIterator for RingBufferIteratorMut<'a, T> {}
+
+impl<'a, T> ExactSizeIterator for RingBufferIteratorMut<'a, T> {}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -403,6 +552,76 @@ mod tests {
         assert!(items.next().is_none());
     }
 
+    #[test]
+    fn iter_empty() {
+        let buffer = unaligned_ringbuf();
+
+        assert_eq!(buffer.iter().len(), 0);
+        assert!(buffer.iter().next().is_none());
+        assert!(buffer.iter().next_back().is_none());
+        assert_eq!(buffer.as_slices(), (&[][..], &[][..]));
+    }
+
+    #[test]
+    fn iter_wrapped() {
+        let mut buffer = unaligned_ringbuf();
+
+        buffer.push(1);
+        buffer.push(2);
+        buffer.push(3);
+
+        // Internal buffer should now look like this:
+        // [3, 1, 2]
+        //     ^_ read head
+        assert_eq!(buffer.as_slices(), (&[1, 2][..], &[3][..]));
+        assert!(buffer.iter().eq(&[1, 2, 3]));
+        assert!(buffer.iter().rev().eq(&[3, 2, 1]));
+
+        _ = buffer.pop_front();
+        _ = buffer.pop_front();
+        buffer.push(4);
+
+        // [3, 4, _]
+        //  ^_ read head
+        assert_eq!(buffer.as_slices(), (&[3, 4][..], &[][..]));
+        assert!(buffer.iter().eq(&[3, 4]));
+
+        let mut items = buffer.iter();
+        assert_matches!(items.next(), Some(3));
+        assert_matches!(items.next_back(), Some(4));
+        assert!(items.next().is_none());
+        assert_eq!(items.len(), 0);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut buffer = RingBuffer::from([1, 2, 3]);
+        buffer.push_overwriting(4);
+
+        for element in buffer.iter_mut() {
+            *element *= 10;
+        }
+
+        assert_eq!(buffer.iter_mut().len(), 3);
+        assert!(buffer.iter().eq(&[20, 30, 40]));
+        assert_eq!(buffer.pop_front(), Some(20));
+    }
+
+    #[test]
+    fn push_overwriting() {
+        let mut buffer = unaligned_ringbuf();
+
+        assert_eq!(buffer.push_overwriting(1), None);
+        assert_eq!(buffer.push_overwriting(2), None);
+        assert_eq!(buffer.push_overwriting(3), None);
+
+        // The oldest elements are evicted first
+        assert_eq!(buffer.push_overwriting(4), Some(1));
+        assert_eq!(buffer.push_overwriting(5), Some(2));
+        assert_eq!(buffer.len(), 3);
+        assert!(buffer.iter().eq(&[3, 4, 5]));
+    }
+
     #[test]
     fn clone() {
         let mut buffer = unaligned_ringbuf();
@@ -0,0 +1,102 @@
+//! Implements <https://url.spec.whatwg.org/#application/x-www-form-urlencoded>
+
+use std::borrow::Cow;
+
+use sl_std::ascii;
+
+use crate::percent_encode::{
+    percent_decode, percent_encode, APPLICATION_X_WWW_FORM_URLENCODED_PERCENT_ENCODE_SET,
+};
+
+/// <https://url.spec.whatwg.org/#concept-urlencoded-parser>
+///
+/// Sequences without a `=` are treated as a name with an empty value.
+pub fn parse(input: &ascii::Str) -> impl Iterator<Item = (Cow<'_, str>, Cow<'_, str>)> {
+    input
+        .split(ascii::Char::Ampersand)
+        .filter(|sequence| !sequence.is_empty())
+        .map(|sequence| {
+            let (name, value) = sequence
+                .split_once(ascii::Char::EqualsSign)
+                .unwrap_or((sequence, ascii::Str::EMPTY));
+
+            (decode(name), decode(value))
+        })
+}
+
+fn decode(input: &ascii::Str) -> Cow<'_, str> {
+    let needs_decoding = input
+        .chars()
+        .iter()
+        .any(|&c| c == ascii::Char::PlusSign || c == ascii::Char::PercentSign);
+
+    if !needs_decoding {
+        return Cow::Borrowed(input.as_str());
+    }
+
+    let with_spaces = input.replace(ascii::Char::PlusSign, ascii!(" "));
+    let bytes = percent_decode(&with_spaces);
+    Cow::Owned(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// <https://url.spec.whatwg.org/#concept-urlencoded-serializer>
+pub fn serialize<'a, I>(pairs: I) -> ascii::String
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let mut output = ascii::String::new();
+
+    for (name, value) in pairs {
+        if !output.is_empty() {
+            output.push(ascii::Char::Ampersand);
+        }
+
+        serialize_component(name, &mut output);
+        output.push(ascii::Char::EqualsSign);
+        serialize_component(value, &mut output);
+    }
+
+    output
+}
+
+/// Percent-encodes the input, encoding spaces as `+`
+fn serialize_component(input: &str, output: &mut ascii::String) {
+    for (index, part) in input.split(' ').enumerate() {
+        if index != 0 {
+            output.push(ascii::Char::PlusSign);
+        }
+
+        percent_encode(
+            part.as_bytes(),
+            APPLICATION_X_WWW_FORM_URLENCODED_PERCENT_ENCODE_SET,
+            output,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_pairs() {
+        let input = ascii!("a=b&flag&&c=d%20e+f&a=%zz");
+        let pairs: Vec<_> = parse(input).collect();
+
+        assert_eq!(
+            pairs,
+            [
+                (Cow::Borrowed("a"), Cow::Borrowed("b")),
+                (Cow::Borrowed("flag"), Cow::Borrowed("")),
+                (Cow::Borrowed("c"), Cow::Owned("d e f".to_string())),
+                (Cow::Borrowed("a"), Cow::Owned("%zz".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn serialize_pairs() {
+        let serialized = serialize([("a b", "c&d"), ("ü", "=~*")]);
+        assert_eq!(serialized.as_str(), "a+b=c%26d&%C3%BC=%3D%7E*");
+    }
+}
@@ -4,7 +4,7 @@ use sl_std::{ascii, punycode};
 
 use crate::{
     ip::{ipv4_parse, ipv6_parse},
-    percent_encode::{is_c0_control, percent_encode, C0_CONTROL},
+    percent_encode::{percent_encode, C0_CONTROL},
     AsciiSet, IPParseError,
 };
 
@@ -55,9 +55,37 @@ pub enum HostParseError {
     MalformedInput,
     ForbiddenCodePoint,
     Punycode(punycode::PunyCodeError),
+
+    /// A label within a domain is empty, too long or starts or ends with a hyphen
+    InvalidDomainLabel,
+
+    /// The domain is longer than 253 characters
+    DomainTooLong,
     IP(IPParseError),
 }
 
+/// The maximum length of a single label within a domain, in bytes
+const MAX_LABEL_LENGTH: usize = 63;
+
+/// The maximum length of a domain, excluding the trailing dot, in bytes
+const MAX_DOMAIN_LENGTH: usize = 253;
+
+impl Host {
+    /// Return the host in a form suitable for displaying it to the user
+    ///
+    /// Punycode-encoded labels within domains are decoded, so `xn--bcher-kva.example`
+    /// is displayed as `bücher.example`. Labels that cannot be decoded are kept as-is.
+    #[must_use]
+    pub fn to_unicode(&self) -> String {
+        match self {
+            Self::Domain(domain) => {
+                punycode::idna_decode(domain.as_str()).unwrap_or_else(|_| domain.to_string())
+            },
+            _ => self.to_string(),
+        }
+    }
+}
+
 impl fmt::Display for Host {
     // <https://url.spec.whatwg.org/#host-serializing>
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -114,8 +142,7 @@ pub(crate) fn parse_with_special(
 
     // Let asciiDomain be the result of running domain to ASCII with domain and false.
     // If asciiDomain is failure, validation error, return failure.
-    let ascii_domain =
-        ascii::String::from_utf8_punycode(input).map_err(HostParseError::Punycode)?;
+    let ascii_domain = domain_to_ascii(input, false)?;
 
     // If asciiDomain contains a forbidden domain code point,
     if ascii_domain
@@ -143,6 +170,89 @@ pub(crate) fn parse_with_special(
     Ok(Host::Domain(ascii_domain))
 }
 
+/// Convert a (possibly non-ascii) domain to its ascii form
+///
+/// Every label that contains non-ascii characters is punycode-encoded and prefixed with `xn--`,
+/// so `bücher.example` becomes `xn--bcher-kva.example`.
+///
+/// The mapping step only lowercases the domain and replaces the ideographic full stops
+/// with `.`, the full UTS #46 mapping table is not implemented.
+///
+/// If `be_strict` is true then the labels must also be valid DNS labels, meaning they
+/// may not be empty, start or end with a hyphen or exceed the DNS length limits.
+/// The URL parser is *not* strict.
+///
+/// <https://url.spec.whatwg.org/#concept-domain-to-ascii>
+fn domain_to_ascii(domain: &str, be_strict: bool) -> Result<ascii::String, HostParseError> {
+    let mapped: String = domain
+        .chars()
+        .filter(|&c| c != '\u{AD}')
+        .map(|c| match c {
+            '\u{3002}' | '\u{FF0E}' | '\u{FF61}' => '.',
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    // A single trailing dot denotes the root of the DNS tree and does not make the last label empty
+    let (labels, has_trailing_dot) = match mapped.strip_suffix('.') {
+        Some(labels) if !labels.is_empty() => (labels, true),
+        _ => (mapped.as_str(), false),
+    };
+
+    let mut result = ascii::String::with_capacity(mapped.len());
+    for (index, label) in labels.split('.').enumerate() {
+        if index != 0 {
+            result.push(ascii::Char::FullStop);
+        }
+
+        if label.is_ascii() {
+            // Labels that are already encoded must contain valid punycode
+            if let Some(encoded) = label.strip_prefix("xn--") {
+                let encoded = ascii::Str::from_bytes(encoded.as_bytes())
+                    .expect("label contains only ascii characters");
+                let decoded =
+                    punycode::punycode_decode(encoded).map_err(HostParseError::Punycode)?;
+
+                if decoded.is_empty() || decoded.is_ascii() {
+                    return Err(HostParseError::InvalidDomainLabel);
+                }
+            }
+
+            let label = ascii::Str::from_bytes(label.as_bytes())
+                .expect("label contains only ascii characters");
+            result.push_str(label);
+        } else {
+            let encoded = punycode::punycode_encode(label).map_err(HostParseError::Punycode)?;
+            result.push_str(ascii!("xn--"));
+            result.push_str(&encoded);
+        }
+    }
+
+    // CheckHyphens and VerifyDnsLength are only set if beStrict is true
+    if be_strict {
+        for label in result.as_str().split('.') {
+            if label.is_empty()
+                || label.len() > MAX_LABEL_LENGTH
+                || label.starts_with('-')
+                || label.ends_with('-')
+            {
+                return Err(HostParseError::InvalidDomainLabel);
+            }
+        }
+
+        if result.len() > MAX_DOMAIN_LENGTH {
+            return Err(HostParseError::DomainTooLong);
+        }
+    }
+
+    if has_trailing_dot {
+        result.push(ascii::Char::FullStop);
+    }
+
+    Ok(result)
+}
+
 /// <https://url.spec.whatwg.org/#concept-opaque-host-parser>
 fn opaque_host_parse(input: &str) -> Result<ascii::String, HostParseError> {
     // If input contains a forbidden host code point
@@ -162,6 +272,85 @@ fn opaque_host_parse(input: &str) -> Result<ascii::String, HostParseError> {
     // Return the result of running UTF-8 percent-encode on input
     // using the C0 control percent-encode set.
     let mut percent_encoded = ascii::String::with_capacity(input.len());
-    percent_encode(input.as_bytes(), is_c0_control, &mut percent_encoded);
+    percent_encode(input.as_bytes(), C0_CONTROL, &mut percent_encoded);
     Ok(percent_encoded)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::URL;
+
+    fn parse_domain(input: &str) -> Result<String, HostParseError> {
+        parse_with_special(input, false).map(|host| host.to_string())
+    }
+
+    #[test]
+    fn idna_to_ascii() {
+        assert_eq!(
+            parse_domain("bücher.example").unwrap(),
+            "xn--bcher-kva.example"
+        );
+        assert_eq!(
+            parse_domain("www.BÜCHER.example").unwrap(),
+            "www.xn--bcher-kva.example"
+        );
+        assert_eq!(parse_domain("Example.COM.").unwrap(), "example.com.");
+
+        // Cases from the URL standard
+        assert_eq!(parse_domain("faß.ExAmPlE").unwrap(), "xn--fa-hia.example");
+        assert_eq!(parse_domain("你好你好").unwrap(), "xn--6qqa088eba");
+        assert_eq!(parse_domain("a\u{3002}b").unwrap(), "a.b");
+    }
+
+    #[test]
+    fn invalid_labels() {
+        assert!(matches!(
+            domain_to_ascii("a..b", true),
+            Err(HostParseError::InvalidDomainLabel)
+        ));
+        assert!(matches!(
+            domain_to_ascii("-foo.example", true),
+            Err(HostParseError::InvalidDomainLabel)
+        ));
+        assert!(matches!(
+            domain_to_ascii("foo-.example", true),
+            Err(HostParseError::InvalidDomainLabel)
+        ));
+        assert!(matches!(
+            domain_to_ascii(&"a".repeat(64), true),
+            Err(HostParseError::InvalidDomainLabel)
+        ));
+        assert!(matches!(
+            domain_to_ascii(&vec!["a".repeat(63); 4].join("."), true),
+            Err(HostParseError::DomainTooLong)
+        ));
+
+        // Labels starting with "xn--" must contain valid punycode
+        assert!(parse_domain("xn--.example").is_err());
+    }
+
+    #[test]
+    fn non_strict_labels() {
+        // The URL parser does not verify that labels are valid DNS labels
+        assert_eq!(parse_domain("a..b").unwrap(), "a..b");
+        assert_eq!(parse_domain("-foo.example").unwrap(), "-foo.example");
+        assert_eq!(parse_domain("foo-.example").unwrap(), "foo-.example");
+        assert_eq!(parse_domain(&"a".repeat(64)).unwrap(), "a".repeat(64));
+
+        let url: URL = "http://a..b/".parse().unwrap();
+        assert_eq!(url.host().unwrap().to_string(), "a..b");
+
+        let url: URL = "http://-foo.example/".parse().unwrap();
+        assert_eq!(url.host().unwrap().to_string(), "-foo.example");
+    }
+
+    #[test]
+    fn to_unicode() {
+        let host = parse_with_special("bücher.example", false).unwrap();
+        assert_eq!(host.to_unicode(), "bücher.example");
+
+        let host = parse_with_special("127.0.0.1", false).unwrap();
+        assert_eq!(host.to_unicode(), "127.0.0.1");
+    }
+}
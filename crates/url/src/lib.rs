@@ -19,8 +19,10 @@
     const_option
 )]
 
+pub mod form_urlencoded;
 mod host;
 mod ip;
+mod origin;
 mod parser;
 mod path;
 mod percent_encode;
@@ -31,6 +33,12 @@ mod util;
 pub use crate::ip::IPParseError;
 pub use crate::url::*;
 pub use host::Host;
+pub use origin::{Origin, TupleOrigin};
 pub use path::PathSegments;
-pub use percent_encode::{percent_decode, percent_encode};
-use set::AsciiSet;
+pub use percent_encode::{
+    percent_decode, percent_encode, APPLICATION_X_WWW_FORM_URLENCODED_PERCENT_ENCODE_SET,
+    C0_CONTROL, C0_CONTROL_PERCENT_ENCODE_SET, COMPONENT_PERCENT_ENCODE_SET,
+    FRAGMENT_PERCENT_ENCODE_SET, PATH_PERCENT_ENCODE_SET, QUERY_PERCENT_ENCODE_SET,
+    SPECIAL_QUERY_PERCENT_ENCODE_SET, USERINFO_PERCENT_ENCODE_SET,
+};
+pub use set::AsciiSet;
@@ -6,13 +6,17 @@
 use sl_std::{ascii, chars::ReversibleCharIterator};
 
 use crate::{
-    host::{self, HostParseError},
+    host::{self, Host, HostParseError},
     is_special_scheme,
     percent_encode::{
-        is_c0_percent_encode_set, is_fragment_percent_encode_set, is_query_percent_encode_set,
-        is_special_query_percent_encode_set, is_userinfo_percent_encode_set, percent_encode,
+        percent_encode, C0_CONTROL_PERCENT_ENCODE_SET, FRAGMENT_PERCENT_ENCODE_SET,
+        PATH_PERCENT_ENCODE_SET, QUERY_PERCENT_ENCODE_SET, SPECIAL_QUERY_PERCENT_ENCODE_SET,
+        USERINFO_PERCENT_ENCODE_SET,
+    },
+    util::{
+        is_double_dot_path_segment, is_normalized_windows_drive_letter, is_single_dot_path_segment,
+        is_windows_drive_letter, starts_with_windows_drive_letter,
     },
-    util::{is_double_dot_path_segment, is_single_dot_path_segment, is_windows_drive_letter},
     URL,
 };
 
@@ -71,7 +75,7 @@ impl<'a> Parser<'a> {
             let scheme = self.url.scheme().as_str();
 
             if scheme == "file" {
-                self.parse_file()
+                self.parse_file(base)
             } else if is_special_scheme(scheme) {
                 if let Some(base) = base
                     && base.scheme() == scheme
@@ -152,7 +156,7 @@ impl<'a> Parser<'a> {
                 c.encode_utf8(&mut buffer);
                 percent_encode(
                     &buffer[..c.len_utf8()],
-                    is_userinfo_percent_encode_set,
+                    USERINFO_PERCENT_ENCODE_SET,
                     &mut self.url.serialization,
                 );
             }
@@ -239,6 +243,7 @@ impl<'a> Parser<'a> {
             .try_into()
             .expect("port numbers are always valid ascii");
 
+        self.url.serialization.push(ascii::Char::Colon);
         self.url.serialization.push_str(port_str);
 
         self.input
@@ -281,59 +286,53 @@ impl<'a> Parser<'a> {
     fn parse_path(&mut self) -> Result<(), Error> {
         let is_special = self.url.is_special();
 
-        let mut terminating_character = None;
-        loop {
+        let terminating_character = loop {
+            // Each iteration parses a single path segment
+            let segment_start = self.url.serialization.len();
             self.url.serialization.push(ascii::Char::Solidus);
-            let path_segment_start = self.url.serialization.len();
-            let mut is_last_segment = false;
 
-            // Inner loop parses a single path segment
-            loop {
+            let terminator = loop {
                 let Some(c) = self.input.next() else {
-                    is_last_segment = true;
-                    break;
+                    break None;
                 };
 
-                if c == '?' || c == '#' {
-                    terminating_character = Some(c);
-                    is_last_segment = true;
-                    break;
-                }
-                if c == '/' || (is_special && c == '\\') {
-                    self.url.serialization.push(ascii::Char::Solidus);
-                    break;
+                if c == '/' || (is_special && c == '\\') || c == '?' || c == '#' {
+                    break Some(c);
                 }
 
                 let mut buffer = [0; 4];
                 c.encode_utf8(&mut buffer);
                 percent_encode(
                     &buffer[..c.len_utf8()],
-                    is_userinfo_percent_encode_set,
+                    PATH_PERCENT_ENCODE_SET,
                     &mut self.url.serialization,
                 );
-            }
+            };
 
-            let segment = &self.url.serialization[path_segment_start..];
+            let is_last_segment = !matches!(terminator, Some('/' | '\\'));
+            let segment = &self.url.serialization[segment_start + 1..];
 
             if is_double_dot_path_segment(segment.as_str()) {
-                self.url.serialization.truncate(path_segment_start - 1);
+                self.url.serialization.truncate(segment_start);
                 self.url.shorten_path();
-            } else if is_single_dot_path_segment(segment.as_str()) {
+
                 if is_last_segment {
                     self.url.serialization.push(ascii::Char::Solidus);
-                } else {
-                    self.url.serialization.push(ascii::Char::FullStop);
                 }
-            } else {
-                if self.url.scheme() == "file" && is_windows_drive_letter(segment.as_str()) {
-                    self.url.serialization[path_segment_start + 1] = ascii::Char::Colon;
+            } else if is_single_dot_path_segment(segment.as_str()) {
+                self.url.serialization.truncate(segment_start);
+
+                if is_last_segment {
+                    self.url.serialization.push(ascii::Char::Solidus);
                 }
+            } else if self.url.scheme() == "file" && is_windows_drive_letter(segment.as_str()) {
+                self.url.serialization[segment_start + 2] = ascii::Char::Colon;
             }
 
             if is_last_segment {
-                break;
+                break terminator;
             }
-        }
+        };
 
         match terminating_character {
             Some('?') => self.parse_query(),
@@ -365,7 +364,7 @@ impl<'a> Parser<'a> {
                 c.encode_utf8(&mut buffer);
                 percent_encode(
                     &buffer[..c.len_utf8()],
-                    is_c0_percent_encode_set,
+                    C0_CONTROL_PERCENT_ENCODE_SET,
                     &mut self.url.serialization,
                 );
             }
@@ -392,6 +391,7 @@ impl<'a> Parser<'a> {
             let base_fragment_start = base
                 .offsets
                 .fragment_start
+                .map(|start| start - 1)
                 .unwrap_or(base.serialization.len());
             self.url.serialization.clear();
             self.url
@@ -399,21 +399,121 @@ impl<'a> Parser<'a> {
                 .push_str(&base.serialization[..base_fragment_start]);
 
             self.url.offsets = base.offsets;
-            self.url.offsets.fragment_start = Some(self.url.serialization.len());
-            self.url.serialization.push(ascii::Char::NumberSign);
+            self.url.offsets.fragment_start = None;
 
             // and set state to fragment state.
             return self.parse_fragment();
         } else if base.scheme() == "file" {
-            return self.parse_file();
+            return self.parse_file(Some(base));
         } else {
             return self.parse_relative(base);
         }
     }
 
-    fn parse_file(&mut self) -> Result<(), Error> {
-        // FIXME
-        Ok(())
+    /// <https://url.spec.whatwg.org/#file-state>
+    fn parse_file(&mut self, base: Option<&URL>) -> Result<(), Error> {
+        // Set url’s scheme to "file".
+        self.url.serialization.clear();
+        self.url.serialization.push_str(ascii!("file:"));
+        self.url.offsets.scheme_end = "file".len();
+
+        // Set url’s host to the empty string.
+        self.url.host = Some(Host::EmptyHost);
+
+        let base = base.filter(|base| base.scheme() == "file");
+
+        if matches!(self.input.current(), Some('/' | '\\')) {
+            self.input.next();
+            return self.parse_file_slash(base);
+        }
+
+        if let Some(base) = base {
+            // Relative references behave the same as they do for every other special url
+            return self.parse_relative(base);
+        }
+
+        self.start_file_authority();
+        self.url.offsets.path_start = self.url.serialization.len();
+        self.parse_path()
+    }
+
+    /// <https://url.spec.whatwg.org/#file-slash-state>
+    fn parse_file_slash(&mut self, base: Option<&URL>) -> Result<(), Error> {
+        if matches!(self.input.current(), Some('/' | '\\')) {
+            self.input.next();
+            return self.parse_file_host();
+        }
+
+        self.start_file_authority();
+
+        if let Some(base) = base {
+            // Set url’s host to base’s host
+            self.url.host = base.host.clone();
+            self.url
+                .serialization
+                .push_str(&base.serialization[base.offsets.host_start..base.offsets.path_start]);
+            self.url.offsets.path_start = self.url.serialization.len();
+
+            // If the code point substring from pointer to the end of input does not start with a Windows drive letter
+            // and base’s path[0] is a normalized Windows drive letter, then append base’s path[0] to url’s path.
+            if !starts_with_windows_drive_letter(self.input.remaining())
+                && let Some(first_segment) = base.path_segments().next()
+                && is_normalized_windows_drive_letter(first_segment.as_str())
+            {
+                self.url.serialization.push(ascii::Char::Solidus);
+                self.url.serialization.push_str(first_segment);
+            }
+        } else {
+            self.url.offsets.path_start = self.url.serialization.len();
+        }
+
+        self.parse_path()
+    }
+
+    /// <https://url.spec.whatwg.org/#file-host-state>
+    fn parse_file_host(&mut self) -> Result<(), Error> {
+        self.start_file_authority();
+
+        let host_start = self.input.position();
+        while self
+            .input
+            .current()
+            .is_some_and(|c| !matches!(c, '/' | '\\' | '?' | '#'))
+        {
+            self.input.next();
+        }
+        let host_buffer = &self.input.source()[host_start..self.input.position()];
+
+        // If buffer is a Windows drive letter, then it is part of the path instead
+        if host_buffer.len() == 2 && is_windows_drive_letter(host_buffer) {
+            self.input.set_position(host_start);
+            self.url.offsets.path_start = self.url.serialization.len();
+            return self.parse_path();
+        }
+
+        if !host_buffer.is_empty() {
+            let mut host = host::parse_with_special(host_buffer, false)?;
+
+            // If host is "localhost", then set host to the empty string.
+            if host == Host::Domain(ascii!("localhost").to_owned()) {
+                host = Host::EmptyHost;
+            }
+
+            let host_serialization: ascii::String =
+                ascii::String::try_from(format!("{host}")).expect("is ascii");
+            self.url.serialization.push_str(&host_serialization);
+            self.url.host = Some(host);
+        }
+
+        self.parse_path_start()
+    }
+
+    /// Serializes the (always empty) credentials of a file url
+    fn start_file_authority(&mut self) {
+        self.url.serialization.push_str(ascii!("//"));
+        self.url.offsets.username_start = self.url.serialization.len();
+        self.url.offsets.password_start = self.url.serialization.len();
+        self.url.offsets.host_start = self.url.serialization.len();
     }
 
     /// <https://url.spec.whatwg.org/#query-state>
@@ -424,15 +524,16 @@ impl<'a> Parser<'a> {
         self.url.offsets.query_start = Some(self.url.serialization.len());
 
         let percent_encode_set = if self.url.is_special() {
-            is_special_query_percent_encode_set
+            SPECIAL_QUERY_PERCENT_ENCODE_SET
         } else {
-            is_query_percent_encode_set
+            QUERY_PERCENT_ENCODE_SET
         };
 
         let query_start = self.input.position();
         while let Some(c) = self.input.next() {
             if c == '#' {
-                let buffer = &self.input.source()[query_start..self.input.position()];
+                // The '#' has already been consumed, but it is not part of the query
+                let buffer = &self.input.source()[query_start..self.input.position() - 1];
 
                 percent_encode(
                     buffer.as_bytes(),
@@ -466,7 +567,7 @@ impl<'a> Parser<'a> {
 
         percent_encode(
             buffer.as_bytes(),
-            is_fragment_percent_encode_set,
+            FRAGMENT_PERCENT_ENCODE_SET,
             &mut self.url.serialization,
         );
 
@@ -476,7 +577,7 @@ impl<'a> Parser<'a> {
     /// <https://url.spec.whatwg.org/#relative-state>
     fn parse_relative(&mut self, base: &URL) -> Result<(), Error> {
         self.url.serialization.clear();
-        self.url.serialization.push_str(&base.scheme());
+        self.url.serialization.push_str(base.scheme());
         self.url.offsets.scheme_end = base.offsets.scheme_end;
         self.url.serialization.push(ascii::Char::Colon);
 
@@ -486,60 +587,73 @@ impl<'a> Parser<'a> {
             return self.parse_relative_slash(base);
         }
 
-        // Copy base up to fragment
-        let base_fragment_end = base
+        // Copy everything from the base url up to (and including) the path
+        let base_path_end = base
             .offsets
-            .fragment_start
+            .query_start
+            .or(base.offsets.fragment_start)
+            .map(|start| start - 1)
             .unwrap_or(base.serialization.len());
-        let username_to_query = &base.serialization[base.offsets.scheme_end + 1..base_fragment_end];
-        self.url.serialization.push_str(username_to_query);
+        let username_to_path = &base.serialization[base.offsets.scheme_end + 1..base_path_end];
+        self.url.serialization.push_str(username_to_path);
         self.url.host = base.host.clone();
+        self.url.port = base.port;
 
         self.url.offsets = base.offsets;
+        self.url.offsets.query_start = None;
         self.url.offsets.fragment_start = None;
 
         match c {
             Some('?') => {
-                if let Some(query_start) = self.url.offsets.query_start {
-                    self.url.serialization.truncate(query_start);
-                }
-
+                self.input.next();
                 self.parse_query()
             },
-            Some('#') => self.parse_fragment(),
+            Some('#') => {
+                self.copy_query_from(base);
+                self.input.next();
+                self.parse_fragment()
+            },
             Some(_) => {
                 self.url.shorten_path();
-                self.url.offsets.path_start = self.url.serialization.len();
                 self.parse_path()
             },
-            _ => Ok(()),
+            None => {
+                self.copy_query_from(base);
+                Ok(())
+            },
         }
     }
 
+    /// Append the query of `base` to the url, if there is one
+    ///
+    /// This expects the serialization to currently end with the path.
+    fn copy_query_from(&mut self, base: &URL) {
+        if let Some(query) = base.query() {
+            self.url.serialization.push(ascii::Char::QuestionMark);
+            self.url.offsets.query_start = Some(self.url.serialization.len());
+            self.url.serialization.push_str(query);
+        }
+    }
+
+    /// <https://url.spec.whatwg.org/#relative-slash-state>
     fn parse_relative_slash(&mut self, base: &URL) -> Result<(), Error> {
         let c = self.input.current();
 
-        if c == Some('/') {
-            self.parse_special_authority_slashes()
-        } else if self.url.is_special() && c == Some('\\') {
+        if c == Some('/') || (self.url.is_special() && c == Some('\\')) {
             self.parse_special_authority_slashes()
         } else {
-            // Copy from username to port
-            if base.host.is_some() {
-                self.url.serialization.push_str(ascii!("//"));
-            }
-
-            let end = base.offsets.path_start;
+            // Copy everything from the base url up to the path
+            self.url.serialization.clear();
             self.url
                 .serialization
-                .push_str(&base.serialization[base.offsets.username_start..end]);
+                .push_str(&base.serialization[..base.offsets.path_start]);
 
-            self.url.offsets.password_start = base.offsets.password_start;
-            self.url.offsets.host_start = base.offsets.host_start;
+            self.url.offsets = base.offsets;
+            self.url.offsets.query_start = None;
+            self.url.offsets.fragment_start = None;
             self.url.port = base.port;
             self.url.host = base.host.clone();
 
-            self.url.offsets.path_start = self.url.serialization.len();
             self.parse_path()
         }
     }
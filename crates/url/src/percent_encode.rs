@@ -6,63 +6,77 @@ use crate::AsciiSet;
 pub const C0_CONTROL: AsciiSet =
     AsciiSet::from_range_inclusive(ascii::Char::Null, ascii::Char::InformationSeparatorOne);
 
-#[inline]
-#[must_use]
-pub(crate) fn is_c0_control(c: u8) -> bool {
-    c <= 0x1F
-}
-
 /// <https://url.spec.whatwg.org/#c0-control-percent-encode-set>
-#[inline]
-#[must_use]
-pub(crate) fn is_c0_percent_encode_set(c: u8) -> bool {
-    is_c0_control(c) | matches!(c, 0x7F..)
-}
+///
+/// Code points above `U+007F` are not part of any [AsciiSet], they are always percent-encoded.
+pub const C0_CONTROL_PERCENT_ENCODE_SET: AsciiSet = C0_CONTROL.add(ascii::Char::Delete);
 
 /// <https://url.spec.whatwg.org/#fragment-percent-encode-set>
-#[inline]
-#[must_use]
-pub(crate) fn is_fragment_percent_encode_set(c: u8) -> bool {
-    is_c0_percent_encode_set(c) | matches!(c, b' ' | b'"' | b'#' | b'<' | b'>')
-}
+pub const FRAGMENT_PERCENT_ENCODE_SET: AsciiSet = C0_CONTROL_PERCENT_ENCODE_SET
+    .add(ascii::Char::Space)
+    .add(ascii::Char::QuotationMark)
+    .add(ascii::Char::LessThanSign)
+    .add(ascii::Char::GreaterThanSign)
+    .add(ascii::Char::GraveAccent);
 
 /// <https://url.spec.whatwg.org/#query-percent-encode-set>
-#[inline]
-#[must_use]
-pub(crate) fn is_query_percent_encode_set(c: u8) -> bool {
-    is_c0_percent_encode_set(c) | matches!(c, b' ' | b'"' | b'#' | b'<' | b'>')
-}
+pub const QUERY_PERCENT_ENCODE_SET: AsciiSet = C0_CONTROL_PERCENT_ENCODE_SET
+    .add(ascii::Char::Space)
+    .add(ascii::Char::QuotationMark)
+    .add(ascii::Char::NumberSign)
+    .add(ascii::Char::LessThanSign)
+    .add(ascii::Char::GreaterThanSign);
 
 /// <https://url.spec.whatwg.org/#special-query-percent-encode-set>
-#[inline]
-#[must_use]
-pub(crate) fn is_special_query_percent_encode_set(c: u8) -> bool {
-    is_query_percent_encode_set(c) || c == b'\''
-}
+pub const SPECIAL_QUERY_PERCENT_ENCODE_SET: AsciiSet =
+    QUERY_PERCENT_ENCODE_SET.add(ascii::Char::Apostrophe);
 
 /// <https://url.spec.whatwg.org/#path-percent-encode-set>
-#[inline]
-#[must_use]
-pub(crate) fn is_path_percent_encode_set(c: u8) -> bool {
-    is_query_percent_encode_set(c) | matches!(c, b'?' | b'`' | b'{' | b'}')
-}
+pub const PATH_PERCENT_ENCODE_SET: AsciiSet = QUERY_PERCENT_ENCODE_SET
+    .add(ascii::Char::QuestionMark)
+    .add(ascii::Char::GraveAccent)
+    .add(ascii::Char::LeftCurlyBracket)
+    .add(ascii::Char::RightCurlyBracket);
 
 /// <https://url.spec.whatwg.org/#userinfo-percent-encode-set>
-#[inline]
-#[must_use]
-pub(crate) fn is_userinfo_percent_encode_set(c: u8) -> bool {
-    is_path_percent_encode_set(c)
-        | matches!(c, b'/' | b':' | b';' | b'=' | b'@' | b'['..=b'^' | b'|')
-}
+pub const USERINFO_PERCENT_ENCODE_SET: AsciiSet = PATH_PERCENT_ENCODE_SET
+    .add(ascii::Char::Solidus)
+    .add(ascii::Char::Colon)
+    .add(ascii::Char::Semicolon)
+    .add(ascii::Char::EqualsSign)
+    .add(ascii::Char::CommercialAt)
+    .merge(AsciiSet::from_range_inclusive(
+        ascii::Char::LeftSquareBracket,
+        ascii::Char::CircumflexAccent,
+    ))
+    .add(ascii::Char::VerticalLine);
+
+/// <https://url.spec.whatwg.org/#component-percent-encode-set>
+pub const COMPONENT_PERCENT_ENCODE_SET: AsciiSet = USERINFO_PERCENT_ENCODE_SET
+    .merge(AsciiSet::from_range_inclusive(
+        ascii::Char::DollarSign,
+        ascii::Char::Ampersand,
+    ))
+    .add(ascii::Char::PlusSign)
+    .add(ascii::Char::Comma);
+
+/// <https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set>
+pub const APPLICATION_X_WWW_FORM_URLENCODED_PERCENT_ENCODE_SET: AsciiSet =
+    COMPONENT_PERCENT_ENCODE_SET
+        .add(ascii::Char::ExclamationMark)
+        .merge(AsciiSet::from_range_inclusive(
+            ascii::Char::Apostrophe,
+            ascii::Char::RightParenthesis,
+        ))
+        .add(ascii::Char::Tilde);
+
 /// <https://url.spec.whatwg.org/#string-percent-encode-after-encoding>
-pub fn percent_encode<W: ascii::Write, F: Fn(u8) -> bool>(
-    input: &[u8],
-    in_encode_set: F,
-    writer: &mut W,
-) {
+///
+/// Bytes that are part of the `encode_set` or not ASCII are percent-encoded.
+pub fn percent_encode<W: ascii::Write>(input: &[u8], encode_set: AsciiSet, writer: &mut W) {
     for &byte in input {
         if let Some(c) = ascii::Char::from_u8(byte)
-            && !in_encode_set(byte)
+            && !encode_set.contains(c)
         {
             writer.write_char(c)
         } else {
@@ -139,7 +153,13 @@ pub fn percent_decode(encoded: &ascii::Str) -> Vec<u8> {
 mod tests {
     use sl_std::ascii;
 
-    use super::{percent_decode, percent_encode_byte};
+    use super::*;
+
+    fn encode(input: &str, encode_set: AsciiSet) -> ascii::String {
+        let mut buffer = ascii::String::default();
+        percent_encode(input.as_bytes(), encode_set, &mut buffer);
+        buffer
+    }
 
     #[test]
     fn test_percent_encode_byte() {
@@ -163,4 +183,43 @@ mod tests {
         let decoded = percent_decode(encoded);
         assert_eq!(decoded, b"%%s%1G");
     }
+
+    #[test]
+    fn encode_sets() {
+        assert_eq!(encode("a b", QUERY_PERCENT_ENCODE_SET).as_str(), "a%20b");
+        assert_eq!(encode("a?b", FRAGMENT_PERCENT_ENCODE_SET).as_str(), "a?b");
+        assert_eq!(encode("a?b", PATH_PERCENT_ENCODE_SET).as_str(), "a%3Fb");
+        assert_eq!(encode("a#b", QUERY_PERCENT_ENCODE_SET).as_str(), "a%23b");
+        assert_eq!(encode("a#b", FRAGMENT_PERCENT_ENCODE_SET).as_str(), "a#b");
+        assert_eq!(encode("a`b", FRAGMENT_PERCENT_ENCODE_SET).as_str(), "a%60b");
+        assert_eq!(encode("'", QUERY_PERCENT_ENCODE_SET).as_str(), "'");
+        assert_eq!(
+            encode("'", SPECIAL_QUERY_PERCENT_ENCODE_SET).as_str(),
+            "%27"
+        );
+        assert_eq!(encode("a:b@c", PATH_PERCENT_ENCODE_SET).as_str(), "a:b@c");
+        assert_eq!(
+            encode("a:b@c", USERINFO_PERCENT_ENCODE_SET).as_str(),
+            "a%3Ab%40c"
+        );
+        assert_eq!(
+            encode("a+b&c", USERINFO_PERCENT_ENCODE_SET).as_str(),
+            "a+b&c"
+        );
+        assert_eq!(
+            encode("a+b&c", COMPONENT_PERCENT_ENCODE_SET).as_str(),
+            "a%2Bb%26c"
+        );
+        assert_eq!(
+            encode("(~)", APPLICATION_X_WWW_FORM_URLENCODED_PERCENT_ENCODE_SET).as_str(),
+            "%28%7E%29"
+        );
+
+        // Non-ASCII bytes are always encoded
+        assert_eq!(
+            encode("\u{7f}ü", C0_CONTROL_PERCENT_ENCODE_SET).as_str(),
+            "%7F%C3%BC"
+        );
+        assert_eq!(encode("ü", AsciiSet::EMPTY).as_str(), "%C3%BC");
+    }
 }
@@ -1,6 +1,7 @@
 //! Implements <https://url.spec.whatwg.org>
 
 use std::{
+    borrow::Cow,
     fmt::Display,
     io,
     path::{self, Path},
@@ -10,9 +11,10 @@ use std::{
 use sl_std::{ascii, chars::ReversibleCharIterator};
 
 use crate::{
+    form_urlencoded,
     host::Host,
     parser::{self, Parser},
-    percent_encode::percent_decode,
+    percent_encode::{percent_decode, percent_encode, PATH_PERCENT_ENCODE_SET},
     util::{self, is_normalized_windows_drive_letter},
     PathSegments,
 };
@@ -189,6 +191,108 @@ impl URL {
         Some(&self.serialization[fragment_start..])
     }
 
+    /// Iterate over the percent-decoded `(key, value)` pairs in the query
+    ///
+    /// The query is parsed as `application/x-www-form-urlencoded`, so `+` is
+    /// decoded as a space. Keys without a value (like `?flag`) have an empty value.
+    ///
+    /// # Example
+    /// ```
+    /// # use url::URL;
+    /// let url: URL = "https://example.com?q=hello+world&flag".parse().unwrap();
+    /// let pairs: Vec<_> = url.query_pairs().collect();
+    ///
+    /// assert_eq!(pairs, [("q".into(), "hello world".into()), ("flag".into(), "".into())]);
+    /// ```
+    pub fn query_pairs(&self) -> impl Iterator<Item = (Cow<'_, str>, Cow<'_, str>)> {
+        form_urlencoded::parse(self.query().unwrap_or_default())
+    }
+
+    /// Set the value of a query parameter
+    ///
+    /// The first pair with the given key has its value replaced and all
+    /// other pairs with the same key are removed. If no such pair exists,
+    /// it is appended to the query instead.
+    ///
+    /// Note that this re-serializes the whole query, so `?flag` becomes `?flag=`.
+    pub fn set_query_param(&mut self, key: &str, value: &str) {
+        let mut pairs = self.owned_query_pairs();
+
+        if let Some(index) = pairs.iter().position(|(k, _)| k == key) {
+            pairs[index].1 = value.to_string();
+
+            let mut position = 0;
+            pairs.retain(|(k, _)| {
+                let keep = position <= index || k != key;
+                position += 1;
+                keep
+            });
+        } else {
+            pairs.push((key.to_string(), value.to_string()));
+        }
+
+        self.set_query_pairs(&pairs);
+    }
+
+    /// Append a new pair to the query, regardless of whether the key already exists
+    ///
+    /// Note that this re-serializes the whole query, so `?flag` becomes `?flag=`.
+    pub fn append_query_param(&mut self, key: &str, value: &str) {
+        let mut pairs = self.owned_query_pairs();
+        pairs.push((key.to_string(), value.to_string()));
+        self.set_query_pairs(&pairs);
+    }
+
+    #[must_use]
+    fn owned_query_pairs(&self) -> Vec<(String, String)> {
+        self.query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect()
+    }
+
+    /// Replace the query with the given pairs, serialized as `application/x-www-form-urlencoded`
+    ///
+    /// If `pairs` is empty, the query is removed.
+    pub fn set_query_pairs(&mut self, pairs: &[(String, String)]) {
+        let serialized_query =
+            form_urlencoded::serialize(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+
+        if serialized_query.is_empty() {
+            self.set_query(None);
+        } else {
+            self.set_query(Some(&serialized_query));
+        }
+    }
+
+    /// Replace the query of the url, keeping the fragment intact
+    ///
+    /// The query is expected to be percent-encoded already.
+    fn set_query(&mut self, query: Option<&ascii::Str>) {
+        let fragment = self.fragment().map(ToOwned::to_owned);
+
+        let path_end = self
+            .offsets
+            .query_start
+            .or(self.offsets.fragment_start)
+            .map(|start| start - 1)
+            .unwrap_or(self.serialization.len());
+        self.serialization.truncate(path_end);
+        self.offsets.query_start = None;
+        self.offsets.fragment_start = None;
+
+        if let Some(query) = query {
+            self.serialization.push(ascii::Char::QuestionMark);
+            self.offsets.query_start = Some(self.serialization.len());
+            self.serialization.push_str(query);
+        }
+
+        if let Some(fragment) = fragment {
+            self.serialization.push(ascii::Char::NumberSign);
+            self.offsets.fragment_start = Some(self.serialization.len());
+            self.serialization.push_str(&fragment);
+        }
+    }
+
     pub fn from_user_input(input: &str) -> Result<Self, Error> {
         let base_url = match Self::cwd() {
             Ok(url) => url,
@@ -202,62 +306,93 @@ impl URL {
             .or_else(|_| format!("http://{input}").parse())
     }
 
-    #[cfg(unix)]
-    pub fn as_file_path(&self) -> Result<path::PathBuf, InvalidFilePath> {
-        use std::ffi::OsStr;
-        use std::os::unix::ffi::OsStrExt;
-
-        let mut bytes = vec![];
-
-        for segment in self.path().split(ascii::Char::Solidus) {
-            bytes.push(b'/');
-            bytes.extend_from_slice(&percent_decode(segment));
+    /// Convert a `file:` url to a path on the local filesystem
+    ///
+    /// Returns [None] if the url does not use the `file` scheme, refers to a
+    /// remote host or cannot be represented as a path on the current platform.
+    ///
+    /// # Example
+    /// ```
+    /// # use url::URL;
+    /// # #[cfg(unix)]
+    /// # {
+    /// let url: URL = "file://localhost/home/user/My%20Documents/".parse().unwrap();
+    ///
+    /// assert_eq!(
+    ///     url.to_file_path(),
+    ///     Some(std::path::PathBuf::from("/home/user/My Documents/"))
+    /// );
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn to_file_path(&self) -> Option<path::PathBuf> {
+        if self.scheme() != "file" {
+            return None;
         }
 
-        let path = path::PathBuf::from(OsStr::from_bytes(&bytes));
-        debug_assert!(path.is_absolute());
+        // "localhost" is equivalent to the empty host, but the parser already takes care of that
+        if !matches!(self.host, None | Some(Host::EmptyHost)) {
+            return None;
+        }
 
-        Ok(path)
-    }
+        let mut segments = vec![];
+        for segment in self.path_segments() {
+            let decoded = percent_decode(segment);
 
-    #[cfg(windows)]
-    pub fn as_file_path(&self) -> Result<path::PathBuf, InvalidFilePath> {
-        let mut segments = self.path().split(ascii::Char::Solidus);
-
-        // Make sure that the first segment is a valid start of a absolute
-        // windows path
-        let first_segment = segments.next().ok_or(InvalidFilePath)?;
-        let mut result: String = match first_segment.len() {
-            2 => {
-                // Drive letter
-                if first_segment[0].to_char().is_ascii_alphabetic()
-                    && first_segment[1] == ascii::Char::Colon
-                {
-                    first_segment.as_str().to_owned()
-                } else {
-                    return Err(InvalidFilePath);
-                }
-            },
-            _ => return Err(InvalidFilePath),
-        };
+            // Encoded separators would change the meaning of the path
+            if decoded.iter().any(|&b| b == b'/' || b == b'\\' || b == 0) {
+                return None;
+            }
 
-        for segment in segments {
-            result.push(path::MAIN_SEPARATOR);
-            result.push_str(&String::from_utf8_lossy(&percent_decode(segment)));
+            segments.push(decoded);
         }
 
-        let path = path::PathBuf::from(result);
+        let path = file_path_from_segments(segments)?;
         debug_assert!(
             path.is_absolute(),
             "to_file_path() failed to produce an absolute Path"
         );
-        Ok(path)
+
+        Some(path)
+    }
+
+    /// Create a `file:` url from an absolute path on the local filesystem
+    ///
+    /// Returns [None] if the path is relative.
+    ///
+    /// # Example
+    /// ```
+    /// # use url::URL;
+    /// # #[cfg(unix)]
+    /// # {
+    /// let url = URL::from_file_path("/tmp/100% real.html").unwrap();
+    ///
+    /// assert_eq!(url.to_string(), "file:///tmp/100%25%20real.html");
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn from_file_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let serialization = serialize_file_path(path.as_ref())?;
+        serialization.as_str().parse().ok()
+    }
+
+    /// Like [from_file_path](URL::from_file_path), except that the resulting url always
+    /// has a trailing slash, so relative references are resolved *inside* the directory.
+    #[must_use]
+    pub fn from_directory_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let mut serialization = serialize_file_path(path.as_ref())?;
+        if serialization.chars().last() != Some(&ascii::Char::Solidus) {
+            serialization.push(ascii::Char::Solidus);
+        }
+
+        serialization.as_str().parse().ok()
     }
 
     pub fn cwd() -> Result<Self, io::Error> {
         let cwd = std::env::current_dir()?;
 
-        Self::try_from(cwd.as_path()).map_err(|_| io::Error::other("path contains non-ascii data"))
+        Self::from_directory_path(&cwd)
+            .ok_or_else(|| io::Error::other("cannot represent cwd as a url"))
     }
 
     /// [Specification](https://url.spec.whatwg.org/#concept-basic-url-parser)
@@ -303,6 +438,20 @@ impl URL {
         Ok(state_machine.url)
     }
 
+    /// Resolve a (possibly relative) url reference, using `self` as the base url
+    ///
+    /// # Example
+    /// ```
+    /// # use url::URL;
+    /// let base: URL = "https://example.com/a/b/c".parse().unwrap();
+    /// let url = base.join("../img/a.png").unwrap();
+    ///
+    /// assert_eq!(url.to_string(), "https://example.com/a/img/a.png");
+    /// ```
+    pub fn join(&self, relative: &str) -> Result<Self, Error> {
+        Self::parse_with_base(relative, Some(self), None)
+    }
+
     /// [Specification](https://url.spec.whatwg.org/#include-credentials)
     ///
     /// A [URL] includes credentials if its  [username](URL::username) or [password](URL::password) is not the empty string.
@@ -325,6 +474,10 @@ impl URL {
     /// This implementation also gets rid of anything after the path (query, fragment),
     /// so it should only be called during parsing
     pub(crate) fn shorten_path(&mut self) {
+        if self.path().is_empty() {
+            return;
+        }
+
         if self.scheme() == "file" {
             let mut segments = self.path_segments();
 
@@ -337,10 +490,11 @@ impl URL {
             }
         }
 
-        let last_slash = self
-            .serialization
+        let path_start = self.offsets.path_start;
+        let last_slash = self.serialization[path_start..]
             .rfind(ascii::Char::Solidus)
-            .unwrap_or(self.offsets.path_start);
+            .map(|index| path_start + index)
+            .unwrap_or(path_start);
 
         // FIXME: do we need to adjust query/fragment offsets here?
         self.serialization.truncate(last_slash)
@@ -383,34 +537,97 @@ impl TryFrom<&Path> for URL {
     type Error = InvalidFilePath;
 
     fn try_from(value: &Path) -> Result<Self, Self::Error> {
-        let absolute_path = value.canonicalize().expect("Failed to canonicalize path");
-
-        let mut serialization = ascii!("file:///").to_owned();
-        let mut offsets = UrlOffsets::default();
-        offsets.scheme_end = "file".len();
-        offsets.path_start = "file:///".len();
-
-        for part in absolute_path.iter().skip(1) {
-            let bytes = part.as_encoded_bytes();
-            let Some(ascii_str) = ascii::Str::from_bytes(bytes) else {
-                log::error!(
-                    "Failed to create file URL for path containing non-ascii data: {}",
-                    absolute_path.display()
-                );
-                return Err(InvalidFilePath);
-            };
-
-            serialization.push_str(ascii_str);
-            serialization.push(ascii::Char::Solidus);
+        let absolute_path = value.canonicalize().map_err(|_| InvalidFilePath)?;
+        Self::from_file_path(absolute_path).ok_or(InvalidFilePath)
+    }
+}
+
+/// Percent-encodes an absolute path, producing the serialization of the equivalent `file:` url
+fn serialize_file_path(path: &Path) -> Option<ascii::String> {
+    if !path.is_absolute() {
+        return None;
+    }
+
+    // Characters that are allowed in the path of a url but would be
+    // misinterpreted when parsing it again
+    let encode_set = PATH_PERCENT_ENCODE_SET
+        .add(ascii::Char::PercentSign)
+        .add(ascii::Char::ReverseSolidus);
+
+    let mut serialization = ascii!("file://").to_owned();
+    for component in path.components() {
+        match component {
+            path::Component::Prefix(prefix) => match prefix.kind() {
+                path::Prefix::Disk(letter) | path::Prefix::VerbatimDisk(letter) => {
+                    serialization.push(ascii::Char::Solidus);
+                    serialization.push(ascii::Char::from_u8(letter)?);
+                    serialization.push(ascii::Char::Colon);
+                },
+                _ => {
+                    // UNC paths and the like can not be represented
+                    return None;
+                },
+            },
+            path::Component::RootDir | path::Component::CurDir => {},
+            path::Component::ParentDir => serialization.push_str(ascii!("/..")),
+            path::Component::Normal(part) => {
+                serialization.push(ascii::Char::Solidus);
+                percent_encode(part.as_encoded_bytes(), encode_set, &mut serialization);
+            },
         }
+    }
+
+    if serialization.len() == "file://".len() {
+        serialization.push(ascii::Char::Solidus);
+    }
+
+    Some(serialization)
+}
+
+#[cfg(unix)]
+fn file_path_from_segments(segments: Vec<Vec<u8>>) -> Option<path::PathBuf> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut bytes = vec![];
+    for segment in segments {
+        bytes.push(b'/');
+        bytes.extend_from_slice(&segment);
+    }
 
-        Ok(Self {
-            host: Some(Host::OpaqueHost(ascii::String::default())),
-            port: None,
-            serialization,
-            offsets,
-        })
+    if bytes.is_empty() {
+        bytes.push(b'/');
     }
+
+    Some(path::PathBuf::from(OsStr::from_bytes(&bytes)))
+}
+
+#[cfg(windows)]
+fn file_path_from_segments(segments: Vec<Vec<u8>>) -> Option<path::PathBuf> {
+    let mut segments = segments.into_iter();
+
+    // Make sure that the first segment is a valid start of a absolute
+    // windows path
+    let first_segment = String::from_utf8(segments.next()?).ok()?;
+    if !util::is_windows_drive_letter(&first_segment) || first_segment.len() != 2 {
+        return None;
+    }
+
+    // Drive letters may be written as "C|" in urls
+    let mut result = first_segment[..1].to_owned();
+    result.push(':');
+
+    for segment in segments {
+        result.push(path::MAIN_SEPARATOR);
+        result.push_str(&String::from_utf8(segment).ok()?);
+    }
+
+    // "C:" on its own is a relative path
+    if result.len() == 2 {
+        result.push(path::MAIN_SEPARATOR);
+    }
+
+    Some(path::PathBuf::from(result))
 }
 
 impl From<parser::Error> for Error {
@@ -492,6 +709,62 @@ mod tests {
         assert_eq!(url.fragment(), None);
     }
 
+    #[test]
+    fn query_pairs() {
+        let url: URL = "https://example.com?a=1&b=x%20y+z&a=2&flag#frag"
+            .parse()
+            .unwrap();
+
+        let pairs: Vec<_> = url.query_pairs().collect();
+        assert_eq!(
+            pairs,
+            [
+                ("a".into(), "1".into()),
+                ("b".into(), "x y z".into()),
+                ("a".into(), "2".into()),
+                ("flag".into(), "".into()),
+            ]
+        );
+
+        let url: URL = "https://example.com".parse().unwrap();
+        assert_eq!(url.query_pairs().count(), 0);
+    }
+
+    #[test]
+    fn set_query_param() {
+        let mut url: URL = "https://example.com?a=1&b=2&a=3#frag".parse().unwrap();
+
+        url.set_query_param("a", "hello world");
+        assert_eq!(
+            url.serialization,
+            "https://example.com/?a=hello+world&b=2#frag"
+        );
+        assert_eq!(url.fragment().map(ascii::Str::as_str), Some("frag"));
+
+        url.set_query_param("c", "&");
+        assert_eq!(
+            url.serialization,
+            "https://example.com/?a=hello+world&b=2&c=%26#frag"
+        );
+        assert_eq!(
+            url.query().map(ascii::Str::as_str),
+            Some("a=hello+world&b=2&c=%26")
+        );
+    }
+
+    #[test]
+    fn append_query_param() {
+        let mut url: URL = "https://example.com?flag".parse().unwrap();
+
+        url.append_query_param("flag", "1");
+        assert_eq!(url.serialization, "https://example.com/?flag=&flag=1");
+
+        let mut url: URL = "https://example.com".parse().unwrap();
+        url.append_query_param("a", "b");
+        assert_eq!(url.serialization, "https://example.com/?a=b");
+        assert_eq!(url.path(), "/");
+    }
+
     #[test]
     fn opaque_path() {
         let url: URL = "data:text/html,Hello World".parse().unwrap();
@@ -508,6 +781,91 @@ mod tests {
         assert!(url.is_err());
     }
 
+    #[test]
+    fn path_with_dot_segments() {
+        let url: URL = "https://example.com/a/./b/../c/".parse().unwrap();
+        assert_eq!(url.path(), "/a/c/");
+
+        let url: URL = "https://example.com/a/b/..".parse().unwrap();
+        assert_eq!(url.path(), "/a/");
+
+        let url: URL = "https://example.com/../a".parse().unwrap();
+        assert_eq!(url.path(), "/a");
+    }
+
+    #[test]
+    fn join() {
+        // Examples from https://www.rfc-editor.org/rfc/rfc3986#section-5.4
+        let base: URL = "http://a/b/c/d;p?q".parse().unwrap();
+
+        let cases = [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g/"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"),
+            ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("../../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            (".g", "http://a/b/c/.g"),
+            ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+        ];
+
+        for (relative, expected) in cases {
+            let url = base.join(relative).unwrap();
+            assert_eq!(
+                url.serialization, expected,
+                "failed to resolve {relative:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn join_keeps_components_intact() {
+        let base: URL = "https://example.com:8080/a/b?x=y#frag".parse().unwrap();
+
+        let url = base.join("c?q#f").unwrap();
+        assert_eq!(url.port(), Some(8080));
+        assert_eq!(url.path(), "/a/c");
+        assert_eq!(url.query().map(ascii::Str::as_str), Some("q"));
+        assert_eq!(url.fragment().map(ascii::Str::as_str), Some("f"));
+
+        let url = base.join("#other").unwrap();
+        assert_eq!(url.path(), "/a/b");
+        assert_eq!(url.query().map(ascii::Str::as_str), Some("x=y"));
+        assert_eq!(url.fragment().map(ascii::Str::as_str), Some("other"));
+
+        let url = base.join("/abs").unwrap();
+        assert_eq!(url.serialization, "https://example.com:8080/abs");
+        assert_eq!(url.path(), "/abs");
+        assert_eq!(url.query(), None);
+    }
+
     #[test]
     fn filename_with_base_url() {
         let base: URL = "https://soju.im/".parse().unwrap();
@@ -520,4 +878,65 @@ mod tests {
         assert_eq!(url.path(), "/style.css");
         assert_eq!(url.serialization, "https://soju.im/style.css");
     }
+
+    #[test]
+    fn localhost_is_empty_file_host() {
+        let url: URL = "file://localhost/etc/hosts".parse().unwrap();
+        assert_eq!(url.serialization, "file:///etc/hosts");
+        assert_eq!(url.host(), Some(&Host::EmptyHost));
+
+        let url: URL = "file:///etc/hosts".parse().unwrap();
+        assert_eq!(url.host(), Some(&Host::EmptyHost));
+        assert_eq!(url.path(), "/etc/hosts");
+
+        let base: URL = "file:///etc/hosts".parse().unwrap();
+        let url = base.join("passwd").unwrap();
+        assert_eq!(url.serialization, "file:///etc/passwd");
+    }
+
+    #[test]
+    fn to_file_path_rejects_non_file_urls() {
+        let url: URL = "https://example.com/etc/hosts".parse().unwrap();
+        assert_eq!(url.to_file_path(), None);
+
+        let url: URL = "file://example.com/etc/hosts".parse().unwrap();
+        assert_eq!(url.to_file_path(), None);
+
+        let url: URL = "file:///etc/a%2Fb".parse().unwrap();
+        assert_eq!(url.to_file_path(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_path_roundtrip() {
+        let url = URL::from_file_path("/tmp/my files/100%/ä#1?.txt").unwrap();
+        assert_eq!(
+            url.serialization,
+            "file:///tmp/my%20files/100%25/%C3%A4%231%3F.txt"
+        );
+        assert_eq!(
+            url.to_file_path(),
+            Some(path::PathBuf::from("/tmp/my files/100%/ä#1?.txt"))
+        );
+
+        let url = URL::from_directory_path("/tmp").unwrap();
+        assert_eq!(url.serialization, "file:///tmp/");
+        assert_eq!(
+            url.join("a.html").unwrap().serialization,
+            "file:///tmp/a.html"
+        );
+
+        assert!(URL::from_file_path("relative/path").is_none());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn file_path_roundtrip() {
+        let url = URL::from_file_path(r"C:\Users\me\a b.txt").unwrap();
+        assert_eq!(url.serialization, "file:///C:/Users/me/a%20b.txt");
+        assert_eq!(
+            url.to_file_path(),
+            Some(path::PathBuf::from(r"C:\Users\me\a b.txt"))
+        );
+    }
 }
@@ -2,6 +2,49 @@ use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
 
+/// What to do if a field is not present in the input
+enum MissingField {
+    /// Fail with a "missing field" error
+    Error,
+
+    /// Use `Default::default()`, from `#[serialize(default)]`
+    Default,
+
+    /// Call the given function, from `#[serialize(default = "path::to::function")]`
+    Function(syn::ExprPath),
+}
+
+impl MissingField {
+    fn for_field(field: &syn::Field) -> Self {
+        let mut missing_field = Self::Error;
+
+        for attribute in field
+            .attrs
+            .iter()
+            .filter(|attribute| attribute.path().is_ident("serialize"))
+        {
+            attribute
+                .parse_nested_meta(|meta| {
+                    if !meta.path.is_ident("default") {
+                        return Err(meta.error("unknown serialize attribute"));
+                    }
+
+                    if meta.input.peek(syn::Token![=]) {
+                        let function: syn::LitStr = meta.value()?.parse()?;
+                        missing_field = Self::Function(function.parse()?);
+                    } else {
+                        missing_field = Self::Default;
+                    }
+
+                    Ok(())
+                })
+                .expect("invalid serialize attribute");
+        }
+
+        missing_field
+    }
+}
+
 pub(crate) fn deserialize_struct(input: syn::ItemStruct) -> TokenStream {
     let struct_ident = &input.ident;
 
@@ -9,52 +52,12 @@ pub(crate) fn deserialize_struct(input: syn::ItemStruct) -> TokenStream {
         panic!("Struct does not have named fields");
     };
 
-    let idents: Vec<&Ident> = fields
-        .named
-        .iter()
-        .map(|field| field.ident.as_ref().expect("struct field without ident"))
-        .collect();
-
-    let expecting = format!("Any of: {:?}", idents);
+    let instance = deserialize_named_fields(quote!(#struct_ident), fields);
 
     quote!(
         #[automatically_derived]
         impl ::serialize::Deserialize for #struct_ident {
             fn deserialize<T: ::serialize::Deserializer>(deserializer: T) -> Result<Self, T::Error> {
-                use ::serialize::deserialization::Error;
-
-                #[allow(non_camel_case_types)]
-                enum Field {
-                    #(#idents,)*
-                }
-
-                impl ::serialize::Deserialize for Field {
-                    fn deserialize<T: ::serialize::Deserializer>(deserializer: T) -> Result<Self, T::Error> {
-                        struct FieldVisitor;
-
-                        impl ::serialize::Visitor for FieldVisitor {
-                            type Value = Field;
-
-                            const EXPECTS: &'static str = #expecting;
-
-                            fn visit_string<E>(&self, value: ::std::string::String) -> Result<Self::Value, E>
-                            where
-                                E: ::serialize::deserialization::Error,
-                            {
-                                let field = match value.as_str() {
-                                    #(
-                                        stringify!(#idents) => Field::#idents,
-                                    )*
-                                    _ => return Err(E::unknown_field(value)),
-                                };
-                                Ok(field)
-                            }
-                        }
-
-                        deserializer.deserialize_string(FieldVisitor)
-                    }
-                }
-
                 struct StructVisitor;
 
                 impl ::serialize::Visitor for StructVisitor {
@@ -64,38 +67,96 @@ pub(crate) fn deserialize_struct(input: syn::ItemStruct) -> TokenStream {
 
                     fn visit_map<M>(&self, mut value: M) -> Result<Self::Value, M::Error>
                     where M: ::serialize::deserialization::MapAccess {
-                        #(
-                            let mut #idents = None;
-                        )*
+                        Ok(#instance)
+                    }
+                }
 
+                deserializer.deserialize_struct(StructVisitor)
+            }
+        }
+    )
+    .into()
+}
 
-                        loop {
-                            let Some(key) = value.next_key()? else {
-                                break;
-                            };
+/// Generate an expression that reads the remaining entries of the map `value`
+/// and constructs `constructor { fields }` from them
+fn deserialize_named_fields(
+    constructor: proc_macro2::TokenStream,
+    fields: &syn::FieldsNamed,
+) -> proc_macro2::TokenStream {
+    let idents: Vec<&Ident> = fields
+        .named
+        .iter()
+        .map(|field| field.ident.as_ref().expect("struct field without ident"))
+        .collect();
 
-                            match key {
-                                #(
-                                    Field::#idents => #idents = Some(value.next_value()?),
-                                )*
-                            }
-                        }
+    let field_values = fields.named.iter().zip(&idents).map(|(field, ident)| {
+        match MissingField::for_field(field) {
+            MissingField::Error => quote!(
+                #ident.ok_or(::serialize::deserialization::Error::missing_field(stringify!(#ident)))?
+            ),
+            MissingField::Default => quote!(#ident.unwrap_or_default()),
+            MissingField::Function(function) => quote!(#ident.unwrap_or_else(#function)),
+        }
+    }).collect::<Vec<_>>();
+
+    let expecting = format!("Any of: {:?}", idents);
 
-                        let instance = Self::Value {
+    quote!({
+        #[allow(non_camel_case_types)]
+        enum Field {
+            #(#idents,)*
+        }
+
+        impl ::serialize::Deserialize for Field {
+            fn deserialize<T: ::serialize::Deserializer>(deserializer: T) -> Result<Self, T::Error> {
+                struct FieldVisitor;
+
+                impl ::serialize::Visitor for FieldVisitor {
+                    type Value = Field;
+
+                    const EXPECTS: &'static str = #expecting;
+
+                    fn visit_string<E>(&self, value: ::std::string::String) -> Result<Self::Value, E>
+                    where
+                        E: ::serialize::deserialization::Error,
+                    {
+                        let field = match value.as_str() {
                             #(
-                                #idents: #idents.ok_or(Error::missing_field(stringify!(#idents)))?,
+                                stringify!(#idents) => Field::#idents,
                             )*
+                            _ => return Err(E::unknown_field(value)),
                         };
-
-                        Ok(instance)
+                        Ok(field)
                     }
                 }
 
-                deserializer.deserialize_struct(StructVisitor)
+                deserializer.deserialize_string(FieldVisitor)
             }
         }
-    )
-    .into()
+
+        #(
+            let mut #idents = None;
+        )*
+
+        loop {
+            let Some(key) = value.next_key::<Field>()? else {
+                break;
+            };
+
+            match key {
+                #(
+                    Field::#idents => #idents = Some(value.next_value()?),
+                )*
+            }
+        }
+
+        #constructor {
+            #(
+                #idents: #field_values,
+            )*
+        }
+    })
 }
 
 pub(crate) fn deserialize_enum(input: syn::ItemEnum) -> TokenStream {
@@ -164,44 +225,15 @@ pub(crate) fn deserialize_enum(input: syn::ItemEnum) -> TokenStream {
         }
     }
 
+    let variant_enum = variant_enum(&variant_idents);
+
     quote!(
         #[automatically_derived]
         impl ::serialize::Deserialize for #enum_ident {
             fn deserialize<T: ::serialize::Deserializer>(deserializer: T) -> Result<Self, T::Error> {
                 use ::serialize::deserialization::{SequentialAccess, EnumVariantAccess, Error};
 
-                enum Variant {
-                    #(
-                        #variant_idents,
-                    )*
-                }
-
-                impl ::serialize::Deserialize for Variant {
-                    fn deserialize<T: ::serialize::Deserializer>(deserializer: T) -> Result<Self, T::Error> {
-                        struct VariantVisitor;
-
-                        impl ::serialize::Visitor for VariantVisitor {
-                            type Value = Variant;
-
-                            const EXPECTS: &'static str = "One of the enum variants";
-
-                            fn visit_string<E>(&self, variant_name: String) -> Result<Self::Value, E>
-                            where E: ::serialize::deserialization::Error {
-                                let variant = match variant_name.as_str() {
-                                    #(
-                                        stringify!(#variant_idents) => Variant::#variant_idents,
-                                    )*
-                                    _ => return Err(Error::unknown_variant(variant_name))
-                                };
-
-                                Ok(variant)
-                            }
-                        }
-
-                        deserializer.deserialize_string(VariantVisitor)
-                    }
-                }
-
+                #variant_enum
 
                 struct EnumVisitor;
 
@@ -229,3 +261,113 @@ pub(crate) fn deserialize_enum(input: syn::ItemEnum) -> TokenStream {
     )
     .into()
 }
+
+/// `#[serialize(tag = "...")]` enums are represented as a map that contains
+/// the name of the variant under the tag key, followed by the fields of the variant
+///
+/// Since the tag determines how the other fields are deserialized, it has to be the first
+/// entry in the map.
+pub(crate) fn deserialize_tagged_enum(input: syn::ItemEnum, tag: syn::LitStr) -> TokenStream {
+    let enum_ident = &input.ident;
+
+    let mut variant_idents = vec![];
+    let mut variant_instances = vec![];
+
+    for variant in &input.variants {
+        let ident = &variant.ident;
+        variant_idents.push(ident.clone());
+
+        let fields = match &variant.fields {
+            syn::Fields::Named(named_fields) => named_fields.clone(),
+            syn::Fields::Unit => syn::parse_quote!({}),
+            syn::Fields::Unnamed(_) => {
+                panic!("Tagged enums can only contain unit and struct variants")
+            },
+        };
+        variant_instances.push(deserialize_named_fields(
+            quote!(#enum_ident::#ident),
+            &fields,
+        ));
+    }
+
+    let variant_enum = variant_enum(&variant_idents);
+    let expect_tag_first = format!("{:?} as the first field", tag.value());
+
+    quote!(
+        #[automatically_derived]
+        impl ::serialize::Deserialize for #enum_ident {
+            fn deserialize<T: ::serialize::Deserializer>(deserializer: T) -> Result<Self, T::Error> {
+                use ::serialize::deserialization::Error;
+
+                #variant_enum
+
+                struct TaggedEnumVisitor;
+
+                impl ::serialize::Visitor for TaggedEnumVisitor {
+                    type Value = #enum_ident;
+
+                    const EXPECTS: &'static str = "a map";
+
+                    fn visit_map<M>(&self, mut value: M) -> Result<Self::Value, M::Error>
+                    where M: ::serialize::deserialization::MapAccess {
+                        let Some(key) = value.next_key::<::std::string::String>()? else {
+                            return Err(M::Error::missing_field(#tag));
+                        };
+
+                        if key != #tag {
+                            return Err(M::Error::expected(#expect_tag_first));
+                        }
+
+                        let instance = match value.next_value::<Variant>()? {
+                            #(
+                                Variant::#variant_idents => #variant_instances,
+                            )*
+                        };
+
+                        Ok(instance)
+                    }
+                }
+
+                deserializer.deserialize_struct(TaggedEnumVisitor)
+            }
+        }
+    )
+    .into()
+}
+
+/// Generate a `Variant` enum that can be deserialized from the name of a variant
+fn variant_enum(variant_idents: &[Ident]) -> proc_macro2::TokenStream {
+    quote!(
+        enum Variant {
+            #(
+                #variant_idents,
+            )*
+        }
+
+        impl ::serialize::Deserialize for Variant {
+            fn deserialize<T: ::serialize::Deserializer>(deserializer: T) -> Result<Self, T::Error> {
+                struct VariantVisitor;
+
+                impl ::serialize::Visitor for VariantVisitor {
+                    type Value = Variant;
+
+                    const EXPECTS: &'static str = "One of the enum variants";
+
+                    fn visit_string<E>(&self, variant_name: String) -> Result<Self::Value, E>
+                    where E: ::serialize::deserialization::Error {
+                        let variant = match variant_name.as_str() {
+                            #(
+                                stringify!(#variant_idents) => Variant::#variant_idents,
+                            )*
+                            _ => return Err(E::unknown_variant(variant_name))
+                        };
+
+                        Ok(variant)
+                    }
+                }
+
+                deserializer.deserialize_string(VariantVisitor)
+            }
+        }
+    )
+}
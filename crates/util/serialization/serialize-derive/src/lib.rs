@@ -3,24 +3,53 @@ use proc_macro::TokenStream;
 mod deserialize;
 mod serialize;
 
-#[proc_macro_derive(Deserialize)]
+#[proc_macro_derive(Deserialize, attributes(serialize))]
 pub fn deserialize(input: TokenStream) -> TokenStream {
     let item: syn::Item = syn::parse(input).expect("Could not parse input as item");
 
     match item {
         syn::Item::Struct(item_struct) => deserialize::deserialize_struct(item_struct),
-        syn::Item::Enum(item_enum) => deserialize::deserialize_enum(item_enum),
+        syn::Item::Enum(item_enum) => match tag_attribute(&item_enum.attrs) {
+            Some(tag) => deserialize::deserialize_tagged_enum(item_enum, tag),
+            None => deserialize::deserialize_enum(item_enum),
+        },
         _ => panic!("Cannot impl Deserialize for this kind of item"),
     }
 }
 
-#[proc_macro_derive(Serialize)]
+#[proc_macro_derive(Serialize, attributes(serialize))]
 pub fn serialize(input: TokenStream) -> TokenStream {
     let item: syn::Item = syn::parse(input).expect("Could not parse input as item");
 
     match item {
         syn::Item::Struct(item_struct) => serialize::serialize_struct(item_struct),
-        syn::Item::Enum(item_enum) => serialize::serialize_enum(item_enum),
+        syn::Item::Enum(item_enum) => match tag_attribute(&item_enum.attrs) {
+            Some(tag) => serialize::serialize_tagged_enum(item_enum, tag),
+            None => serialize::serialize_enum(item_enum),
+        },
         _ => panic!("Cannot impl Serialize for this kind of item"),
     }
 }
+
+/// Find the key of an internally tagged enum, declared with `#[serialize(tag = "key")]`
+fn tag_attribute(attributes: &[syn::Attribute]) -> Option<syn::LitStr> {
+    let mut tag = None;
+
+    for attribute in attributes
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("serialize"))
+    {
+        attribute
+            .parse_nested_meta(|meta| {
+                if !meta.path.is_ident("tag") {
+                    return Err(meta.error("unknown serialize attribute"));
+                }
+
+                tag = Some(meta.value()?.parse()?);
+                Ok(())
+            })
+            .expect("invalid serialize attribute");
+    }
+
+    tag
+}
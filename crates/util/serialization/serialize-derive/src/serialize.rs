@@ -103,3 +103,55 @@ pub(crate) fn serialize_enum(input: syn::ItemEnum) -> TokenStream {
     )
     .into()
 }
+
+/// See [deserialize_tagged_enum](crate::deserialize::deserialize_tagged_enum) for the representation
+pub(crate) fn serialize_tagged_enum(input: syn::ItemEnum, tag: syn::LitStr) -> TokenStream {
+    let enum_ident = &input.ident;
+
+    let mut serialize_arms = vec![];
+    for variant in input.variants {
+        let ident = variant.ident;
+
+        let field_names: Vec<Ident> = match variant.fields {
+            syn::Fields::Unit => vec![],
+            syn::Fields::Named(named_fields) => named_fields
+                .named
+                .into_iter()
+                .flat_map(|f| f.ident)
+                .collect(),
+            syn::Fields::Unnamed(_) => {
+                panic!("Tagged enums can only contain unit and struct variants")
+            },
+        };
+
+        let code = quote! {
+            Self::#ident{ #(#field_names,)* } => {
+                let mut struct_serializer = serializer.serialize_struct()?;
+                struct_serializer.serialize_field(#tag, &stringify!(#ident))?;
+                #(
+                    struct_serializer.serialize_field(stringify!(#field_names), #field_names)?;
+                )*
+                struct_serializer.finish()?;
+            }
+        };
+        serialize_arms.push(code);
+    }
+
+    quote!(
+        #[automatically_derived]
+        impl ::serialize::Serialize for #enum_ident {
+            fn serialize_to<T: ::serialize::Serializer>(&self, serializer: &mut T) -> Result<(), T::Error> {
+                use ::serialize::serialization::SerializeStruct;
+
+                match self {
+                    #(
+                        #serialize_arms,
+                    )*
+                }
+
+                Ok(())
+            }
+        }
+    )
+    .into()
+}
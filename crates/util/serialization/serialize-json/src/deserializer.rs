@@ -1,3 +1,5 @@
+use std::io;
+
 use sl_std::chars::ReversibleCharIterator;
 
 use serialize::{
@@ -12,6 +14,10 @@ pub enum JsonError {
     UnknownVariant(String),
     MissingField(&'static str),
     UnexpectedToken,
+
+    /// The input could not be read
+    Io(io::ErrorKind),
+    InvalidUtf8,
 }
 
 impl Error for JsonError {
@@ -32,10 +38,15 @@ impl Error for JsonError {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Token {
     String(String),
-    Numeric(u32),
+
+    /// A number without fraction or exponent
+    Integer(i64),
+
+    /// A number with a fraction or exponent, or an integer that is too large for an `i64`
+    Float(f64),
     True,
     False,
     Null,
@@ -174,22 +185,7 @@ impl<'a> JsonDeserializer<'a> {
                     // whitespace is skipped
                     self.chars.next();
                 },
-                '0'..='9' => {
-                    // Parse a numeric value
-                    let mut num = 0;
-
-                    while let Some(c) = self.chars.current() {
-                        if let Some(digit) = c.to_digit(10) {
-                            num *= 10;
-                            num += digit;
-                            self.chars.next();
-                        } else {
-                            break;
-                        }
-                    }
-
-                    return Some(Token::Numeric(num));
-                },
+                '-' | '0'..='9' => return self.consume_number(),
                 't' | 'f' | 'n' => {
                     // Parse an identifier (true, false or null)
                     let remaining = self.chars.remaining();
@@ -214,6 +210,61 @@ impl<'a> JsonDeserializer<'a> {
         }
     }
 
+    /// Consume a number like `-12.5e3`
+    #[must_use]
+    fn consume_number(&mut self) -> Option<Token> {
+        let mut number = String::new();
+        let mut is_integer = true;
+
+        if self.chars.current() == Some('-') {
+            number.push('-');
+            self.chars.next();
+        }
+
+        self.consume_digits(&mut number)?;
+
+        if self.chars.current() == Some('.') {
+            is_integer = false;
+            number.push('.');
+            self.chars.next();
+            self.consume_digits(&mut number)?;
+        }
+
+        if let Some(c @ ('e' | 'E')) = self.chars.current() {
+            is_integer = false;
+            number.push(c);
+            self.chars.next();
+
+            if let Some(sign @ ('+' | '-')) = self.chars.current() {
+                number.push(sign);
+                self.chars.next();
+            }
+
+            self.consume_digits(&mut number)?;
+        }
+
+        if is_integer {
+            if let Ok(integer) = number.parse() {
+                return Some(Token::Integer(integer));
+            }
+        }
+
+        number.parse().ok().map(Token::Float)
+    }
+
+    /// Consume at least one decimal digit
+    #[must_use]
+    fn consume_digits(&mut self, number: &mut String) -> Option<()> {
+        let length_before = number.len();
+
+        while let Some(c @ '0'..='9') = self.chars.current() {
+            number.push(c);
+            self.chars.next();
+        }
+
+        (number.len() != length_before).then_some(())
+    }
+
     #[must_use]
     fn peek_token(&mut self) -> Option<Token> {
         let old_position = self.chars.position();
@@ -234,7 +285,9 @@ impl<'a> Deserializer for &mut JsonDeserializer<'a> {
             Some(Token::BracketOpen) => self.deserialize_sequence(visitor),
             Some(Token::CurlyBraceOpen) => self.deserialize_map(visitor),
             Some(Token::String(_)) => self.deserialize_string(visitor),
-            Some(Token::Numeric(_)) => self.deserialize_usize(visitor),
+            Some(Token::Integer(integer)) if integer >= 0 => self.deserialize_usize(visitor),
+            Some(Token::Integer(_)) => self.deserialize_i64(visitor),
+            Some(Token::Float(_)) => self.deserialize_f64(visitor),
             Some(Token::True | Token::False) => self.deserialize_bool(visitor),
             Some(Token::Null) => self.deserialize_option(visitor),
             _ => return Err(Error::expected("any valid json value")),
@@ -291,11 +344,35 @@ impl<'a> Deserializer for &mut JsonDeserializer<'a> {
     where
         V: Visitor,
     {
-        let Some(Token::Numeric(num)) = self.next_token() else {
+        let Some(Token::Integer(integer)) = self.next_token() else {
+            return Err(JsonError::UnexpectedToken);
+        };
+
+        let integer =
+            usize::try_from(integer).map_err(|_| Error::expected("a positive integer"))?;
+        visitor.visit_usize(integer)
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor,
+    {
+        let Some(Token::Integer(integer)) = self.next_token() else {
             return Err(JsonError::UnexpectedToken);
         };
 
-        visitor.visit_usize(num as usize)
+        visitor.visit_i64(integer)
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor,
+    {
+        match self.next_token() {
+            Some(Token::Float(float)) => visitor.visit_f64(float),
+            Some(Token::Integer(integer)) => visitor.visit_f64(integer as f64),
+            _ => Err(JsonError::UnexpectedToken),
+        }
     }
 
     fn deserialize_enum<V: Visitor>(self, visitor: V) -> Result<V::Value, Self::Error> {
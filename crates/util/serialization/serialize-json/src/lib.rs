@@ -2,10 +2,12 @@
 
 mod deserializer;
 mod serializer;
+mod stream;
 mod value;
 
 pub use deserializer::{JsonDeserializer, JsonError};
 pub use serializer::{JsonSerializer, MapSerializer, SequenceSerializer};
+pub use stream::ArrayStream;
 pub use value::Value;
 
 #[cfg(test)]
@@ -31,10 +33,128 @@ mod tests {
         assert_eq!(serialize_deserialize(s.clone()), s);
     }
 
+    #[test]
+    fn serialize_escaped_string() {
+        let s = "\"quoted\"\n\\\u{1b}\0".to_string();
+        let serialized = JsonSerializer::serialize_to_string(s.clone()).unwrap();
+        assert_eq!(serialized, r#""\"quoted\"\n\\\u001b\u0000""#);
+        assert_eq!(serialize_deserialize(s.clone()), s);
+    }
+
+    #[test]
+    fn deserialize_missing_fields() {
+        fn default_port() -> usize {
+            80
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Config {
+            host: String,
+
+            #[serialize(default)]
+            secure: bool,
+
+            #[serialize(default = "default_port")]
+            port: usize,
+        }
+
+        let mut deserializer = JsonDeserializer::new(r#"{"host": "example.com"}"#);
+        let config = Config::deserialize(&mut deserializer).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                host: "example.com".to_string(),
+                secure: false,
+                port: 80,
+            }
+        );
+
+        // Fields without a default are still required
+        let mut deserializer = JsonDeserializer::new(r#"{"port": 8080}"#);
+        assert!(Config::deserialize(&mut deserializer).is_err());
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serialize(tag = "type")]
+    enum Connection {
+        Direct,
+        Proxy { address: String, port: usize },
+    }
+
+    #[test]
+    fn tagged_enum() {
+        let proxy = Connection::Proxy {
+            address: "localhost".to_string(),
+            port: 8080,
+        };
+        let serialized = JsonSerializer::serialize_to_string(proxy.clone()).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"type":"Proxy","address":"localhost","port":8080}"#
+        );
+        assert_eq!(serialize_deserialize(proxy.clone()), proxy);
+
+        let serialized = JsonSerializer::serialize_to_string(Connection::Direct).unwrap();
+        assert_eq!(serialized, r#"{"type":"Direct"}"#);
+        assert_eq!(
+            serialize_deserialize(Connection::Direct),
+            Connection::Direct
+        );
+    }
+
+    #[test]
+    fn tagged_enum_errors() {
+        let mut deserializer = JsonDeserializer::new(r#"{"type": "Socks", "port": 1080}"#);
+        assert!(Connection::deserialize(&mut deserializer).is_err());
+
+        // Fields of other variants are rejected
+        let mut deserializer = JsonDeserializer::new(r#"{"type": "Direct", "port": 1080}"#);
+        assert!(Connection::deserialize(&mut deserializer).is_err());
+    }
+
     #[test]
     fn serialize_deserialize_option() {
         let value: Option<usize> = Some(3);
 
         assert_eq!(serialize_deserialize(value), value)
     }
+
+    #[test]
+    fn deserialize_numbers() {
+        let mut deserializer = JsonDeserializer::new("-5");
+        assert_eq!(i32::deserialize(&mut deserializer).unwrap(), -5);
+
+        let mut deserializer = JsonDeserializer::new("-5");
+        assert!(u32::deserialize(&mut deserializer).is_err());
+
+        let mut deserializer = JsonDeserializer::new("2.5");
+        assert_eq!(f64::deserialize(&mut deserializer).unwrap(), 2.5);
+
+        let mut deserializer = JsonDeserializer::new("1e3");
+        assert_eq!(f64::deserialize(&mut deserializer).unwrap(), 1000.);
+
+        // Integers can be read as floats, but not the other way around
+        let mut deserializer = JsonDeserializer::new("7");
+        assert_eq!(f32::deserialize(&mut deserializer).unwrap(), 7.);
+
+        let mut deserializer = JsonDeserializer::new("2.5");
+        assert!(i64::deserialize(&mut deserializer).is_err());
+    }
+
+    #[test]
+    fn value_preserves_number_type() {
+        let mut deserializer = JsonDeserializer::new("[-5, 1e3, 2.0]");
+        let value = Value::deserialize(&mut deserializer).unwrap();
+        assert_eq!(
+            value,
+            Value::List(vec![
+                Value::Integer(-5),
+                Value::Float(1000.),
+                Value::Float(2.)
+            ])
+        );
+
+        let serialized = JsonSerializer::serialize_to_string(value).unwrap();
+        assert_eq!(serialized, "[-5,1000.0,2.0]");
+    }
 }
@@ -52,13 +52,28 @@ where
     }
 
     fn serialize_string(&mut self, value: &str) -> Result<(), Self::Error> {
-        write!(self.writer, "{value:?}")
+        write_escaped_string(&mut self.writer, value)
     }
 
     fn serialize_usize(&mut self, value: usize) -> Result<(), Self::Error> {
         write!(self.writer, "{value}")
     }
 
+    fn serialize_i64(&mut self, value: i64) -> Result<(), Self::Error> {
+        write!(self.writer, "{value}")
+    }
+
+    fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error> {
+        if value.is_finite() {
+            // The debug representation always contains a fraction or an exponent,
+            // so the value is read back as a float
+            write!(self.writer, "{value:?}")
+        } else {
+            // JSON can't represent infinity or NaN
+            write!(self.writer, "null")
+        }
+    }
+
     fn serialize_option<T>(&mut self, value: &Option<T>) -> Result<(), Self::Error>
     where
         T: Serialize,
@@ -94,7 +109,9 @@ where
         &'a mut self,
         variant_name: &str,
     ) -> Result<Self::StructVariantSerializer<'a>, Self::Error> {
-        write!(self.writer, "{{{variant_name:?}:{{")?;
+        write!(self.writer, "{{")?;
+        write_escaped_string(&mut self.writer, variant_name)?;
+        write!(self.writer, ":{{")?;
 
         let struct_variant_serializer = StructVariantSerializer(CommaSeparatedSequence::new(self));
 
@@ -105,7 +122,9 @@ where
         &'a mut self,
         variant_name: &str,
     ) -> Result<Self::TupleVariantSerializer<'a>, Self::Error> {
-        write!(self.writer, "{{{variant_name:?}:[")?;
+        write!(self.writer, "{{")?;
+        write_escaped_string(&mut self.writer, variant_name)?;
+        write!(self.writer, ":[")?;
 
         let struct_variant_serializer = TupleVariantSerializer(CommaSeparatedSequence::new(self));
 
@@ -120,7 +139,9 @@ where
     where
         T: Serialize,
     {
-        write!(self.writer, "{{{variant_name:?}:")?;
+        write!(self.writer, "{{")?;
+        write_escaped_string(&mut self.writer, variant_name)?;
+        write!(self.writer, ":")?;
         value.serialize_to(self)?;
         write!(self.writer, "}}")?;
 
@@ -128,6 +149,28 @@ where
     }
 }
 
+/// Write a string literal, escaping all characters that may not appear
+/// unescaped within json strings
+fn write_escaped_string<W: Write>(writer: &mut W, value: &str) -> fmt::Result {
+    writer.write_char('"')?;
+
+    for c in value.chars() {
+        match c {
+            '"' => writer.write_str("\\\"")?,
+            '\\' => writer.write_str("\\\\")?,
+            '\n' => writer.write_str("\\n")?,
+            '\r' => writer.write_str("\\r")?,
+            '\t' => writer.write_str("\\t")?,
+            '\x08' => writer.write_str("\\b")?,
+            '\x0c' => writer.write_str("\\f")?,
+            '\0'..='\x1f' => write!(writer, "\\u{:04x}", c as u32)?,
+            other => writer.write_char(other)?,
+        }
+    }
+
+    writer.write_char('"')
+}
+
 struct CommaSeparatedSequence<'a, W> {
     serializer: &'a mut JsonSerializer<W>,
     is_first_element: bool,
@@ -0,0 +1,207 @@
+//! Incremental deserialization of large json arrays
+
+use std::{
+    io::{BufReader, Bytes, Read},
+    marker::PhantomData,
+};
+
+use serialize::Deserialize;
+
+use crate::{JsonDeserializer, JsonError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    BeforeArray,
+    InArray,
+    Done,
+}
+
+/// Deserializes the elements of a top-level json array one at a time
+///
+/// Only the text of the current element is kept in memory, the elements
+/// themselves are parsed by a regular [JsonDeserializer].
+///
+/// ```
+/// # use serialize_json::ArrayStream;
+/// let json = "[1, 2, 3]".as_bytes();
+/// let sum: usize = ArrayStream::<_, usize>::new(json)
+///     .map(Result::unwrap)
+///     .sum();
+/// assert_eq!(sum, 6);
+/// ```
+pub struct ArrayStream<R, T> {
+    bytes: Bytes<BufReader<R>>,
+    state: State,
+
+    /// The text of the element that is currently being read
+    buffer: Vec<u8>,
+    element: PhantomData<T>,
+}
+
+impl<R, T> ArrayStream<R, T>
+where
+    R: Read,
+{
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self {
+            bytes: BufReader::new(reader).bytes(),
+            state: State::BeforeArray,
+            buffer: vec![],
+            element: PhantomData,
+        }
+    }
+
+    fn next_byte(&mut self) -> Result<Option<u8>, JsonError> {
+        self.bytes
+            .next()
+            .transpose()
+            .map_err(|error| JsonError::Io(error.kind()))
+    }
+
+    fn next_non_whitespace_byte(&mut self) -> Result<Option<u8>, JsonError> {
+        loop {
+            match self.next_byte()? {
+                Some(b' ' | b'\t' | b'\r' | b'\n') => continue,
+                other => return Ok(other),
+            }
+        }
+    }
+
+    /// Read the text of the next element into the buffer
+    ///
+    /// Returns the byte that terminated the element, which is either
+    /// a `,` or the closing `]` of the array.
+    fn read_element(&mut self) -> Result<u8, JsonError> {
+        self.buffer.clear();
+
+        let mut depth: usize = 0;
+        let mut is_in_string = false;
+        let mut is_escaped = false;
+
+        // Structural characters are all ascii and never occur within
+        // multibyte utf-8 sequences, so the input can be scanned bytewise
+        loop {
+            let byte = self.next_byte()?.ok_or(JsonError::UnexpectedToken)?;
+
+            if is_in_string {
+                match byte {
+                    _ if is_escaped => is_escaped = false,
+                    b'\\' => is_escaped = true,
+                    b'"' => is_in_string = false,
+                    _ => {},
+                }
+            } else {
+                match byte {
+                    b',' | b']' if depth == 0 => return Ok(byte),
+                    b'"' => is_in_string = true,
+                    b'[' | b'{' => depth += 1,
+                    b']' | b'}' => {
+                        depth = depth.checked_sub(1).ok_or(JsonError::UnexpectedToken)?
+                    },
+                    _ => {},
+                }
+            }
+
+            self.buffer.push(byte);
+        }
+    }
+
+    fn next_element(&mut self) -> Result<Option<T>, JsonError>
+    where
+        T: Deserialize,
+    {
+        match self.state {
+            State::BeforeArray => {
+                if self.next_non_whitespace_byte()? != Some(b'[') {
+                    return Err(JsonError::UnexpectedToken);
+                }
+                self.state = State::InArray;
+            },
+            State::InArray => {},
+            State::Done => return Ok(None),
+        }
+
+        let terminator = self.read_element()?;
+        if terminator == b']' {
+            self.state = State::Done;
+
+            // The array may be empty or end with a trailing comma
+            if self.buffer.iter().all(u8::is_ascii_whitespace) {
+                return Ok(None);
+            }
+        }
+
+        let text = std::str::from_utf8(&self.buffer).map_err(|_| JsonError::InvalidUtf8)?;
+        let element = T::deserialize(&mut JsonDeserializer::new(text))?;
+        Ok(Some(element))
+    }
+}
+
+impl<R, T> Iterator for ArrayStream<R, T>
+where
+    R: Read,
+    T: Deserialize,
+{
+    type Item = Result<T, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.next_element();
+
+        if element.is_err() {
+            // Don't try to recover from invalid input
+            self.state = State::Done;
+        }
+
+        element.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Value;
+
+    #[test]
+    fn sum_large_array() {
+        let count = 100_000;
+        let mut json = String::from("[");
+        for i in 0..count {
+            json.push_str(&format!("{i},\n"));
+        }
+        json.push(']');
+
+        let sum: usize = ArrayStream::<_, usize>::new(json.as_bytes())
+            .map(Result::unwrap)
+            .sum();
+        assert_eq!(sum, count * (count - 1) / 2);
+    }
+
+    #[test]
+    fn nested_elements() {
+        let json = r#"[{"a": [1, 2]}, "],\"", [[]]]"#;
+        let elements: Vec<Value> = ArrayStream::new(json.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(elements.len(), 3);
+        assert_eq!(elements[1], Value::String("],\"".to_string()));
+        assert_eq!(elements[2], Value::List(vec![Value::List(vec![])]));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut stream = ArrayStream::<_, usize>::new(r#"{"a": 1}"#.as_bytes());
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+
+        // Unterminated array
+        let mut stream = ArrayStream::<_, usize>::new("[1, 2".as_bytes());
+        assert_eq!(stream.next().unwrap().unwrap(), 1);
+        assert!(stream.next().unwrap().is_err());
+
+        let mut stream = ArrayStream::<_, usize>::new(" [ ] ".as_bytes());
+        assert!(stream.next().is_none());
+    }
+}
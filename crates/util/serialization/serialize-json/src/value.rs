@@ -6,10 +6,13 @@ use serialize::{
     Deserialize, Deserializer, Serialize, Visitor,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Value {
     String(String),
-    Integer(usize),
+    Integer(i64),
+
+    /// A number with a fraction or an exponent
+    Float(f64),
     Boolean(bool),
     List(Vec<Value>),
     Map(HashMap<String, Value>),
@@ -23,7 +26,8 @@ impl Serialize for Value {
     {
         match self {
             Self::String(s) => serializer.serialize_string(s),
-            Self::Integer(int) => serializer.serialize_usize(*int),
+            Self::Integer(int) => serializer.serialize_i64(*int),
+            Self::Float(float) => serializer.serialize_f64(*float),
             Self::Boolean(boolean) => serializer.serialize_bool(*boolean),
             Self::List(list) => {
                 let mut sequence = serializer.serialize_sequence()?;
@@ -71,12 +75,29 @@ impl Deserialize for Value {
             }
 
             fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match i64::try_from(value) {
+                    Ok(integer) => Ok(Value::Integer(integer)),
+                    Err(_) => Ok(Value::Float(value as f64)),
+                }
+            }
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
             where
                 E: Error,
             {
                 Ok(Value::Integer(value))
             }
 
+            fn visit_f64<E>(&self, value: f64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Value::Float(value))
+            }
+
             fn visit_map<M>(&self, mut value: M) -> Result<Self::Value, M::Error>
             where
                 M: MapAccess,
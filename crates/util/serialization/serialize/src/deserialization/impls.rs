@@ -157,6 +157,225 @@ impl Deserialize for usize {
     }
 }
 
+impl Deserialize for i8 {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
+        struct I8Visitor;
+
+        impl Visitor for I8Visitor {
+            type Value = i8;
+
+            const EXPECTS: &'static str = "a i8";
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+
+            fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+        }
+
+        deserializer.deserialize_i64(I8Visitor)
+    }
+}
+
+impl Deserialize for i16 {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
+        struct I16Visitor;
+
+        impl Visitor for I16Visitor {
+            type Value = i16;
+
+            const EXPECTS: &'static str = "a i16";
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+
+            fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+        }
+
+        deserializer.deserialize_i64(I16Visitor)
+    }
+}
+
+impl Deserialize for i32 {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
+        struct I32Visitor;
+
+        impl Visitor for I32Visitor {
+            type Value = i32;
+
+            const EXPECTS: &'static str = "a i32";
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+
+            fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+        }
+
+        deserializer.deserialize_i64(I32Visitor)
+    }
+}
+
+impl Deserialize for i64 {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
+        struct I64Visitor;
+
+        impl Visitor for I64Visitor {
+            type Value = i64;
+
+            const EXPECTS: &'static str = "a i64";
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(value)
+            }
+
+            fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+        }
+
+        deserializer.deserialize_i64(I64Visitor)
+    }
+}
+
+impl Deserialize for isize {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
+        struct IsizeVisitor;
+
+        impl Visitor for IsizeVisitor {
+            type Value = isize;
+
+            const EXPECTS: &'static str = "a isize";
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+
+            fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                let value = Self::Value::try_from(value).map_err(|_| E::expected(Self::EXPECTS))?;
+                Ok(value)
+            }
+        }
+
+        deserializer.deserialize_i64(IsizeVisitor)
+    }
+}
+
+impl Deserialize for f32 {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
+        struct F32Visitor;
+
+        impl Visitor for F32Visitor {
+            type Value = f32;
+
+            const EXPECTS: &'static str = "a number";
+
+            fn visit_f64<E>(&self, value: f64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(value as f32)
+            }
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(value as f32)
+            }
+
+            fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(value as f32)
+            }
+        }
+
+        deserializer.deserialize_f64(F32Visitor)
+    }
+}
+
+impl Deserialize for f64 {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
+        struct F64Visitor;
+
+        impl Visitor for F64Visitor {
+            type Value = f64;
+
+            const EXPECTS: &'static str = "a number";
+
+            fn visit_f64<E>(&self, value: f64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(value)
+            }
+
+            fn visit_i64<E>(&self, value: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(value as f64)
+            }
+
+            fn visit_usize<E>(&self, value: usize) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(value as f64)
+            }
+        }
+
+        deserializer.deserialize_f64(F64Visitor)
+    }
+}
+
 impl<T: Deserialize> Deserialize for Vec<T> {
     fn deserialize<D: Deserializer>(deserializer: D) -> Result<Self, D::Error> {
         struct VecVisitor<T> {
@@ -38,6 +38,10 @@ pub trait Deserializer {
 
     fn deserialize_usize<V: Visitor>(self, visitor: V) -> Result<V::Value, Self::Error>;
 
+    fn deserialize_i64<V: Visitor>(self, visitor: V) -> Result<V::Value, Self::Error>;
+
+    fn deserialize_f64<V: Visitor>(self, visitor: V) -> Result<V::Value, Self::Error>;
+
     fn deserialize_option<V: Visitor>(self, visitor: V) -> Result<V::Value, Self::Error>;
 
     fn deserialize_enum<V: Visitor>(self, visitor: V) -> Result<V::Value, Self::Error>;
@@ -74,6 +74,69 @@ impl Serialize for u128 {
     }
 }
 
+impl Serialize for i8 {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(*self as i64)
+    }
+}
+
+impl Serialize for i16 {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(*self as i64)
+    }
+}
+
+impl Serialize for i32 {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(*self as i64)
+    }
+}
+
+impl Serialize for i64 {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(*self)
+    }
+}
+
+impl Serialize for isize {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_i64(*self as i64)
+    }
+}
+
+impl Serialize for f32 {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(*self as f64)
+    }
+}
+
+impl Serialize for f64 {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(*self)
+    }
+}
+
 impl<T> Serialize for [T]
 where
     T: Serialize,
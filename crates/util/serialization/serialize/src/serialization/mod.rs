@@ -35,6 +35,10 @@ pub trait Serializer {
 
     fn serialize_usize(&mut self, value: usize) -> Result<(), Self::Error>;
 
+    fn serialize_i64(&mut self, value: i64) -> Result<(), Self::Error>;
+
+    fn serialize_f64(&mut self, value: f64) -> Result<(), Self::Error>;
+
     fn serialize_option<T>(&mut self, value: &Option<T>) -> Result<(), Self::Error>
     where
         T: Serialize;
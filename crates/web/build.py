@@ -17,12 +17,84 @@ def to_snake_case(text):
     return text.replace("-", "_")
 
 
+# NOTE: These must match the hash functions in the perfect_hash crate
+def str_hash(string):
+    b = 378551
+    a = 63689
+    hash = 0
+    for byte in string.encode("utf-8"):
+        hash = (hash * a + byte) & 0xFFFFFFFF
+        a = (a * b) & 0xFFFFFFFF
+    return hash
+
+
+def int_hash(x):
+    x = (((x >> 16) ^ x) * 0x45D9F3B) & 0xFFFFFFFF
+    x = (((x >> 16) ^ x) * 0x45D9F3B) & 0xFFFFFFFF
+    x = (x >> 16) ^ x
+    return x
+
+
+def build_perfect_hash_table(keys):
+    """
+    Builds the same table as the perfect_set!() macro from the perfect_hash crate.
+
+    Returns a tuple of (second_level_functions, keys ordered by their hash)
+    """
+    size = len(keys)
+
+    first_level_buckets = [[] for _ in range(size)]
+    for key in keys:
+        hash = str_hash(key)
+        first_level_buckets[hash % size].append(hash)
+
+    occupied = [False] * size
+    second_level_functions = [0] * size
+
+    # Place the largest buckets first
+    bucket_order = sorted(range(size), key=lambda index: len(first_level_buckets[index]))
+    for bucket_index in reversed(bucket_order):
+        bucket = first_level_buckets[bucket_index]
+        function = 0
+        while True:
+            indices = [int_hash(hash ^ function) % size for hash in bucket]
+            if len(set(indices)) == len(indices) and not any(
+                occupied[index] for index in indices
+            ):
+                break
+            function += 1
+
+        for index in indices:
+            occupied[index] = True
+        second_level_functions[bucket_index] = function
+
+    ordered_keys = [None] * size
+    for key in keys:
+        primary_hash = str_hash(key)
+        function = second_level_functions[primary_hash % size]
+        ordered_keys[int_hash(primary_hash ^ function) % size] = key
+
+    return second_level_functions, ordered_keys
+
+
 def build_named_entities(env, target_dir, download_dir):
     with open(download_dir / "html_named_entities.json", "r") as infile:
         named_entities = json.load(infile)
 
+    # The leading "&" is not part of the name
+    names = [name[1:] for name in named_entities]
+    second_level_functions, ordered_names = build_perfect_hash_table(names)
+    resolved_references = [
+        named_entities["&" + name]["characters"] for name in ordered_names
+    ]
+
     template = env.get_template("named_entities.rs.jinja")
-    autogenerated_code = template.render(named_entities=named_entities)
+    autogenerated_code = template.render(
+        second_level_functions=second_level_functions,
+        names=ordered_names,
+        resolved_references=resolved_references,
+        max_length=max(len(name) for name in names),
+    )
 
     with open(target_dir / "named_entities.rs", "w", encoding="utf-8") as outfile:
         outfile.write(autogenerated_code)
@@ -10,6 +10,7 @@ use crate::{
         display_list::Painter,
         fragment_tree::FragmentTree,
         layout::{BoxTree, Pixels, Size},
+        media_queries::ColorScheme,
         StyleComputer, Stylesheet,
     },
     dom::{
@@ -33,6 +34,12 @@ struct CurrentPage {
     stylesheets: Vec<Stylesheet>,
     hovered_element: Option<DomPtr<dom_objects::Element>>,
     needs_relayout: bool,
+
+    /// The size of the viewport during the last layout
+    ///
+    /// Media queries and viewport-relative lengths depend on this, so the page
+    /// needs to be laid out again when the viewport is resized.
+    layout_viewport_size: Option<Size<Pixels>>,
 }
 
 #[derive(Debug, Error)]
@@ -81,6 +88,7 @@ impl BrowsingContext {
             stylesheets,
             hovered_element: None,
             needs_relayout: true,
+            layout_viewport_size: None,
         };
 
         self.current_page = Some(current_page);
@@ -98,7 +106,7 @@ impl BrowsingContext {
             height: Pixels(viewport_size.1 as f32),
         };
 
-        if current_page.needs_relayout {
+        if current_page.needs_relayout || current_page.layout_viewport_size != Some(viewport_size) {
             current_page.layout(viewport_size);
         }
 
@@ -131,7 +139,12 @@ impl BrowsingContext {
 impl CurrentPage {
     fn layout(&mut self, viewport_size: Size<Pixels>) {
         let layout_start = time::Instant::now();
-        let style_computer = StyleComputer::new(&self.stylesheets, Pixels(16.), viewport_size);
+        let style_computer = StyleComputer::new(
+            &self.stylesheets,
+            Pixels(16.),
+            viewport_size,
+            ColorScheme::default(),
+        );
 
         // Build a box tree for the parsed document
         let box_tree = BoxTree::new(self.document.clone(), style_computer);
@@ -147,6 +160,7 @@ impl CurrentPage {
         );
 
         self.needs_relayout = false;
+        self.layout_viewport_size = Some(viewport_size);
     }
 
     fn update_hovered_element(&mut self, hovered_element: Option<DomPtr<dom_objects::Element>>) {
@@ -1,6 +1,6 @@
 use image::{AccessMode, Texture};
 use math::Vec2D;
-use render::{Composition, Path, Source};
+use render::{draw_text, Composition, Path, Source};
 
 use crate::css::{
     display_list::{
@@ -72,18 +72,17 @@ impl Painter {
                         ));
                 },
                 Command::Text(text_command) => {
-                    composition
-                        .get_or_insert_layer(index as u16)
-                        .text(
-                            &text_command.text,
-                            *text_command.font_metrics.font_face.clone(),
-                            text_command.font_metrics.size.into(),
-                            Vec2D {
-                                x: text_command.position.x.0,
-                                y: text_command.position.y.0,
-                            },
-                        )
-                        .with_source(Source::Solid(text_command.color));
+                    draw_text(
+                        composition.get_or_insert_layer(index as u16),
+                        &text_command.font_metrics.font_face,
+                        &text_command.text,
+                        Vec2D {
+                            x: text_command.position.x.0,
+                            y: text_command.position.y.0,
+                        },
+                        text_command.font_metrics.size.into(),
+                        text_command.color,
+                    );
                 },
                 Command::Image(image_command) => {
                     let texture_source = Source::Texture {
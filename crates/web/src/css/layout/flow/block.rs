@@ -10,7 +10,7 @@ use crate::{
             computed::{Clear, Margin, Padding},
             specified::DisplayInside,
         },
-        values::{AutoOr, PercentageOr},
+        values::AutoOr,
         ComputedStyle, StyleComputer,
     },
     dom::{dom_objects, DomPtr},
@@ -615,20 +615,13 @@ impl BlockDimensions {
 
         // Compute the height according to https://drafts.csswg.org/css2/#normal-block
         // If the height is a percentage it is
-        let height = style.height().flat_map(|percentage_or_length| {
-            match percentage_or_length {
-                PercentageOr::Percentage(percentage) => {
-                    if let Some(available_height) = containing_block.height() {
-                        AutoOr::NotAuto(available_height * percentage.as_fraction())
-                    } else {
-                        // If the value is a percentage but the length of the containing block is not
-                        // yet determined, the value should be treated as auto.
-                        // (https://drafts.csswg.org/css2/#the-height-property)
-                        AutoOr::Auto
-                    }
-                },
-                PercentageOr::NotPercentage(length) => AutoOr::NotAuto(length),
-            }
+        let height = style.height().flat_map(|length_percentage| {
+            // If the value is a percentage but the length of the containing block is not
+            // yet determined, the value should be treated as auto.
+            // (https://drafts.csswg.org/css2/#the-height-property)
+            length_percentage
+                .try_resolve_against(containing_block.height())
+                .map_or(AutoOr::Auto, AutoOr::NotAuto)
         });
 
         let margin = Sides {
@@ -13,6 +13,7 @@ use crate::{
             },
             formatting_context::IndependentFormattingContext,
         },
+        selectors::PseudoElementSelector,
         style::specified::{Display, DisplayBox, DisplayInside, DisplayOutside},
         ComputedStyle, StyleComputer,
     },
@@ -67,21 +68,23 @@ impl<'stylesheets, 'parent_style> BlockContainerBuilder<'stylesheets, 'parent_st
     ) -> BlockContainer {
         let mut builder = Self::new(style, style_computer);
 
-        if display_inside.has_list_item_flag() {
-            if let Some(element) = node.try_into_type() {
+        if let Some(element) = node.try_into_type() {
+            if display_inside.has_list_item_flag() {
                 let marker_content = style.list_style_type().as_str();
 
                 if let Some(content) = marker_content {
                     builder.push_inline_box(
-                        element,
+                        element.clone(),
                         style.clone(),
                         Content::PseudoElement(content),
                     );
                 }
             }
-        }
 
-        builder.traverse_subtree(node, style);
+            builder.traverse_element(element, style);
+        } else {
+            builder.traverse_subtree(node, style);
+        }
 
         builder.finish()
     }
@@ -106,6 +109,41 @@ impl<'stylesheets, 'parent_style> BlockContainerBuilder<'stylesheets, 'parent_st
         }
     }
 
+    /// Traverse the children of an element, including its `::before` and `::after` pseudo-elements
+    fn traverse_element(&mut self, element: DomPtr<dom_objects::Element>, style: &ComputedStyle) {
+        self.push_pseudo_element(&element, style, PseudoElementSelector::Before);
+        self.traverse_subtree(element.clone().upcast(), style);
+        self.push_pseudo_element(&element, style, PseudoElementSelector::After);
+    }
+
+    /// Generate the box for a `::before` or `::after` pseudo-element
+    ///
+    /// <https://drafts.csswg.org/css-pseudo-4/#generated-content>
+    fn push_pseudo_element(
+        &mut self,
+        element: &DomPtr<dom_objects::Element>,
+        element_style: &ComputedStyle,
+        pseudo_element: PseudoElementSelector,
+    ) {
+        let style = self.style_computer.get_pseudo_element_style(
+            element.clone(),
+            pseudo_element,
+            element_style,
+        );
+
+        if style.display().is_none() {
+            return;
+        }
+
+        let Some(text) = style.content().pseudo_element_text() else {
+            return;
+        };
+
+        // FIXME: Generated content is always laid out as inline content, regardless
+        //        of the "display" value of the pseudo-element
+        self.push_text(TextRun::new(text.to_owned(), style));
+    }
+
     pub fn handle_element(&mut self, element: DomPtr<dom_objects::Element>, style: ComputedStyle) {
         let content = Content::for_element(element.clone(), style.clone());
 
@@ -119,7 +157,7 @@ impl<'stylesheets, 'parent_style> BlockContainerBuilder<'stylesheets, 'parent_st
             Display::Box(DisplayBox::None) => {
                 // This element does not generate a box
             },
-            Display::Box(DisplayBox::Contents) => self.traverse_subtree(element.upcast(), &style),
+            Display::Box(DisplayBox::Contents) => self.traverse_element(element, &style),
         }
     }
 
@@ -161,7 +199,7 @@ impl<'stylesheets, 'parent_style> BlockContainerBuilder<'stylesheets, 'parent_st
                 self.inline_stack.push(inline_box);
 
                 // Traverse all children, they will be appended to the inline box we just created
-                self.traverse_subtree(element.upcast(), &style);
+                self.traverse_element(element, &style);
 
                 // Pop the inline box from the stack and append it to its parents list of children
                 // unless the stack of open inline boxes is empty, in which case this was a top level box
@@ -270,3 +308,90 @@ impl<'stylesheets, 'parent_style> BlockContainerBuilder<'stylesheets, 'parent_st
         self.block_level_boxes.push(block_box);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        css::{
+            font_metrics::DEFAULT_FONT_SIZE,
+            layout::{Pixels, Size},
+            media_queries::ColorScheme,
+            Origin, Parser,
+        },
+        dom::{dom_objects::Node, DomPtr, ElementCustomState},
+        infra::Namespace,
+        static_interned,
+    };
+
+    /// Build the box tree of `<div>text</div>` and return the text of all generated text runs
+    fn text_runs(css: &str) -> Vec<String> {
+        let stylesheets = [Parser::new(css, Origin::Author).parse_stylesheet(0)];
+        let style_computer = StyleComputer::new(
+            &stylesheets,
+            DEFAULT_FONT_SIZE,
+            Size {
+                width: Pixels(800.),
+                height: Pixels(600.),
+            },
+            ColorScheme::Light,
+        );
+
+        let div = DomPtr::new(dom_objects::Element::new(
+            Namespace::HTML,
+            None,
+            static_interned!("div"),
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        ));
+        let mut text = dom_objects::Text::default();
+        text.content_mut().push_str("text");
+        Node::append_child(div.clone().upcast(), DomPtr::new(text).upcast());
+
+        let style = style_computer.get_computed_style(div.clone(), &ComputedStyle::default());
+        let Display::InsideOutside(inside_outside) = *style.display() else {
+            panic!("div should generate a box");
+        };
+
+        let container = BlockContainerBuilder::build(
+            div.upcast(),
+            style_computer,
+            &style,
+            inside_outside.inside,
+        );
+        let BlockContainer::InlineFormattingContext(formatting_context) = container else {
+            panic!("div should only contain inline content");
+        };
+
+        formatting_context
+            .elements()
+            .iter()
+            .map(|element| match element {
+                InlineLevelBox::TextRun(text_run) => text_run.text().to_owned(),
+                other => panic!("Expected a text run, found {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn generated_content() {
+        assert_eq!(text_runs("div::before { content: \"•\" }"), ["•", "text"]);
+        assert_eq!(
+            text_runs("div::before { content: \"<\" } div:after { content: \">\" }"),
+            ["<", "text", ">"]
+        );
+
+        // No box is generated if there is no content or the pseudo-element is hidden
+        assert_eq!(text_runs("div::before { content: none }"), ["text"]);
+        assert_eq!(text_runs("div::after { color: red }"), ["text"]);
+        assert_eq!(
+            text_runs("div::before { content: \"•\"; display: none }"),
+            ["text"]
+        );
+
+        // Rules without a pseudo-element don't apply to generated content
+        assert_eq!(text_runs("div { content: \"•\" }"), ["text"]);
+    }
+}
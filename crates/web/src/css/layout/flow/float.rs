@@ -15,7 +15,7 @@ use crate::{
             computed::{Margin, Padding},
             specified::FloatSide,
         },
-        values::{AutoOr, PercentageOr},
+        values::AutoOr,
     },
     dom::{dom_objects, DomPtr},
     TreeDebug, TreeFormatter,
@@ -85,19 +85,11 @@ impl FloatingBox {
                 todo!("compute shrink-to-fit width");
             });
 
-        let height =
-            self.style
-                .height()
-                .flat_map(|percentage_or_length| match percentage_or_length {
-                    PercentageOr::Percentage(percentage) => {
-                        if let Some(available_height) = containing_block.height() {
-                            AutoOr::NotAuto(available_height * percentage.as_fraction())
-                        } else {
-                            AutoOr::Auto
-                        }
-                    },
-                    PercentageOr::NotPercentage(length) => AutoOr::NotAuto(length),
-                });
+        let height = self.style.height().flat_map(|length_percentage| {
+            length_percentage
+                .try_resolve_against(containing_block.height())
+                .map_or(AutoOr::Auto, AutoOr::NotAuto)
+        });
 
         // Compute the containing block (us) that our children will be laid out in
         let content_offset = Vec2D::new(
@@ -81,7 +81,7 @@ where
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Size<T> {
     pub width: T,
     pub height: T,
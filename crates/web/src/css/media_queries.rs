@@ -0,0 +1,473 @@
+//! Media queries like `@media screen and (min-width: 600px)`
+//!
+//! <https://drafts.csswg.org/mediaqueries/>
+
+use crate::{
+    css::{
+        layout::{Pixels, Size},
+        style::{specified::Length, StyleContext, ToComputedStyle},
+        syntax::Token,
+        CSSParse, ParseError, Parser,
+    },
+    static_interned,
+};
+
+/// The properties of the output device that media queries can refer to
+#[derive(Clone, Copy, Debug)]
+pub struct MediaContext {
+    pub viewport: Size<Pixels>,
+    pub color_scheme: ColorScheme,
+}
+
+/// <https://drafts.csswg.org/mediaqueries-5/#prefers-color-scheme>
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+/// A comma-separated list of media queries, which matches if any of the queries match
+///
+/// <https://drafts.csswg.org/mediaqueries/#media-query-list>
+#[derive(Clone, Debug, Default)]
+pub struct MediaQueryList {
+    queries: Vec<MediaQuery>,
+}
+
+/// <https://drafts.csswg.org/mediaqueries/#media-query>
+#[derive(Clone, Debug)]
+struct MediaQuery {
+    negated: bool,
+    media_type: MediaType,
+    condition: Option<MediaCondition>,
+}
+
+/// <https://drafts.csswg.org/mediaqueries/#media-types>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MediaType {
+    All,
+    Screen,
+    Print,
+
+    /// Unknown media types are valid, but never match
+    Unknown,
+}
+
+/// <https://drafts.csswg.org/mediaqueries/#typedef-media-condition>
+#[derive(Clone, Debug)]
+enum MediaCondition {
+    Feature(MediaFeature),
+    Not(Box<Self>),
+    And(Vec<Self>),
+    Or(Vec<Self>),
+}
+
+/// <https://drafts.csswg.org/mediaqueries/#mq-features>
+#[derive(Clone, Debug)]
+enum MediaFeature {
+    /// <https://drafts.csswg.org/mediaqueries/#width>
+    Width(Comparison, Length),
+
+    /// <https://drafts.csswg.org/mediaqueries/#height>
+    Height(Comparison, Length),
+
+    /// <https://drafts.csswg.org/mediaqueries-5/#prefers-color-scheme>
+    PrefersColorScheme(ColorScheme),
+}
+
+/// How the value of a media feature is compared to the value in the query
+///
+/// For example, `(min-width: 600px)` is equivalent to `(width >= 600px)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl MediaQueryList {
+    /// Parse a media query list from the prelude of a `@media` rule
+    ///
+    /// Media queries that fail to parse are replaced with `not all`, as required by
+    /// <https://drafts.csswg.org/mediaqueries/#error-handling>.
+    #[must_use]
+    pub fn from_tokens(tokens: &[Token]) -> Self {
+        if tokens.iter().all(Token::is_whitespace) {
+            // An empty media query list always matches
+            return Self::default();
+        }
+
+        let queries = split_at_top_level_commas(tokens)
+            .into_iter()
+            .map(|tokens| {
+                let mut parser = Parser::from_tokens(tokens.into(), crate::css::Origin::Author);
+                MediaQuery::parse_complete(&mut parser).unwrap_or(MediaQuery::NEVER)
+            })
+            .collect();
+
+        Self { queries }
+    }
+
+    #[must_use]
+    pub fn matches(&self, context: &MediaContext) -> bool {
+        self.queries.is_empty() || self.queries.iter().any(|query| query.matches(context))
+    }
+}
+
+impl MediaQuery {
+    /// A query that never matches (`not all`)
+    const NEVER: Self = Self {
+        negated: true,
+        media_type: MediaType::All,
+        condition: None,
+    };
+
+    fn matches(&self, context: &MediaContext) -> bool {
+        let matches = self.media_type.matches()
+            && self
+                .condition
+                .as_ref()
+                .map_or(true, |condition| condition.matches(context));
+
+        matches != self.negated
+    }
+}
+
+impl MediaType {
+    fn matches(&self) -> bool {
+        // We are always rendering to a screen
+        matches!(self, Self::All | Self::Screen)
+    }
+}
+
+impl MediaCondition {
+    fn matches(&self, context: &MediaContext) -> bool {
+        match self {
+            Self::Feature(feature) => feature.matches(context),
+            Self::Not(condition) => !condition.matches(context),
+            Self::And(conditions) => conditions.iter().all(|c| c.matches(context)),
+            Self::Or(conditions) => conditions.iter().any(|c| c.matches(context)),
+        }
+    }
+
+    /// <https://drafts.csswg.org/mediaqueries/#typedef-media-condition>
+    ///
+    /// If `allow_or` is false, this parses a `<media-condition-without-or>` instead.
+    fn parse_with_or(parser: &mut Parser<'_>, allow_or: bool) -> Result<Self, ParseError> {
+        if matches!(
+            parser.peek_token_ignoring_whitespace(0),
+            Some(Token::Ident(static_interned!("not")))
+        ) {
+            _ = parser.next_token_ignoring_whitespace();
+            let condition = Self::parse_in_parens(parser)?;
+            return Ok(Self::Not(Box::new(condition)));
+        }
+
+        let first = Self::parse_in_parens(parser)?;
+
+        // "and" and "or" cannot be mixed without parentheses
+        let is_and = match parser.peek_token_ignoring_whitespace(0) {
+            Some(Token::Ident(static_interned!("and"))) => true,
+            Some(Token::Ident(static_interned!("or"))) if allow_or => false,
+            _ => return Ok(first),
+        };
+        let operator = if is_and {
+            static_interned!("and")
+        } else {
+            static_interned!("or")
+        };
+
+        let mut conditions = vec![first];
+        while parser.peek_token_ignoring_whitespace(0) == Some(&Token::Ident(operator)) {
+            _ = parser.next_token_ignoring_whitespace();
+            conditions.push(Self::parse_in_parens(parser)?);
+        }
+
+        if is_and {
+            Ok(Self::And(conditions))
+        } else {
+            Ok(Self::Or(conditions))
+        }
+    }
+
+    /// <https://drafts.csswg.org/mediaqueries/#typedef-media-in-parens>
+    fn parse_in_parens(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        parser.expect_token(Token::ParenthesisOpen)?;
+
+        let condition = match parser.parse_optional_value(|p| Self::parse_with_or(p, true)) {
+            Some(condition) => condition,
+            None => Self::Feature(MediaFeature::parse(parser)?),
+        };
+
+        parser.expect_token(Token::ParenthesisClose)?;
+        Ok(condition)
+    }
+}
+
+impl MediaFeature {
+    fn matches(&self, context: &MediaContext) -> bool {
+        let style_context = StyleContext::new(context.viewport);
+
+        match self {
+            Self::Width(comparison, length) => comparison.compare(
+                context.viewport.width,
+                length.to_computed_style(&style_context),
+            ),
+            Self::Height(comparison, length) => comparison.compare(
+                context.viewport.height,
+                length.to_computed_style(&style_context),
+            ),
+            Self::PrefersColorScheme(color_scheme) => context.color_scheme == *color_scheme,
+        }
+    }
+}
+
+impl Comparison {
+    fn compare(self, actual: Pixels, expected: Pixels) -> bool {
+        match self {
+            Self::Less => actual < expected,
+            Self::LessOrEqual => actual <= expected,
+            Self::Equal => actual == expected,
+            Self::GreaterOrEqual => actual >= expected,
+            Self::Greater => actual > expected,
+        }
+    }
+
+    /// Parse one of `<`, `<=`, `=`, `>=` or `>`
+    ///
+    /// <https://drafts.csswg.org/mediaqueries/#typedef-mf-comparison>
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let first = match parser.next_token_ignoring_whitespace() {
+            Some(Token::Delim(c @ ('<' | '>' | '='))) => c,
+            _ => return Err(ParseError),
+        };
+
+        // There must not be any whitespace between the two characters of "<=" and ">="
+        let or_equal = first != '=' && parser.peek_token(0) == Some(&Token::Delim('='));
+        if or_equal {
+            _ = parser.next_token();
+        }
+
+        let comparison = match (first, or_equal) {
+            ('<', false) => Self::Less,
+            ('<', true) => Self::LessOrEqual,
+            ('>', false) => Self::Greater,
+            ('>', true) => Self::GreaterOrEqual,
+            _ => Self::Equal,
+        };
+        Ok(comparison)
+    }
+}
+
+impl<'a> CSSParse<'a> for MediaQuery {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        // <media-condition>
+        if let Some(condition) =
+            parser.parse_optional_value(|p| MediaCondition::parse_with_or(p, true))
+        {
+            return Ok(Self {
+                negated: false,
+                media_type: MediaType::All,
+                condition: Some(condition),
+            });
+        }
+
+        // [ not | only ]? <media-type> [ and <media-condition-without-or> ]?
+        let negated = match parser.peek_token_ignoring_whitespace(0) {
+            Some(Token::Ident(static_interned!("not"))) => {
+                _ = parser.next_token_ignoring_whitespace();
+                true
+            },
+            Some(Token::Ident(static_interned!("only"))) => {
+                _ = parser.next_token_ignoring_whitespace();
+                false
+            },
+            _ => false,
+        };
+
+        let media_type = parser.parse()?;
+
+        let condition = if matches!(
+            parser.peek_token_ignoring_whitespace(0),
+            Some(Token::Ident(static_interned!("and")))
+        ) {
+            _ = parser.next_token_ignoring_whitespace();
+            Some(MediaCondition::parse_with_or(parser, false)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            negated,
+            media_type,
+            condition,
+        })
+    }
+}
+
+impl<'a> CSSParse<'a> for MediaType {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        let media_type = match parser.expect_identifier()? {
+            static_interned!("all") => Self::All,
+            static_interned!("screen") => Self::Screen,
+            static_interned!("print") => Self::Print,
+
+            // These keywords are reserved and cannot be used as a media type
+            static_interned!("only")
+            | static_interned!("not")
+            | static_interned!("and")
+            | static_interned!("or") => return Err(ParseError),
+            _ => Self::Unknown,
+        };
+
+        Ok(media_type)
+    }
+}
+
+impl<'a> CSSParse<'a> for MediaFeature {
+    /// Parse the part of a `<media-feature>` that is enclosed in parentheses
+    ///
+    /// Both the plain syntax (`min-width: 600px`) and the range syntax (`width >= 600px`)
+    /// are supported. Unknown features are a parse error.
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        let name = parser.expect_identifier()?;
+
+        let comparison = if parser.peek_token_ignoring_whitespace(0) == Some(&Token::Colon) {
+            _ = parser.next_token_ignoring_whitespace();
+            None
+        } else {
+            Some(Comparison::parse(parser)?)
+        };
+
+        let feature = match (name, comparison) {
+            (static_interned!("width"), comparison) => {
+                Self::Width(comparison.unwrap_or(Comparison::Equal), parser.parse()?)
+            },
+            (static_interned!("min-width"), None) => {
+                Self::Width(Comparison::GreaterOrEqual, parser.parse()?)
+            },
+            (static_interned!("max-width"), None) => {
+                Self::Width(Comparison::LessOrEqual, parser.parse()?)
+            },
+            (static_interned!("height"), comparison) => {
+                Self::Height(comparison.unwrap_or(Comparison::Equal), parser.parse()?)
+            },
+            (static_interned!("min-height"), None) => {
+                Self::Height(Comparison::GreaterOrEqual, parser.parse()?)
+            },
+            (static_interned!("max-height"), None) => {
+                Self::Height(Comparison::LessOrEqual, parser.parse()?)
+            },
+            (static_interned!("prefers-color-scheme"), None) => {
+                let color_scheme = match parser.expect_identifier()? {
+                    static_interned!("light") => ColorScheme::Light,
+                    static_interned!("dark") => ColorScheme::Dark,
+                    _ => return Err(ParseError),
+                };
+                Self::PrefersColorScheme(color_scheme)
+            },
+            _ => return Err(ParseError),
+        };
+
+        Ok(feature)
+    }
+}
+
+/// Split a list of tokens at every comma that is not nested inside a block
+fn split_at_top_level_commas(tokens: &[Token]) -> Vec<Vec<Token>> {
+    let mut parts = vec![vec![]];
+    let mut nesting_level: usize = 0;
+
+    for token in tokens {
+        match token {
+            Token::Comma if nesting_level == 0 => {
+                parts.push(vec![]);
+                continue;
+            },
+            Token::Function(_) | Token::ParenthesisOpen | Token::BracketOpen => nesting_level += 1,
+            Token::ParenthesisClose | Token::BracketClose => {
+                nesting_level = nesting_level.saturating_sub(1)
+            },
+            _ => {},
+        }
+
+        parts
+            .last_mut()
+            .expect("parts is never empty")
+            .push(token.clone());
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(query: &str, width: f32) -> bool {
+        let mut parser = Parser::new(query, crate::css::Origin::Author);
+        let tokens: Vec<Token> = std::iter::from_fn(|| parser.next_token()).collect();
+
+        let context = MediaContext {
+            viewport: Size {
+                width: Pixels(width),
+                height: Pixels(600.),
+            },
+            color_scheme: ColorScheme::Dark,
+        };
+
+        MediaQueryList::from_tokens(&tokens).matches(&context)
+    }
+
+    #[test]
+    fn width_queries() {
+        assert!(matches("(min-width: 600px)", 800.));
+        assert!(!matches("(min-width: 600px)", 400.));
+        assert!(matches("(max-width: 600px)", 600.));
+        assert!(matches("(width >= 600px)", 600.));
+        assert!(!matches("(width > 600px)", 600.));
+        assert!(matches("(width < 50em)", 600.));
+        assert!(matches("(width: 600px)", 600.));
+        assert!(matches("(min-height: 80vh)", 0.));
+    }
+
+    #[test]
+    fn combined_queries() {
+        assert!(matches("screen and (min-width: 600px)", 800.));
+        assert!(!matches("print and (min-width: 600px)", 800.));
+        assert!(matches("not print", 800.));
+        assert!(matches("only screen", 800.));
+        assert!(matches("(min-width: 600px) and (max-width: 900px)", 800.));
+        assert!(!matches("(min-width: 600px) and (max-width: 700px)", 800.));
+        assert!(matches("(max-width: 300px) or (min-width: 700px)", 800.));
+        assert!(matches("not (max-width: 300px)", 800.));
+        assert!(matches(
+            "((max-width: 300px) or (prefers-color-scheme: dark))",
+            800.
+        ));
+        assert!(!matches("(prefers-color-scheme: light)", 800.));
+
+        // Any matching query in the list is enough
+        assert!(matches("print, (min-width: 600px)", 800.));
+        assert!(matches("", 800.));
+    }
+
+    #[test]
+    fn invalid_queries_never_match() {
+        // "and" and "or" cannot be mixed without parentheses
+        assert!(!matches(
+            "(min-width: 1px) and (min-width: 2px) or (min-width: 3px)",
+            800.
+        ));
+        assert!(!matches("(unknown-feature: 1px)", 800.));
+        assert!(!matches("(min-width 600px)", 800.));
+        assert!(!matches("not", 800.));
+        assert!(!matches("fancy-device", 800.));
+
+        // Invalid queries do not affect the others in the list
+        assert!(matches("(min-width 600px), screen", 800.));
+    }
+}
@@ -6,6 +6,7 @@ mod font_metrics;
 pub(crate) mod fragment_tree;
 pub(crate) mod layout;
 mod line_break;
+pub(crate) mod media_queries;
 mod properties;
 mod selectors;
 pub mod style;
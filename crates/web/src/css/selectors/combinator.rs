@@ -81,12 +81,16 @@ impl Combinator {
             },
             // These tokens are valid starts of simple selectors
             Some(
-                Token::Delim('*' | '.') | Token::Ident(_) | Token::Hash(..) | Token::BracketOpen,
+                Token::Delim('*' | '.')
+                | Token::Ident(_)
+                | Token::Hash(..)
+                | Token::BracketOpen
+                | Token::Colon,
             ) => {
-                // There is no combinator between these two simple selectors.
-                // There *must* be at least one whitespace (descendant combinator)
+                // Without whitespace, the simple selector is part of the same compound selector
+                // (like in "div.foo"). Otherwise this is a descendant combinator.
                 if !has_whitespace {
-                    return Err(ParseError);
+                    return Ok(None);
                 }
 
                 Combinator::Descendant
@@ -122,9 +126,9 @@ impl<'a> CSSParse<'a> for Combinator {
 
 impl CSSValidateSelector for Combinator {
     fn is_valid(&self) -> bool {
-        // We don't support *any* combinators
-        // As per spec, we therefore treat them as invalid
-        false
+        // We don't support the column combinator
+        // As per spec, we therefore treat it as invalid
+        !matches!(self, Self::Column)
     }
 }
 
@@ -10,6 +10,7 @@ mod attribute_selector;
 mod combinator;
 mod namespace_prefix;
 mod pseudo_class_selector;
+mod pseudo_element_selector;
 mod qualified_name;
 mod specificity;
 mod type_selector;
@@ -20,6 +21,7 @@ pub use attribute_selector::AttributeSelector;
 pub use combinator::Combinator;
 pub use namespace_prefix::NamespacePrefix;
 pub use pseudo_class_selector::PseudoClassSelector;
+pub use pseudo_element_selector::PseudoElementSelector;
 pub use qualified_name::WellQualifiedName;
 pub use specificity::Specificity;
 pub use type_selector::TypeSelector;
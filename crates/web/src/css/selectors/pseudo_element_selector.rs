@@ -0,0 +1,55 @@
+//! <https://drafts.csswg.org/selectors-4/#pseudo-elements>
+
+use crate::{
+    css::{syntax::Token, CSSParse, ParseError, Parser},
+    static_interned,
+};
+
+/// <https://drafts.csswg.org/css-pseudo-4/#generated-content>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PseudoElementSelector {
+    /// <https://drafts.csswg.org/css-pseudo-4/#selectordef-before>
+    Before,
+
+    /// <https://drafts.csswg.org/css-pseudo-4/#selectordef-after>
+    After,
+}
+
+impl PseudoElementSelector {
+    /// Return true if the upcoming tokens start a pseudo-element selector
+    ///
+    /// This includes the legacy single-colon syntax (`:before`), as described in
+    /// <https://drafts.csswg.org/selectors-4/#pseudo-element-syntax>.
+    #[must_use]
+    pub fn is_next(parser: &mut Parser<'_>) -> bool {
+        match parser.peek_token_ignoring_whitespace(1) {
+            Some(Token::Colon) => true,
+            Some(Token::Ident(ident)) => {
+                matches!(
+                    *ident,
+                    static_interned!("before") | static_interned!("after")
+                )
+            },
+            _ => false,
+        }
+    }
+}
+
+impl<'a> CSSParse<'a> for PseudoElementSelector {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        parser.expect_token(Token::Colon)?;
+
+        // The second colon is optional for legacy pseudo-elements
+        if parser.peek_token(0) == Some(&Token::Colon) {
+            _ = parser.next_token();
+        }
+
+        let pseudo_element_selector = match parser.next_token() {
+            Some(Token::Ident(static_interned!("before"))) => Self::Before,
+            Some(Token::Ident(static_interned!("after"))) => Self::After,
+            _ => return Err(ParseError),
+        };
+
+        Ok(pseudo_element_selector)
+    }
+}
@@ -2,13 +2,16 @@ use std::fmt;
 
 use crate::{
     css::{selectors::Specificity, syntax::Token, CSSParse, ParseError, Parser},
-    dom::{dom_objects::Element, DomPtr},
+    dom::{
+        dom_objects::{Document, Element},
+        DomPtr,
+    },
     static_interned, InternedString,
 };
 
 use super::{
-    type_selector, AttributeSelector, Combinator, PseudoClassSelector, TypeSelector,
-    WellQualifiedName,
+    type_selector, AttributeSelector, Combinator, PseudoClassSelector, PseudoElementSelector,
+    TypeSelector, WellQualifiedName,
 };
 
 pub trait CSSValidateSelector {
@@ -54,6 +57,13 @@ pub enum SelectorComponent {
     /// <https://drafts.csswg.org/selectors-4/#typedef-pseudo-class-selector>
     PseudoClass(PseudoClassSelector),
 
+    /// Match a pseudo-element of an element (`::before`)
+    ///
+    /// This is always the last component of a selector.
+    ///
+    /// <https://drafts.csswg.org/selectors-4/#pseudo-elements>
+    PseudoElement(PseudoElementSelector),
+
     /// Match an element by type (`foo`)
     ///
     /// <https://drafts.csswg.org/selectors-4/#type-selectors>
@@ -66,8 +76,20 @@ impl<'a> CSSParse<'a> for Selector {
 
         loop {
             let component = SelectorComponent::parse(parser)?;
+            let is_pseudo_element = matches!(component, SelectorComponent::PseudoElement(_));
             components.push(component.into());
 
+            if is_pseudo_element {
+                // Nothing may follow a pseudo-element
+                if !matches!(
+                    parser.peek_token_ignoring_whitespace(0),
+                    Some(Token::CurlyBraceOpen | Token::Comma) | None
+                ) {
+                    return Err(ParseError);
+                }
+                break;
+            }
+
             if let Some(combinator) = Combinator::next_combinator(parser)? {
                 components.push(combinator.into());
             };
@@ -114,6 +136,12 @@ impl<'a> CSSParse<'a> for SelectorComponent {
                 Self::Attribute(attribute_selector)
             },
             Some(Token::Colon) => {
+                if PseudoElementSelector::is_next(parser) {
+                    let pseudo_element_selector = PseudoElementSelector::parse(parser)?;
+
+                    return Ok(Self::PseudoElement(pseudo_element_selector));
+                }
+
                 let pseudo_class_selector = PseudoClassSelector::parse(parser)?;
 
                 if matches!(
@@ -180,27 +208,137 @@ impl Selector {
         specificity
     }
 
+    /// Return the pseudo-element targeted by the selector, if any
+    #[must_use]
+    pub fn pseudo_element(&self) -> Option<PseudoElementSelector> {
+        match self.components.last()? {
+            SelectorComponentOrCombinator::SelectorComponent(SelectorComponent::PseudoElement(
+                pseudo_element,
+            )) => Some(*pseudo_element),
+            _ => None,
+        }
+    }
+
+    /// Check whether the given element is the subject of the selector
+    ///
+    /// Selectors are matched right-to-left: The rightmost compound selector must match the element itself,
+    /// every compound selector further to the left must match an element that is related to the previous
+    /// one as described by the [Combinator] between them.
     #[must_use]
     pub fn matches(&self, element: &DomPtr<Element>) -> bool {
-        let mut components = self.components();
+        self.pseudo_element().is_none() && self.matches_ignoring_pseudo_element(element)
+    }
+
+    /// Check whether the selector matches the given pseudo-element of `element`
+    #[must_use]
+    pub fn matches_pseudo_element(
+        &self,
+        element: &DomPtr<Element>,
+        pseudo_element: PseudoElementSelector,
+    ) -> bool {
+        self.pseudo_element() == Some(pseudo_element)
+            && self.matches_ignoring_pseudo_element(element)
+    }
+
+    #[must_use]
+    fn matches_ignoring_pseudo_element(&self, element: &DomPtr<Element>) -> bool {
+        let mut compounds = vec![];
+        let mut combinators = vec![];
 
+        let mut components = self.components();
         loop {
-            if components.all(|selector| selector.matches(element)) {
-                return true;
-            }
+            compounds.push(components.by_ref().collect::<Vec<_>>());
 
             let Some(combinator) = components.next_component() else {
                 break;
             };
-
-            // FIXME: use the combinator
-            _ = combinator;
+            combinators.push(combinator);
         }
 
-        false
+        matches_complex_selector(&compounds, &combinators, element)
+    }
+}
+
+/// Match the rightmost compound selector against `element` and the remaining
+/// ones against its ancestors/siblings
+///
+/// `combinators[i]` is the combinator between `compounds[i]` and `compounds[i + 1]`.
+fn matches_complex_selector(
+    compounds: &[Vec<&SelectorComponent>],
+    combinators: &[Combinator],
+    element: &DomPtr<Element>,
+) -> bool {
+    let Some((compound, remaining_compounds)) = compounds.split_last() else {
+        return true;
+    };
+
+    if !compound.iter().all(|component| component.matches(element)) {
+        return false;
+    }
+
+    let Some((combinator, remaining_combinators)) = combinators.split_last() else {
+        return true;
+    };
+
+    let matches_remaining = |element: &DomPtr<Element>| {
+        matches_complex_selector(remaining_compounds, remaining_combinators, element)
+    };
+
+    match combinator {
+        Combinator::Descendant => {
+            let mut ancestor = parent_element(element);
+            while let Some(element) = ancestor {
+                if matches_remaining(&element) {
+                    return true;
+                }
+                ancestor = parent_element(&element);
+            }
+            false
+        },
+        Combinator::Child => {
+            parent_element(element).is_some_and(|parent| matches_remaining(&parent))
+        },
+        Combinator::NextSibling => previous_element_siblings(element)
+            .first()
+            .is_some_and(matches_remaining),
+        Combinator::SubsequentSibling => previous_element_siblings(element)
+            .iter()
+            .any(matches_remaining),
+        Combinator::Column => {
+            // FIXME: implement the column combinator (we don't support tables yet)
+            false
+        },
     }
 }
 
+/// Return the parent of `element`, if it is an [Element]
+///
+/// The walk up the tree stops at the document, since the document itself is never matched by a selector.
+fn parent_element(element: &DomPtr<Element>) -> Option<DomPtr<Element>> {
+    element.borrow().parent_node()?.try_into_type()
+}
+
+/// Return the element siblings preceding `element`, starting with the closest one
+fn previous_element_siblings(element: &DomPtr<Element>) -> Vec<DomPtr<Element>> {
+    let Some(parent) = element.borrow().parent_node() else {
+        return vec![];
+    };
+
+    let parent = parent.borrow();
+    let siblings = parent.children();
+
+    let position = siblings
+        .iter()
+        .position(|sibling| DomPtr::ptr_eq(sibling, element))
+        .expect("Parent node does not contain child");
+
+    siblings[..position]
+        .iter()
+        .rev()
+        .filter_map(DomPtr::try_into_type)
+        .collect()
+}
+
 impl SelectorComponent {
     #[must_use]
     fn specificity(&self) -> Specificity {
@@ -209,6 +347,7 @@ impl SelectorComponent {
             Self::Class(_) => Specificity::new(0, 1, 0),
             Self::Attribute(_) => Specificity::new(0, 1, 0),
             Self::PseudoClass(_) | Self::Hover => Specificity::new(0, 1, 0),
+            Self::PseudoElement(_) => Specificity::new(0, 0, 1),
             Self::Type(type_selector) => type_selector.specificity(),
         }
     }
@@ -221,15 +360,23 @@ impl SelectorComponent {
                 .attributes()
                 .get(&static_interned!("id"))
                 .is_some_and(|attr| attr == id),
-            Self::Class(_) => {
-                // FIXME: implement class selector
-                false
+            Self::Class(class) => Element::class_list(element.clone()).contains(*class),
+            Self::PseudoClass(PseudoClassSelector::Ident(static_interned!("root"))) => {
+                // The root element is the only element whose parent is the document
+                element
+                    .borrow()
+                    .parent_node()
+                    .is_some_and(|parent| parent.is_a::<Document>())
             },
             Self::PseudoClass(_) => {
                 // FIXME: implement pseudo class selectors
                 false
             },
             Self::Hover => element.borrow().is_hovered(),
+            Self::PseudoElement(_) => {
+                // Pseudo-elements are matched by the selector itself
+                true
+            },
             Self::Attribute(attribute_selector) => attribute_selector.matches(element),
             Self::Type(type_selector) => type_selector.matches(element),
         }
@@ -282,10 +429,67 @@ impl From<Combinator> for SelectorComponentOrCombinator {
 
 #[cfg(test)]
 mod tests {
-    use crate::css::selectors::{AttributeMatcher, AttributeModifier, NamespacePrefix};
+    use crate::{
+        css::selectors::{AttributeMatcher, AttributeModifier, NamespacePrefix},
+        dom::{dom_objects::Node, ElementCustomState},
+        infra::Namespace,
+    };
 
     use super::*;
 
+    /// Build the following tree:
+    ///
+    /// ```text
+    /// #document
+    ///   html
+    ///     body
+    ///       div
+    ///         p#first
+    ///         span#second
+    ///         p#third
+    ///           em#deep
+    /// ```
+    ///
+    /// The document is returned too, since the tree only holds weak references to its parents
+    fn make_tree() -> (DomPtr<Node>, Vec<DomPtr<Element>>) {
+        let document: DomPtr<Node> = DomPtr::new(Document::default()).upcast();
+
+        let element = |local_name: &str, id: Option<&str>| {
+            let mut element = Element::new(
+                Namespace::HTML,
+                None,
+                local_name.into(),
+                ElementCustomState::Uncustomized,
+                None,
+                None,
+            );
+
+            if let Some(id) = id {
+                element.append_attribute(static_interned!("id"), id.into());
+            }
+
+            DomPtr::new(element)
+        };
+
+        let html = element("html", None);
+        let body = element("body", None);
+        let div = element("div", None);
+        let first = element("p", Some("first"));
+        let second = element("span", Some("second"));
+        let third = element("p", Some("third"));
+        let deep = element("em", Some("deep"));
+
+        Node::append_child(document.clone(), html.clone().upcast());
+        Node::append_child(html.clone().upcast(), body.clone().upcast());
+        Node::append_child(body.clone().upcast(), div.clone().upcast());
+        Node::append_child(div.clone().upcast(), first.clone().upcast());
+        Node::append_child(div.clone().upcast(), second.clone().upcast());
+        Node::append_child(div.clone().upcast(), third.clone().upcast());
+        Node::append_child(third.clone().upcast(), deep.clone().upcast());
+
+        (document, vec![html, body, div, first, second, third, deep])
+    }
+
     #[test]
     fn parse_id_selector() {
         let selector = Selector::parse_from_str("#foo").unwrap();
@@ -496,6 +700,41 @@ mod tests {
         assert!(components.next_component().is_none());
     }
 
+    #[test]
+    fn parse_pseudo_element_selector() {
+        let pseudo_element = |source| Selector::parse_from_str(source).unwrap().pseudo_element();
+
+        assert_eq!(
+            pseudo_element("p::before"),
+            Some(PseudoElementSelector::Before)
+        );
+        assert_eq!(
+            pseudo_element("p .foo::after"),
+            Some(PseudoElementSelector::After)
+        );
+        assert_eq!(pseudo_element("p"), None);
+
+        // Legacy syntax
+        assert_eq!(
+            pseudo_element(":before"),
+            Some(PseudoElementSelector::Before)
+        );
+        assert_eq!(
+            pseudo_element("div:after"),
+            Some(PseudoElementSelector::After)
+        );
+
+        // Pseudo elements must be the last component
+        assert!(Selector::parse_from_str("p::before span").is_err());
+        assert!(Selector::parse_from_str("p::after.foo").is_err());
+        assert!(Selector::parse_from_str("p::unknown").is_err());
+
+        assert_eq!(
+            Selector::parse_from_str("p::before").unwrap().specificity(),
+            Specificity::new(0, 0, 2)
+        );
+    }
+
     #[test]
     fn div_selector_with_hover() {
         let source = "div:hover";
@@ -512,4 +751,87 @@ mod tests {
 
         assert!(components.next_component().is_none());
     }
+
+    #[test]
+    fn specificity_of_compound_selector() {
+        let specificity = |source| Selector::parse_from_str(source).unwrap().specificity();
+
+        assert_eq!(specificity("div"), Specificity::new(0, 0, 1));
+        assert_eq!(specificity("div.foo#bar"), Specificity::new(1, 1, 1));
+        assert_eq!(
+            specificity(".foo.bar[baz]:hover"),
+            Specificity::new(0, 4, 0)
+        );
+        assert_eq!(specificity("*"), Specificity::ZERO);
+        assert_eq!(specificity("*.foo"), Specificity::new(0, 1, 0));
+    }
+
+    #[test]
+    fn specificity_of_complex_selector() {
+        let specificity = |source| Selector::parse_from_str(source).unwrap().specificity();
+
+        assert_eq!(specificity("ul li"), Specificity::new(0, 0, 2));
+        assert_eq!(
+            specificity("#nav > ul li.active"),
+            Specificity::new(1, 1, 2)
+        );
+        assert_eq!(specificity("body * p"), Specificity::new(0, 0, 2));
+        assert!(specificity("#a") > specificity("div.b.c.d p"));
+    }
+
+    #[test]
+    fn match_combinators() {
+        let (_document, elements) = make_tree();
+        let [html, body, div, first, second, third, deep] = elements.as_slice() else {
+            unreachable!()
+        };
+
+        let matches = |source, element| Selector::parse_from_str(source).unwrap().matches(element);
+
+        // Descendant combinator
+        assert!(matches("div em", deep));
+        assert!(matches("html body em", deep));
+        assert!(matches("div #deep", deep));
+        assert!(!matches("span em", deep));
+        assert!(!matches("em div", div));
+
+        // Child combinator
+        assert!(matches("p > em", deep));
+        assert!(matches("body > div > p", first));
+        assert!(!matches("div > em", deep));
+        assert!(!matches("body > p", third));
+
+        // Next-sibling combinator
+        assert!(matches("p + span", second));
+        assert!(matches("span + p", third));
+        assert!(!matches("p + p", third));
+        assert!(!matches("span + p", first));
+
+        // Subsequent-sibling combinator
+        assert!(matches("p ~ p", third));
+        assert!(matches("#first ~ #third", third));
+        assert!(!matches("p ~ p", first));
+        assert!(!matches("#third ~ span", second));
+
+        // Mixed combinators
+        assert!(matches("body div > p + span ~ p > em", deep));
+        assert!(!matches("body div > span + p ~ p > em", deep));
+
+        // The document is never matched by a selector
+        assert!(matches(":root", html));
+        assert!(matches(":root > body", body));
+        assert!(!matches(":root", body));
+        assert!(!matches("* html", html));
+        assert!(!matches("* > html", html));
+        assert!(!matches("* :root", html));
+
+        // A selector with a pseudo-element never matches the element itself
+        assert!(!matches("p::before", first));
+        assert!(Selector::parse_from_str("div > p::before")
+            .unwrap()
+            .matches_pseudo_element(first, PseudoElementSelector::Before));
+        assert!(!Selector::parse_from_str("div > p::before")
+            .unwrap()
+            .matches_pseudo_element(first, PseudoElementSelector::After));
+    }
 }
@@ -98,6 +98,10 @@ impl TypeSelector {
     }
 
     pub fn specificity(&self) -> Specificity {
-        Specificity::new(0, 0, 1)
+        match self {
+            // The universal selector does not contribute to the specificity
+            Self::Universal(_) => Specificity::ZERO,
+            Self::Typename(_) => Specificity::new(0, 0, 1),
+        }
     }
 }
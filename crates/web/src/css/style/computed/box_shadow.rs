@@ -0,0 +1,34 @@
+//! <https://drafts.csswg.org/css-backgrounds/#box-shadow>
+
+use crate::css::{layout::Pixels, values::Color};
+
+/// <https://drafts.csswg.org/css-backgrounds/#box-shadow>
+#[derive(Clone, Debug, Default)]
+pub struct BoxShadow {
+    shadows: Vec<Shadow>,
+}
+
+/// <https://drafts.csswg.org/css-backgrounds/#typedef-shadow>
+#[derive(Clone, Copy, Debug)]
+pub struct Shadow {
+    /// The color of the shadow, `None` for `currentcolor`
+    pub color: Option<Color>,
+    pub offset_x: Pixels,
+    pub offset_y: Pixels,
+    pub blur_radius: Pixels,
+    pub spread_distance: Pixels,
+    pub inset: bool,
+}
+
+impl BoxShadow {
+    #[must_use]
+    pub fn new(shadows: Vec<Shadow>) -> Self {
+        Self { shadows }
+    }
+
+    /// The shadows, from front to back
+    #[must_use]
+    pub fn shadows(&self) -> &[Shadow] {
+        &self.shadows
+    }
+}
@@ -0,0 +1,67 @@
+//! <https://drafts.csswg.org/css-values-4/#typedef-length-percentage>
+
+use crate::css::{layout::Pixels, values::Percentage};
+
+/// A length, a percentage or the sum of both
+///
+/// Percentages can only be resolved during layout, once the size
+/// they refer to is known.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LengthPercentage {
+    length: Pixels,
+    percentage: Option<Percentage>,
+}
+
+impl LengthPercentage {
+    pub const ZERO: Self = Self::from_length(Pixels::ZERO);
+
+    #[inline]
+    #[must_use]
+    pub const fn new(length: Pixels, percentage: Option<Percentage>) -> Self {
+        Self { length, percentage }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn from_length(length: Pixels) -> Self {
+        Self::new(length, None)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn from_percentage(percentage: Percentage) -> Self {
+        Self::new(Pixels::ZERO, Some(percentage))
+    }
+
+    /// Return true if the value depends on the size that percentages resolve against
+    #[inline]
+    #[must_use]
+    pub const fn has_percentage(&self) -> bool {
+        self.percentage.is_some()
+    }
+
+    #[must_use]
+    pub fn resolve_against(self, percent_of: Pixels) -> Pixels {
+        match self.percentage {
+            Some(percentage) => self.length + percent_of * percentage,
+            None => self.length,
+        }
+    }
+
+    /// Like [resolve_against](Self::resolve_against), but returns `None` if the value contains
+    /// a percentage and the size it refers to is not known
+    #[must_use]
+    pub fn try_resolve_against(self, percent_of: Option<Pixels>) -> Option<Pixels> {
+        match (self.percentage, percent_of) {
+            (None, _) => Some(self.length),
+            (Some(_), Some(percent_of)) => Some(self.resolve_against(percent_of)),
+            (Some(_), None) => None,
+        }
+    }
+}
+
+impl Default for LengthPercentage {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
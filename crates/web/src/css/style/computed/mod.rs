@@ -1,14 +1,15 @@
 mod border;
+mod box_shadow;
+mod length_percentage;
 mod line_height;
 
-use crate::css::{
-    layout::Pixels,
-    values::{AutoOr, PercentageOr},
-};
+use crate::css::{layout::Pixels, values::AutoOr};
 
 use super::specified;
 
 pub use border::Border;
+pub use box_shadow::{BoxShadow, Shadow};
+pub use length_percentage::LengthPercentage;
 pub use line_height::LineHeight;
 
 /// </// <https://drafts.csswg.org/css-backgrounds/#background-color>>
@@ -26,6 +27,9 @@ pub type BorderWidth = Pixels;
 /// <https://drafts.csswg.org/css2/#propdef-clear>
 pub type Clear = specified::Clear;
 
+/// <https://drafts.csswg.org/css-content/#content-property>
+pub type Content = specified::Content;
+
 /// <https://drafts.csswg.org/css-ui/#cursor>
 pub type Cursor = specified::Cursor;
 
@@ -45,7 +49,7 @@ pub type FontSize = Pixels;
 pub type FontStyle = specified::FontStyle;
 
 /// <https://drafts.csswg.org/css-position/#inset-properties>
-pub type Inset = AutoOr<PercentageOr<Length>>;
+pub type Inset = AutoOr<LengthPercentage>;
 
 /// <https://drafts.csswg.org/css-align-3/#propdef-justify-self>
 pub type JustifySelf = specified::JustifySelf;
@@ -62,10 +66,10 @@ pub type LineWidth = Pixels;
 pub type ListStyleType = specified::ListStyleType;
 
 /// <https://drafts.csswg.org/css2/#value-def-margin-width>
-pub type Margin = AutoOr<PercentageOr<Length>>;
+pub type Margin = AutoOr<LengthPercentage>;
 
 /// <https://drafts.csswg.org/css2/#value-def-padding-width>
-pub type Padding = PercentageOr<Length>;
+pub type Padding = LengthPercentage;
 
 /// <https://drafts.csswg.org/css-position/#position-property>
 pub type Position = specified::Position;
@@ -0,0 +1,377 @@
+//! Custom properties (`--foo: bar`) and `var()` substitution
+//!
+//! <https://drafts.csswg.org/css-variables/>
+
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    css::{syntax::Token, Origin, ParseError, Parser, StyleProperty},
+    static_interned, InternedString,
+};
+
+/// A property whose value is kept as a list of tokens
+///
+/// This is used for custom properties (whose value is never parsed) and for properties
+/// whose value contains `var()` references (which can only be parsed after substitution).
+#[derive(Clone, Debug)]
+pub struct UnparsedProperty {
+    name: InternedString,
+    value: Rc<[Token]>,
+}
+
+/// The computed values of all custom properties on an element
+///
+/// Custom properties that are not present have the [guaranteed-invalid value](https://drafts.csswg.org/css-variables/#guaranteed-invalid-value).
+#[derive(Clone, Debug, Default)]
+pub struct CustomProperties {
+    /// Custom properties are inherited by default, so the values are usually shared
+    /// between lots of elements
+    values: Rc<HashMap<InternedString, Rc<[Token]>>>,
+}
+
+/// <https://drafts.csswg.org/css-variables/#custom-property>
+#[must_use]
+pub fn is_custom_property_name(name: InternedString) -> bool {
+    name.to_string().starts_with("--")
+}
+
+/// Return true if there is a `var()` function anywhere within the value
+#[must_use]
+pub fn contains_variable_reference(value: &[Token]) -> bool {
+    value
+        .iter()
+        .any(|token| matches!(token, Token::Function(static_interned!("var"))))
+}
+
+impl UnparsedProperty {
+    #[must_use]
+    pub fn new(name: InternedString, value: Vec<Token>) -> Self {
+        Self {
+            name,
+            value: value.into(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> InternedString {
+        self.name
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn value(&self) -> &[Token] {
+        &self.value
+    }
+
+    /// Substitute all `var()` references and parse the resulting value
+    ///
+    /// If this fails, the property is [invalid at computed-value time](https://drafts.csswg.org/css-variables/#invalid-at-computed-value-time).
+    pub fn resolve(
+        &self,
+        custom_properties: &CustomProperties,
+    ) -> Result<StyleProperty, ParseError> {
+        let substituted =
+            substitute_variables(&self.value, &mut |name| custom_properties.get(name))
+                .ok_or(ParseError)?;
+
+        // NOTE: The origin of the parser is irrelevant for parsing property values
+        Parser::from_tokens(substituted.into(), Origin::Author)
+            .parse_complete_property_value(self.name)
+    }
+}
+
+impl CustomProperties {
+    #[must_use]
+    pub fn get(&self, name: InternedString) -> Option<Rc<[Token]>> {
+        self.values.get(&name).cloned()
+    }
+
+    /// Compute the custom properties of an element
+    ///
+    /// `declarations` must be sorted in cascade order (least important first).
+    /// Custom properties that are not declared are inherited from the parent.
+    #[must_use]
+    pub fn cascade<'a, I>(inherited: &Self, declarations: I) -> Self
+    where
+        I: IntoIterator<Item = &'a UnparsedProperty>,
+    {
+        let mut declared = HashMap::new();
+        for declaration in declarations {
+            declared.insert(declaration.name, declaration.value.clone());
+        }
+
+        if declared.is_empty() {
+            return inherited.clone();
+        }
+
+        let mut resolver = Resolver {
+            declared: &declared,
+            inherited,
+            resolved: HashMap::new(),
+            in_progress: vec![],
+            cyclic: HashSet::new(),
+        };
+
+        let mut values = inherited.values.as_ref().clone();
+        for &name in declared.keys() {
+            match resolver.resolve(name) {
+                Some(value) => {
+                    values.insert(name, value);
+                },
+                None => {
+                    values.remove(&name);
+                },
+            }
+        }
+
+        Self {
+            values: Rc::new(values),
+        }
+    }
+}
+
+/// Resolves `var()` references within the custom properties declared on an element
+struct Resolver<'a> {
+    /// The (unresolved) values of custom properties declared on the element
+    declared: &'a HashMap<InternedString, Rc<[Token]>>,
+
+    /// The (resolved) custom properties of the parent element
+    inherited: &'a CustomProperties,
+
+    resolved: HashMap<InternedString, Option<Rc<[Token]>>>,
+
+    /// The custom properties that are currently being resolved, used to detect cycles
+    in_progress: Vec<InternedString>,
+
+    /// Custom properties that are part of a dependency cycle
+    cyclic: HashSet<InternedString>,
+}
+
+impl<'a> Resolver<'a> {
+    /// Return the computed value of a custom property or `None` if it has the guaranteed-invalid value
+    fn resolve(&mut self, name: InternedString) -> Option<Rc<[Token]>> {
+        if let Some(resolved) = self.resolved.get(&name) {
+            return resolved.clone();
+        }
+
+        let declared = self.declared;
+        let Some(value) = declared.get(&name) else {
+            return self.inherited.get(name);
+        };
+
+        if let Some(index) = self.in_progress.iter().position(|&n| n == name) {
+            // https://drafts.csswg.org/css-variables/#cycles
+            // If there is a cycle in the dependency graph, all the custom properties in
+            // the cycle are invalid at computed-value time.
+            self.cyclic
+                .extend(self.in_progress[index..].iter().copied());
+            return None;
+        }
+
+        self.in_progress.push(name);
+        let substituted = substitute_variables(value, &mut |name| self.resolve(name));
+        self.in_progress.pop();
+
+        let resolved = if self.cyclic.contains(&name) {
+            None
+        } else {
+            substituted.map(Rc::from)
+        };
+
+        self.resolved.insert(name, resolved.clone());
+        resolved
+    }
+}
+
+/// Replace all `var()` functions in `value` with the values of the custom properties they reference
+///
+/// Returns `None` if the result is invalid at computed-value time, for example because
+/// a referenced custom property has the guaranteed-invalid value and there is no fallback.
+///
+/// <https://drafts.csswg.org/css-variables/#substitute-a-var>
+fn substitute_variables<F>(value: &[Token], lookup: &mut F) -> Option<Vec<Token>>
+where
+    F: FnMut(InternedString) -> Option<Rc<[Token]>>,
+{
+    let mut result = Vec::with_capacity(value.len());
+    let mut tokens = value.iter();
+
+    while let Some(token) = tokens.next() {
+        if !matches!(token, Token::Function(static_interned!("var"))) {
+            result.push(token.clone());
+            continue;
+        }
+
+        // Collect the arguments of the var() function
+        let mut arguments = vec![];
+        let mut nesting_level: usize = 0;
+        loop {
+            match tokens.next()? {
+                Token::ParenthesisClose if nesting_level == 0 => break,
+                token @ Token::ParenthesisClose => {
+                    nesting_level -= 1;
+                    arguments.push(token);
+                },
+                token @ (Token::Function(_) | Token::ParenthesisOpen) => {
+                    nesting_level += 1;
+                    arguments.push(token);
+                },
+                token => arguments.push(token),
+            }
+        }
+
+        // var() = var( <custom-property-name> , <declaration-value>? )
+        let (name, fallback) = match arguments.iter().position(|t| **t == Token::Comma) {
+            Some(comma) => (&arguments[..comma], Some(&arguments[comma + 1..])),
+            None => (&arguments[..], None),
+        };
+
+        let mut name_tokens = name.iter().filter(|token| !token.is_whitespace());
+        let (Some(Token::Ident(name)), None) = (name_tokens.next(), name_tokens.next()) else {
+            return None;
+        };
+
+        if !is_custom_property_name(*name) {
+            return None;
+        }
+
+        // 1. If the value of the custom property named by the first argument to the var() function
+        //    is anything but the initial value, replace the var() function by the value of the
+        //    corresponding custom property.
+        if let Some(value) = lookup(*name) {
+            result.extend(value.iter().cloned());
+            continue;
+        }
+
+        // 2. Otherwise, if the var() function has a fallback value as its second argument,
+        //    replace the var() function by the fallback value. If there are any var() references
+        //    in the fallback, substitute them as well.
+        // 3. Otherwise, the property containing the var() function is invalid at computed-value time.
+        // The whitespace after the comma is not part of the fallback
+        let fallback: Vec<Token> = fallback?
+            .iter()
+            .map(|&token| token.clone())
+            .skip_while(Token::is_whitespace)
+            .collect();
+        result.extend(substitute_variables(&fallback, lookup)?);
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css::properties::Important;
+
+    fn tokens(source: &str) -> Vec<Token> {
+        let mut parser = Parser::new(source, Origin::Author);
+        std::iter::from_fn(|| parser.next_token()).collect()
+    }
+
+    fn declare(declarations: &[(&str, &str)]) -> Vec<UnparsedProperty> {
+        declarations
+            .iter()
+            .map(|(name, value)| UnparsedProperty::new((*name).into(), tokens(value)))
+            .collect()
+    }
+
+    #[test]
+    fn substitute_and_fallback() {
+        let parent = CustomProperties::cascade(
+            &CustomProperties::default(),
+            &declare(&[("--color", "red"), ("--size", "3px")]),
+        );
+
+        let child = CustomProperties::cascade(
+            &parent,
+            &declare(&[
+                ("--size", "5px"),
+                ("--border", "var(--size) solid var(--color)"),
+                ("--missing", "var(--undefined)"),
+                ("--fallback", "var(--undefined, var(--color, blue))"),
+            ]),
+        );
+
+        // --color is inherited from the parent, --size is overridden
+        assert_eq!(
+            child.get("--color".into()).as_deref(),
+            Some(&tokens("red")[..])
+        );
+        assert_eq!(
+            child.get("--border".into()).as_deref(),
+            Some(&tokens("5px solid red")[..])
+        );
+        assert_eq!(child.get("--missing".into()), None);
+        assert_eq!(
+            child.get("--fallback".into()).as_deref(),
+            Some(&tokens("red")[..])
+        );
+    }
+
+    #[test]
+    fn cyclic_references_are_invalid() {
+        let custom_properties = CustomProperties::cascade(
+            &CustomProperties::default(),
+            &declare(&[
+                ("--a", "var(--b)"),
+                ("--b", "var(--a, 1px)"),
+                ("--self", "var(--self, 2px)"),
+                ("--uses-cycle", "var(--a, 3px)"),
+            ]),
+        );
+
+        assert_eq!(custom_properties.get("--a".into()), None);
+        assert_eq!(custom_properties.get("--b".into()), None);
+        assert_eq!(custom_properties.get("--self".into()), None);
+        assert_eq!(
+            custom_properties.get("--uses-cycle".into()).as_deref(),
+            Some(&tokens("3px")[..])
+        );
+    }
+
+    #[test]
+    fn resolve_property() {
+        let custom_properties = CustomProperties::cascade(
+            &CustomProperties::default(),
+            &declare(&[("--width", "7px")]),
+        );
+
+        let width = UnparsedProperty::new("width".into(), tokens("var(--width)"));
+        assert!(matches!(
+            width.resolve(&custom_properties),
+            Ok(StyleProperty::Width(_))
+        ));
+
+        let invalid = UnparsedProperty::new("width".into(), tokens("var(--height)"));
+        assert!(invalid.resolve(&custom_properties).is_err());
+
+        let fallback = UnparsedProperty::new("width".into(), tokens("var(--height, 1px)"));
+        assert!(fallback.resolve(&custom_properties).is_ok());
+    }
+
+    #[test]
+    fn parse_declarations() {
+        let mut parser = Parser::new(
+            "--foo: { a b } c !important; width: var(--foo)",
+            Origin::Author,
+        );
+
+        let custom = parser.consume_declaration().unwrap();
+        assert_eq!(custom.important, Important::Yes);
+        let StyleProperty::Custom(custom) = custom.value else {
+            panic!("expected a custom property");
+        };
+        assert_eq!(custom.name(), "--foo".into());
+        assert_eq!(custom.value(), tokens("{ a b } c"));
+
+        assert_eq!(parser.next_token(), Some(Token::Semicolon));
+
+        let width = parser.consume_declaration().unwrap();
+        assert!(matches!(width.value, StyleProperty::WithVariables(_)));
+    }
+}
@@ -4,8 +4,13 @@ use super::{
 };
 
 pub mod computed;
+mod custom_properties;
 pub mod specified;
 
+pub use custom_properties::{
+    contains_variable_reference, is_custom_property_name, CustomProperties, UnparsedProperty,
+};
+
 pub trait ToComputedStyle {
     /// <https://www.w3.org/TR/css-cascade/#computed-value>
     type Computed;
@@ -25,6 +30,45 @@ pub struct StyleContext {
     pub viewport: Size<Pixels>,
 }
 
+/// Contains everything that is needed to resolve a length to pixels
+#[derive(Clone, Copy, Debug)]
+pub struct ResolutionContext {
+    /// The font size of the element, `em` depends on this
+    pub font_size: Pixels,
+
+    /// The font size of the root element, `rem` depends on this
+    pub root_font_size: Pixels,
+
+    /// The used line height of the element, `lh` depends on this
+    pub line_height: Pixels,
+
+    /// The used line height of the root element, `rlh` depends on this
+    pub root_line_height: Pixels,
+
+    /// The size of the viewport
+    pub viewport: Size<Pixels>,
+
+    /// The size that percentages refer to, usually one of the dimensions
+    /// of the containing block
+    pub percentage_basis: Pixels,
+}
+
+impl From<&StyleContext> for ResolutionContext {
+    fn from(context: &StyleContext) -> Self {
+        // The line height is not known during style computation
+        let line_height = |font_size| computed::LineHeight::Normal.used_value(font_size);
+
+        Self {
+            font_size: context.font_size,
+            root_font_size: context.root_font_size,
+            line_height: line_height(context.font_size),
+            root_line_height: line_height(context.root_font_size),
+            viewport: context.viewport,
+            percentage_basis: Pixels::ZERO,
+        }
+    }
+}
+
 impl StyleContext {
     #[must_use]
     pub fn new(viewport: Size<Pixels>) -> Self {
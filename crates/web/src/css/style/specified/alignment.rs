@@ -2,16 +2,16 @@ use crate::{
     css::{
         self,
         style::{computed, StyleContext, ToComputedStyle},
-        values::{AutoOr, PercentageOr},
+        values::AutoOr,
         CSSParse,
     },
     static_interned, InternedString,
 };
 
-use super::Length;
+use super::LengthPercentage;
 
 /// The value of an [inset property](https://drafts.csswg.org/css-position/#inset-properties)
-pub type Inset = AutoOr<PercentageOr<Length>>;
+pub type Inset = AutoOr<LengthPercentage>;
 
 /// <https://drafts.csswg.org/css-align-3/#typedef-overflow-position>
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
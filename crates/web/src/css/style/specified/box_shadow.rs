@@ -0,0 +1,225 @@
+//! <https://drafts.csswg.org/css-backgrounds/#box-shadow>
+
+use crate::{
+    css::{
+        style::{computed, StyleContext, ToComputedStyle},
+        syntax::Token,
+        values::Color,
+        CSSParse, ParseError, Parser,
+    },
+    static_interned,
+};
+
+use super::Length;
+
+/// <https://drafts.csswg.org/css-backgrounds/#box-shadow>
+#[derive(Clone, Debug, Default)]
+pub struct BoxShadow {
+    /// The shadows, from front to back
+    ///
+    /// `box-shadow: none` is represented as an empty list.
+    shadows: Vec<Shadow>,
+}
+
+/// A single shadow within a [BoxShadow]
+///
+/// <https://drafts.csswg.org/css-backgrounds/#typedef-shadow>
+#[derive(Clone, Copy, Debug)]
+pub struct Shadow {
+    /// The color of the shadow, `None` for `currentcolor`
+    pub color: Option<Color>,
+    pub offset_x: Length,
+    pub offset_y: Length,
+
+    /// Must not be negative
+    pub blur_radius: Length,
+    pub spread_distance: Length,
+
+    /// Whether the shadow is drawn inside the border box instead of outside
+    pub inset: bool,
+}
+
+impl BoxShadow {
+    #[must_use]
+    pub fn shadows(&self) -> &[Shadow] {
+        &self.shadows
+    }
+}
+
+impl<'a> CSSParse<'a> for BoxShadow {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        if let Some(Token::Ident(static_interned!("none"))) =
+            parser.peek_token_ignoring_whitespace(0)
+        {
+            _ = parser.next_token_ignoring_whitespace();
+            return Ok(Self::default());
+        }
+
+        let mut shadows = vec![parser.parse()?];
+        while let Some(Token::Comma) = parser.peek_token_ignoring_whitespace(0) {
+            _ = parser.next_token_ignoring_whitespace();
+            shadows.push(parser.parse()?);
+        }
+
+        Ok(Self { shadows })
+    }
+}
+
+impl<'a> CSSParse<'a> for Shadow {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        // <shadow> = <color>? && [<length>{2} <length [0,∞]>? <length>?] && inset?
+        let mut color = None;
+        let mut lengths = None;
+        let mut inset = false;
+
+        // The three components can appear in any order
+        for _ in 0..3 {
+            match parser.peek_token_ignoring_whitespace(0) {
+                Some(Token::Ident(static_interned!("inset"))) if !inset => {
+                    _ = parser.next_token_ignoring_whitespace();
+                    inset = true;
+                },
+                Some(Token::Dimension(..) | Token::Number(_)) if lengths.is_none() => {
+                    let offset_x = parser.parse()?;
+                    let offset_y = parser.parse()?;
+                    let blur_radius: Option<Length> = parser.parse_optional();
+                    let spread_distance = parser.parse_optional();
+
+                    if blur_radius.is_some_and(|radius| radius.is_negative()) {
+                        return Err(ParseError);
+                    }
+
+                    lengths = Some((offset_x, offset_y, blur_radius, spread_distance));
+                },
+                Some(Token::Ident(static_interned!("currentcolor"))) if color.is_none() => {
+                    _ = parser.next_token_ignoring_whitespace();
+                    color = Some(None);
+                },
+                Some(Token::Ident(_) | Token::Hash(..) | Token::Function(_)) if color.is_none() => {
+                    color = Some(Some(parser.parse()?));
+                },
+                _ => break,
+            }
+        }
+
+        let Some((offset_x, offset_y, blur_radius, spread_distance)) = lengths else {
+            return Err(ParseError);
+        };
+
+        let shadow = Self {
+            color: color.flatten(),
+            offset_x,
+            offset_y,
+            blur_radius: blur_radius.unwrap_or(Length::ZERO),
+            spread_distance: spread_distance.unwrap_or(Length::ZERO),
+            inset,
+        };
+
+        Ok(shadow)
+    }
+}
+
+impl ToComputedStyle for BoxShadow {
+    type Computed = computed::BoxShadow;
+
+    fn to_computed_style(&self, context: &StyleContext) -> Self::Computed {
+        let shadows = self
+            .shadows
+            .iter()
+            .map(|shadow| computed::Shadow {
+                color: shadow.color,
+                offset_x: shadow.offset_x.to_computed_style(context),
+                offset_y: shadow.offset_y.to_computed_style(context),
+                blur_radius: shadow.blur_radius.to_computed_style(context),
+                spread_distance: shadow.spread_distance.to_computed_style(context),
+                inset: shadow.inset,
+            })
+            .collect();
+
+        computed::BoxShadow::new(shadows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css::layout::{Pixels, Size};
+
+    fn parse(source: &str) -> Result<computed::BoxShadow, ParseError> {
+        let context = StyleContext::new(Size {
+            width: Pixels(800.),
+            height: Pixels(600.),
+        });
+
+        BoxShadow::parse_from_str(source).map(|value| value.to_computed_style(&context))
+    }
+
+    #[test]
+    fn single_shadow() {
+        let box_shadow = parse("1px 2px").unwrap();
+        let [shadow] = box_shadow.shadows() else {
+            panic!("expected exactly one shadow");
+        };
+        assert_eq!(shadow.offset_x, Pixels(1.));
+        assert_eq!(shadow.offset_y, Pixels(2.));
+        assert_eq!(shadow.blur_radius, Pixels::ZERO);
+        assert_eq!(shadow.spread_distance, Pixels::ZERO);
+        assert_eq!(shadow.color, None);
+        assert!(!shadow.inset);
+
+        let box_shadow = parse("red 1px 2px 3px -4px").unwrap();
+        let [shadow] = box_shadow.shadows() else {
+            panic!("expected exactly one shadow");
+        };
+        assert_eq!(shadow.blur_radius, Pixels(3.));
+        assert_eq!(shadow.spread_distance, Pixels(-4.));
+        assert_eq!(shadow.color, Some(Color::RED));
+
+        assert!(parse("none").unwrap().shadows().is_empty());
+    }
+
+    #[test]
+    fn multiple_shadows() {
+        let box_shadow = parse("1px 1px blue, inset 0 0 5px, 2px 2px currentcolor").unwrap();
+        let shadows = box_shadow.shadows();
+        assert_eq!(shadows.len(), 3);
+
+        assert_eq!(shadows[0].color, Some(Color::BLUE));
+        assert!(shadows[1].inset);
+        assert_eq!(shadows[1].blur_radius, Pixels(5.));
+        assert_eq!(shadows[1].color, None);
+        assert_eq!(shadows[2].offset_x, Pixels(2.));
+        assert_eq!(shadows[2].color, None);
+    }
+
+    #[test]
+    fn inset_position() {
+        for source in [
+            "inset 1px 2px red",
+            "1px 2px inset red",
+            "1px 2px red inset",
+            "red inset 1px 2px",
+        ] {
+            let box_shadow = parse(source).unwrap();
+            let [shadow] = box_shadow.shadows() else {
+                panic!("expected exactly one shadow in {source:?}");
+            };
+            assert!(shadow.inset, "{source:?}");
+            assert_eq!(shadow.color, Some(Color::RED), "{source:?}");
+            assert_eq!(shadow.offset_y, Pixels(2.), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_shadows() {
+        assert!(parse("").is_err());
+        assert!(parse("1px").is_err());
+        assert!(parse("red").is_err());
+        assert!(parse("1px 1px -1px").is_err());
+        assert!(parse("inset inset 1px 1px").is_err());
+        assert!(parse("1px 1px red blue").is_err());
+        assert!(parse("1px 1px 1px 1px 1px").is_err());
+        assert!(parse("1px 1px,").is_err());
+        assert!(parse("none, 1px 1px").is_err());
+    }
+}
@@ -0,0 +1,392 @@
+//! <https://drafts.csswg.org/css-values-4/#calc-func>
+
+use crate::{
+    css::{
+        layout::Pixels,
+        style::{computed, StyleContext, ToComputedStyle},
+        syntax::Token,
+        values::Percentage,
+        CSSParse, ParseError, Parser,
+    },
+    static_interned,
+};
+
+use super::Length;
+
+/// A mathematical expression like `calc(100% - 2em)`
+///
+/// <https://drafts.csswg.org/css-values-4/#calc-func>
+#[derive(Clone, Debug)]
+pub struct Calc {
+    root: CalcNode,
+}
+
+/// <https://drafts.csswg.org/css-values-4/#calculation-tree>
+#[derive(Clone, Debug)]
+enum CalcNode {
+    Number(f32),
+    Length(Length),
+    Percentage(Percentage),
+    Sum(Box<Self>, Box<Self>),
+    Difference(Box<Self>, Box<Self>),
+    Product(Box<Self>, Box<Self>),
+    Quotient(Box<Self>, Box<Self>),
+}
+
+/// <https://drafts.csswg.org/css-values-4/#determine-the-type-of-a-calculation>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalcType {
+    Number,
+    Length,
+    Percentage,
+
+    /// The result of adding a length to a percentage, which
+    /// can only be resolved once the percentage basis is known
+    LengthPercentage,
+}
+
+/// The result of evaluating a [CalcNode]
+///
+/// Because of type checking, a value has either a `number` or a `length` and `percentage`.
+#[derive(Clone, Copy, Debug, Default)]
+struct CalcValue {
+    number: f32,
+    length: Pixels,
+    percentage: Option<f32>,
+}
+
+impl Calc {
+    #[inline]
+    #[must_use]
+    pub fn value_type(&self) -> CalcType {
+        self.root
+            .value_type()
+            .expect("calc() expressions are type checked during parsing")
+    }
+}
+
+impl CalcType {
+    /// <https://drafts.csswg.org/css-values-4/#calc-type-checking>
+    fn add(self, other: Self) -> Result<Self, ParseError> {
+        match (self, other) {
+            _ if self == other => Ok(self),
+            (Self::Number, _) | (_, Self::Number) => Err(ParseError),
+
+            // Lengths and percentages can be added, since percentages resolve against lengths
+            _ => Ok(Self::LengthPercentage),
+        }
+    }
+
+    fn multiply(self, other: Self) -> Result<Self, ParseError> {
+        // At least one of the factors must be a number
+        match (self, other) {
+            (Self::Number, other) | (other, Self::Number) => Ok(other),
+            _ => Err(ParseError),
+        }
+    }
+
+    fn divide(self, other: Self) -> Result<Self, ParseError> {
+        // The divisor must be a number
+        match other {
+            Self::Number => Ok(self),
+            _ => Err(ParseError),
+        }
+    }
+}
+
+impl CalcNode {
+    fn value_type(&self) -> Result<CalcType, ParseError> {
+        match self {
+            Self::Number(_) => Ok(CalcType::Number),
+            Self::Length(_) => Ok(CalcType::Length),
+            Self::Percentage(_) => Ok(CalcType::Percentage),
+            Self::Sum(a, b) | Self::Difference(a, b) => a.value_type()?.add(b.value_type()?),
+            Self::Product(a, b) => a.value_type()?.multiply(b.value_type()?),
+            Self::Quotient(a, b) => a.value_type()?.divide(b.value_type()?),
+        }
+    }
+
+    fn evaluate(&self, context: &StyleContext) -> CalcValue {
+        match self {
+            Self::Number(number) => CalcValue {
+                number: *number,
+                ..Default::default()
+            },
+            Self::Length(length) => CalcValue {
+                length: length.to_computed_style(context),
+                ..Default::default()
+            },
+            Self::Percentage(percentage) => CalcValue {
+                percentage: Some(percentage.as_fraction()),
+                ..Default::default()
+            },
+            Self::Sum(a, b) => a.evaluate(context).add(b.evaluate(context)),
+            Self::Difference(a, b) => a.evaluate(context).add(b.evaluate(context).scale(-1.)),
+            Self::Product(a, b) => {
+                let (a_value, b_value) = (a.evaluate(context), b.evaluate(context));
+
+                if a.value_type() == Ok(CalcType::Number) {
+                    b_value.scale(a_value.number)
+                } else {
+                    a_value.scale(b_value.number)
+                }
+            },
+            Self::Quotient(a, b) => a
+                .evaluate(context)
+                .scale(b.evaluate(context).number.recip()),
+        }
+    }
+
+    /// Evaluate an expression that only consists of numbers
+    ///
+    /// Returns `None` if the expression contains lengths or percentages.
+    fn constant_number(&self) -> Option<f32> {
+        let value = match self {
+            Self::Number(number) => *number,
+            Self::Length(_) | Self::Percentage(_) => return None,
+            Self::Sum(a, b) => a.constant_number()? + b.constant_number()?,
+            Self::Difference(a, b) => a.constant_number()? - b.constant_number()?,
+            Self::Product(a, b) => a.constant_number()? * b.constant_number()?,
+            Self::Quotient(a, b) => a.constant_number()? / b.constant_number()?,
+        };
+
+        Some(value)
+    }
+
+    /// <https://drafts.csswg.org/css-values-4/#typedef-calc-sum>
+    fn parse_sum(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let mut sum = Self::parse_product(parser)?;
+
+        loop {
+            // The + and - operators must be surrounded by whitespace
+            if !parser.peek_token(0).is_some_and(Token::is_whitespace) {
+                break;
+            }
+
+            let is_sum = match parser.peek_token(1) {
+                Some(Token::Delim('+')) => true,
+                Some(Token::Delim('-')) => false,
+                _ => break,
+            };
+
+            _ = parser.next_token();
+            _ = parser.next_token();
+            parser.expect_whitespace()?;
+
+            let operand = Box::new(Self::parse_product(parser)?);
+            sum = if is_sum {
+                Self::Sum(Box::new(sum), operand)
+            } else {
+                Self::Difference(Box::new(sum), operand)
+            };
+        }
+
+        Ok(sum)
+    }
+
+    /// <https://drafts.csswg.org/css-values-4/#typedef-calc-product>
+    fn parse_product(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let mut product = Self::parse_value(parser)?;
+
+        loop {
+            let is_product = match parser.peek_token_ignoring_whitespace(0) {
+                Some(Token::Delim('*')) => true,
+                Some(Token::Delim('/')) => false,
+                _ => break,
+            };
+
+            _ = parser.next_token_ignoring_whitespace();
+
+            let operand = Box::new(Self::parse_value(parser)?);
+
+            // Divisors are numbers, so we can catch divisions by zero before evaluating anything
+            if !is_product && operand.constant_number() == Some(0.) {
+                return Err(ParseError);
+            }
+
+            product = if is_product {
+                Self::Product(Box::new(product), operand)
+            } else {
+                Self::Quotient(Box::new(product), operand)
+            };
+        }
+
+        Ok(product)
+    }
+
+    /// <https://drafts.csswg.org/css-values-4/#typedef-calc-value>
+    fn parse_value(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let value = match parser.next_token_ignoring_whitespace() {
+            Some(Token::Number(number)) => Self::Number(number.into()),
+            Some(Token::Dimension(number, unit)) => {
+                Self::Length(Length::from_dimension(number, unit)?)
+            },
+            Some(Token::Percentage(number)) => {
+                Self::Percentage(Percentage::from_css_percentage(number))
+            },
+            Some(Token::ParenthesisOpen | Token::Function(static_interned!("calc"))) => {
+                // Parenthesized expression or nested calc()
+                let sum = Self::parse_sum(parser)?;
+                parser.expect_token(Token::ParenthesisClose)?;
+                sum
+            },
+            _ => return Err(ParseError),
+        };
+
+        Ok(value)
+    }
+}
+
+impl CalcValue {
+    fn add(self, other: Self) -> Self {
+        let percentage = match (self.percentage, other.percentage) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+
+        Self {
+            number: self.number + other.number,
+            length: self.length + other.length,
+            percentage,
+        }
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        Self {
+            number: self.number * factor,
+            length: self.length * factor,
+            percentage: self.percentage.map(|percentage| percentage * factor),
+        }
+    }
+
+    /// Replace `NaN` with zero and infinities with the largest finite values
+    ///
+    /// <https://drafts.csswg.org/css-values-4/#calc-range>
+    fn clamp_to_finite(self) -> Self {
+        let clamp = |value: f32| {
+            if value.is_nan() {
+                0.
+            } else {
+                value.clamp(f32::MIN, f32::MAX)
+            }
+        };
+
+        Self {
+            number: clamp(self.number),
+            length: Pixels(clamp(self.length.0)),
+            percentage: self.percentage.map(clamp),
+        }
+    }
+}
+
+impl<'a> CSSParse<'a> for Calc {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        let Some(Token::Function(static_interned!("calc"))) =
+            parser.next_token_ignoring_whitespace()
+        else {
+            return Err(ParseError);
+        };
+
+        let root = CalcNode::parse_sum(parser)?;
+        parser.expect_token(Token::ParenthesisClose)?;
+
+        // Reject expressions like "1px + 2"
+        root.value_type()?;
+
+        Ok(Self { root })
+    }
+}
+
+impl ToComputedStyle for Calc {
+    type Computed = computed::LengthPercentage;
+
+    fn to_computed_style(&self, context: &StyleContext) -> Self::Computed {
+        let value = self.root.evaluate(context).clamp_to_finite();
+
+        computed::LengthPercentage::new(
+            value.length,
+            value.percentage.map(Percentage::from_fraction),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css::layout::Size;
+
+    fn evaluate(source: &str, percent_of: f32) -> f32 {
+        let context = StyleContext::new(Size {
+            width: Pixels(800.),
+            height: Pixels(600.),
+        });
+
+        let calc = Calc::parse_from_str(source).unwrap();
+        calc.to_computed_style(&context)
+            .resolve_against(Pixels(percent_of))
+            .0
+    }
+
+    #[test]
+    fn operator_precedence() {
+        assert_eq!(evaluate("calc(100% - 20px)", 200.), 180.);
+        assert_eq!(evaluate("calc(10px + 2 * 5px)", 0.), 20.);
+        assert_eq!(evaluate("calc(10px - 4px / 2)", 0.), 8.);
+        assert_eq!(evaluate("calc((10px + 2px) * 3)", 0.), 36.);
+        assert_eq!(evaluate("calc( 1px + 0px )", 0.), 1.);
+        assert_eq!(evaluate("calc(2 * 3 * 1px)", 0.), 6.);
+    }
+
+    #[test]
+    fn nested_calc() {
+        assert_eq!(evaluate("calc(2 * calc(10px + (5% * 2)))", 100.), 40.);
+        assert_eq!(evaluate("calc(calc(50%) - calc(1em))", 100.), 34.);
+        assert_eq!(evaluate("calc(1vw + calc(calc(2px)))", 0.), 10.);
+    }
+
+    #[test]
+    fn type_checking() {
+        let value_type = |source| Calc::parse_from_str(source).map(|calc| calc.value_type());
+
+        assert_eq!(value_type("calc(1 + 2 * 3)"), Ok(CalcType::Number));
+        assert_eq!(value_type("calc(1px * 2)"), Ok(CalcType::Length));
+        assert_eq!(value_type("calc(10% / 2)"), Ok(CalcType::Percentage));
+        assert_eq!(
+            value_type("calc(10% + 3em)"),
+            Ok(CalcType::LengthPercentage)
+        );
+
+        // Lengths cannot be mixed with numbers
+        assert!(value_type("calc(1px + 2)").is_err());
+        assert!(value_type("calc(2 - 10%)").is_err());
+        assert!(value_type("calc(2px * 3px)").is_err());
+        assert!(value_type("calc(10px / 2px)").is_err());
+        assert!(value_type("calc(2 / 1px)").is_err());
+        assert!(value_type("calc(calc(1px) * calc(1%))").is_err());
+    }
+
+    #[test]
+    fn invalid_syntax() {
+        // "+" and "-" must be surrounded by whitespace
+        assert!(Calc::parse_from_str("calc(1px -2px)").is_err());
+        assert!(Calc::parse_from_str("calc(1px +2px)").is_err());
+        assert!(Calc::parse_from_str("calc(1px+ 2px)").is_err());
+        assert!(Calc::parse_from_str("calc(1px - )").is_err());
+        assert!(Calc::parse_from_str("calc()").is_err());
+        assert!(Calc::parse_from_str("calc(1px").is_err());
+        assert!(Calc::parse_from_str("calc(1px 2px)").is_err());
+        assert!(Calc::parse_from_str("calc(1foo)").is_err());
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert!(Calc::parse_from_str("calc(10px / 0)").is_err());
+        assert!(Calc::parse_from_str("calc(10px / (2 - 2))").is_err());
+        assert!(Calc::parse_from_str("calc(10px / calc(0 * 5))").is_err());
+        assert_eq!(evaluate("calc(10px / 0.5)", 0.), 20.);
+
+        // Overflowing values are clamped instead of becoming infinite
+        assert_eq!(evaluate("calc(1e38px * 10)", 0.), f32::MAX);
+        assert_eq!(evaluate("calc(-1e38px * 10)", 0.), f32::MIN);
+    }
+}
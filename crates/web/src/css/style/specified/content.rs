@@ -0,0 +1,96 @@
+//! <https://drafts.csswg.org/css-content/#content-property>
+
+use crate::{
+    css::{
+        style::{computed, StyleContext, ToComputedStyle},
+        syntax::Token,
+        CSSParse, ParseError, Parser,
+    },
+    static_interned,
+};
+
+/// <https://drafts.csswg.org/css-content/#content-property>
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Content {
+    /// Computes to `none` on `::before` and `::after`
+    #[default]
+    Normal,
+
+    /// The pseudo-element does not generate a box
+    None,
+
+    /// The concatenation of one or more strings
+    String(String),
+}
+
+impl Content {
+    /// Return the text of the box generated for a `::before` or `::after` pseudo-element
+    ///
+    /// Returns `None` if no box should be generated.
+    #[must_use]
+    pub fn pseudo_element_text(&self) -> Option<&str> {
+        match self {
+            Self::Normal | Self::None => None,
+            Self::String(text) => Some(text),
+        }
+    }
+}
+
+impl<'a> CSSParse<'a> for Content {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        match parser.peek_token_ignoring_whitespace(0) {
+            Some(Token::Ident(static_interned!("normal"))) => {
+                _ = parser.next_token_ignoring_whitespace();
+                return Ok(Self::Normal);
+            },
+            Some(Token::Ident(static_interned!("none"))) => {
+                _ = parser.next_token_ignoring_whitespace();
+                return Ok(Self::None);
+            },
+            _ => {},
+        }
+
+        let parse_string = |parser: &mut Parser<'a>| match parser.next_token_ignoring_whitespace() {
+            Some(Token::String(s)) => Ok(s),
+            _ => Err(ParseError),
+        };
+
+        let strings = parser.parse_any_number_of(parse_string);
+        if strings.is_empty() {
+            return Err(ParseError);
+        }
+
+        let text = strings.iter().map(ToString::to_string).collect();
+        Ok(Self::String(text))
+    }
+}
+
+impl ToComputedStyle for Content {
+    type Computed = computed::Content;
+
+    fn to_computed_style(&self, context: &StyleContext) -> Self::Computed {
+        _ = context;
+
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_content() {
+        assert_eq!(Content::parse_from_str("normal"), Ok(Content::Normal));
+        assert_eq!(Content::parse_from_str("none"), Ok(Content::None));
+        assert_eq!(
+            Content::parse_from_str("\"•\""),
+            Ok(Content::String("•".to_string()))
+        );
+        assert_eq!(
+            Content::parse_from_str("\"a\" \"b\""),
+            Ok(Content::String("ab".to_string()))
+        );
+        assert!(Content::parse_from_str("foo").is_err());
+    }
+}
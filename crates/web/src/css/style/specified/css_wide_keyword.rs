@@ -0,0 +1,45 @@
+use crate::{
+    css::{CSSParse, ParseError, Parser},
+    static_interned,
+};
+
+/// A keyword that is a valid value for every property
+///
+/// <https://drafts.csswg.org/css-cascade/#defaulting-keywords>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CssWideKeyword {
+    /// <https://drafts.csswg.org/css-cascade/#valdef-all-initial>
+    Initial,
+
+    /// <https://drafts.csswg.org/css-cascade/#valdef-all-inherit>
+    Inherit,
+
+    /// Behaves like `inherit` for inherited properties and like `initial` otherwise
+    ///
+    /// <https://drafts.csswg.org/css-cascade/#valdef-all-unset>
+    Unset,
+}
+
+impl CssWideKeyword {
+    /// Resolve `unset` to either `inherit` or `initial`
+    #[must_use]
+    pub fn resolve(self, is_inherited: bool) -> Self {
+        match self {
+            Self::Unset if is_inherited => Self::Inherit,
+            Self::Unset => Self::Initial,
+            other => other,
+        }
+    }
+}
+
+impl<'a> CSSParse<'a> for CssWideKeyword {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        let keyword = match parser.expect_identifier()? {
+            static_interned!("initial") => Self::Initial,
+            static_interned!("inherit") => Self::Inherit,
+            static_interned!("unset") => Self::Unset,
+            _ => return Err(ParseError),
+        };
+        Ok(keyword)
+    }
+}
@@ -221,7 +221,7 @@ impl<'a> CSSParse<'a> for Display {
                     let _ = parser.next_token_ignoring_whitespace();
                     idents.push(ident)
                 },
-                Some(Token::Semicolon | Token::CurlyBraceClose) => break,
+                Some(Token::Semicolon | Token::CurlyBraceClose) | None => break,
                 _ => return Err(ParseError),
             }
         }
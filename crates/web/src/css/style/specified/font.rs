@@ -0,0 +1,87 @@
+//! <https://drafts.csswg.org/css-fonts/#font-prop>
+
+use crate::{
+    css::{syntax::Token, values::Number, CSSParse, ParseError, Parser},
+    static_interned,
+};
+
+use super::{FontFamily, FontSize, FontStyle, LineHeight};
+
+/// The `font` shorthand
+///
+/// Longhands that are not specified are reset to their initial value.
+///
+/// <https://drafts.csswg.org/css-fonts/#font-prop>
+#[derive(Clone, Debug)]
+pub struct Font {
+    pub style: FontStyle,
+    pub size: FontSize,
+    pub line_height: LineHeight,
+    pub family: FontFamily,
+}
+
+impl<'a> CSSParse<'a> for Font {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        let mut style = None;
+        let mut has_variant = false;
+        let mut has_weight = false;
+
+        // Style, variant and weight may appear in any order before the font size.
+        // "normal" is a valid value for each of them.
+        // FIXME: We don't support font-variant and font-weight yet, so their values are parsed
+        //        but not applied
+        for _ in 0..3 {
+            match parser.peek_token_ignoring_whitespace(0) {
+                Some(Token::Ident(static_interned!("normal"))) => {
+                    _ = parser.next_token_ignoring_whitespace();
+                },
+                Some(Token::Ident(static_interned!("italic") | static_interned!("oblique")))
+                    if style.is_none() =>
+                {
+                    style = Some(parser.parse()?);
+                },
+                Some(Token::Ident(static_interned!("small-caps"))) if !has_variant => {
+                    _ = parser.next_token_ignoring_whitespace();
+                    has_variant = true;
+                },
+                Some(Token::Ident(
+                    static_interned!("bold")
+                    | static_interned!("bolder")
+                    | static_interned!("lighter"),
+                )) if !has_weight => {
+                    _ = parser.next_token_ignoring_whitespace();
+                    has_weight = true;
+                },
+                Some(Token::Number(weight)) if !has_weight && is_valid_font_weight(*weight) => {
+                    _ = parser.next_token_ignoring_whitespace();
+                    has_weight = true;
+                },
+                _ => break,
+            }
+        }
+
+        let size = parser.parse()?;
+
+        let line_height = if parser.peek_token_ignoring_whitespace(0) == Some(&Token::Delim('/')) {
+            _ = parser.next_token_ignoring_whitespace();
+            parser.parse()?
+        } else {
+            LineHeight::Normal
+        };
+
+        let family = parser.parse()?;
+
+        Ok(Self {
+            style: style.unwrap_or(FontStyle::Normal),
+            size,
+            line_height,
+            family,
+        })
+    }
+}
+
+/// <https://drafts.csswg.org/css-fonts/#font-weight-absolute-values>
+#[must_use]
+fn is_valid_font_weight(weight: Number) -> bool {
+    (1. ..=1000.).contains(&f32::from(weight))
+}
@@ -84,11 +84,7 @@ impl Default for FontFamily {
 
 impl<'a> CSSParse<'a> for FontFamily {
     fn parse(parser: &mut css::Parser<'a>) -> Result<Self, css::ParseError> {
-        let mut desired_fonts = vec![];
-
-        while let Some(desired_font) = parser.parse_optional_value(CSSParse::parse) {
-            desired_fonts.push(desired_font);
-        }
+        let desired_fonts = parser.parse_comma_seperated_list(CSSParse::parse);
 
         if desired_fonts.is_empty() {
             return Err(css::ParseError);
@@ -115,7 +111,7 @@ impl<'a> CSSParse<'a> for FontName {
 
 impl<'a> CSSParse<'a> for GenericFontFamily {
     fn parse(parser: &mut css::Parser<'a>) -> Result<Self, css::ParseError> {
-        let parsed_value = match parser.next_token() {
+        let parsed_value = match parser.next_token_ignoring_whitespace() {
             Some(Token::Ident(static_interned!("serif"))) => Self::Serif,
             Some(Token::Ident(static_interned!("sans-serif"))) => Self::SansSerif,
             Some(Token::Ident(static_interned!("cursive"))) => Self::Cursive,
@@ -1,7 +1,7 @@
 use crate::{
     css::{
         layout::Pixels,
-        style::{computed, StyleContext, ToComputedStyle},
+        style::{computed, ResolutionContext, StyleContext, ToComputedStyle},
         syntax::Token,
         values::{Number, Percentage},
         CSSParse, ParseError, Parser,
@@ -127,6 +127,75 @@ impl Length {
         }
     }
 
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        self.value < 0.
+    }
+
+    /// Resolve the length to pixels
+    ///
+    /// Font metrics are not available here, so font-relative units that depend on
+    /// them use the fallbacks from the specification.
+    #[must_use]
+    pub fn resolve(&self, context: &ResolutionContext) -> Pixels {
+        let viewport = context.viewport;
+        let (viewport_min, viewport_max) = if viewport.width < viewport.height {
+            (viewport.width, viewport.height)
+        } else {
+            (viewport.height, viewport.width)
+        };
+
+        match self.unit {
+            Unit::Cm => Pixels(self.value * 96. / 2.54),
+            Unit::Mm => Pixels(self.value * 96. / 2.54 / 10.),
+            Unit::Q => Pixels(self.value * 96. / 2.54 / 40.),
+            Unit::In => Pixels(self.value * 96.),
+            Unit::Pc => Pixels(self.value * 96. / 6.),
+            Unit::Pt => Pixels(self.value * 96. / 72.),
+            Unit::Px => Pixels(self.value),
+
+            // Viewport-relative units
+            Unit::Vw
+            | Unit::Svw
+            | Unit::Lvw
+            | Unit::Dvw
+            | Unit::Vi
+            | Unit::Svi
+            | Unit::Lvi
+            | Unit::Dvi => viewport.width / 100. * self.value,
+            Unit::Vh
+            | Unit::Svh
+            | Unit::Lvh
+            | Unit::Dvh
+            | Unit::Vb
+            | Unit::Svb
+            | Unit::Lvb
+            | Unit::Dvb => viewport.height / 100. * self.value,
+            Unit::Vmin | Unit::Svmin | Unit::Lvmin | Unit::Dvmin => {
+                viewport_min / 100. * self.value
+            },
+            Unit::Vmax | Unit::Svmax | Unit::Lvmax | Unit::Dvmax => {
+                viewport_max / 100. * self.value
+            },
+
+            // Font-relative units
+            Unit::Em => context.font_size * self.value,
+            Unit::Rem => context.root_font_size * self.value,
+
+            // The x-height and the advance of "0" are assumed to be 0.5em
+            Unit::Ex | Unit::Ch => context.font_size * (self.value / 2.),
+            Unit::Rex | Unit::Rch => context.root_font_size * (self.value / 2.),
+
+            // The cap height is approximated by the ascent, which is assumed to be 1em,
+            // just like the advance of "水"
+            Unit::Cap | Unit::Ic => context.font_size * self.value,
+            Unit::Rcap | Unit::Ric => context.root_font_size * self.value,
+
+            Unit::Lh => context.line_height * self.value,
+            Unit::Rlh => context.root_line_height * self.value,
+        }
+    }
+
     pub fn from_dimension(value: Number, unit_name: InternedString) -> Result<Self, ParseError> {
         let length = Self {
             value: value.into(),
@@ -239,51 +308,42 @@ impl ToComputedStyle for Length {
     type Computed = computed::Length;
 
     fn to_computed_style(&self, context: &StyleContext) -> Self::Computed {
-        match self.unit {
-            Unit::Cm => Pixels(self.value * 96. / 2.54),
-            Unit::Mm => Pixels(self.value * 96. / 2.54 / 10.),
-            Unit::Q => Pixels(self.value * 96. / 2.54 / 40.),
-            Unit::In => Pixels(self.value * 96.),
-            Unit::Pc => Pixels(self.value * 96. / 6.),
-            Unit::Pt => Pixels(self.value * 96. / 72.),
-            Unit::Px => Pixels(self.value),
+        self.resolve(&ResolutionContext::from(context))
+    }
+}
 
-            // Viewport-relative units
-            Unit::Vw
-            | Unit::Svw
-            | Unit::Lvw
-            | Unit::Dvw
-            | Unit::Vi
-            | Unit::Svi
-            | Unit::Lvi
-            | Unit::Dvi => context.viewport.width / 100. * self.value,
-            Unit::Vh
-            | Unit::Svh
-            | Unit::Lvh
-            | Unit::Dvh
-            | Unit::Vb
-            | Unit::Svb
-            | Unit::Lvb
-            | Unit::Dvb => context.viewport.height / 100. * self.value,
-            Unit::Vmin | Unit::Svmin | Unit::Lvmin | Unit::Dvmin => {
-                if context.viewport.width < context.viewport.height {
-                    (context.viewport.width / 100.) * self.value
-                } else {
-                    (context.viewport.height / 100.) * self.value
-                }
-            },
-            Unit::Vmax | Unit::Svmax | Unit::Lvmax | Unit::Dvmax => {
-                if context.viewport.width < context.viewport.height {
-                    (context.viewport.height / 100.) * self.value
-                } else {
-                    (context.viewport.width / 100.) * self.value
-                }
-            },
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            // Font-relative units
-            Unit::Em => context.font_size * self.value,
-            Unit::Rem => context.root_font_size * self.value,
-            _ => todo!("absolutize font-relative length: {self:?}"),
-        }
+    use crate::css::layout::Size;
+
+    const CONTEXT: ResolutionContext = ResolutionContext {
+        font_size: Pixels(20.),
+        root_font_size: Pixels(16.),
+        line_height: Pixels(30.),
+        root_line_height: Pixels(24.),
+        viewport: Size {
+            width: Pixels(800.),
+            height: Pixels(600.),
+        },
+        percentage_basis: Pixels(300.),
+    };
+
+    #[test]
+    fn resolve_length() {
+        let resolve = |source| Length::parse_from_str(source).unwrap().resolve(&CONTEXT);
+
+        assert_eq!(resolve("2em"), Pixels(40.));
+        assert_eq!(resolve("2rem"), Pixels(32.));
+        assert_eq!(resolve("1ex"), Pixels(10.));
+        assert_eq!(resolve("2lh"), Pixels(60.));
+        assert_eq!(resolve("50vw"), Pixels(400.));
+        assert_eq!(resolve("50vh"), Pixels(300.));
+        assert_eq!(resolve("10vmin"), Pixels(60.));
+        assert_eq!(resolve("10vmax"), Pixels(80.));
+        assert_eq!(resolve("1in"), Pixels(96.));
+        assert_eq!(resolve("12pt"), Pixels(16.));
+        assert_eq!(resolve("0"), Pixels::ZERO);
     }
 }
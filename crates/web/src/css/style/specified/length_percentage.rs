@@ -0,0 +1,123 @@
+//! <https://drafts.csswg.org/css-values-4/#typedef-length-percentage>
+
+use crate::{
+    css::{
+        layout::Pixels,
+        style::{computed, ResolutionContext, StyleContext, ToComputedStyle},
+        syntax::Token,
+        values::Percentage,
+        CSSParse, ParseError, Parser,
+    },
+    static_interned,
+};
+
+use super::{Calc, CalcType, Length};
+
+/// <https://drafts.csswg.org/css-values-4/#typedef-length-percentage>
+#[derive(Clone, Debug)]
+pub enum LengthPercentage {
+    Length(Length),
+    Percentage(Percentage),
+
+    /// A `calc()` expression that resolves to a length, a percentage or a mix of both
+    Calc(Box<Calc>),
+}
+
+impl LengthPercentage {
+    pub const ZERO: Self = Self::Length(Length::ZERO);
+
+    /// Resolve the value to pixels, percentages refer to the
+    /// [percentage basis](ResolutionContext::percentage_basis)
+    #[must_use]
+    pub fn resolve(&self, context: &ResolutionContext) -> Pixels {
+        match self {
+            Self::Length(length) => length.resolve(context),
+            Self::Percentage(percentage) => context.percentage_basis * *percentage,
+            Self::Calc(calc) => {
+                let style_context = StyleContext {
+                    font_size: context.font_size,
+                    root_font_size: context.root_font_size,
+                    viewport: context.viewport,
+                };
+
+                calc.to_computed_style(&style_context)
+                    .resolve_against(context.percentage_basis)
+            },
+        }
+    }
+}
+
+impl From<Length> for LengthPercentage {
+    fn from(value: Length) -> Self {
+        Self::Length(value)
+    }
+}
+
+impl<'a> CSSParse<'a> for LengthPercentage {
+    fn parse(parser: &mut Parser<'a>) -> Result<Self, ParseError> {
+        match parser.peek_token_ignoring_whitespace(0) {
+            Some(Token::Percentage(n)) => {
+                let percentage = Percentage::from_css_percentage(*n);
+                _ = parser.next_token_ignoring_whitespace();
+                Ok(Self::Percentage(percentage))
+            },
+            Some(Token::Function(static_interned!("calc"))) => {
+                let calc: Calc = parser.parse()?;
+
+                if calc.value_type() == CalcType::Number {
+                    return Err(ParseError);
+                }
+
+                Ok(Self::Calc(Box::new(calc)))
+            },
+            _ => Ok(Self::Length(parser.parse()?)),
+        }
+    }
+}
+
+impl ToComputedStyle for LengthPercentage {
+    type Computed = computed::LengthPercentage;
+
+    fn to_computed_style(&self, context: &StyleContext) -> Self::Computed {
+        match self {
+            Self::Length(length) => {
+                computed::LengthPercentage::from_length(length.to_computed_style(context))
+            },
+            Self::Percentage(percentage) => {
+                computed::LengthPercentage::from_percentage(*percentage)
+            },
+            Self::Calc(calc) => calc.to_computed_style(context),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::css::layout::Size;
+
+    #[test]
+    fn resolve_length_percentage() {
+        let context = ResolutionContext {
+            font_size: Pixels(20.),
+            root_font_size: Pixels(16.),
+            line_height: Pixels(20.),
+            root_line_height: Pixels(16.),
+            viewport: Size {
+                width: Pixels(800.),
+                height: Pixels(600.),
+            },
+            percentage_basis: Pixels(300.),
+        };
+        let resolve = |source| {
+            LengthPercentage::parse_from_str(source)
+                .unwrap()
+                .resolve(&context)
+        };
+
+        assert_eq!(resolve("10%"), Pixels(30.));
+        assert_eq!(resolve("2em"), Pixels(40.));
+        assert_eq!(resolve("calc(50% + 1vw)"), Pixels(158.));
+    }
+}
@@ -4,13 +4,19 @@ mod alignment;
 mod background_color;
 mod background_image;
 mod border;
+mod box_shadow;
+mod calc;
+mod content;
+mod css_wide_keyword;
 mod cursor;
 mod display;
 mod float;
+mod font;
 mod font_family;
 mod font_size;
 mod font_style;
 mod length;
+mod length_percentage;
 mod line_height;
 mod list_style_type;
 mod position;
@@ -20,22 +26,28 @@ pub use alignment::{Inset, JustifySelf};
 pub use background_color::BackgroundColor;
 pub use background_image::BackgroundImage;
 pub use border::{Border, LineStyle, LineWidth};
+pub use box_shadow::{BoxShadow, Shadow};
+pub use calc::{Calc, CalcType};
+pub use content::Content;
+pub use css_wide_keyword::CssWideKeyword;
 pub use cursor::Cursor;
 pub use display::{Display, DisplayBox, DisplayInside, DisplayInsideOutside, DisplayOutside};
 pub use float::{Clear, Float, FloatSide};
-pub use font_family::{FontFamily, FontName};
+pub use font::Font;
+pub use font_family::{FontFamily, FontName, GenericFontFamily};
 pub use font_size::FontSize;
 pub use font_style::FontStyle;
 pub use length::Length;
+pub use length_percentage::LengthPercentage;
 pub use line_height::LineHeight;
 pub use list_style_type::ListStyleType;
 pub use position::Position;
 pub use vertical_align::VerticalAlign;
 
-use crate::css::values::{AutoOr, PercentageOr};
+use crate::css::values::AutoOr;
 
 /// <https://drafts.csswg.org/css2/#value-def-margin-width>
-pub type Margin = AutoOr<PercentageOr<Length>>;
+pub type Margin = AutoOr<LengthPercentage>;
 
 /// <https://drafts.csswg.org/css2/#value-def-padding-width>
-pub type Padding = PercentageOr<Length>;
+pub type Padding = LengthPercentage;
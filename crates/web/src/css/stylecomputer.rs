@@ -4,7 +4,7 @@ use crate::{
     css::{
         computed_style::ComputedStyle,
         properties::Important,
-        selectors::{Selector, Specificity},
+        selectors::{PseudoElementSelector, Selector, Specificity},
         syntax::RuleParser,
         Origin, Parser, StyleProperty, StylePropertyDeclaration, Stylesheet,
     },
@@ -15,7 +15,8 @@ use crate::{
 use super::{
     font_metrics::DEFAULT_FONT_SIZE,
     layout::{Pixels, Size},
-    style::{StyleContext, ToComputedStyle},
+    media_queries::{ColorScheme, MediaContext},
+    style::{specified::CssWideKeyword, CustomProperties, StyleContext, ToComputedStyle},
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -23,6 +24,7 @@ pub struct StyleComputer<'a> {
     stylesheets: &'a [Stylesheet],
     root_font_size: Pixels,
     viewport_size: Size<Pixels>,
+    color_scheme: ColorScheme,
 }
 
 #[derive(Clone, Debug)]
@@ -117,6 +119,7 @@ impl<'a> StyleComputer<'a> {
         stylesheets: &'a [Stylesheet],
         root_font_size: Pixels,
         viewport_size: Size<Pixels>,
+        color_scheme: ColorScheme,
     ) -> Self {
         // Sort the list in cascade order:
         // https://drafts.csswg.org/css-cascade-4/#cascade-specificity
@@ -125,21 +128,43 @@ impl<'a> StyleComputer<'a> {
             stylesheets,
             root_font_size,
             viewport_size,
+            color_scheme,
         }
     }
 
-    // Find all the [StyleRules](super::StyleRule) that apply to an [Element]
-    fn collect_matched_properties(&self, element: DomPtr<Element>) -> Vec<MatchingProperty<'_>> {
+    // Find all the [StyleRules](super::StyleRule) that apply to an [Element] or one of its pseudo-elements
+    fn collect_matched_properties(
+        &self,
+        element: DomPtr<Element>,
+        pseudo_element: Option<PseudoElementSelector>,
+    ) -> Vec<MatchingProperty<'_>> {
         let mut matched_properties = vec![];
 
+        let media_context = MediaContext {
+            viewport: self.viewport_size,
+            color_scheme: self.color_scheme,
+        };
+
         for stylesheet in self.stylesheets {
             for (rule_index, rule) in stylesheet.rules().iter().enumerate() {
-                if rule.selectors().iter().any(|s| s.matches(&element)) {
-                    let new_properties = rule.properties().iter().map(|prop| {
-                        // FIXME: This should be the specificity of the most-specific matching selector,
-                        //        not the sum
-                        let specificity = rule.selectors().iter().map(Selector::specificity).sum();
+                if !rule.matches_media(&media_context) {
+                    continue;
+                }
 
+                // The specificity of a selector list is that of the most specific selector
+                // that matches the element
+                let specificity = rule
+                    .selectors()
+                    .iter()
+                    .filter(|s| match pseudo_element {
+                        Some(pseudo_element) => s.matches_pseudo_element(&element, pseudo_element),
+                        None => s.matches(&element),
+                    })
+                    .map(Selector::specificity)
+                    .max();
+
+                if let Some(specificity) = specificity {
+                    let new_properties = rule.properties().iter().map(|prop| {
                         MatchingProperty::new(
                             prop,
                             specificity,
@@ -173,37 +198,90 @@ impl<'a> StyleComputer<'a> {
             MatchingProperty::new(property, Specificity::MAX, 0, 0, Origin::Author)
         });
 
-        let mut matched_properties = self.collect_matched_properties(element);
+        let mut matched_properties = self.collect_matched_properties(element, None);
         matched_properties.extend(attribute_style);
 
+        self.cascade(matched_properties, parent_style)
+    }
+
+    /// Compute the style of a pseudo-element of an [Element]
+    ///
+    /// Pseudo-elements inherit from their originating element.
+    pub fn get_pseudo_element_style(
+        &self,
+        element: DomPtr<Element>,
+        pseudo_element: PseudoElementSelector,
+        element_style: &ComputedStyle,
+    ) -> ComputedStyle {
+        let matched_properties = self.collect_matched_properties(element, Some(pseudo_element));
+
+        self.cascade(matched_properties, element_style)
+    }
+
+    fn cascade(
+        &self,
+        mut matched_properties: Vec<MatchingProperty<'_>>,
+        parent_style: &ComputedStyle,
+    ) -> ComputedStyle {
         // Sort matching rules in cascade order, see
         // https://drafts.csswg.org/css-cascade-4/#cascade-sort for more info
         matched_properties.sort_unstable_by(MatchingProperty::compare_in_cascade_order);
 
+        // Custom properties need to be known before any var() references can be substituted
+        let custom_properties = CustomProperties::cascade(
+            parent_style.custom_properties(),
+            matched_properties.iter().filter_map(|matched_property| {
+                match &matched_property.property.value {
+                    StyleProperty::Custom(custom_property) => Some(custom_property),
+                    _ => None,
+                }
+            }),
+        );
+
+        let properties: Vec<StyleProperty> = matched_properties
+            .iter()
+            .filter_map(|matched_property| match matched_property.property() {
+                StyleProperty::Custom(_) => None,
+                StyleProperty::WithVariables(unresolved) => {
+                    // FIXME: A property that is invalid at computed-value time should behave like "unset",
+                    //        we currently ignore the declaration instead
+                    unresolved.resolve(&custom_properties).ok()
+                },
+                property => Some(property),
+            })
+            .collect();
+
         // Find the font size of the element
         // This is done seperately, as only the font-size property refers to the font size
         // of the parent instead of the element itself
-        let font_size = matched_properties
+        let parent_style_context = StyleContext {
+            font_size: *parent_style.font_size(),
+            root_font_size: self.root_font_size,
+            viewport: self.viewport_size,
+        };
+        let font_size = properties
             .iter()
             .rev()
-            .flat_map(|prop| {
-                if let StyleProperty::FontSize(font_size) = prop.property() {
-                    Some(font_size)
-                } else {
-                    None
-                }
-            })
-            .map(|font_size| {
-                let style_context = StyleContext {
-                    font_size: *parent_style.font_size(),
-                    root_font_size: self.root_font_size,
-                    viewport: self.viewport_size,
-                };
-
-                font_size.to_computed_style(&style_context)
+            .find_map(|property| match property {
+                StyleProperty::FontSize(font_size) => {
+                    Some(font_size.to_computed_style(&parent_style_context))
+                },
+                StyleProperty::Font(font) => {
+                    Some(font.size.to_computed_style(&parent_style_context))
+                },
+                StyleProperty::CssWideKeyword(
+                    static_interned!("font-size") | static_interned!("font"),
+                    keyword,
+                ) => {
+                    // font-size is an inherited property, so "unset" behaves like "inherit"
+                    match keyword.resolve(true) {
+                        CssWideKeyword::Initial => Some(DEFAULT_FONT_SIZE),
+                        _ => Some(*parent_style.font_size()),
+                    }
+                },
+                _ => None,
             })
-            .next()
-            .unwrap_or(DEFAULT_FONT_SIZE);
+            .unwrap_or(*parent_style.font_size());
 
         let style_context = StyleContext {
             font_size,
@@ -214,12 +292,20 @@ impl<'a> StyleComputer<'a> {
         // Add properties in logical order (least important first)
         // That way, more important rules can override less important ones
         let mut computed_style = parent_style.get_inherited();
-
-        for matched_property in matched_properties.into_iter() {
-            let property = matched_property.property();
-            computed_style.set_property(property, &style_context);
+        computed_style.set_custom_properties(custom_properties);
+
+        for property in properties {
+            match property {
+                StyleProperty::CssWideKeyword(name, keyword) => {
+                    computed_style.apply_css_wide_keyword(name, keyword, parent_style);
+                },
+                property => computed_style.set_property(property, &style_context),
+            }
         }
 
+        // Relative font sizes refer to the parent element and were already resolved above
+        computed_style.set_font_size(font_size);
+
         computed_style
     }
 }
@@ -247,3 +333,239 @@ fn attribute_style_for_element(element: DomPtr<Element>) -> Vec<StylePropertyDec
         })
         .unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{
+        css::{
+            style::{
+                computed::{LengthPercentage, LineHeight},
+                specified::{Font, FontName, FontStyle, GenericFontFamily, LineStyle},
+            },
+            values::{AutoOr, Color},
+            CSSParse,
+        },
+        dom::{dom_objects::Node, ElementCustomState},
+        infra::Namespace,
+        InternedString,
+    };
+
+    fn element(local_name: InternedString) -> DomPtr<Element> {
+        DomPtr::new(Element::new(
+            Namespace::HTML,
+            None,
+            local_name,
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        ))
+    }
+
+    #[test]
+    fn inheritance_and_css_wide_keywords() {
+        let stylesheets = [Parser::new(
+            "div { color: red; margin: 5px; font-size: 20px; border-color: blue; }
+            section { margin-left: inherit; border: initial; }
+            p { color: unset; margin: unset; font-size: initial; }",
+            Origin::Author,
+        )
+        .parse_stylesheet(0)];
+
+        let style_computer = StyleComputer::new(
+            &stylesheets,
+            DEFAULT_FONT_SIZE,
+            Size {
+                width: Pixels(800.),
+                height: Pixels(600.),
+            },
+            ColorScheme::Light,
+        );
+
+        let div = element(static_interned!("div"));
+        let section = element(static_interned!("section"));
+        let p = element(static_interned!("p"));
+        Node::append_child(div.clone().upcast(), section.clone().upcast());
+        Node::append_child(div.clone().upcast(), p.clone().upcast());
+
+        let div_style = style_computer.get_computed_style(div, &ComputedStyle::default());
+        let section_style = style_computer.get_computed_style(section, &div_style);
+        let p_style = style_computer.get_computed_style(p, &div_style);
+
+        let five_pixels = AutoOr::NotAuto(LengthPercentage::from_length(Pixels(5.)));
+        let zero = AutoOr::NotAuto(LengthPercentage::ZERO);
+
+        // color and font-size are inherited, margin and border-color are not
+        assert_eq!(*section_style.color(), Color::RED);
+        assert_eq!(*section_style.font_size(), Pixels(20.));
+        assert_eq!(*section_style.margin_top(), zero);
+        assert_eq!(*section_style.margin_left(), five_pixels);
+        assert_eq!(*section_style.border_top_color(), Color::BLACK);
+
+        // "unset" inherits inherited properties and resets all others
+        assert_eq!(*p_style.color(), Color::RED);
+        assert_eq!(*p_style.margin_left(), zero);
+        assert_eq!(*p_style.font_size(), DEFAULT_FONT_SIZE);
+    }
+
+    #[test]
+    fn media_queries() {
+        let stylesheets = [Parser::new(
+            "div { color: red; }
+            @media (min-width: 1000px) { div { color: blue; } }
+            @media screen and (max-width: 1000px) {
+                div { margin: 5px; }
+                @media (prefers-color-scheme: dark) { div { margin: 10px; } }
+            }
+            @import url(foo.css);
+            div { font-size: 20px; }",
+            Origin::Author,
+        )
+        .parse_stylesheet(0)];
+
+        let div = element(static_interned!("div"));
+        let style_for_viewport = |width| {
+            StyleComputer::new(
+                &stylesheets,
+                DEFAULT_FONT_SIZE,
+                Size {
+                    width: Pixels(width),
+                    height: Pixels(600.),
+                },
+                ColorScheme::Light,
+            )
+            .get_computed_style(div.clone(), &ComputedStyle::default())
+        };
+
+        let style = style_for_viewport(800.);
+        assert_eq!(*style.color(), Color::RED);
+        assert_eq!(
+            *style.margin_top(),
+            AutoOr::NotAuto(LengthPercentage::from_length(Pixels(5.)))
+        );
+        assert_eq!(*style.font_size(), Pixels(20.));
+
+        // The same stylesheet gives a different result when the viewport is resized
+        let style = style_for_viewport(1200.);
+        assert_eq!(*style.color(), Color::BLUE);
+        assert_eq!(*style.margin_top(), AutoOr::NotAuto(LengthPercentage::ZERO));
+    }
+
+    #[test]
+    fn shorthand_expansion() {
+        let stylesheets = [Parser::new(
+            "div, p { margin: 1px 2px 3px; padding: 4px 5px; border: 6px solid red; }
+            p { margin-left: 7px; border-width: 1px 2px 3px 4px; }",
+            Origin::Author,
+        )
+        .parse_stylesheet(0)];
+
+        let style_computer = StyleComputer::new(
+            &stylesheets,
+            DEFAULT_FONT_SIZE,
+            Size {
+                width: Pixels(800.),
+                height: Pixels(600.),
+            },
+            ColorScheme::Light,
+        );
+
+        let div_style = style_computer
+            .get_computed_style(element(static_interned!("div")), &ComputedStyle::default());
+        let p_style = style_computer
+            .get_computed_style(element(static_interned!("p")), &ComputedStyle::default());
+
+        let margin = |pixels| AutoOr::NotAuto(LengthPercentage::from_length(Pixels(pixels)));
+        let padding = |pixels| LengthPercentage::from_length(Pixels(pixels));
+
+        // Three values: top, left/right, bottom
+        assert_eq!(*div_style.margin_top(), margin(1.));
+        assert_eq!(*div_style.margin_right(), margin(2.));
+        assert_eq!(*div_style.margin_bottom(), margin(3.));
+        assert_eq!(*div_style.margin_left(), margin(2.));
+
+        // Two values: top/bottom, left/right
+        assert_eq!(*div_style.padding_top(), padding(4.));
+        assert_eq!(*div_style.padding_right(), padding(5.));
+        assert_eq!(*div_style.padding_bottom(), padding(4.));
+        assert_eq!(*div_style.padding_left(), padding(5.));
+
+        // "border" sets the width, style and color of all four sides
+        assert_eq!(*div_style.border_left_width(), Pixels(6.));
+        assert_eq!(*div_style.border_top_style(), LineStyle::Solid);
+        assert_eq!(*div_style.border_bottom_color(), Color::RED);
+
+        // Longhands declared later override the shorthand
+        assert_eq!(*p_style.margin_top(), margin(1.));
+        assert_eq!(*p_style.margin_left(), margin(7.));
+        assert_eq!(*p_style.border_top_width(), Pixels(1.));
+        assert_eq!(*p_style.border_left_width(), Pixels(4.));
+        assert_eq!(*p_style.border_left_style(), LineStyle::Solid);
+    }
+
+    #[test]
+    fn font_shorthand() {
+        let stylesheets = [Parser::new(
+            "div { font: italic bold 14px/1.5 \"Helvetica\", sans-serif; }
+            p { line-height: 3; font-style: italic; font: 16px serif; }
+            section { font: small-caps 700 2em/20px monospace; }",
+            Origin::Author,
+        )
+        .parse_stylesheet(0)];
+
+        let style_computer = StyleComputer::new(
+            &stylesheets,
+            DEFAULT_FONT_SIZE,
+            Size {
+                width: Pixels(800.),
+                height: Pixels(600.),
+            },
+            ColorScheme::Light,
+        );
+
+        let div_style = style_computer
+            .get_computed_style(element(static_interned!("div")), &ComputedStyle::default());
+        assert_eq!(*div_style.font_style(), FontStyle::Italic);
+        assert_eq!(*div_style.font_size(), Pixels(14.));
+        assert_eq!(
+            div_style.line_height().used_value(*div_style.font_size()),
+            Pixels(21.)
+        );
+        assert_matches!(
+            div_style.font_family().fonts(),
+            [
+                FontName::Family(name),
+                FontName::Generic(GenericFontFamily::SansSerif)
+            ] if name.to_string() == "Helvetica"
+        );
+
+        // Longhands that are not specified are reset to their initial value
+        let p_style = style_computer
+            .get_computed_style(element(static_interned!("p")), &ComputedStyle::default());
+        assert_eq!(*p_style.font_style(), FontStyle::Normal);
+        assert_eq!(*p_style.font_size(), Pixels(16.));
+        assert_matches!(p_style.line_height(), LineHeight::Normal);
+        assert_matches!(
+            p_style.font_family().fonts(),
+            [FontName::Generic(GenericFontFamily::Serif)]
+        );
+
+        // Relative font sizes refer to the parent element
+        let section_style =
+            style_computer.get_computed_style(element(static_interned!("section")), &div_style);
+        assert_eq!(*section_style.font_style(), FontStyle::Normal);
+        assert_eq!(*section_style.font_size(), Pixels(28.));
+        assert_eq!(
+            section_style
+                .line_height()
+                .used_value(*section_style.font_size()),
+            Pixels(20.)
+        );
+
+        // Style, variant and weight must precede the font size
+        assert!(Font::parse_from_str("16px italic serif").is_err());
+        assert!(Font::parse_from_str("italic serif").is_err());
+    }
+}
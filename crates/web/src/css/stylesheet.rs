@@ -1,4 +1,10 @@
-use super::{selectors::Selector, Parser, StylePropertyDeclaration};
+use std::rc::Rc;
+
+use super::{
+    media_queries::{MediaContext, MediaQueryList},
+    selectors::Selector,
+    Parser, StylePropertyDeclaration,
+};
 
 /// <https://drafts.csswg.org/css-cascade-4/#cascading-origins>
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -67,6 +73,11 @@ impl Stylesheet {
 pub struct StyleRule {
     selectors: Vec<Selector>,
     properties: Vec<StylePropertyDeclaration>,
+
+    /// The queries of all the `@media` rules that this rule is nested in
+    ///
+    /// The rule only applies if all of them match.
+    media_queries: Rc<[MediaQueryList]>,
 }
 
 impl StyleRule {
@@ -74,9 +85,16 @@ impl StyleRule {
         Self {
             selectors,
             properties,
+            media_queries: Rc::from([]),
         }
     }
 
+    #[must_use]
+    pub fn with_media_queries(mut self, media_queries: Rc<[MediaQueryList]>) -> Self {
+        self.media_queries = media_queries;
+        self
+    }
+
     #[must_use]
     pub fn selectors(&self) -> &[Selector] {
         &self.selectors
@@ -86,4 +104,12 @@ impl StyleRule {
     pub fn properties(&self) -> &[StylePropertyDeclaration] {
         &self.properties
     }
+
+    /// Whether the `@media` rules that this rule is nested in allow it to apply
+    #[must_use]
+    pub fn matches_media(&self, context: &MediaContext) -> bool {
+        self.media_queries
+            .iter()
+            .all(|media_queries| media_queries.matches(context))
+    }
 }
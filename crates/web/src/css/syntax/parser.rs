@@ -11,13 +11,17 @@ use super::{
 
 use crate::{
     css::{
-        layout::Sides, properties::Important, values::Number, Origin, StyleProperty,
-        StylePropertyDeclaration, StyleRule, Stylesheet,
+        layout::Sides,
+        media_queries::MediaQueryList,
+        properties::Important,
+        style::{contains_variable_reference, is_custom_property_name, UnparsedProperty},
+        values::Number,
+        Origin, StyleProperty, StylePropertyDeclaration, StyleRule, Stylesheet,
     },
     static_interned, InternedString,
 };
 
-use std::fmt::Debug;
+use std::{fmt::Debug, rc::Rc};
 
 const MAX_ITERATIONS: usize = 128;
 
@@ -38,9 +42,43 @@ pub enum TopLevel {
     No,
 }
 
+/// Where a [Parser] gets its tokens from
+#[derive(Clone, Debug)]
+enum TokenSource<'a> {
+    Tokenizer(Tokenizer<'a>),
+
+    /// A list of tokens that was previously produced by a [Tokenizer]
+    ///
+    /// This is used to parse property values after `var()` references were substituted.
+    Tokens {
+        tokens: Rc<[Token]>,
+        position: usize,
+    },
+}
+
+impl<'a> TokenSource<'a> {
+    fn next_token(&mut self) -> Option<Token> {
+        match self {
+            Self::Tokenizer(tokenizer) => tokenizer.next_token(),
+            Self::Tokens { tokens, position } => {
+                let token = tokens.get(*position).cloned()?;
+                *position += 1;
+                Some(token)
+            },
+        }
+    }
+
+    fn get_position(&self) -> usize {
+        match self {
+            Self::Tokenizer(tokenizer) => tokenizer.get_position(),
+            Self::Tokens { position, .. } => *position,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Parser<'a> {
-    tokenizer: Tokenizer<'a>,
+    tokenizer: TokenSource<'a>,
     queued_tokens: RingBuffer<Token, MAX_LOOKAHEAD>,
     origin: Origin,
 }
@@ -52,7 +90,20 @@ impl<'a> Parser<'a> {
     #[must_use]
     pub fn new(source: &'a str, origin: Origin) -> Self {
         Self {
-            tokenizer: Tokenizer::new(source),
+            tokenizer: TokenSource::Tokenizer(Tokenizer::new(source)),
+            queued_tokens: RingBuffer::default(),
+            origin,
+        }
+    }
+
+    /// Create a parser that reads from a list of tokens instead of source code
+    #[must_use]
+    pub fn from_tokens(tokens: Rc<[Token]>, origin: Origin) -> Self {
+        Self {
+            tokenizer: TokenSource::Tokens {
+                tokens,
+                position: 0,
+            },
             queued_tokens: RingBuffer::default(),
             origin,
         }
@@ -263,35 +314,108 @@ impl<'a> Parser<'a> {
         }
 
         // 4. Discard whitespace from input.
+        // 5. Consume a list of component values from input, with nested, and with <semicolon-token> as the stop token,
+        //    and set decl’s value to the result.
+        let mut value = self.consume_declaration_value();
+
+        // 6. If the last two non-<whitespace-token>s in decl’s value are a <delim-token> with the value "!"
+        //    followed by an <ident-token> with a value that is an ASCII case-insensitive match for "important",
+        //    remove them from decl’s value and set decl’s important flag.
+        let mut non_whitespace_tokens = value
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, token)| !token.is_whitespace());
+        let important_index = match (non_whitespace_tokens.next(), non_whitespace_tokens.next()) {
+            (
+                Some((_, Token::Ident(static_interned!("important")))),
+                Some((index, Token::Delim('!'))),
+            ) => Some(index),
+            _ => None,
+        };
+
+        if let Some(index) = important_index {
+            value.truncate(index);
+            important = Important::Yes;
+        }
+
+        // 7. While the last item in decl’s value is a <whitespace-token>, remove that token.
+        while value.last().is_some_and(Token::is_whitespace) {
+            value.pop();
+        }
+
+        // 8. If decl’s name is a custom property name string, then [...]
+        //    set decl’s value to the result.
+        if is_custom_property_name(declaration_name) {
+            let value = StyleProperty::Custom(UnparsedProperty::new(declaration_name, value));
+            return Some(StylePropertyDeclaration { value, important });
+        }
+
+        // Values that reference custom properties can only be parsed once the
+        // custom properties of the element are known
+        if contains_variable_reference(&value) {
+            let value =
+                StyleProperty::WithVariables(UnparsedProperty::new(declaration_name, value));
+            return Some(StylePropertyDeclaration { value, important });
+        }
 
         // NOTE: At this point we deviate from the spec because the spec gets a little silly
-        let value = if let Ok(value) = StyleProperty::parse_value(self, declaration_name) {
-            value
-        } else {
+        let mut value_parser = Parser::from_tokens(value.into(), self.origin);
+        let Ok(value) = value_parser.parse_complete_property_value(declaration_name) else {
             self.consume_remnants_of_bad_declaration(nested);
             return None;
         };
 
-        // Check for !important
-        if matches!(
-            self.peek_token_ignoring_whitespace(0),
-            Some(Token::Delim('!'))
-        ) {
-            let _ = self.next_token_ignoring_whitespace();
+        Some(StylePropertyDeclaration { value, important })
+    }
 
-            #[allow(clippy::redundant_guards)] // In this case, the guard helps with readability
-            match self.next_token() {
-                Some(Token::Ident(i)) if i == static_interned!("important") => {
-                    important = Important::Yes;
-                },
-                _ => {
-                    self.consume_remnants_of_bad_declaration(nested);
-                    return None;
+    /// Consume the tokens making up the value of a declaration
+    ///
+    /// This stops at a top-level `;` or `}` (which are not consumed).
+    fn consume_declaration_value(&mut self) -> Vec<Token> {
+        while self.peek_token(0).is_some_and(Token::is_whitespace) {
+            _ = self.next_token();
+        }
+
+        let mut value = vec![];
+        let mut nesting_level: usize = 0;
+
+        loop {
+            match self.peek_token(0) {
+                None => break,
+                Some(Token::Semicolon | Token::CurlyBraceClose) if nesting_level == 0 => break,
+                Some(
+                    Token::Function(_)
+                    | Token::ParenthesisOpen
+                    | Token::BracketOpen
+                    | Token::CurlyBraceOpen,
+                ) => nesting_level += 1,
+                Some(Token::ParenthesisClose | Token::BracketClose | Token::CurlyBraceClose) => {
+                    nesting_level = nesting_level.saturating_sub(1)
                 },
+                Some(_) => {},
             }
+
+            value.extend(self.next_token());
         }
 
-        Some(StylePropertyDeclaration { value, important })
+        value
+    }
+
+    /// Parse the value of the property with the given name
+    ///
+    /// If there are tokens left after the value, an error is returned.
+    pub fn parse_complete_property_value(
+        &mut self,
+        property_name: InternedString,
+    ) -> Result<StyleProperty, ParseError> {
+        let value = StyleProperty::parse_value(self, property_name)?;
+
+        if self.next_token_ignoring_whitespace().is_some() {
+            return Err(ParseError);
+        }
+
+        Ok(value)
     }
 
     /// <https://drafts.csswg.org/css-syntax-3/#consume-the-remnants-of-a-bad-declaration>
@@ -323,24 +447,117 @@ impl<'a> Parser<'a> {
         let mut rule_parser = RuleParser::default();
 
         let mut rules = vec![];
+        self.consume_rules_with_media_queries(&mut rule_parser, &Rc::from([]), &mut rules);
 
-        while self.peek_token_ignoring_whitespace(0).is_some() {
-            let rule =
-                match self.consume_qualified_rule(&mut rule_parser, MixedWithDeclarations::No) {
-                    Ok(rule) => rule,
-                    Err(error) => {
-                        log::debug!("Failed to parse CSS rule: {error:?}");
+        Stylesheet::new(self.origin, rules, index)
+    }
+
+    /// Consume style rules until the end of the input or the end of the current block
+    ///
+    /// All consumed rules only apply if all of the `media_queries` match.
+    fn consume_rules_with_media_queries(
+        &mut self,
+        rule_parser: &mut RuleParser,
+        media_queries: &Rc<[MediaQueryList]>,
+        rules: &mut Vec<StyleRule>,
+    ) {
+        let is_nested = !media_queries.is_empty();
+
+        loop {
+            match self.peek_token_ignoring_whitespace(0) {
+                None => return,
+                Some(Token::CurlyBraceClose) if is_nested => return,
+                Some(Token::AtKeyword(static_interned!("media"))) => {
+                    // https://drafts.csswg.org/css-conditional-3/#at-media
+                    _ = self.next_token_ignoring_whitespace();
+                    let prelude = self.consume_at_rule_prelude();
+
+                    if self.next_token_ignoring_whitespace() != Some(Token::CurlyBraceOpen) {
+                        log::debug!("@media rule without a block");
                         continue;
-                    },
-                };
+                    }
+
+                    // The rules inside nested @media blocks must match the queries of all their parents
+                    let mut nested_media_queries = media_queries.to_vec();
+                    nested_media_queries.push(MediaQueryList::from_tokens(&prelude));
+
+                    self.consume_rules_with_media_queries(
+                        rule_parser,
+                        &nested_media_queries.into(),
+                        rules,
+                    );
+
+                    // Consume the closing "}"
+                    _ = self.next_token_ignoring_whitespace();
+                },
+                Some(Token::AtKeyword(name)) => {
+                    log::debug!("Ignoring unsupported at-rule: @{}", name.to_string());
+
+                    _ = self.next_token_ignoring_whitespace();
+                    _ = self.consume_at_rule_prelude();
+                    if self.next_token_ignoring_whitespace() == Some(Token::CurlyBraceOpen) {
+                        self.consume_remnants_of_block();
+                    }
+                },
+                Some(_) => {
+                    let rule =
+                        match self.consume_qualified_rule(rule_parser, MixedWithDeclarations::No) {
+                            Ok(rule) => rule,
+                            Err(error) => {
+                                log::debug!("Failed to parse CSS rule: {error:?}");
+                                continue;
+                            },
+                        };
+
+                    // There's no point in caring about empty rules, so let's drop them
+                    if !rule.properties().is_empty() {
+                        rules.push(rule.with_media_queries(media_queries.clone()));
+                    }
+                },
+            }
+        }
+    }
 
-            // There's no point in caring about empty rules, so let's drop them
-            if !rule.properties().is_empty() {
-                rules.push(rule);
+    /// Consume the tokens before the block of an at-rule
+    ///
+    /// This stops at a top-level `;` or `{` (which are not consumed).
+    fn consume_at_rule_prelude(&mut self) -> Vec<Token> {
+        let mut prelude = vec![];
+        let mut nesting_level: usize = 0;
+
+        loop {
+            match self.peek_token(0) {
+                None => break,
+                Some(Token::CurlyBraceOpen | Token::Semicolon) if nesting_level == 0 => break,
+                Some(Token::Function(_) | Token::ParenthesisOpen | Token::BracketOpen) => {
+                    nesting_level += 1
+                },
+                Some(Token::ParenthesisClose | Token::BracketClose) => {
+                    nesting_level = nesting_level.saturating_sub(1)
+                },
+                Some(_) => {},
             }
+
+            prelude.extend(self.next_token());
         }
 
-        Stylesheet::new(self.origin, rules, index)
+        prelude
+    }
+
+    /// Skip over the contents of a block whose opening `{` was already consumed,
+    /// including the closing `}`
+    fn consume_remnants_of_block(&mut self) {
+        let mut nesting_level: usize = 0;
+
+        loop {
+            match self.next_token() {
+                None => break,
+                Some(Token::CurlyBraceClose) if nesting_level == 0 => break,
+                Some(Token::CurlyBraceClose) => nesting_level -= 1,
+                Some(Token::CurlyBraceOpen) => nesting_level += 1,
+                Some(_) => {},
+            }
+        }
     }
 
     /// Applies a parser as often as possible, seperating individual parser calls by
@@ -501,22 +718,27 @@ impl<'a> Parser<'a> {
         self.parse_optional_value(T::parse)
     }
 
-    pub fn parse_four_sided_property<T: CSSParse<'a> + Copy>(
+    pub fn parse_four_sided_property<T: CSSParse<'a> + Clone>(
         &mut self,
     ) -> Result<Sides<T>, ParseError> {
         let first: T = self.parse()?;
 
         let Some(second) = self.parse_optional_value(T::parse) else {
             // If only one value is supplied, it is used for all four sides
-            return Ok(Sides::all(first));
+            return Ok(Sides {
+                top: first.clone(),
+                right: first.clone(),
+                bottom: first.clone(),
+                left: first,
+            });
         };
 
         let Some(third) = self.parse_optional_value(T::parse) else {
             // If two values are supplied then the first one is used for the
             // top/bottom and the second one is used for left/right
             return Ok(Sides {
-                top: first,
-                right: second,
+                top: first.clone(),
+                right: second.clone(),
                 bottom: first,
                 left: second,
             });
@@ -527,7 +749,7 @@ impl<'a> Parser<'a> {
             // top, the second is used for left/right and the third is used for the bottom
             return Ok(Sides {
                 top: first,
-                right: second,
+                right: second.clone(),
                 bottom: third,
                 left: second,
             });
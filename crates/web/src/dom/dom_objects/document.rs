@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use dom_derive::inherit;
 use url::URL;
 
-use super::Node;
+use super::{Element, Node};
+use crate::{dom::DomPtr, InternedString};
 
 /// <https://dom.spec.whatwg.org/#interface-document>
 #[inherit(Node)]
@@ -10,6 +13,12 @@ pub struct Document {
     url: URL,
 
     charset: String,
+
+    /// The first element in tree order for every id within the document
+    ///
+    /// This is kept up to date whenever an element is inserted or its `id` attribute changes,
+    /// so that [Document::get_element_by_id] doesn't have to traverse the whole tree.
+    elements_by_id: HashMap<InternedString, DomPtr<Element>>,
 }
 
 impl Document {
@@ -25,4 +34,142 @@ impl Document {
     pub fn set_url(&mut self, url: URL) {
         self.url = url;
     }
+
+    /// <https://dom.spec.whatwg.org/#dom-nonelementparentnode-getelementbyid>
+    #[must_use]
+    pub fn get_element_by_id(&self, id: InternedString) -> Option<DomPtr<Element>> {
+        self.elements_by_id.get(&id).cloned()
+    }
+
+    /// Called when an element with the given id becomes part of the document
+    pub(crate) fn register_element_id(
+        document: DomPtr<Self>,
+        id: InternedString,
+        element: DomPtr<Element>,
+    ) {
+        let current = document.borrow().elements_by_id.get(&id).cloned();
+
+        // If there are multiple elements with the same id then the first one in tree order wins
+        let is_first = current.map_or(true, |current| {
+            Node::is_preceding(element.clone().upcast(), current.upcast())
+        });
+
+        if is_first {
+            document.borrow_mut().elements_by_id.insert(id, element);
+        }
+    }
+
+    /// Called when an element with the given id is no longer part of the document
+    /// or its id changes
+    pub(crate) fn unregister_element_id(
+        document: DomPtr<Self>,
+        id: InternedString,
+        element: &DomPtr<Element>,
+    ) {
+        let is_registered = document
+            .borrow()
+            .elements_by_id
+            .get(&id)
+            .is_some_and(|registered| DomPtr::ptr_eq(registered, element));
+
+        if !is_registered {
+            return;
+        }
+
+        // Another element with the same id might take its place
+        let mut replacement = None;
+        Node::for_each_inclusive_descendant(document.clone().upcast(), &mut |node| {
+            if replacement.is_some() {
+                return;
+            }
+
+            if let Some(other) = node.try_into_type::<Element>() {
+                if !DomPtr::ptr_eq(&other, element) && other.borrow().id() == Some(id) {
+                    replacement = Some(other);
+                }
+            }
+        });
+
+        let mut document = document.borrow_mut();
+        match replacement {
+            Some(replacement) => {
+                document.elements_by_id.insert(id, replacement);
+            },
+            None => {
+                document.elements_by_id.remove(&id);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dom::ElementCustomState, infra::Namespace, static_interned};
+
+    fn element(id: Option<&str>) -> DomPtr<Element> {
+        let mut element = Element::new(
+            Namespace::HTML,
+            None,
+            "div".into(),
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        );
+
+        if let Some(id) = id {
+            element.append_attribute(static_interned!("id"), id.into());
+        }
+
+        DomPtr::new(element)
+    }
+
+    #[test]
+    fn id_index() {
+        let document = DomPtr::new(Document::default());
+        let lookup = |id: &str| document.borrow().get_element_by_id(id.into());
+        let is = |found: Option<DomPtr<Element>>, expected: &DomPtr<Element>| {
+            found.is_some_and(|found| DomPtr::ptr_eq(&found, expected))
+        };
+
+        let html = element(None);
+        let first = element(Some("a"));
+        let nested = element(Some("b"));
+        Node::append_child(first.clone().upcast(), nested.clone().upcast());
+
+        // Elements are only indexed once they are part of the document
+        Node::append_child(html.clone().upcast(), first.clone().upcast());
+        assert!(lookup("a").is_none());
+        Node::append_child(document.clone().upcast(), html.clone().upcast());
+        assert!(is(lookup("a"), &first));
+        assert!(is(lookup("b"), &nested));
+
+        // Adding an id
+        let second = element(None);
+        Node::append_child(html.clone().upcast(), second.clone().upcast());
+        Element::set_attribute(second.clone(), static_interned!("id"), "c".into());
+        assert!(is(lookup("c"), &second));
+
+        // Renaming an id to a duplicate keeps the first element in tree order
+        Element::set_attribute(second.clone(), static_interned!("id"), "b".into());
+        assert!(lookup("c").is_none());
+        assert!(is(lookup("b"), &nested));
+
+        // Renaming the first element makes the next one with the same id visible
+        Element::set_attribute(nested.clone(), static_interned!("id"), "d".into());
+        assert!(is(lookup("b"), &second));
+        assert!(is(lookup("d"), &nested));
+
+        // An element that precedes the current one takes its place
+        Element::set_attribute(first.clone(), static_interned!("id"), "b".into());
+        assert!(lookup("a").is_none());
+        assert!(is(lookup("b"), &first));
+
+        // Removing ids
+        Element::remove_attribute(first.clone(), static_interned!("id"));
+        assert!(is(lookup("b"), &second));
+        Element::remove_attribute(second.clone(), static_interned!("id"));
+        assert!(lookup("b").is_none());
+        assert!(is(lookup("d"), &nested));
+    }
 }
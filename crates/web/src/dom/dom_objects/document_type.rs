@@ -11,6 +11,24 @@ pub struct DocumentType {
 }
 
 impl DocumentType {
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> InternedString {
+        self.name
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn public_id(&self) -> InternedString {
+        self.public_id
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn system_id(&self) -> InternedString {
+        self.system_id
+    }
+
     pub fn set_name(&mut self, name: InternedString) {
         self.name = name;
     }
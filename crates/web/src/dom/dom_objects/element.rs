@@ -2,9 +2,13 @@ use std::{collections::HashMap, fmt};
 
 use dom_derive::inherit;
 
-use crate::{dom::ElementCustomState, infra::Namespace, static_interned, InternedString};
+use crate::{
+    dom::{DomPtr, DomTokenList, ElementCustomState},
+    infra::Namespace,
+    static_interned, InternedString,
+};
 
-use super::Node;
+use super::{Document, Node};
 
 /// Bitflag for states like active, hovered
 ///
@@ -33,6 +37,13 @@ impl ElementFlags {
     }
 }
 
+/// An attribute in a namespace other than the null namespace, like `xlink:href`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NamespacedAttribute {
+    pub prefix: Option<InternedString>,
+    pub value: InternedString,
+}
+
 /// <https://dom.spec.whatwg.org/#interface-element>
 #[inherit(Node)]
 pub struct Element {
@@ -42,7 +53,12 @@ pub struct Element {
     custom_state: ElementCustomState,
     is: Option<InternedString>,
     id: InternedString,
+
+    /// Attributes in the null namespace, keyed by their local name
     attributes: HashMap<InternedString, InternedString>,
+
+    /// Attributes in any other namespace, keyed by their namespace and local name
+    namespaced_attributes: HashMap<(Namespace, InternedString), NamespacedAttribute>,
     flags: ElementFlags,
 
     intrinsic_size: Option<math::Rectangle>,
@@ -68,19 +84,150 @@ impl Element {
     }
 
     /// <https://dom.spec.whatwg.org/#concept-element-attributes-append>
+    ///
+    /// This must only be used for elements that are not part of a document yet,
+    /// use [Element::set_attribute] otherwise.
     #[inline]
     pub fn append_attribute(&mut self, key: InternedString, value: InternedString) {
         self.attributes.insert(key, value);
     }
 
+    /// <https://dom.spec.whatwg.org/#dom-element-setattribute>
+    pub fn set_attribute(element: DomPtr<Self>, key: InternedString, value: InternedString) {
+        let old_value = element.borrow_mut().attributes.insert(key, value);
+        Self::handle_attribute_change(element, key, old_value, Some(value));
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-element-removeattribute>
+    pub fn remove_attribute(element: DomPtr<Self>, key: InternedString) {
+        let old_value = element.borrow_mut().attributes.remove(&key);
+        Self::handle_attribute_change(element, key, old_value, None);
+    }
+
+    /// Like [Element::append_attribute], but for an attribute in the given namespace
+    ///
+    /// Attributes in the null namespace are not allowed to have a prefix.
+    pub fn append_attribute_ns(
+        &mut self,
+        namespace: Option<Namespace>,
+        prefix: Option<InternedString>,
+        local_name: InternedString,
+        value: InternedString,
+    ) {
+        match namespace {
+            Some(namespace) => {
+                let attribute = NamespacedAttribute { prefix, value };
+                self.namespaced_attributes
+                    .insert((namespace, local_name), attribute);
+            },
+            None => {
+                debug_assert!(prefix.is_none());
+                self.append_attribute(local_name, value);
+            },
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#concept-element-attributes-get-by-namespace>
+    #[must_use]
+    pub fn get_attribute_ns(
+        &self,
+        namespace: Option<Namespace>,
+        local_name: InternedString,
+    ) -> Option<InternedString> {
+        match namespace {
+            Some(namespace) => self
+                .namespaced_attributes
+                .get(&(namespace, local_name))
+                .map(|attribute| attribute.value),
+            None => self.attributes.get(&local_name).copied(),
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-element-setattributens>
+    pub fn set_attribute_ns(
+        element: DomPtr<Self>,
+        namespace: Option<Namespace>,
+        prefix: Option<InternedString>,
+        local_name: InternedString,
+        value: InternedString,
+    ) {
+        match namespace {
+            Some(namespace) => {
+                let attribute = NamespacedAttribute { prefix, value };
+                element
+                    .borrow_mut()
+                    .namespaced_attributes
+                    .insert((namespace, local_name), attribute);
+            },
+            None => {
+                debug_assert!(prefix.is_none());
+                Self::set_attribute(element, local_name, value);
+            },
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-element-removeattributens>
+    pub fn remove_attribute_ns(
+        element: DomPtr<Self>,
+        namespace: Option<Namespace>,
+        local_name: InternedString,
+    ) {
+        match namespace {
+            Some(namespace) => {
+                element
+                    .borrow_mut()
+                    .namespaced_attributes
+                    .remove(&(namespace, local_name));
+            },
+            None => Self::remove_attribute(element, local_name),
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#handle-attribute-changes>
+    fn handle_attribute_change(
+        element: DomPtr<Self>,
+        key: InternedString,
+        old_value: Option<InternedString>,
+        value: Option<InternedString>,
+    ) {
+        if key != static_interned!("id") || old_value == value {
+            return;
+        }
+
+        // Keep the id index of the document up to date
+        let root = Node::root(element.clone().upcast());
+        let Some(document) = root.try_into_type::<Document>() else {
+            return;
+        };
+
+        if let Some(old_id) = old_value {
+            Document::unregister_element_id(document.clone(), old_id, &element);
+        }
+
+        if let Some(id) = value {
+            Document::register_element_id(document, id, element);
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-element-classlist>
+    #[must_use]
+    pub fn class_list(element: DomPtr<Self>) -> DomTokenList {
+        DomTokenList::new(element, static_interned!("class"))
+    }
+
     #[inline]
     pub fn attributes(&self) -> &HashMap<InternedString, InternedString> {
         &self.attributes
     }
 
+    /// The attributes of this element that are not in the null namespace
+    ///
+    /// Attributes in the null namespace are available through [Element::attributes].
     #[inline]
-    pub fn attributes_mut(&mut self) -> &mut HashMap<InternedString, InternedString> {
-        &mut self.attributes
+    pub fn namespaced_attributes(
+        &self,
+    ) -> &HashMap<(Namespace, InternedString), NamespacedAttribute> {
+        &self.namespaced_attributes
     }
 
     #[inline]
@@ -95,6 +242,19 @@ impl Element {
         self.namespace
     }
 
+    #[inline]
+    #[must_use]
+    pub fn namespace_prefix(&self) -> Option<InternedString> {
+        self.namespace_prefix
+    }
+
+    /// <https://dom.spec.whatwg.org/#concept-element-is-value>
+    #[inline]
+    #[must_use]
+    pub fn is_value(&self) -> Option<InternedString> {
+        self.is
+    }
+
     #[inline]
     pub fn id(&self) -> Option<InternedString> {
         self.attributes.get(&static_interned!("id")).copied()
@@ -133,3 +293,58 @@ impl fmt::Debug for ElementFlags {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespaced_attributes() {
+        let element = DomPtr::new(Element::new(
+            Namespace::SVG,
+            None,
+            static_interned!("a"),
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        ));
+
+        Element::set_attribute_ns(
+            element.clone(),
+            Some(Namespace::XLink),
+            Some(static_interned!("xlink")),
+            static_interned!("href"),
+            "namespaced".into(),
+        );
+        Element::set_attribute(element.clone(), static_interned!("href"), "plain".into());
+
+        // The attributes share a local name, but live in different namespaces
+        let element_ref = element.borrow();
+        assert_eq!(
+            element_ref.get_attribute_ns(Some(Namespace::XLink), static_interned!("href")),
+            Some("namespaced".into())
+        );
+        assert_eq!(
+            element_ref.get_attribute_ns(None, static_interned!("href")),
+            Some("plain".into())
+        );
+        assert_eq!(
+            element_ref.attributes().get(&static_interned!("href")),
+            Some(&"plain".into())
+        );
+        assert_eq!(
+            element_ref.get_attribute_ns(Some(Namespace::XML), static_interned!("href")),
+            None
+        );
+        drop(element_ref);
+
+        Element::remove_attribute_ns(element.clone(), None, static_interned!("href"));
+        assert_eq!(
+            element
+                .borrow()
+                .get_attribute_ns(Some(Namespace::XLink), static_interned!("href")),
+            Some("namespaced".into())
+        );
+        assert!(element.borrow().attributes().is_empty());
+    }
+}
@@ -0,0 +1,407 @@
+use dom_derive::inherit;
+
+use super::Node;
+use crate::{
+    dom::{
+        AddEventListenerOptions, DomPtr, Event, EventListener, EventListenerCallback, EventPhase,
+    },
+    InternedString,
+};
+
+/// <https://dom.spec.whatwg.org/#interface-eventtarget>
+#[inherit]
+pub struct EventTarget {
+    /// <https://dom.spec.whatwg.org/#eventtarget-event-listener-list>
+    event_listeners: Vec<EventListener>,
+}
+
+impl EventTarget {
+    #[inline]
+    #[must_use]
+    pub fn event_listeners(&self) -> &[EventListener] {
+        &self.event_listeners
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-eventtarget-addeventlistener>
+    pub fn add_event_listener(
+        &mut self,
+        event_type: InternedString,
+        callback: EventListenerCallback,
+        options: AddEventListenerOptions,
+    ) {
+        // https://dom.spec.whatwg.org/#add-an-event-listener
+        // If eventTarget’s event listener list does not contain an event listener whose type is
+        // listener’s type, callback is listener’s callback, and capture is listener’s capture,
+        // then append listener to eventTarget’s event listener list.
+        let is_duplicate = self.event_listeners.iter().any(|listener| {
+            listener.event_type == event_type
+                && listener.callback == callback
+                && listener.capture == options.capture
+        });
+
+        if !is_duplicate {
+            self.event_listeners
+                .push(EventListener::new(event_type, callback, options));
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-eventtarget-removeeventlistener>
+    pub fn remove_event_listener(
+        &mut self,
+        event_type: InternedString,
+        callback: &EventListenerCallback,
+        capture: bool,
+    ) {
+        // https://dom.spec.whatwg.org/#remove-an-event-listener
+        // Set listener’s removed to true and remove listener from eventTarget’s event listener list.
+        self.event_listeners.retain(|listener| {
+            let matches = listener.event_type == event_type
+                && listener.callback == *callback
+                && listener.capture == capture;
+
+            if matches {
+                listener.set_removed();
+            }
+
+            !matches
+        });
+    }
+
+    /// <https://dom.spec.whatwg.org/#concept-event-dispatch>
+    ///
+    /// Returns `false` if a listener cancelled the event and `true` otherwise.
+    pub fn dispatch_event(target: DomPtr<Self>, event: &mut Event) -> bool {
+        // NOTE: This does not implement shadow trees, activation behaviour or the
+        //       global object, which are not supported yet.
+
+        // Set event’s target to target.
+        event.set_target(Some(target.clone()));
+
+        // Build the event path, starting at the target and ending at the root of the tree.
+        // https://dom.spec.whatwg.org/#get-the-parent
+        let mut path = vec![target.clone()];
+        let mut current = target.try_into_type::<Node>();
+        while let Some(node) = current {
+            let parent = node.borrow().parent_node();
+            if let Some(parent) = &parent {
+                path.push(parent.clone().upcast());
+            }
+            current = parent;
+        }
+
+        // For each struct in event’s path, in reverse order:
+        for current_target in path.iter().rev() {
+            // If struct’s shadow-adjusted target is non-null, then set event’s eventPhase attribute to AT_TARGET.
+            // Otherwise, set event’s eventPhase attribute to CAPTURING_PHASE.
+            let phase = if DomPtr::ptr_eq(current_target, &target) {
+                EventPhase::AtTarget
+            } else {
+                EventPhase::Capturing
+            };
+            event.set_event_phase(phase);
+
+            // Invoke with struct, event, "capturing", and legacyOutputDidListenersThrowFlag if given.
+            Self::invoke(current_target, event, EventPhase::Capturing);
+        }
+
+        // For each struct in event’s path:
+        for current_target in &path {
+            if DomPtr::ptr_eq(current_target, &target) {
+                // If struct’s shadow-adjusted target is non-null, then set event’s eventPhase attribute to AT_TARGET.
+                event.set_event_phase(EventPhase::AtTarget);
+            } else {
+                // Otherwise:
+                // If event’s bubbles attribute is false, then continue.
+                if !event.bubbles() {
+                    continue;
+                }
+
+                // Set event’s eventPhase attribute to BUBBLING_PHASE.
+                event.set_event_phase(EventPhase::Bubbling);
+            }
+
+            // Invoke with struct, event, "bubbling", and legacyOutputDidListenersThrowFlag if given.
+            Self::invoke(current_target, event, EventPhase::Bubbling);
+        }
+
+        // Set event’s eventPhase attribute to NONE.
+        event.set_event_phase(EventPhase::None);
+
+        // Set event’s currentTarget attribute to null.
+        event.set_current_target(None);
+
+        // Unset event’s dispatch flag, stop propagation flag, and stop immediate propagation flag.
+        event.reset_propagation_flags();
+
+        // Return false if event’s canceled flag is set; otherwise true.
+        !event.default_prevented()
+    }
+
+    /// <https://dom.spec.whatwg.org/#concept-event-listener-invoke>
+    fn invoke(current_target: &DomPtr<Self>, event: &mut Event, phase: EventPhase) {
+        // If event’s stop propagation flag is set, then return.
+        if event.is_propagation_stopped() {
+            return;
+        }
+
+        // Set event’s currentTarget attribute to struct’s invocation target.
+        event.set_current_target(Some(current_target.clone()));
+
+        // Let listeners be a clone of event’s currentTarget attribute value’s event listener list.
+        // NOTE: This is also required because listeners may modify the DOM.
+        let listeners = current_target.borrow().event_listeners.clone();
+
+        // https://dom.spec.whatwg.org/#concept-event-listener-inner-invoke
+        for listener in listeners {
+            // If listener’s removed is true, then continue.
+            if listener.is_removed() {
+                continue;
+            }
+
+            // If event’s type attribute value is not listener’s type, then continue.
+            if listener.event_type != event.event_type() {
+                continue;
+            }
+
+            // If phase is "capturing" and listener’s capture is false, then continue.
+            // If phase is "bubbling" and listener’s capture is true, then continue.
+            if listener.capture != (phase == EventPhase::Capturing) {
+                continue;
+            }
+
+            // If listener’s once is true, then remove an event listener given event’s currentTarget
+            // attribute value and listener.
+            if listener.once {
+                current_target.borrow_mut().remove_event_listener(
+                    listener.event_type,
+                    &listener.callback,
+                    listener.capture,
+                );
+            }
+
+            // Call a user object’s operation with listener’s callback, "handleEvent", « event », and event’s currentTarget attribute value.
+            listener.callback.call(event);
+
+            // If event’s stop immediate propagation flag is set, then break.
+            if event.is_immediate_propagation_stopped() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{
+        dom::{dom_objects::Element, ElementCustomState},
+        infra::Namespace,
+    };
+
+    fn element(local_name: &str) -> DomPtr<Node> {
+        let element = Element::new(
+            Namespace::HTML,
+            None,
+            local_name.into(),
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        );
+
+        DomPtr::new(element).upcast()
+    }
+
+    /// Register a listener on `target` that records `name` and the current phase
+    fn record(
+        target: &DomPtr<Node>,
+        log: &Rc<RefCell<Vec<(&'static str, EventPhase)>>>,
+        name: &'static str,
+        capture: bool,
+    ) {
+        let log = log.clone();
+        let callback = EventListenerCallback::new(move |event| {
+            log.borrow_mut().push((name, event.event_phase()));
+        });
+
+        target.borrow_mut().add_event_listener(
+            "click".into(),
+            callback,
+            AddEventListenerOptions {
+                capture,
+                once: false,
+            },
+        );
+    }
+
+    #[test]
+    fn register_and_remove_listeners() {
+        let mut target = EventTarget::default();
+        let callback = EventListenerCallback::new(|_| {});
+        let other_callback = EventListenerCallback::new(|_| {});
+        let capture = AddEventListenerOptions {
+            capture: true,
+            once: false,
+        };
+
+        target.add_event_listener(
+            "click".into(),
+            callback.clone(),
+            AddEventListenerOptions::default(),
+        );
+
+        // Identical registrations are ignored, even if "once" differs
+        target.add_event_listener(
+            "click".into(),
+            callback.clone(),
+            AddEventListenerOptions {
+                capture: false,
+                once: true,
+            },
+        );
+        assert_eq!(target.event_listeners().len(), 1);
+        assert!(!target.event_listeners()[0].once);
+
+        // ...but the same callback can be registered for capturing, another type
+        // or with a different callback
+        target.add_event_listener("click".into(), callback.clone(), capture);
+        target.add_event_listener("focus".into(), callback.clone(), capture);
+        target.add_event_listener(
+            "click".into(),
+            other_callback.clone(),
+            AddEventListenerOptions::default(),
+        );
+        assert_eq!(target.event_listeners().len(), 4);
+
+        let first = target.event_listeners()[0].clone();
+        target.remove_event_listener("click".into(), &callback, false);
+        assert!(first.is_removed());
+        assert_eq!(target.event_listeners().len(), 3);
+        assert!(target
+            .event_listeners()
+            .iter()
+            .all(|listener| listener.capture || listener.callback == other_callback));
+
+        // Removing a listener that was never registered does nothing
+        target.remove_event_listener("click".into(), &callback, false);
+        assert_eq!(target.event_listeners().len(), 3);
+    }
+
+    #[test]
+    fn dispatch_order() {
+        let body = element("body");
+        let div = element("div");
+        let span = element("span");
+        Node::append_child(body.clone(), div.clone());
+        Node::append_child(div.clone(), span.clone());
+
+        let log = Rc::new(RefCell::new(vec![]));
+        record(&body, &log, "body bubble", false);
+        record(&body, &log, "body capture", true);
+        record(&div, &log, "div bubble", false);
+        record(&div, &log, "div capture", true);
+        record(&span, &log, "span bubble", false);
+        record(&span, &log, "span capture", true);
+
+        let mut event = Event::new("click".into(), true, true);
+        assert!(EventTarget::dispatch_event(
+            span.clone().upcast(),
+            &mut event
+        ));
+        assert_eq!(
+            *log.borrow(),
+            [
+                ("body capture", EventPhase::Capturing),
+                ("div capture", EventPhase::Capturing),
+                ("span capture", EventPhase::AtTarget),
+                ("span bubble", EventPhase::AtTarget),
+                ("div bubble", EventPhase::Bubbling),
+                ("body bubble", EventPhase::Bubbling),
+            ]
+        );
+        assert_eq!(event.event_phase(), EventPhase::None);
+        assert!(event.current_target().is_none());
+        assert!(event
+            .target()
+            .is_some_and(|target| DomPtr::ptr_eq(&target, &span)));
+
+        // Events that don't bubble are only seen by capturing listeners and the target
+        log.borrow_mut().clear();
+        let mut event = Event::new("click".into(), false, true);
+        EventTarget::dispatch_event(span.clone().upcast(), &mut event);
+        assert_eq!(
+            log.borrow()
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>(),
+            ["body capture", "div capture", "span capture", "span bubble"]
+        );
+    }
+
+    #[test]
+    fn stop_propagation_and_prevent_default() {
+        let div = element("div");
+        let span = element("span");
+        Node::append_child(div.clone(), span.clone());
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let stop = EventListenerCallback::new(|event| {
+            event.stop_immediate_propagation();
+            event.prevent_default();
+        });
+        record(&span, &log, "span first", false);
+        span.borrow_mut().add_event_listener(
+            "click".into(),
+            stop,
+            AddEventListenerOptions::default(),
+        );
+        record(&span, &log, "span second", false);
+        record(&div, &log, "div", false);
+
+        let mut event = Event::new("click".into(), true, true);
+        assert!(!EventTarget::dispatch_event(
+            span.clone().upcast(),
+            &mut event
+        ));
+        assert!(event.default_prevented());
+        assert_eq!(
+            log.borrow()
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>(),
+            ["span first"]
+        );
+
+        // Events that are not cancelable cannot be cancelled
+        let mut event = Event::new("click".into(), true, false);
+        assert!(EventTarget::dispatch_event(
+            span.clone().upcast(),
+            &mut event
+        ));
+    }
+
+    #[test]
+    fn once_listeners_are_removed() {
+        let target = element("div");
+        let count = Rc::new(RefCell::new(0));
+
+        let counter = count.clone();
+        target.borrow_mut().add_event_listener(
+            "click".into(),
+            EventListenerCallback::new(move |_| *counter.borrow_mut() += 1),
+            AddEventListenerOptions {
+                capture: false,
+                once: true,
+            },
+        );
+
+        for _ in 0..3 {
+            let mut event = Event::new("click".into(), false, false);
+            EventTarget::dispatch_event(target.clone().upcast(), &mut event);
+        }
+
+        assert_eq!(*count.borrow(), 1);
+        assert!(target.borrow().event_listeners().is_empty());
+    }
+}
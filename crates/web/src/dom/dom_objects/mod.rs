@@ -3,6 +3,7 @@ mod comment;
 mod document;
 mod document_type;
 mod element;
+mod event_target;
 mod html_anchor_element;
 mod html_body_element;
 mod html_button_element;
@@ -33,6 +34,7 @@ pub use comment::Comment;
 pub use document::Document;
 pub use document_type::DocumentType;
 pub use element::Element;
+pub use event_target::EventTarget;
 pub use html_anchor_element::HtmlAnchorElement;
 pub use html_body_element::HtmlBodyElement;
 pub use html_button_element::HtmlButtonElement;
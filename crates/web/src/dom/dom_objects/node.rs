@@ -1,14 +1,14 @@
 use dom_derive::inherit;
 use std::fmt;
 
-use super::{Comment, Document, Text};
+use super::{Comment, Document, DocumentType, Element, EventTarget, Text};
 use crate::{
-    dom::{DomPtr, DomType, WeakDomPtr},
+    dom::{create_element_for_interface, DomPtr, DomType, ElementCustomState, WeakDomPtr},
     TreeDebug, TreeFormatter,
 };
 
 /// <https://dom.spec.whatwg.org/#interface-node>
-#[inherit]
+#[inherit(EventTarget)]
 pub struct Node {
     parent_node: Option<WeakDomPtr<Node>>,
     child_nodes: Vec<DomPtr<Node>>,
@@ -94,7 +94,230 @@ impl Node {
 
     pub fn append_child(parent: DomPtr<Node>, child: DomPtr<Node>) {
         child.borrow_mut().parent_node = Some(parent.downgrade());
-        parent.borrow_mut().child_nodes.push(child);
+        parent.borrow_mut().child_nodes.push(child.clone());
+
+        // Elements that are inserted into a document become reachable via their id
+        if let Some(document) = Self::root(parent).try_into_type::<Document>() {
+            Self::for_each_inclusive_descendant(child, &mut |node| {
+                if let Some(element) = node.try_into_type::<Element>() {
+                    let id = element.borrow().id();
+                    if let Some(id) = id {
+                        Document::register_element_id(document.clone(), id, element);
+                    }
+                }
+            });
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#concept-tree-root>
+    #[must_use]
+    pub fn root(node: DomPtr<Self>) -> DomPtr<Self> {
+        let mut root = node;
+        loop {
+            let parent = root.borrow().parent_node();
+            match parent {
+                Some(parent) => root = parent,
+                None => return root,
+            }
+        }
+    }
+
+    /// Visit the node and all of its descendants in tree order
+    pub fn for_each_inclusive_descendant<F>(node: DomPtr<Self>, f: &mut F)
+    where
+        F: FnMut(DomPtr<Self>),
+    {
+        f(node.clone());
+
+        let children = node.borrow().children().to_vec();
+        for child in children {
+            Self::for_each_inclusive_descendant(child, f);
+        }
+    }
+
+    /// Merge adjacent [Text] nodes in the subtree of `node` and remove empty ones
+    ///
+    /// <https://dom.spec.whatwg.org/#dom-node-normalize>
+    pub fn normalize(node: DomPtr<Self>) {
+        let children = std::mem::take(&mut node.borrow_mut().child_nodes);
+        let mut normalized_children: Vec<DomPtr<Self>> = Vec::with_capacity(children.len());
+
+        for child in children {
+            let Some(text) = child.try_into_type::<Text>() else {
+                Self::normalize(child.clone());
+                normalized_children.push(child);
+                continue;
+            };
+
+            let previous_text = normalized_children
+                .last()
+                .and_then(|previous| previous.try_into_type::<Text>());
+
+            match previous_text {
+                Some(previous_text) => {
+                    // Contiguous text nodes are merged into the first one of them
+                    previous_text
+                        .borrow_mut()
+                        .content_mut()
+                        .push_str(text.borrow().content());
+                },
+                None if !text.borrow().content().is_empty() => {
+                    normalized_children.push(child);
+                    continue;
+                },
+                None => {},
+            }
+
+            // The text node was either merged into its predecessor or is empty
+            child.borrow_mut().parent_node = None;
+        }
+
+        node.borrow_mut().child_nodes = normalized_children;
+    }
+
+    /// Return true if `this` is [preceding](https://dom.spec.whatwg.org/#concept-tree-preceding) `other`
+    ///
+    /// The two nodes should be in the same tree.
+    #[must_use]
+    pub fn is_preceding(this: DomPtr<Self>, other: DomPtr<Self>) -> bool {
+        let inclusive_ancestors = |node: DomPtr<Self>| {
+            let mut ancestors = vec![];
+            let mut current = Some(node);
+            while let Some(node) = current {
+                current = node.borrow().parent_node();
+                ancestors.push(node);
+            }
+            ancestors.reverse();
+            ancestors
+        };
+
+        let this_ancestors = inclusive_ancestors(this);
+        let other_ancestors = inclusive_ancestors(other);
+
+        // Find the first ancestor that is not shared between the two nodes
+        let first_difference = this_ancestors
+            .iter()
+            .zip(&other_ancestors)
+            .position(|(a, b)| !DomPtr::ptr_eq(a, b));
+
+        let Some(index) = first_difference else {
+            // One node is an inclusive ancestor of the other, ancestors precede their descendants
+            return this_ancestors.len() < other_ancestors.len();
+        };
+
+        if index == 0 {
+            // The nodes are not in the same tree
+            return false;
+        }
+
+        // Compare the positions of the two subtrees within their common ancestor
+        let common_ancestor = this_ancestors[index - 1].borrow();
+        let position_of = |node: &DomPtr<Self>| {
+            common_ancestor
+                .children()
+                .iter()
+                .position(|child| DomPtr::ptr_eq(child, node))
+        };
+
+        position_of(&this_ancestors[index]) < position_of(&other_ancestors[index])
+    }
+
+    /// Create a copy of `node` that is not part of any tree
+    ///
+    /// If `deep` is true, then all descendants are copied as well. The copy belongs
+    /// to the same document, but elements are only registered in its id index
+    /// once they are inserted.
+    ///
+    /// <https://dom.spec.whatwg.org/#concept-node-clone>
+    #[must_use]
+    pub fn clone_node(node: DomPtr<Self>, deep: bool) -> DomPtr<Self> {
+        let owning_document = node.borrow().owning_document.clone();
+
+        let copy: DomPtr<Self> = match node.underlying_type() {
+            DomType::Document => DomPtr::new(Document::default()).upcast(),
+            DomType::DocumentType => {
+                let doctype = node.clone().into_type::<DocumentType>();
+                let doctype = doctype.borrow();
+
+                let mut copy = DocumentType::default();
+                copy.set_name(doctype.name());
+                copy.set_public_id(doctype.public_id());
+                copy.set_system_id(doctype.system_id());
+                DomPtr::new(copy).upcast()
+            },
+            DomType::Text => {
+                let mut copy = Text::default();
+                copy.content_mut()
+                    .push_str(node.clone().into_type::<Text>().borrow().content());
+                DomPtr::new(copy).upcast()
+            },
+            DomType::Comment => {
+                let mut copy = Comment::default();
+                copy.content_mut()
+                    .push_str(node.clone().into_type::<Comment>().borrow().comment_data());
+                DomPtr::new(copy).upcast()
+            },
+            _ if node.is_a::<Element>() => {
+                let element = node.clone().into_type::<Element>();
+                let element = element.borrow();
+
+                // 1. Let copy be the result of creating an element, given document, node’s local name,
+                //    node’s namespace, node’s namespace prefix, and node’s is value, with the synchronous
+                //    custom elements flag unset.
+                let mut element_data = Element::new(
+                    element.namespace(),
+                    element.namespace_prefix(),
+                    element.local_name(),
+                    ElementCustomState::Uncustomized,
+                    None,
+                    element.is_value(),
+                );
+
+                // 2. For each attribute in node’s attribute list:
+                //    Append copyAttribute to copy.
+                for (&key, &value) in element.attributes() {
+                    element_data.append_attribute(key, value);
+                }
+                for (&(namespace, local_name), attribute) in element.namespaced_attributes() {
+                    element_data.append_attribute_ns(
+                        Some(namespace),
+                        attribute.prefix,
+                        local_name,
+                        attribute.value,
+                    );
+                }
+
+                create_element_for_interface(
+                    element.local_name(),
+                    element.namespace(),
+                    element_data,
+                )
+                .upcast()
+            },
+            other => {
+                log::warn!("Cannot clone node of type {other:?}");
+                DomPtr::new(Self::default())
+            },
+        };
+
+        // Set copy’s node document and document to copy, if copy is a document,
+        // and set copy’s node document to document otherwise.
+        if !copy.is_a::<Document>() {
+            if let Some(document) = owning_document {
+                copy.borrow_mut().set_owning_document(document);
+            }
+        }
+
+        // If the clone children flag is set, then for each child child of node, in tree order:
+        // append the result of cloning child with document and the clone children flag set, to copy.
+        if deep {
+            let children = node.borrow().children().to_vec();
+            for child in children {
+                Self::append_child(copy.clone(), Self::clone_node(child, true));
+            }
+        }
+
+        copy
     }
 
     pub fn owning_document(&self) -> Option<DomPtr<Document>> {
@@ -112,3 +335,114 @@ impl fmt::Debug for DomPtr<Node> {
         self.tree_fmt(&mut tree_formatter)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{infra::Namespace, static_interned};
+
+    #[test]
+    fn clone_node() {
+        let mut element = Element::new(
+            Namespace::HTML,
+            None,
+            static_interned!("div"),
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        );
+        element.append_attribute(static_interned!("id"), "original".into());
+        element.append_attribute(static_interned!("class"), "a b".into());
+        let original: DomPtr<Node> = DomPtr::new(element).upcast();
+
+        let mut text = Text::default();
+        text.content_mut().push_str("hello");
+        let text: DomPtr<Node> = DomPtr::new(text).upcast();
+        Node::append_child(original.clone(), text.clone());
+
+        // A shallow clone has no children
+        let shallow = Node::clone_node(original.clone(), false);
+        assert!(shallow.borrow().children().is_empty());
+        assert!(shallow.borrow().parent_node().is_none());
+
+        let deep = Node::clone_node(text.clone(), true);
+        assert!(deep.borrow().parent_node().is_none());
+        assert_eq!(deep.into_type::<Text>().borrow().content(), "hello");
+
+        let deep = Node::clone_node(original.clone(), true).into_type::<Element>();
+        assert!(!DomPtr::ptr_eq(&deep, &original));
+        assert_eq!(deep.borrow().local_name(), static_interned!("div"));
+        assert_eq!(
+            deep.borrow().attributes(),
+            original
+                .clone()
+                .into_type::<Element>()
+                .borrow()
+                .attributes()
+        );
+
+        // Modifying the copy does not affect the original
+        let cloned_text = deep.borrow().children()[0].clone();
+        assert!(!DomPtr::ptr_eq(&cloned_text, &text));
+        cloned_text
+            .into_type::<Text>()
+            .borrow_mut()
+            .content_mut()
+            .push_str(" world");
+        assert_eq!(text.clone().into_type::<Text>().borrow().content(), "hello");
+
+        Element::set_attribute(deep.clone(), static_interned!("id"), "copy".into());
+        let original = original.into_type::<Element>();
+        assert_eq!(original.borrow().id(), Some("original".into()));
+        assert!(DomPtr::ptr_eq(
+            &text.borrow().parent_node().unwrap(),
+            &original
+        ));
+    }
+
+    #[test]
+    fn normalize() {
+        let text = |content: &str| -> DomPtr<Node> {
+            let mut text = Text::default();
+            text.content_mut().push_str(content);
+            DomPtr::new(text).upcast()
+        };
+        let element = |local_name| -> DomPtr<Node> {
+            DomPtr::new(Element::new(
+                Namespace::HTML,
+                None,
+                local_name,
+                ElementCustomState::Uncustomized,
+                None,
+                None,
+            ))
+            .upcast()
+        };
+
+        let parent = element(static_interned!("div"));
+
+        let first = text("foo");
+        let second = text("bar");
+        Node::append_child(parent.clone(), first.clone());
+        Node::append_child(parent.clone(), second.clone());
+        Node::append_child(parent.clone(), text("baz"));
+
+        Node::normalize(parent.clone());
+
+        assert_eq!(parent.borrow().children().len(), 1);
+        assert!(DomPtr::ptr_eq(&parent.borrow().children()[0], &first));
+        assert_eq!(first.into_type::<Text>().borrow().content(), "foobarbaz");
+        assert!(second.borrow().parent_node().is_none());
+
+        // Empty text nodes are removed entirely, even if they are not adjacent to other text
+        let br = element(static_interned!("br"));
+        Node::append_child(parent.clone(), br.clone());
+        Node::append_child(br.clone(), text(""));
+        Node::append_child(parent.clone(), text(""));
+
+        Node::normalize(parent.clone());
+
+        assert_eq!(parent.borrow().children().len(), 2);
+        assert!(br.borrow().children().is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! <https://dom.spec.whatwg.org/#events>
+
+use std::{cell::Cell, fmt, rc::Rc};
+
+use crate::{
+    dom::{dom_objects::EventTarget, DomPtr},
+    InternedString,
+};
+
+/// <https://dom.spec.whatwg.org/#interface-event>
+#[derive(Clone)]
+pub struct Event {
+    event_type: InternedString,
+    bubbles: bool,
+    cancelable: bool,
+
+    /// <https://dom.spec.whatwg.org/#event-target>
+    target: Option<DomPtr<EventTarget>>,
+
+    /// <https://dom.spec.whatwg.org/#dom-event-currenttarget>
+    current_target: Option<DomPtr<EventTarget>>,
+
+    /// <https://dom.spec.whatwg.org/#dom-event-eventphase>
+    event_phase: EventPhase,
+
+    /// <https://dom.spec.whatwg.org/#stop-propagation-flag>
+    stop_propagation: bool,
+
+    /// <https://dom.spec.whatwg.org/#stop-immediate-propagation-flag>
+    stop_immediate_propagation: bool,
+
+    /// <https://dom.spec.whatwg.org/#canceled-flag>
+    canceled: bool,
+}
+
+/// <https://dom.spec.whatwg.org/#dom-event-eventphase>
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventPhase {
+    #[default]
+    None,
+    Capturing,
+    AtTarget,
+    Bubbling,
+}
+
+/// A function that is invoked when an [Event] is dispatched
+///
+/// Two callbacks are considered equal if they refer to the same function object,
+/// just like in javascript.
+#[derive(Clone)]
+pub struct EventListenerCallback(Rc<dyn Fn(&mut Event)>);
+
+/// <https://dom.spec.whatwg.org/#dictdef-addeventlisteneroptions>
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AddEventListenerOptions {
+    /// Whether the listener should be invoked during the capturing phase
+    /// instead of the bubbling phase
+    pub capture: bool,
+
+    /// Whether the listener should be removed after it was invoked once
+    pub once: bool,
+}
+
+/// <https://dom.spec.whatwg.org/#concept-event-listener>
+#[derive(Clone, Debug)]
+pub struct EventListener {
+    pub event_type: InternedString,
+    pub callback: EventListenerCallback,
+    pub capture: bool,
+    pub once: bool,
+
+    /// Shared between all clones of the listener, so that listeners that are removed
+    /// while an event is being dispatched are not invoked anymore
+    removed: Rc<Cell<bool>>,
+}
+
+impl Event {
+    #[must_use]
+    pub fn new(event_type: InternedString, bubbles: bool, cancelable: bool) -> Self {
+        Self {
+            event_type,
+            bubbles,
+            cancelable,
+            target: None,
+            current_target: None,
+            event_phase: EventPhase::None,
+            stop_propagation: false,
+            stop_immediate_propagation: false,
+            canceled: false,
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-type>
+    #[inline]
+    #[must_use]
+    pub fn event_type(&self) -> InternedString {
+        self.event_type
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-bubbles>
+    #[inline]
+    #[must_use]
+    pub fn bubbles(&self) -> bool {
+        self.bubbles
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-cancelable>
+    #[inline]
+    #[must_use]
+    pub fn cancelable(&self) -> bool {
+        self.cancelable
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-target>
+    #[inline]
+    #[must_use]
+    pub fn target(&self) -> Option<DomPtr<EventTarget>> {
+        self.target.clone()
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-currenttarget>
+    #[inline]
+    #[must_use]
+    pub fn current_target(&self) -> Option<DomPtr<EventTarget>> {
+        self.current_target.clone()
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-eventphase>
+    #[inline]
+    #[must_use]
+    pub fn event_phase(&self) -> EventPhase {
+        self.event_phase
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-stoppropagation>
+    pub fn stop_propagation(&mut self) {
+        self.stop_propagation = true;
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-stopimmediatepropagation>
+    pub fn stop_immediate_propagation(&mut self) {
+        self.stop_propagation = true;
+        self.stop_immediate_propagation = true;
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-preventdefault>
+    pub fn prevent_default(&mut self) {
+        // https://dom.spec.whatwg.org/#set-the-canceled-flag
+        // FIXME: Listeners can be passive, in which case this does nothing
+        if self.cancelable {
+            self.canceled = true;
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-event-defaultprevented>
+    #[inline]
+    #[must_use]
+    pub fn default_prevented(&self) -> bool {
+        self.canceled
+    }
+
+    pub(crate) fn set_target(&mut self, target: Option<DomPtr<EventTarget>>) {
+        self.target = target;
+    }
+
+    pub(crate) fn set_current_target(&mut self, current_target: Option<DomPtr<EventTarget>>) {
+        self.current_target = current_target;
+    }
+
+    pub(crate) fn set_event_phase(&mut self, event_phase: EventPhase) {
+        self.event_phase = event_phase;
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_propagation_stopped(&self) -> bool {
+        self.stop_propagation
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_immediate_propagation_stopped(&self) -> bool {
+        self.stop_immediate_propagation
+    }
+
+    /// Unset the flags that are only valid during a single dispatch
+    pub(crate) fn reset_propagation_flags(&mut self) {
+        self.stop_propagation = false;
+        self.stop_immediate_propagation = false;
+    }
+}
+
+impl EventListenerCallback {
+    #[must_use]
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&mut Event) + 'static,
+    {
+        Self(Rc::new(callback))
+    }
+
+    pub fn call(&self, event: &mut Event) {
+        (self.0)(event)
+    }
+}
+
+impl PartialEq for EventListenerCallback {
+    fn eq(&self, other: &Self) -> bool {
+        // Compare only the data pointers, vtables are not guaranteed to be unique
+        Rc::as_ptr(&self.0).cast::<()>() == Rc::as_ptr(&other.0).cast::<()>()
+    }
+}
+
+impl fmt::Debug for EventListenerCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EventListenerCallback({:?})",
+            Rc::as_ptr(&self.0).cast::<()>()
+        )
+    }
+}
+
+impl EventListener {
+    #[must_use]
+    pub fn new(
+        event_type: InternedString,
+        callback: EventListenerCallback,
+        options: AddEventListenerOptions,
+    ) -> Self {
+        Self {
+            event_type,
+            callback,
+            capture: options.capture,
+            once: options.once,
+            removed: Rc::default(),
+        }
+    }
+
+    /// <https://dom.spec.whatwg.org/#event-listener-removed>
+    #[inline]
+    #[must_use]
+    pub fn is_removed(&self) -> bool {
+        self.removed.get()
+    }
+
+    pub fn set_removed(&self) {
+        self.removed.set(true);
+    }
+}
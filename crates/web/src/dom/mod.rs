@@ -4,6 +4,8 @@ mod boundary_point;
 mod codegen;
 pub mod dom_objects;
 mod dom_ptr;
+mod event;
+mod token_list;
 
 pub use boundary_point::{BoundaryPoint, RelativePosition};
 pub use codegen::{DomType, DomTyped, IsA};
@@ -15,6 +17,8 @@ use dom_objects::{
     HtmlTemplateElement, HtmlTitleElement,
 };
 pub use dom_ptr::{DomPtr, WeakDomPtr};
+pub use event::{AddEventListenerOptions, Event, EventListener, EventListenerCallback, EventPhase};
+pub use token_list::DomTokenList;
 
 use crate::{infra::Namespace, static_interned, InternedString};
 
@@ -117,7 +121,7 @@ pub fn lookup_custom_element_definition(
     None
 }
 
-fn create_element_for_interface(
+pub(crate) fn create_element_for_interface(
     local_name: InternedString,
     namespace: Namespace,
     element_data: Element,
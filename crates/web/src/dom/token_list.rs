@@ -0,0 +1,195 @@
+//! <https://dom.spec.whatwg.org/#interface-domtokenlist>
+
+use crate::{
+    dom::{dom_objects::Element, DomPtr},
+    InternedString,
+};
+
+/// A view of the whitespace-separated tokens within an attribute, like `class`
+///
+/// Modifying the token list writes the (normalized) tokens back to the attribute.
+///
+/// <https://dom.spec.whatwg.org/#interface-domtokenlist>
+#[derive(Clone)]
+pub struct DomTokenList {
+    element: DomPtr<Element>,
+    attribute: InternedString,
+}
+
+impl DomTokenList {
+    #[must_use]
+    pub(crate) fn new(element: DomPtr<Element>, attribute: InternedString) -> Self {
+        Self { element, attribute }
+    }
+
+    /// <https://dom.spec.whatwg.org/#concept-dtl-tokens>
+    ///
+    /// Tokens are returned in order of their first occurence, without duplicates.
+    #[must_use]
+    pub fn tokens(&self) -> Vec<InternedString> {
+        let Some(value) = self
+            .element
+            .borrow()
+            .attributes()
+            .get(&self.attribute)
+            .copied()
+        else {
+            return vec![];
+        };
+
+        // https://infra.spec.whatwg.org/#ordered-set
+        let mut tokens: Vec<InternedString> = vec![];
+        for token in value.to_string().split_ascii_whitespace() {
+            let token = InternedString::from(token);
+            if !tokens.contains(&token) {
+                tokens.push(token);
+            }
+        }
+
+        tokens
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-domtokenlist-contains>
+    #[must_use]
+    pub fn contains(&self, token: InternedString) -> bool {
+        self.tokens().contains(&token)
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-domtokenlist-add>
+    pub fn add(&self, token: InternedString) {
+        let mut tokens = self.tokens();
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+
+        self.update(&tokens);
+    }
+
+    /// <https://dom.spec.whatwg.org/#dom-domtokenlist-remove>
+    pub fn remove(&self, token: InternedString) {
+        let mut tokens = self.tokens();
+        tokens.retain(|&t| t != token);
+
+        self.update(&tokens);
+    }
+
+    /// Remove the token if it is present, otherwise add it
+    ///
+    /// Returns whether the token is present afterwards.
+    ///
+    /// <https://dom.spec.whatwg.org/#dom-domtokenlist-toggle>
+    pub fn toggle(&self, token: InternedString) -> bool {
+        let mut tokens = self.tokens();
+
+        let is_present = match tokens.iter().position(|&t| t == token) {
+            Some(index) => {
+                tokens.remove(index);
+                false
+            },
+            None => {
+                tokens.push(token);
+                true
+            },
+        };
+
+        self.update(&tokens);
+        is_present
+    }
+
+    /// <https://dom.spec.whatwg.org/#concept-dtl-update>
+    fn update(&self, tokens: &[InternedString]) {
+        // 1. If the associated element does not have an associated attribute and token set is empty,
+        //    then return.
+        let has_attribute = self
+            .element
+            .borrow()
+            .attributes()
+            .contains_key(&self.attribute);
+        if !has_attribute && tokens.is_empty() {
+            return;
+        }
+
+        // 2. Set an attribute value for the associated element using associated attribute’s local name
+        //    and the result of running the ordered set serializer for token set.
+        let serialized = tokens
+            .iter()
+            .map(InternedString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Element::set_attribute(self.element.clone(), self.attribute, serialized.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dom::{dom_objects::Element, DomPtr, ElementCustomState},
+        infra::Namespace,
+        static_interned,
+    };
+
+    fn element_with_class(class: Option<&str>) -> DomPtr<Element> {
+        let mut element = Element::new(
+            Namespace::HTML,
+            None,
+            "div".into(),
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        );
+
+        if let Some(class) = class {
+            element.append_attribute(static_interned!("class"), class.into());
+        }
+
+        DomPtr::new(element)
+    }
+
+    fn class_attribute(element: &DomPtr<Element>) -> Option<String> {
+        element
+            .borrow()
+            .attributes()
+            .get(&static_interned!("class"))
+            .map(ToString::to_string)
+    }
+
+    #[test]
+    fn toggle() {
+        let element = element_with_class(Some("  foo\tbar foo "));
+        let class_list = Element::class_list(element.clone());
+
+        assert_eq!(class_list.tokens(), ["foo".into(), "bar".into()]);
+        assert!(class_list.contains("bar".into()));
+        assert!(!class_list.contains("baz".into()));
+
+        // Toggling an absent class adds it
+        assert!(class_list.toggle("baz".into()));
+        assert!(class_list.contains("baz".into()));
+        assert_eq!(class_attribute(&element).as_deref(), Some("foo bar baz"));
+
+        // Toggling a present class removes it
+        assert!(!class_list.toggle("foo".into()));
+        assert!(!class_list.contains("foo".into()));
+        assert_eq!(class_attribute(&element).as_deref(), Some("bar baz"));
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let element = element_with_class(None);
+        let class_list = Element::class_list(element.clone());
+
+        // Removing from an empty list does not create the attribute
+        class_list.remove("foo".into());
+        assert_eq!(class_attribute(&element), None);
+
+        class_list.add("foo".into());
+        class_list.add("bar".into());
+        class_list.add("foo".into());
+        assert_eq!(class_attribute(&element).as_deref(), Some("foo bar"));
+
+        class_list.remove("foo".into());
+        class_list.remove("bar".into());
+        assert_eq!(class_attribute(&element).as_deref(), Some(""));
+        assert!(class_list.tokens().is_empty());
+    }
+}
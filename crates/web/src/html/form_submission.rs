@@ -0,0 +1,435 @@
+//! <https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#form-submission-2>
+
+use error_derive::Error;
+use http::{multipart::MultipartBody, Header, Request};
+use url::URL;
+
+use crate::{
+    dom::{
+        dom_objects::{Element, HtmlFormElement, Node, Text},
+        DomPtr,
+    },
+    static_interned, InternedString,
+};
+
+#[derive(Clone, Copy, Debug, Error)]
+pub enum FormSubmissionError {
+    #[msg = "form is not part of a document"]
+    NoDocument,
+
+    #[msg = "form action is not a valid url"]
+    InvalidAction,
+
+    #[msg = "form action is not a http(s) url"]
+    UnsupportedScheme,
+
+    #[msg = "dialog forms are not submitted over the network"]
+    DialogMethod,
+}
+
+/// <https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#attr-fs-method>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    Get,
+    Post,
+    Dialog,
+}
+
+/// <https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#attr-fs-enctype>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Enctype {
+    UrlEncoded,
+    FormData,
+    TextPlain,
+}
+
+/// Build the request that is sent when `form` is submitted
+///
+/// `submitter` is the button that was used to submit the form, if any. Its `formaction`,
+/// `formmethod` and `formenctype` attributes take precedence over the ones on the form.
+///
+/// <https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#concept-form-submit>
+pub fn build_submission_request(
+    form: DomPtr<HtmlFormElement>,
+    submitter: Option<DomPtr<Element>>,
+) -> Result<Request, FormSubmissionError> {
+    let document = form
+        .borrow()
+        .owning_document()
+        .ok_or(FormSubmissionError::NoDocument)?;
+    let document_url = document.borrow().url().clone();
+
+    let form_element: DomPtr<Element> = form.clone().upcast();
+    let attribute = |name: InternedString, submitter_override: InternedString| {
+        submitter
+            .as_ref()
+            .and_then(|submitter| {
+                submitter
+                    .borrow()
+                    .attributes()
+                    .get(&submitter_override)
+                    .copied()
+            })
+            .or_else(|| form_element.borrow().attributes().get(&name).copied())
+    };
+
+    // If action is the empty string, let action be the URL of the form document.
+    let action = attribute(static_interned!("action"), static_interned!("formaction"));
+    let mut action_url = match action {
+        Some(action) if !action.to_string().is_empty() => {
+            URL::parse_with_base(&action.to_string(), Some(&document_url), None)
+                .map_err(|_| FormSubmissionError::InvalidAction)?
+        },
+        _ => document_url,
+    };
+
+    if !matches!(action_url.scheme().as_str(), "http" | "https") {
+        return Err(FormSubmissionError::UnsupportedScheme);
+    }
+
+    let method = match attribute(static_interned!("method"), static_interned!("formmethod")) {
+        Some(method) => Method::from_attribute(method),
+        None => Method::Get,
+    };
+
+    let enctype = match attribute(static_interned!("enctype"), static_interned!("formenctype")) {
+        Some(enctype) => Enctype::from_attribute(enctype),
+        None => Enctype::UrlEncoded,
+    };
+
+    let entries = construct_entry_list(form, submitter.as_ref());
+
+    let request = match method {
+        Method::Dialog => return Err(FormSubmissionError::DialogMethod),
+        Method::Get => {
+            // https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#submit-mutate-action
+            action_url.set_query_pairs(&entries);
+            Request::get(&action_url)
+        },
+        Method::Post => {
+            // https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#submit-body
+            let (body, content_type) = match enctype {
+                Enctype::UrlEncoded => (
+                    url::form_urlencoded::serialize(
+                        entries
+                            .iter()
+                            .map(|(name, value)| (name.as_str(), value.as_str())),
+                    )
+                    .as_str()
+                    .as_bytes()
+                    .to_vec(),
+                    "application/x-www-form-urlencoded".to_string(),
+                ),
+                Enctype::FormData => {
+                    let mut body = MultipartBody::new();
+                    for (name, value) in &entries {
+                        body.add_field(name, value);
+                    }
+                    let content_type = body.content_type();
+                    (body.finish(), content_type)
+                },
+                Enctype::TextPlain => {
+                    // https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#text/plain-encoding-algorithm
+                    let body: String = entries
+                        .iter()
+                        .map(|(name, value)| format!("{name}={value}\r\n"))
+                        .collect();
+                    (body.into_bytes(), "text/plain".to_string())
+                },
+            };
+
+            let mut request = Request::post(&action_url, body);
+            request
+                .headers_mut()
+                .set(Header::CONTENT_TYPE, content_type);
+            request
+        },
+    };
+
+    Ok(request)
+}
+
+impl Method {
+    fn from_attribute(value: InternedString) -> Self {
+        // The invalid value default is the GET state
+        match value.to_string().to_ascii_lowercase().as_str() {
+            "post" => Self::Post,
+            "dialog" => Self::Dialog,
+            _ => Self::Get,
+        }
+    }
+}
+
+impl Enctype {
+    fn from_attribute(value: InternedString) -> Self {
+        // The invalid value default is the application/x-www-form-urlencoded state
+        match value.to_string().to_ascii_lowercase().as_str() {
+            "multipart/form-data" => Self::FormData,
+            "text/plain" => Self::TextPlain,
+            _ => Self::UrlEncoded,
+        }
+    }
+}
+
+/// Collect the names and values of all the controls in the form that are submitted
+///
+/// Line breaks in names and values are normalized to `\r\n`.
+///
+/// <https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#constructing-the-form-data-set>
+fn construct_entry_list(
+    form: DomPtr<HtmlFormElement>,
+    submitter: Option<&DomPtr<Element>>,
+) -> Vec<(String, String)> {
+    let mut entries = vec![];
+
+    Node::for_each_inclusive_descendant(form.upcast(), &mut |node| {
+        let Some(element) = node.try_into_type::<Element>() else {
+            return;
+        };
+
+        collect_entries_for_control(element, submitter, &mut entries);
+    });
+
+    entries
+        .into_iter()
+        .map(|(name, value)| (normalize_newlines(&name), normalize_newlines(&value)))
+        .collect()
+}
+
+fn collect_entries_for_control(
+    element: DomPtr<Element>,
+    submitter: Option<&DomPtr<Element>>,
+    entries: &mut Vec<(String, String)>,
+) {
+    let element_ref = element.borrow();
+    let attribute = |name| {
+        element_ref
+            .attributes()
+            .get(&name)
+            .map(InternedString::to_string)
+    };
+
+    // The field element is disabled
+    if attribute(static_interned!("disabled")).is_some() {
+        return;
+    }
+
+    let input_type = attribute(static_interned!("type"))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let is_submitter = submitter.is_some_and(|submitter| submitter.ptr_eq(&element));
+
+    let name = attribute(static_interned!("name")).unwrap_or_default();
+    let value = attribute(static_interned!("value"));
+
+    match element_ref.local_name() {
+        static_interned!("input") => {
+            match input_type.as_str() {
+                // Buttons are only submitted if they were used to submit the form
+                "submit" | "reset" | "button" if !is_submitter => {},
+                "image" if is_submitter => {
+                    // FIXME: These should be the coordinates that were clicked on
+                    let prefix = if name.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{name}.")
+                    };
+                    entries.push((format!("{prefix}x"), "0".to_string()));
+                    entries.push((format!("{prefix}y"), "0".to_string()));
+                },
+                "image" => {},
+                "checkbox" | "radio" => {
+                    if attribute(static_interned!("checked")).is_some() && !name.is_empty() {
+                        entries.push((name, value.unwrap_or_else(|| "on".to_string())));
+                    }
+                },
+                // FIXME: Support file uploads
+                "file" => {},
+                "hidden" if name == "_charset_" => {
+                    entries.push((name, "UTF-8".to_string()));
+                },
+                _ if !name.is_empty() => {
+                    entries.push((name, value.unwrap_or_default()));
+                },
+                _ => {},
+            }
+        },
+        static_interned!("button") => {
+            if is_submitter && !name.is_empty() {
+                entries.push((name, value.unwrap_or_default()));
+            }
+        },
+        static_interned!("textarea") if !name.is_empty() => {
+            entries.push((name, text_content(element.clone().upcast())));
+        },
+        static_interned!("select") if !name.is_empty() => {
+            let mut options = vec![];
+            Node::for_each_inclusive_descendant(element.clone().upcast(), &mut |node| {
+                if let Some(option) = node.try_into_type::<Element>() {
+                    if option.borrow().local_name() == static_interned!("option") {
+                        options.push(option);
+                    }
+                }
+            });
+
+            let option_value = |option: &DomPtr<Element>| {
+                option
+                    .borrow()
+                    .attributes()
+                    .get(&static_interned!("value"))
+                    .map(InternedString::to_string)
+                    .unwrap_or_else(|| text_content(option.clone().upcast()))
+            };
+
+            let is_selected = |option: &&DomPtr<Element>| {
+                option
+                    .borrow()
+                    .attributes()
+                    .contains_key(&static_interned!("selected"))
+            };
+
+            let allows_multiple = attribute(static_interned!("multiple")).is_some();
+            let selected: Vec<_> = options.iter().filter(is_selected).collect();
+
+            if selected.is_empty() && !allows_multiple {
+                // Without an explicitly selected option, the first option is selected
+                if let Some(first) = options.first() {
+                    entries.push((name, option_value(first)));
+                }
+            } else {
+                for option in selected {
+                    entries.push((name.clone(), option_value(option)));
+                }
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Concatenate the contents of all the text nodes within `node`
+fn text_content(node: DomPtr<Node>) -> String {
+    let mut content = String::new();
+
+    Node::for_each_inclusive_descendant(node, &mut |node| {
+        if let Some(text) = node.try_into_type::<Text>() {
+            content.push_str(text.borrow().content());
+        }
+    });
+
+    content
+}
+
+/// Replace every `\r` not followed by `\n` and every `\n` not preceded by `\r` with `\r\n`
+fn normalize_newlines(input: &str) -> String {
+    input
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', "\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dom::dom_objects::Document,
+        html::{tokenization::IgnoreParseErrors, Parser},
+    };
+
+    fn parse_form(html: &str) -> (DomPtr<Document>, DomPtr<HtmlFormElement>) {
+        let document = DomPtr::new(Document::default());
+        document
+            .borrow_mut()
+            .set_owning_document(document.clone().downgrade());
+        document
+            .borrow_mut()
+            .set_url("http://example.com/page/index.html".parse().unwrap());
+
+        let parser: Parser<IgnoreParseErrors> = Parser::new(html, document);
+        let (document, _) = parser.parse();
+
+        let form = document
+            .borrow()
+            .get_element_by_id(static_interned!("form"))
+            .expect("document contains a form")
+            .into_type();
+        (document, form)
+    }
+
+    fn element_by_id(document: &DomPtr<Document>, id: &str) -> DomPtr<Element> {
+        document
+            .borrow()
+            .get_element_by_id(id.into())
+            .expect("element exists")
+    }
+
+    #[test]
+    fn get_form() {
+        let (document, form) = parse_form(
+            r#"<form id="form" action="search?old=query">
+                <input name="q" value="hello world">
+                <input type="checkbox" name="checked" checked>
+                <input type="checkbox" name="unchecked">
+                <input name="disabled" value="x" disabled>
+                <textarea name="text">line</textarea>
+                <input type="submit" name="action" value="Search" id="search">
+                <input type="submit" name="other" value="Other">
+            </form>"#,
+        );
+
+        let submitter = element_by_id(&document, "search");
+        let request = build_submission_request(form, Some(submitter)).unwrap();
+
+        assert_eq!(request.method(), http::request::Method::Get);
+        assert!(request.body().is_empty());
+        assert_eq!(
+            request.url().serialize(url::ExcludeFragment::No),
+            "http://example.com/page/search?q=hello+world&checked=on&text=line&action=Search"
+        );
+    }
+
+    #[test]
+    fn post_form() {
+        let (document, form) = parse_form(
+            r#"<form id="form" method="get" action="/ignored">
+                <input name="name" value="a&b">
+                <input type="hidden" name="_charset_">
+                <button id="submit" formmethod="POST" formaction="/submit" name="go" value="1">
+            </form>"#,
+        );
+
+        let submitter = element_by_id(&document, "submit");
+        let request = build_submission_request(form, Some(submitter)).unwrap();
+
+        assert_eq!(request.method(), http::request::Method::Post);
+        assert_eq!(
+            request.url().serialize(url::ExcludeFragment::No),
+            "http://example.com/submit"
+        );
+        assert_eq!(
+            request.headers().get(Header::CONTENT_TYPE),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(request.body(), b"name=a%26b&_charset_=UTF-8&go=1");
+    }
+
+    #[test]
+    fn multipart_form() {
+        let (_document, form) = parse_form(
+            r#"<form id="form" method="post" enctype="multipart/form-data">
+                <input name="field" value="value">
+            </form>"#,
+        );
+
+        let request = build_submission_request(form, None).unwrap();
+
+        let content_type = request.headers().get(Header::CONTENT_TYPE).unwrap();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+
+        let body = String::from_utf8(request.body().to_vec()).unwrap();
+        assert!(body.starts_with(&format!("--{boundary}\r\n")));
+        assert!(body.contains("Content-Disposition: form-data; name=\"field\"\r\n\r\nvalue\r\n"));
+        assert!(body.ends_with(&format!("--{boundary}--\r\n")));
+    }
+}
@@ -1,5 +1,8 @@
+pub mod form_submission;
 pub mod links;
+mod serialization;
 pub mod tokenization;
 pub mod treebuilding;
 
+pub use serialization::serialize_html;
 pub use treebuilding::parser::Parser;
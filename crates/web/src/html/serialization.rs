@@ -0,0 +1,349 @@
+//! <https://html.spec.whatwg.org/multipage/parsing.html#serialising-html-fragments>
+
+use crate::{
+    dom::{
+        dom_objects::{Comment, DocumentType, Element, Node, Text},
+        DomPtr, DomType,
+    },
+    infra::Namespace,
+    static_interned, InternedString,
+};
+
+/// Serialize the children of `node` as HTML
+///
+/// This is the algorithm used by `innerHTML`.
+///
+/// <https://html.spec.whatwg.org/multipage/parsing.html#html-fragment-serialisation-algorithm>
+#[must_use]
+pub fn serialize_html(node: &DomPtr<Node>) -> String {
+    let mut output = String::new();
+    serialize_children(node, &mut output);
+    output
+}
+
+fn serialize_children(node: &DomPtr<Node>, output: &mut String) {
+    // 1. If the node serializes as void, then return the empty string.
+    if node
+        .try_into_type::<Element>()
+        .is_some_and(|element| serializes_as_void(&element.borrow()))
+    {
+        return;
+    }
+
+    // 2. Let s be a string, and initialize it to the empty string.
+    // 3. FIXME: If the node is a template element, then let the node instead be the template element's template contents
+
+    // 4. For each child node of the node, in tree order, run the following steps:
+    let children = node.borrow().children().to_vec();
+    for current_node in &children {
+        // 1. Let current node be the child node being processed.
+        // 2. Append the appropriate string from the following list to s:
+        match current_node.underlying_type() {
+            DomType::Comment => {
+                // Append the literal string "<!--" (U+003C LESS-THAN SIGN, U+0021 EXCLAMATION MARK,
+                // U+002D HYPHEN-MINUS, U+002D HYPHEN-MINUS), followed by the value of current node's
+                // data IDL attribute, followed by the literal string "-->"
+                // (U+002D HYPHEN-MINUS, U+002D HYPHEN-MINUS, U+003E GREATER-THAN SIGN).
+                let comment = current_node.clone().into_type::<Comment>();
+                output.push_str("<!--");
+                output.push_str(comment.borrow().comment_data());
+                output.push_str("-->");
+            },
+            DomType::Text => {
+                let text = current_node.clone().into_type::<Text>();
+
+                // If the parent of current node is a style, script, xmp, iframe, noembed,
+                // noframes, or plaintext element, or if the parent of current node is a noscript
+                // element and scripting is enabled for the node, then append the value of
+                // current node's data IDL attribute literally.
+                // FIXME: noscript should only be serialized literally if scripting is enabled
+                let is_raw_text = node.try_into_type::<Element>().is_some_and(|parent| {
+                    let parent = parent.borrow();
+                    parent.namespace() == Namespace::HTML
+                        && matches!(
+                            parent.local_name(),
+                            static_interned!("style")
+                                | static_interned!("script")
+                                | static_interned!("xmp")
+                                | static_interned!("iframe")
+                                | static_interned!("noembed")
+                                | static_interned!("noframes")
+                                | static_interned!("plaintext")
+                        )
+                });
+
+                if is_raw_text {
+                    output.push_str(text.borrow().content());
+                } else {
+                    // Otherwise, append the value of current node's data IDL attribute, escaped as described below.
+                    escape(text.borrow().content(), false, output);
+                }
+            },
+            DomType::DocumentType => {
+                // Append the literal string "<!DOCTYPE" (U+003C LESS-THAN SIGN, U+0021 EXCLAMATION MARK,
+                // U+0044 LATIN CAPITAL LETTER D, U+004F LATIN CAPITAL LETTER O, U+0043 LATIN CAPITAL LETTER C,
+                // U+0054 LATIN CAPITAL LETTER T, U+0059 LATIN CAPITAL LETTER Y, U+0050 LATIN CAPITAL LETTER P,
+                // U+0045 LATIN CAPITAL LETTER E), followed by a space (U+0020 SPACE), followed by the value of
+                // current node's name IDL attribute, followed by the literal string ">" (U+003E GREATER-THAN SIGN).
+                let doctype = current_node.clone().into_type::<DocumentType>();
+                output.push_str("<!DOCTYPE ");
+                output.push_str(&doctype.borrow().name().to_string());
+                output.push('>');
+            },
+            _ => {
+                let Some(element) = current_node.try_into_type::<Element>() else {
+                    // Other nodes (like documents) cannot be children
+                    continue;
+                };
+
+                serialize_element(&element, output);
+            },
+        }
+    }
+}
+
+fn serialize_element(element: &DomPtr<Element>, output: &mut String) {
+    // If current node is an element in the HTML namespace, the MathML namespace, or the SVG namespace,
+    // then let tagname be current node's local name. Otherwise, let tagname be current node's qualified name.
+    // FIXME: We don't store qualified names
+    let tagname = element.borrow().local_name().to_string();
+
+    // Append a U+003C LESS-THAN SIGN character (<), followed by tagname.
+    output.push('<');
+    output.push_str(&tagname);
+
+    // For each attribute that the element has, append a U+0020 SPACE character, the attribute's
+    // serialized name as described below, a U+003D EQUALS SIGN character (=), a U+0022 QUOTATION
+    // MARK character ("), the attribute's value, escaped as described below in attribute mode,
+    // and a second U+0022 QUOTATION MARK character (").
+    // NOTE: The attributes are sorted to get a stable output, since we don't keep track of the
+    //       order in which they were added.
+    let mut attributes = serialized_attributes(&element.borrow());
+    attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (name, value) in attributes {
+        output.push(' ');
+        output.push_str(&name);
+        output.push_str("=\"");
+        escape(&value.to_string(), true, output);
+        output.push('"');
+    }
+
+    // Append a U+003E GREATER-THAN SIGN character (>).
+    output.push('>');
+
+    // If current node serializes as void, then continue on to the next child node at this point.
+    if serializes_as_void(&element.borrow()) {
+        return;
+    }
+
+    // Append the value of running the HTML fragment serialization algorithm on the current node element
+    // (thus recursing into this algorithm for that element), followed by a U+003C LESS-THAN SIGN character (<),
+    // a U+002F SOLIDUS character (/), tagname again, and finally a U+003E GREATER-THAN SIGN character (>).
+    serialize_children(&element.clone().upcast(), output);
+    output.push_str("</");
+    output.push_str(&tagname);
+    output.push('>');
+}
+
+/// The attributes of `element`, along with their serialized names
+fn serialized_attributes(element: &Element) -> Vec<(String, InternedString)> {
+    let null_namespace_attributes = element
+        .attributes()
+        .iter()
+        .map(|(name, value)| (name.to_string(), *value));
+    let namespaced_attributes =
+        element
+            .namespaced_attributes()
+            .iter()
+            .map(|(&(namespace, local_name), attribute)| {
+                let name = serialized_attribute_name(namespace, attribute.prefix, local_name);
+                (name, attribute.value)
+            });
+
+    null_namespace_attributes
+        .chain(namespaced_attributes)
+        .collect()
+}
+
+/// The serialized name of an attribute that is not in the null namespace
+///
+/// <https://html.spec.whatwg.org/multipage/parsing.html#attribute's-serialized-name>
+fn serialized_attribute_name(
+    namespace: Namespace,
+    prefix: Option<InternedString>,
+    local_name: InternedString,
+) -> String {
+    match namespace {
+        // If the attribute is in the XML namespace, the attribute's serialized name is the string "xml:"
+        // followed by the attribute's local name.
+        Namespace::XML => format!("xml:{local_name}"),
+
+        // If the attribute is in the XMLNS namespace and the attribute's local name is xmlns, the
+        // attribute's serialized name is the string "xmlns".
+        Namespace::XMLNS if local_name == static_interned!("xmlns") => "xmlns".to_string(),
+
+        // If the attribute is in the XMLNS namespace and the attribute's local name is not xmlns, the
+        // attribute's serialized name is the string "xmlns:" followed by the attribute's local name.
+        Namespace::XMLNS => format!("xmlns:{local_name}"),
+
+        // If the attribute is in the XLink namespace, the attribute's serialized name is the string "xlink:"
+        // followed by the attribute's local name.
+        Namespace::XLink => format!("xlink:{local_name}"),
+
+        // If the attribute is in some other namespace, the attribute's serialized name is the attribute's
+        // qualified name.
+        _ => match prefix {
+            Some(prefix) => format!("{prefix}:{local_name}"),
+            None => local_name.to_string(),
+        },
+    }
+}
+
+/// <https://html.spec.whatwg.org/multipage/parsing.html#serializes-as-void>
+fn serializes_as_void(element: &Element) -> bool {
+    // For the purposes of the following algorithm, an element serializes as void if its element type is one of
+    // the void elements, or is basefont, bgsound, frame, keygen, or param.
+    element.namespace() == Namespace::HTML
+        && matches!(
+            element.local_name(),
+            static_interned!("area")
+                | static_interned!("base")
+                | static_interned!("basefont")
+                | static_interned!("bgsound")
+                | static_interned!("br")
+                | static_interned!("col")
+                | static_interned!("embed")
+                | static_interned!("frame")
+                | static_interned!("hr")
+                | static_interned!("img")
+                | static_interned!("input")
+                | static_interned!("keygen")
+                | static_interned!("link")
+                | static_interned!("meta")
+                | static_interned!("param")
+                | static_interned!("source")
+                | static_interned!("track")
+                | static_interned!("wbr")
+        )
+}
+
+/// <https://html.spec.whatwg.org/multipage/parsing.html#escapingString>
+fn escape(string: &str, attribute_mode: bool, output: &mut String) {
+    for c in string.chars() {
+        match c {
+            // 1. Replace any occurrence of the "&" character by the string "&amp;".
+            '&' => output.push_str("&amp;"),
+
+            // 2. Replace any occurrences of the U+00A0 NO-BREAK SPACE character by the string "&nbsp;".
+            '\u{00A0}' => output.push_str("&nbsp;"),
+
+            // 3. If the algorithm was invoked in the attribute mode, then replace any occurrences of
+            //    the """ character by the string "&quot;".
+            '"' if attribute_mode => output.push_str("&quot;"),
+
+            // 4. If the algorithm was not invoked in the attribute mode, then replace any occurrences of
+            //    the "<" character by the string "&lt;", and any occurrences of the ">" character by the string "&gt;".
+            '<' if !attribute_mode => output.push_str("&lt;"),
+            '>' if !attribute_mode => output.push_str("&gt;"),
+            other => output.push(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dom::{dom_objects::Document, ElementCustomState},
+        html::{tokenization::IgnoreParseErrors, Parser},
+    };
+
+    fn parse(source: &str) -> DomPtr<Node> {
+        let document = DomPtr::new(Document::default());
+        let weak_document = document.downgrade();
+        document.borrow_mut().set_owning_document(weak_document);
+
+        let (document, _) = Parser::<IgnoreParseErrors>::new(source, document).parse();
+        document.upcast()
+    }
+
+    #[test]
+    fn serialize_tree() {
+        let element = |local_name: &str| {
+            DomPtr::new(Element::new(
+                Namespace::HTML,
+                None,
+                local_name.into(),
+                ElementCustomState::Uncustomized,
+                None,
+                None,
+            ))
+        };
+        let text = |content: &str| {
+            let mut text = Text::default();
+            text.content_mut().push_str(content);
+            DomPtr::new(text).upcast()
+        };
+
+        let div = element("div");
+        let p = element("p");
+        p.borrow_mut()
+            .append_attribute("title".into(), "\"a\" & <b>".into());
+        let br = element("br");
+        let style = element("style");
+
+        Node::append_child(div.clone().upcast(), p.clone().upcast());
+        Node::append_child(p.clone().upcast(), text("1 < 2 & 3 > 2\u{A0}"));
+        Node::append_child(p.clone().upcast(), br.clone().upcast());
+        Node::append_child(div.clone().upcast(), style.clone().upcast());
+        Node::append_child(style.clone().upcast(), text("a > b { content: \"&\" }"));
+
+        assert_eq!(
+            serialize_html(&div.upcast()),
+            "<p title=\"&quot;a&quot; &amp; <b>\">1 &lt; 2 &amp; 3 &gt; 2&nbsp;<br></p>\
+                <style>a > b { content: \"&\" }</style>"
+        );
+
+        // Void elements have no children
+        assert_eq!(serialize_html(&br.upcast()), "");
+    }
+
+    #[test]
+    fn round_trip() {
+        let source = "<!DOCTYPE html><html><head><title>a &amp; b</title></head>\
+            <body><p class=\"x\" id=\"y\">Hello<br>world<!-- comment --></p>\
+            <script>if (a < b && c) {}</script><img src=\"a.png\"></body></html>";
+
+        let serialized = serialize_html(&parse(source));
+        assert_eq!(serialized, source);
+        assert_eq!(serialize_html(&parse(&serialized)), serialized);
+    }
+
+    #[test]
+    fn foreign_attributes() {
+        let source = "<svg href=\"plain\" xlink:href=\"namespaced\" xml:lang=\"en\" \
+            xmlns:xlink=\"http://www.w3.org/1999/xlink\"></svg>";
+
+        let document = parse(source);
+        let body = document.borrow().children()[0].borrow().children()[1].clone();
+        let svg = body.borrow().children()[0].clone().into_type::<Element>();
+        assert_eq!(
+            svg.borrow()
+                .get_attribute_ns(Some(Namespace::XLink), static_interned!("href")),
+            Some("namespaced".into())
+        );
+        assert_eq!(
+            svg.borrow()
+                .get_attribute_ns(None, static_interned!("href")),
+            Some("plain".into())
+        );
+        assert_eq!(
+            svg.borrow()
+                .get_attribute_ns(Some(Namespace::XMLNS), static_interned!("xlink")),
+            Some("http://www.w3.org/1999/xlink".into())
+        );
+
+        assert_eq!(serialize_html(&body), source);
+    }
+}
@@ -1,2 +1,63 @@
-#![allow(clippy::all)]
-include!(concat!(env!("OUT_DIR"), "/named_entities.rs"));
+//! <https://html.spec.whatwg.org/multipage/named-characters.html#named-character-references>
+
+mod autogenerated_code {
+    #![allow(clippy::all)]
+    include!(concat!(env!("OUT_DIR"), "/named_entities.rs"));
+}
+
+use autogenerated_code::{MAX_NAME_LENGTH, NAMED_CHARACTER_REFERENCES, RESOLVED_REFERENCES};
+
+/// Find the longest named character reference at the start of `html`
+///
+/// `html` should not include the leading `&`. Some legacy references (like `&not`) are
+/// also valid without a trailing semicolon, so `"notit;"` matches `"not"`.
+///
+/// Returns a tuple of `(matched_str, resolved_reference)`
+#[must_use]
+pub fn lookup_character_reference(html: &str) -> Option<(&'static str, &'static str)> {
+    // Names consist of ascii alphanumerics, optionally followed by a semicolon
+    let mut candidate_length = html
+        .bytes()
+        .take(MAX_NAME_LENGTH)
+        .take_while(u8::is_ascii_alphanumeric)
+        .count();
+
+    if candidate_length < MAX_NAME_LENGTH && html.as_bytes().get(candidate_length) == Some(&b';') {
+        candidate_length += 1;
+    }
+
+    // Try all the prefixes of the candidate, starting with the longest one
+    (1..=candidate_length).rev().find_map(|length| {
+        let hash = NAMED_CHARACTER_REFERENCES.try_get(&html[..length])?;
+
+        Some((
+            NAMED_CHARACTER_REFERENCES.lookup(hash),
+            RESOLVED_REFERENCES[hash as usize],
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lookup_character_reference;
+
+    #[test]
+    fn longest_match() {
+        assert_eq!(lookup_character_reference("amp;"), Some(("amp;", "&")));
+        assert_eq!(lookup_character_reference("amp"), Some(("amp", "&")));
+        assert_eq!(lookup_character_reference("notin;"), Some(("notin;", "∉")));
+        assert_eq!(lookup_character_reference("notit;"), Some(("not", "¬")));
+        assert_eq!(lookup_character_reference("notinx"), Some(("not", "¬")));
+        assert_eq!(
+            lookup_character_reference("CounterClockwiseContourIntegral;"),
+            Some(("CounterClockwiseContourIntegral;", "∳"))
+        );
+
+        // Only some legacy references are valid without a semicolon
+        assert_eq!(lookup_character_reference("notin"), Some(("not", "¬")));
+        assert_eq!(lookup_character_reference("hellip"), None);
+        assert_eq!(lookup_character_reference("foo;"), None);
+        assert_eq!(lookup_character_reference(";"), None);
+        assert_eq!(lookup_character_reference(""), None);
+    }
+}
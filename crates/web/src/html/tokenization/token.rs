@@ -1,6 +1,6 @@
 use std::mem;
 
-use crate::{static_interned, InternedString};
+use crate::{infra::Namespace, static_interned, InternedString};
 
 #[derive(Debug, Clone)]
 pub enum Token {
@@ -62,6 +62,18 @@ pub struct TagData {
     ///
     /// For example, the tag `<tag foo=bar baz=boo>` has two attributes, `("foo", "bar")` and `("baz", "boo")`.
     pub attributes: Vec<(InternedString, InternedString)>,
+
+    /// Attributes that were moved into a namespace by [TagData::adjust_foreign_attributes]
+    pub foreign_attributes: Vec<ForeignAttribute>,
+}
+
+/// A tag attribute in a namespace other than the null namespace, like `xlink:href`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForeignAttribute {
+    pub namespace: Namespace,
+    pub prefix: Option<InternedString>,
+    pub local_name: InternedString,
+    pub value: InternedString,
 }
 
 impl DocTypeBuilder {
@@ -146,6 +158,7 @@ impl TagBuilder {
             self_closing: self.is_self_closing,
             name: InternedString::new(self.name),
             attributes: self.attributes,
+            foreign_attributes: vec![],
         };
 
         if self.is_opening {
@@ -172,6 +185,12 @@ impl TagData {
         &self.attributes
     }
 
+    #[inline]
+    #[must_use]
+    pub fn foreign_attributes(&self) -> &[ForeignAttribute] {
+        &self.foreign_attributes
+    }
+
     /// <https://html.spec.whatwg.org/multipage/parsing.html#adjust-mathml-attributes>
     pub fn adjust_mathml_attributes(&mut self) {
         for (key, _) in self.attributes.iter_mut() {
@@ -184,9 +203,76 @@ impl TagData {
 
     /// <https://html.spec.whatwg.org/multipage/parsing.html#adjust-foreign-attributes>
     pub fn adjust_foreign_attributes(&mut self) {
-        _ = self;
-        // FIXME: implement this!
-        //        This requires "namespaced attributes"
+        let mut index = 0;
+        while index < self.attributes.len() {
+            let (key, value) = self.attributes[index];
+
+            let (namespace, prefix, local_name) = match key {
+                static_interned!("xlink:actuate") => (
+                    Namespace::XLink,
+                    Some(static_interned!("xlink")),
+                    static_interned!("actuate"),
+                ),
+                static_interned!("xlink:arcrole") => (
+                    Namespace::XLink,
+                    Some(static_interned!("xlink")),
+                    static_interned!("arcrole"),
+                ),
+                static_interned!("xlink:href") => (
+                    Namespace::XLink,
+                    Some(static_interned!("xlink")),
+                    static_interned!("href"),
+                ),
+                static_interned!("xlink:role") => (
+                    Namespace::XLink,
+                    Some(static_interned!("xlink")),
+                    static_interned!("role"),
+                ),
+                static_interned!("xlink:show") => (
+                    Namespace::XLink,
+                    Some(static_interned!("xlink")),
+                    static_interned!("show"),
+                ),
+                static_interned!("xlink:title") => (
+                    Namespace::XLink,
+                    Some(static_interned!("xlink")),
+                    static_interned!("title"),
+                ),
+                static_interned!("xlink:type") => (
+                    Namespace::XLink,
+                    Some(static_interned!("xlink")),
+                    static_interned!("type"),
+                ),
+                static_interned!("xml:lang") => (
+                    Namespace::XML,
+                    Some(static_interned!("xml")),
+                    static_interned!("lang"),
+                ),
+                static_interned!("xml:space") => (
+                    Namespace::XML,
+                    Some(static_interned!("xml")),
+                    static_interned!("space"),
+                ),
+                static_interned!("xmlns") => (Namespace::XMLNS, None, static_interned!("xmlns")),
+                static_interned!("xmlns:xlink") => (
+                    Namespace::XMLNS,
+                    Some(static_interned!("xmlns")),
+                    static_interned!("xlink"),
+                ),
+                _ => {
+                    index += 1;
+                    continue;
+                },
+            };
+
+            self.attributes.remove(index);
+            self.foreign_attributes.push(ForeignAttribute {
+                namespace,
+                prefix,
+                local_name,
+                value,
+            });
+        }
     }
 
     /// <https://html.spec.whatwg.org/multipage/parsing.html#adjust-svg-attributes>
@@ -3058,22 +3058,42 @@ impl<P: ParseErrorHandler> Tokenizer<P> {
             },
             // https://html.spec.whatwg.org/multipage/parsing.html#named-character-reference-state
             TokenizerState::NamedCharacterReference => {
+                // Consume the maximum number of characters possible, where the consumed
+                // characters are one of the identifiers in the named character references table.
                 match lookup_character_reference(self.source.remaining()) {
                     Some((matched_str, resolved_reference)) => {
                         let _ = self.source.advance_by(matched_str.len());
 
-                        // FIXME:
+                        // Append each character to the temporary buffer when it's consumed.
+                        self.buffer.push_str(matched_str);
+
+                        let last_character_is_semicolon = matched_str.ends_with(';');
+
                         // If the character reference was consumed as part of an attribute, and
                         // the last character matched is not a U+003B SEMICOLON character (;),
                         // and the next input character is either a U+003D EQUALS SIGN
                         // character (=) or an ASCII alphanumeric, then, for historical
                         // reasons, flush code points consumed as a character reference and
                         // switch to the return state.
-                        //
+                        let next_character = self.source.remaining().chars().next();
+                        if self.is_inside_attribute()
+                            && !last_character_is_semicolon
+                            && next_character.is_some_and(|c| c == '=' || c.is_ascii_alphanumeric())
+                        {
+                            self.flush_code_points_consumed_as_character_reference();
+                            self.switch_to(self.return_state.expect("No return state"));
+                            return;
+                        }
+
                         // Otherwise:
                         // If the last character matched is not a U+003B SEMICOLON
                         // character (;), then this is a
                         // missing-semicolon-after-character-reference parse error.
+                        if !last_character_is_semicolon {
+                            self.parse_error(
+                                HtmlParseError::MissingSemicolonAfterCharacterReference,
+                            );
+                        }
 
                         // Set the temporary buffer to the empty string.
                         // Append one or two characters corresponding to
@@ -3315,98 +3335,98 @@ impl<P: ParseErrorHandler> Tokenizer<P> {
                         // This is a noncharacter-character-reference parse error.
                         self.parse_error(HtmlParseError::NoncharacterCharacterReference);
                     },
-                    c @ (0x0D | 0xC0 | 0x007F..=0x009F) => {
-                        if c != TAB as u32
-                            || c != LINE_FEED as u32
-                            || c != FORM_FEED as u32
-                            || c != SPACE as u32
-                        {
-                            // This is a control character reference parse error
-                            self.parse_error(HtmlParseError::ControlCharacterReference);
-                            match c {
-                                0x80 => {
-                                    self.character_reference_code = 0x20AC;
-                                },
-                                0x82 => {
-                                    self.character_reference_code = 0x201A;
-                                },
-                                0x83 => {
-                                    self.character_reference_code = 0x0192;
-                                },
-                                0x84 => {
-                                    self.character_reference_code = 0x201E;
-                                },
-                                0x85 => {
-                                    self.character_reference_code = 0x2026;
-                                },
-                                0x86 => {
-                                    self.character_reference_code = 0x2020;
-                                },
-                                0x87 => {
-                                    self.character_reference_code = 0x2021;
-                                },
-                                0x88 => {
-                                    self.character_reference_code = 0x02C6;
-                                },
-                                0x89 => {
-                                    self.character_reference_code = 0x2030;
-                                },
-                                0x8A => {
-                                    self.character_reference_code = 0x0160;
-                                },
-                                0x8B => {
-                                    self.character_reference_code = 0x2039;
-                                },
-                                0x8C => {
-                                    self.character_reference_code = 0x0152;
-                                },
-                                0x8E => {
-                                    self.character_reference_code = 0x017D;
-                                },
-                                0x91 => {
-                                    self.character_reference_code = 0x2018;
-                                },
-                                0x92 => {
-                                    self.character_reference_code = 0x2019;
-                                },
-                                0x93 => {
-                                    self.character_reference_code = 0x201C;
-                                },
-                                0x94 => {
-                                    self.character_reference_code = 0x201D;
-                                },
-                                0x95 => {
-                                    self.character_reference_code = 0x2022;
-                                },
-                                0x96 => {
-                                    self.character_reference_code = 0x2013;
-                                },
-                                0x97 => {
-                                    self.character_reference_code = 0x2014;
-                                },
-                                0x98 => {
-                                    self.character_reference_code = 0x02DC;
-                                },
-                                0x99 => {
-                                    self.character_reference_code = 0x2122;
-                                },
-                                0x9A => {
-                                    self.character_reference_code = 0x0161;
-                                },
-                                0x9B => {
-                                    self.character_reference_code = 0x203A;
-                                },
-                                0x9C => {
-                                    self.character_reference_code = 0x0153;
-                                },
-                                0x9E => {
-                                    self.character_reference_code = 0x017E;
-                                },
-                                0x9F => {
-                                    self.character_reference_code = 0x0178;
-                                },
-                                _ => {}, // no mapping
-                            }
+                    // Either 0x0D or a control that's not ascii whitespace
+                    c @ (0x01..=0x1F | 0x7F..=0x9F) if !matches!(c, 0x09 | 0x0A | 0x0C) => {
+                        // This is a control-character-reference parse error.
+                        self.parse_error(HtmlParseError::ControlCharacterReference);
+
+                        // If the number is one of the numbers in the first column of the
+                        // following table, then find the row with that number in the first
+                        // column, and set the character reference code to the number in the
+                        // second column of that row.
+                        match c {
+                            0x80 => {
+                                self.character_reference_code = 0x20AC;
+                            },
+                            0x82 => {
+                                self.character_reference_code = 0x201A;
+                            },
+                            0x83 => {
+                                self.character_reference_code = 0x0192;
+                            },
+                            0x84 => {
+                                self.character_reference_code = 0x201E;
+                            },
+                            0x85 => {
+                                self.character_reference_code = 0x2026;
+                            },
+                            0x86 => {
+                                self.character_reference_code = 0x2020;
+                            },
+                            0x87 => {
+                                self.character_reference_code = 0x2021;
+                            },
+                            0x88 => {
+                                self.character_reference_code = 0x02C6;
+                            },
+                            0x89 => {
+                                self.character_reference_code = 0x2030;
+                            },
+                            0x8A => {
+                                self.character_reference_code = 0x0160;
+                            },
+                            0x8B => {
+                                self.character_reference_code = 0x2039;
+                            },
+                            0x8C => {
+                                self.character_reference_code = 0x0152;
+                            },
+                            0x8E => {
+                                self.character_reference_code = 0x017D;
+                            },
+                            0x91 => {
+                                self.character_reference_code = 0x2018;
+                            },
+                            0x92 => {
+                                self.character_reference_code = 0x2019;
+                            },
+                            0x93 => {
+                                self.character_reference_code = 0x201C;
+                            },
+                            0x94 => {
+                                self.character_reference_code = 0x201D;
+                            },
+                            0x95 => {
+                                self.character_reference_code = 0x2022;
+                            },
+                            0x96 => {
+                                self.character_reference_code = 0x2013;
+                            },
+                            0x97 => {
+                                self.character_reference_code = 0x2014;
+                            },
+                            0x98 => {
+                                self.character_reference_code = 0x02DC;
+                            },
+                            0x99 => {
+                                self.character_reference_code = 0x2122;
+                            },
+                            0x9A => {
+                                self.character_reference_code = 0x0161;
+                            },
+                            0x9B => {
+                                self.character_reference_code = 0x203A;
+                            },
+                            0x9C => {
+                                self.character_reference_code = 0x0153;
+                            },
+                            0x9E => {
+                                self.character_reference_code = 0x017E;
+                            },
+                            0x9F => {
+                                self.character_reference_code = 0x0178;
+                            },
+                            _ => {}, // no mapping
                         }
                     },
                     _ => {},
@@ -3447,3 +3467,60 @@ impl<P: ParseErrorHandler> Iterator for Tokenizer<P> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html::tokenization::IgnoreParseErrors;
+
+    fn text_content(html: &str) -> String {
+        Tokenizer::<IgnoreParseErrors>::new(html)
+            .filter_map(|token| match token {
+                Token::Character(c) => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn attribute_value(html: &str) -> String {
+        Tokenizer::<IgnoreParseErrors>::new(html)
+            .find_map(|token| match token {
+                Token::StartTag(tag) => Some(tag.attributes[0].1.to_string()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn named_character_references() {
+        assert_eq!(text_content("&amp;&lt;b&gt;"), "&<b>");
+        assert_eq!(text_content("&notit;"), "¬it;");
+        assert_eq!(text_content("&notin;"), "∉");
+        assert_eq!(text_content("&amp"), "&");
+        assert_eq!(text_content("&foo; & &;"), "&foo; & &;");
+
+        // Legacy references without a semicolon are not expanded in attributes
+        // if they are followed by "=" or an alphanumeric
+        assert_eq!(attribute_value("<a href='?a=1&copy=2'>"), "?a=1&copy=2");
+        assert_eq!(attribute_value("<a href='&copyx'>"), "&copyx");
+        assert_eq!(attribute_value("<a href='&copy;x'>"), "©x");
+        assert_eq!(attribute_value("<a href='&copy x'>"), "© x");
+    }
+
+    #[test]
+    fn numeric_character_references() {
+        assert_eq!(text_content("&#169;&#xA9;&#Xa9"), "©©©");
+        assert_eq!(text_content("&#x1F600;"), "😀");
+
+        // "Windows-1252" overrides for C1 controls
+        assert_eq!(text_content("&#x80;&#150;&#x9F;"), "€–Ÿ");
+        assert_eq!(text_content("&#x81;&#x0D;&#x09;"), "\u{81}\r\t");
+
+        // Invalid code points
+        assert_eq!(text_content("&#0;"), "\u{FFFD}");
+        assert_eq!(text_content("&#xD800;"), "\u{FFFD}");
+        assert_eq!(text_content("&#x110000;"), "\u{FFFD}");
+        assert_eq!(text_content("&#99999999999999999999;"), "\u{FFFD}");
+        assert_eq!(text_content("&#;&#x;"), "&#;&#x;");
+    }
+}
@@ -146,6 +146,11 @@ pub struct Parser<P: ParseErrorHandler> {
     /// <https://html.spec.whatwg.org/multipage/parsing.html#form-element-pointer>
     form: Option<DomPtr<HtmlFormElement>>,
 
+    /// <https://html.spec.whatwg.org/multipage/parsing.html#concept-frag-parse-context>
+    ///
+    /// Only set if the parser was created by the fragment parsing algorithm
+    context: Option<DomPtr<Element>>,
+
     /// <https://html.spec.whatwg.org/multipage/parsing.html#list-of-active-formatting-elements>
     active_formatting_elements: ActiveFormattingElements,
 
@@ -177,6 +182,7 @@ impl<P: ParseErrorHandler> Parser<P> {
             open_elements: vec![],
             head: None,
             form: None,
+            context: None,
             frameset_ok: FramesetOkFlag::default(),
             active_formatting_elements: ActiveFormattingElements::default(),
             execute_script: !SETTINGS.disable_javascript,
@@ -294,6 +300,110 @@ impl<P: ParseErrorHandler> Parser<P> {
         (self.document, self.stylesheets)
     }
 
+    /// Parse `source` as if it was the content of `context`
+    ///
+    /// This is used for things like setting `innerHTML`, where the markup is not a
+    /// full document. The returned nodes are not inserted into `context`.
+    ///
+    /// <https://html.spec.whatwg.org/multipage/parsing.html#parsing-html-fragments>
+    pub fn parse_fragment(source: &str, context: DomPtr<Element>) -> Vec<DomPtr<Node>> {
+        // 1. Create a new Document node, and mark it as being an HTML document.
+        let document = DomPtr::new(Document::default());
+        document
+            .borrow_mut()
+            .set_owning_document(document.clone().downgrade());
+
+        // 2. If the node document of the context element is in quirks mode, then let the Document be in quirks mode.
+        //    Otherwise, the node document of the context element is in limited-quirks mode, then let the Document be
+        //    in limited-quirks mode. Otherwise, leave the Document in no-quirks mode.
+        // NOTE: We don't support quirks mode. We do however inherit the url for resolving links.
+        if let Some(context_document) = context.borrow().owning_document() {
+            let url = context_document.borrow().url().clone();
+            document.borrow_mut().set_url(url);
+        }
+
+        // 3. Create a new HTML parser, and associate it with the just created Document node.
+        let mut parser = Self::new(source, document.clone());
+        parser.context = Some(context.clone());
+
+        // 4. Set the state of the HTML parser's tokenization stage as follows, switching on the context element:
+        let context_name = context.borrow().local_name();
+        let tokenizer_state = match context_name {
+            static_interned!("title") | static_interned!("textarea") => {
+                Some(TokenizerState::RCDATA)
+            },
+            static_interned!("style")
+            | static_interned!("xmp")
+            | static_interned!("iframe")
+            | static_interned!("noembed")
+            | static_interned!("noframes") => Some(TokenizerState::RAWTEXT),
+            static_interned!("script") => Some(TokenizerState::ScriptData),
+            static_interned!("noscript") if parser.execute_script => Some(TokenizerState::RAWTEXT),
+            static_interned!("plaintext") => Some(TokenizerState::PLAINTEXT),
+            _ => None,
+        };
+
+        if let Some(tokenizer_state) = tokenizer_state {
+            parser.tokenizer.switch_to(tokenizer_state);
+
+            // The end tag of the context element is an appropriate end tag
+            parser
+                .tokenizer
+                .set_last_start_tag(Some(context_name.to_string()));
+        }
+
+        // 5. Let root be a new html element with no attributes.
+        let root = dom::create_element(
+            document.downgrade(),
+            static_interned!("html"),
+            Namespace::HTML,
+            None,
+            None,
+            false,
+        );
+
+        // 6. Append the element root to the Document node created above.
+        Node::append_child(document.upcast(), root.clone().upcast());
+
+        // 7. Set up the parser's stack of open elements so that it contains just the single element root.
+        parser.open_elements.push(root.clone());
+
+        // 8. If the context element is a template element, push "in template" onto the stack of
+        //    template insertion modes so that it is the new current template insertion mode.
+        if context_name == static_interned!("template") {
+            parser
+                .template_insertion_modes
+                .push(InsertionMode::InTemplate);
+        }
+
+        // 9. Create a start tag token whose name is the local name of context and whose attributes
+        //    are the attributes of context.
+        // FIXME: This token is only used for the adjusted current node, which we don't implement yet
+
+        // 10. Reset the parser's insertion mode appropriately.
+        parser.reset_insertion_mode_appropriately();
+
+        // 11. Set the parser's form element pointer to the nearest node to the context element that is a
+        //     form element (going straight up the ancestor chain, and including the element itself), if
+        //     there is such an element.
+        let mut ancestor = Some(context.upcast::<Node>());
+        while let Some(node) = ancestor {
+            if let Some(form) = node.try_into_type::<HtmlFormElement>() {
+                parser.form = Some(form);
+                break;
+            }
+            ancestor = node.borrow().parent_node();
+        }
+
+        // 12. Place the input into the input stream for the HTML parser just created.
+        // 13. Start the parser and let it run until it has consumed all the characters just inserted into the input stream.
+        _ = parser.parse();
+
+        // 14. Return the child nodes of root, in tree order.
+        let children = root.borrow().children().to_vec();
+        children
+    }
+
     /// <https://html.spec.whatwg.org/multipage/parsing.html#current-node>
     fn current_node(&self) -> DomPtr<Element> {
         // The current node is the bottommost node in this stack of open elements.
@@ -588,13 +698,16 @@ impl<P: ParseErrorHandler> Parser<P> {
         self.finish_loading_stylesheets();
 
         // FIXME: I assume this must be done at some point, but i can't find it in the spec
-        let html_element = self
+        let html_element: DomPtr<Node> = self
             .open_elements
             .first()
             .expect("no root element found")
             .clone()
             .upcast();
-        Node::append_child(self.document.clone().upcast(), html_element);
+        let is_inserted = html_element.borrow().parent_node().is_some();
+        if !is_inserted {
+            Node::append_child(self.document.clone().upcast(), html_element);
+        }
 
         // 4. Pop all the nodes off the stack of open elements.
         while !self.open_elements.is_empty() {
@@ -617,6 +730,10 @@ impl<P: ParseErrorHandler> Parser<P> {
         loop {
             if node_index == 0 {
                 last = true;
+
+                if let Some(context) = &self.context {
+                    node = context.clone();
+                }
             }
 
             // 4. If node is a select element, run these substeps:
@@ -790,6 +907,14 @@ impl<P: ParseErrorHandler> Parser<P> {
         for (key, value) in tagdata.attributes() {
             element.borrow_mut().append_attribute(*key, *value);
         }
+        for attribute in tagdata.foreign_attributes() {
+            element.borrow_mut().append_attribute_ns(
+                Some(attribute.namespace),
+                attribute.prefix,
+                attribute.local_name,
+                attribute.value,
+            );
+        }
 
         // FIXME: If will execute script is true, then:
         //      Let queue be the result of popping from document's relevant agent's custom element reactions stack. (This will be the same element queue as was pushed above.)
@@ -1325,6 +1450,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                             name: static_interned!("head"),
                             self_closing: false,
                             attributes: vec![],
+                            foreign_attributes: vec![],
                         };
                         let head_element = self
                             .insert_html_element_for_token(&bogus_head_token)
@@ -1676,6 +1802,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                             name: static_interned!("body"),
                             self_closing: false,
                             attributes: vec![],
+                            foreign_attributes: vec![],
                         };
                         self.insert_html_element_for_token(&body_token);
 
@@ -1726,11 +1853,11 @@ impl<P: ParseErrorHandler> Parser<P> {
                                 .expect("Stack of open elements cannot be empty");
 
                             for (key, value) in tagdata.attributes() {
-                                html_element
-                                    .borrow_mut()
-                                    .attributes_mut()
-                                    .entry(*key)
-                                    .or_insert(*value);
+                                let is_present =
+                                    html_element.borrow().attributes().contains_key(key);
+                                if !is_present {
+                                    Element::set_attribute(html_element.clone(), *key, *value);
+                                }
                             }
                         }
                     },
@@ -1780,10 +1907,12 @@ impl<P: ParseErrorHandler> Parser<P> {
                             // not, add the attribute and its corresponding value to that element.
                             self.frameset_ok = FramesetOkFlag::NotOk;
 
-                            let mut previous_body = previous_body.borrow_mut();
-                            let attributes = previous_body.attributes_mut();
                             for (key, value) in tagdata.attributes() {
-                                attributes.entry(*key).or_insert(*value);
+                                let is_present =
+                                    previous_body.borrow().attributes().contains_key(key);
+                                if !is_present {
+                                    Element::set_attribute(previous_body.clone(), *key, *value);
+                                }
                             }
                         }
                     },
@@ -2171,6 +2300,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                                 name: static_interned!("p"),
                                 self_closing: false,
                                 attributes: vec![],
+                                foreign_attributes: vec![],
                             });
                         }
 
@@ -2815,6 +2945,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                             name: static_interned!("colgroup"),
                             self_closing: false,
                             attributes: vec![],
+                            foreign_attributes: vec![],
                         };
                         self.insert_html_element_for_token(&fake_tag);
                         self.insertion_mode = InsertionMode::InColumnGroup;
@@ -2854,6 +2985,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                             name: static_interned!("tbody"),
                             self_closing: false,
                             attributes: vec![],
+                            foreign_attributes: vec![],
                         };
                         self.insert_html_element_for_token(&fake_tag);
                         self.insertion_mode = InsertionMode::InTableBody;
@@ -3123,6 +3255,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                             name: static_interned!("tr"),
                             self_closing: false,
                             attributes: vec![],
+                            foreign_attributes: vec![],
                         };
                         self.insert_html_element_for_token(&fake_tag);
                         self.insertion_mode = InsertionMode::InRow;
@@ -3238,7 +3371,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                     Token::EndTag(tag) if tag.name == static_interned!("tr") => {
                         // If the stack of open elements does not have a tr element in table scope,
                         // this is a parse error; ignore the token.
-                        if self.is_element_in_table_scope(static_interned!("tr")) {
+                        if !self.is_element_in_table_scope(static_interned!("tr")) {
                             return;
                         }
 
@@ -3266,7 +3399,7 @@ impl<P: ParseErrorHandler> Parser<P> {
                     {
                         // If the stack of open elements does not have a tr element in table scope,
                         // this is a parse error; ignore the token.
-                        if self.is_element_in_table_scope(static_interned!("tr")) {
+                        if !self.is_element_in_table_scope(static_interned!("tr")) {
                             return;
                         }
 
@@ -3288,7 +3421,7 @@ impl<P: ParseErrorHandler> Parser<P> {
 
                         // If the stack of open elements does not have a tr element in table scope,
                         // this is a parse error; ignore the token.
-                        if self.is_element_in_table_scope(static_interned!("tr")) {
+                        if !self.is_element_in_table_scope(static_interned!("tr")) {
                             return;
                         }
 
@@ -3401,11 +3534,13 @@ impl<P: ParseErrorHandler> Parser<P> {
                                 | static_interned!("tr")
                         ) =>
                     {
-                        // Assert: The stack of open elements has a td or th element in table scope.
-                        assert!(
-                            self.is_element_in_table_scope(static_interned!("td"))
-                                | self.is_element_in_table_scope(static_interned!("th"))
-                        );
+                        // If the stack of open elements does not have a td or th element in table scope,
+                        // then this is a parse error; ignore the token. (fragment case)
+                        if !self.is_element_in_table_scope(static_interned!("td"))
+                            && !self.is_element_in_table_scope(static_interned!("th"))
+                        {
+                            return;
+                        }
 
                         // Close the cell and reprocess the token.
                         self.close_the_cell();
@@ -3813,3 +3948,78 @@ fn is_element_in_special_category(tagname: InternedString) -> bool {
             | static_interned!("desc")
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dom::ElementCustomState, html::tokenization::IgnoreParseErrors};
+
+    fn parse_fragment(source: &str, context: &str) -> Vec<DomPtr<Node>> {
+        let context = DomPtr::new(Element::new(
+            Namespace::HTML,
+            None,
+            context.into(),
+            ElementCustomState::Uncustomized,
+            None,
+            None,
+        ));
+
+        Parser::<IgnoreParseErrors>::parse_fragment(source, context)
+    }
+
+    fn local_name(node: &DomPtr<Node>) -> InternedString {
+        node.try_into_type::<Element>()
+            .expect("node is an element")
+            .borrow()
+            .local_name()
+    }
+
+    #[test]
+    fn fragment_in_table_row() {
+        let nodes = parse_fragment("<td>a</td><td>b<tr><td>c", "tr");
+
+        // The <tr> start tag is ignored, since there is no table to close
+        assert_eq!(nodes.len(), 3);
+        assert!(nodes
+            .iter()
+            .all(|node| local_name(node) == static_interned!("td")));
+
+        let cell_content = |node: &DomPtr<Node>| {
+            let child = node.borrow().children()[0].clone();
+            let text = child.into_type::<Text>();
+            let content = text.borrow().content().to_string();
+            content
+        };
+        assert_eq!(cell_content(&nodes[0]), "a");
+        assert_eq!(cell_content(&nodes[1]), "b");
+        assert_eq!(cell_content(&nodes[2]), "c");
+    }
+
+    #[test]
+    fn fragment_in_body() {
+        let nodes = parse_fragment("text<p>paragraph", "div");
+
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes[0].is_a::<Text>());
+        assert_eq!(local_name(&nodes[1]), static_interned!("p"));
+    }
+
+    #[test]
+    fn fragment_tokenizer_state() {
+        // <textarea> contents are parsed as RCDATA
+        let nodes = parse_fragment("<b>bold</b> &amp; </textarea>after", "textarea");
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(
+            nodes[0].clone().into_type::<Text>().borrow().content(),
+            "<b>bold</b> & after"
+        );
+
+        // <style> contents are parsed as RAWTEXT
+        let nodes = parse_fragment("a &amp; <b>", "style");
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(
+            nodes[0].clone().into_type::<Text>().borrow().content(),
+            "a &amp; <b>"
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! <https://infra.spec.whatwg.org>
 
 /// <https://infra.spec.whatwg.org/#namespaces>
-#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 pub enum Namespace {
     /// <https://infra.spec.whatwg.org/#html-namespace>
     #[default]
@@ -0,0 +1,106 @@
+//! Configures the global logger
+
+use std::{fmt, fs::File, io, io::Write};
+
+use serialize::{serialization::SerializeStruct, Serialize, Serializer};
+use serialize_json::JsonSerializer;
+use settings::{LogFormat, SETTINGS};
+
+/// A log record, emitted as a single json object when using [LogFormat::Json]
+struct LogMessage<'a> {
+    level: log::Level,
+    file: Option<&'a str>,
+    line: Option<u32>,
+    target: &'a str,
+    message: String,
+}
+
+pub fn init() {
+    // STORMLICHT_LOG takes precedence over the more generic RUST_LOG
+    let filter_variable = if std::env::var_os("STORMLICHT_LOG").is_some() {
+        "STORMLICHT_LOG"
+    } else {
+        env_logger::DEFAULT_FILTER_ENV
+    };
+    let mut logger = env_logger::Builder::from_env(
+        env_logger::Env::default().filter_or(filter_variable, "info"),
+    );
+    if let Some(log_file) = &SETTINGS.log_file {
+        match File::create(log_file) {
+            Ok(file) => {
+                logger.target(env_logger::Target::Pipe(Box::new(file)));
+            },
+            Err(error) => {
+                eprintln!("Failed to open log file {}: {error}", log_file.display());
+            },
+        }
+    }
+
+    if SETTINGS.log_format == LogFormat::Json {
+        logger.format(|buffer, record| {
+            let json = format_json(record)
+                .map_err(|_| io::Error::other("failed to serialize log message"))?;
+            writeln!(buffer, "{json}")
+        });
+    }
+
+    logger.init();
+}
+
+fn format_json(record: &log::Record<'_>) -> Result<String, fmt::Error> {
+    let message = LogMessage {
+        level: record.level(),
+        file: record.file(),
+        line: record.line(),
+        target: record.target(),
+        message: record.args().to_string(),
+    };
+
+    JsonSerializer::serialize_to_string(message)
+}
+
+impl Serialize for LogMessage<'_> {
+    fn serialize_to<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        let mut serializer = serializer.serialize_struct()?;
+        serializer.serialize_field("level", &self.level.as_str())?;
+        serializer.serialize_field("file", &self.file)?;
+        serializer.serialize_field("line", &self.line)?;
+        serializer.serialize_field("target", &self.target)?;
+        serializer.serialize_field("msg", &self.message)?;
+        serializer.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serialize::Deserialize;
+    use serialize_json::{JsonDeserializer, Value};
+
+    #[test]
+    fn json_log_message() {
+        let message = "a \"quoted\"\nmessage\t\\";
+        let json = format_json(
+            &log::Record::builder()
+                .args(format_args!("{message}"))
+                .level(log::Level::Info)
+                .target("dns")
+                .file(Some("crates/dns/src/lib.rs"))
+                .line(Some(42))
+                .build(),
+        )
+        .unwrap();
+        let value = Value::deserialize(&mut JsonDeserializer::new(&json)).unwrap();
+        let fields = value.as_map().unwrap();
+
+        assert_eq!(fields["level"].as_str(), Some("INFO"));
+        assert_eq!(fields["file"].as_str(), Some("crates/dns/src/lib.rs"));
+        assert!(matches!(fields["line"], Value::Integer(42)));
+        assert_eq!(fields["target"].as_str(), Some("dns"));
+        assert_eq!(fields["msg"].as_str(), Some(message));
+    }
+}
@@ -1,6 +1,7 @@
 #![feature(panic_update_hook, cfg_match, error_reporter)]
 
 mod chrome;
+mod logging;
 
 use std::{process::ExitCode, sync::LazyLock};
 
@@ -22,7 +23,10 @@ pub fn main() -> ExitCode {
         prev(info);
     });
 
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // Initialize settings object
+    LazyLock::force(&SETTINGS);
+
+    logging::init();
 
     #[cfg(all(target_os = "linux", not(miri)))]
     if unsafe { geteuid() } == 0 {
@@ -30,8 +34,5 @@ pub fn main() -> ExitCode {
         return ExitCode::FAILURE;
     }
 
-    // Initialize settings object
-    LazyLock::force(&SETTINGS);
-
     chrome::run()
 }